@@ -0,0 +1,514 @@
+use bevy::{
+    ecs::system::SystemParam,
+    platform::collections::{
+        HashMap,
+        HashSet,
+    },
+    prelude::*,
+};
+use hsd::{
+    attributes::{
+        Attribute,
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationPath,
+            Interpolation,
+        },
+        slots,
+    },
+    id::PrimId,
+};
+use unavi_physics::finite;
+
+use crate::{
+    HsdChild,
+    HsdPrimIndex,
+    HsdRelationships,
+    HsdSlots,
+    attributes::{
+        AttributeParser,
+        ParseError,
+        xform::XformData,
+    },
+};
+
+/// Fastest a clip may play, either way. Past this a walk cycle is a blur,
+/// and a clip sampled at a few keyframes per frame looks like noise.
+const MAX_SPEED: f32 = 16.0;
+
+#[derive(Component, Debug, Clone)]
+pub struct AnimationData(pub AnimationAttr);
+
+/// A clip's channels, decoded from its slots. A channel whose keyframes do
+/// not add up is left out rather than sampled out of bounds.
+#[derive(Component, Debug, Clone, Default)]
+pub struct AnimationCurves {
+    curves:   Vec<Curve>,
+    duration: f32,
+}
+
+impl AnimationCurves {
+    /// Seconds to the clip's last keyframe.
+    #[must_use]
+    pub const fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.curves.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.curves.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Curve {
+    /// `None` moves the clip's own prim.
+    target:        Option<PrimId>,
+    path:          AnimationPath,
+    interpolation: Interpolation,
+    times:         Vec<f32>,
+    values:        Vec<f32>,
+    /// Floats per value: 3, or 4 for a rotation.
+    width:         usize,
+}
+
+/// A clip that is driving its targets, playing or paused. A clip without one
+/// is stopped, and its targets sit at their own `xform`.
+///
+/// Runtime state, like an audio sink: it is per peer and never written to the
+/// document.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct AnimationPlayback {
+    /// Seconds into the clip. Anything past its end is its end, which is
+    /// where a clip played backwards starts, since playback can begin before
+    /// the clip's length is known.
+    pub time:    f32,
+    pub speed:   f32,
+    /// How far the clip pulls its targets from their own pose, 0 to 1. Two
+    /// clips on one target share it by weight.
+    pub weight:  f32,
+    pub looping: bool,
+    pub paused:  bool,
+}
+
+impl AnimationPlayback {
+    /// Playing from the start, or from the end if `speed` is negative, with
+    /// numbers a peer wrote held to what the sampler can use.
+    #[must_use]
+    pub fn playing(speed: f32, weight: f32, looping: bool) -> Self {
+        let speed = if speed.is_finite() {
+            speed.clamp(-MAX_SPEED, MAX_SPEED)
+        } else {
+            1.0
+        };
+        Self {
+            time: if speed < 0.0 { f32::INFINITY } else { 0.0 },
+            speed,
+            weight: if weight.is_finite() {
+                weight.clamp(0.0, 1.0)
+            } else {
+                1.0
+            },
+            looping,
+            paused: false,
+        }
+    }
+}
+
+/// A prim a clip has moved, so it is put back when no clip drives it.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Animated;
+
+pub struct AnimationParser;
+
+impl AttributeParser for AnimationParser {
+    fn key(&self) -> &'static str {
+        AnimationAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                commands
+                    .entity(prim)
+                    .insert(AnimationData(AnimationAttr::decode(payload)?));
+            }
+            None => {
+                commands
+                    .entity(prim)
+                    .remove::<(AnimationData, AnimationCurves, AnimationPlayback)>();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(SystemParam)]
+pub struct AnimationCtx<'w, 's> {
+    pub children: Query<'w, 's, &'static HsdChild>,
+    pub indices:  Query<'w, 's, &'static HsdPrimIndex>,
+}
+
+impl AnimationCtx<'_, '_> {
+    /// Resolved each frame rather than at build, so a clip loaded before the
+    /// prims it moves still finds them.
+    fn target_of(&self, clip: Entity, target: Option<PrimId>) -> Option<Entity> {
+        let Some(target) = target else {
+            return Some(clip);
+        };
+        let doc = self.children.get(clip).ok()?;
+        self.indices.get(doc.0).ok()?.0.get(&target).copied()
+    }
+}
+
+/// Rebuilds on the channels, their targets, or their keyframes. Only a
+/// rewritten attribute resets playback: new keyframes under a playing clip
+/// keep it playing.
+pub fn rebuild_animation(
+    changed: Query<
+        (
+            Entity,
+            Ref<AnimationData>,
+            Option<&HsdRelationships>,
+            Option<&HsdSlots>,
+        ),
+        Or<(
+            Changed<AnimationData>,
+            Changed<HsdRelationships>,
+            Changed<HsdSlots>,
+        )>,
+    >,
+    mut commands: Commands,
+) {
+    for (clip, data, relationships, buffers) in &changed {
+        let curves = build(&data.0, relationships, buffers);
+        let mut entity = commands.entity(clip);
+        entity.insert(curves);
+
+        if data.is_changed() {
+            let attr = &data.0;
+            if attr.autoplay.unwrap_or(false) {
+                entity.insert(AnimationPlayback::playing(
+                    attr.speed.map_or(1.0, |speed| speed as f32),
+                    1.0,
+                    attr.looping.unwrap_or(false),
+                ));
+            } else {
+                entity.remove::<AnimationPlayback>();
+            }
+        }
+    }
+}
+
+fn build(
+    attr: &AnimationAttr,
+    relationships: Option<&HsdRelationships>,
+    buffers: Option<&HsdSlots>,
+) -> AnimationCurves {
+    let floats = |name: &str| {
+        buffers
+            .and_then(|buffers| buffers.0.get(name))
+            .and_then(|bytes| f32s(bytes))
+    };
+
+    let mut out = AnimationCurves::default();
+    for (index, channel) in attr.channels.iter().enumerate() {
+        if channel.path == AnimationPath::MorphWeights {
+            warn!(
+                channel = index,
+                "no mesh carries morph targets to weight; skipping animation channel"
+            );
+            continue;
+        }
+        let (Some(times), Some(values)) = (
+            floats(&slots::animation_times(index)),
+            floats(&slots::animation_values(index)),
+        ) else {
+            continue;
+        };
+        let Some(width) = width(channel.path, channel.interpolation, &times, &values) else {
+            warn!(
+                channel = index,
+                "animation channel keyframes do not add up; skipping"
+            );
+            continue;
+        };
+
+        out.duration = out.duration.max(times.last().copied().unwrap_or(0.0));
+        out.curves.push(Curve {
+            target: relationships
+                .and_then(|rels| rels.0.get(animation::target(index).as_str()))
+                .copied(),
+            path: channel.path,
+            interpolation: channel.interpolation,
+            times,
+            values,
+            width,
+        });
+    }
+    out
+}
+
+fn f32s(bytes: &[u8]) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+    )
+}
+
+/// Floats per value, if the keyframes are ones the sampler can walk: finite,
+/// ascending times, and exactly one value (or tangent triple) for each.
+fn width(
+    path: AnimationPath,
+    interpolation: Interpolation,
+    times: &[f32],
+    values: &[f32],
+) -> Option<usize> {
+    if times.is_empty()
+        || !times.iter().chain(values).all(|v| v.is_finite())
+        || times.windows(2).any(|pair| pair[0] > pair[1])
+    {
+        return None;
+    }
+
+    let per_key = match interpolation {
+        Interpolation::CubicSpline => 3,
+        Interpolation::Step | Interpolation::Linear => 1,
+    };
+    let width = match path {
+        AnimationPath::Translation | AnimationPath::Scale => 3,
+        AnimationPath::Rotation => 4,
+        AnimationPath::MorphWeights => return None,
+    };
+    (values.len() == times.len() * per_key * width).then_some(width)
+}
+
+impl Curve {
+    /// The value at keyframe `key`; for a cubic spline, the middle of its
+    /// triple.
+    fn key(&self, key: usize) -> &[f32] {
+        let start = match self.interpolation {
+            Interpolation::CubicSpline => (key * 3 + 1) * self.width,
+            Interpolation::Step | Interpolation::Linear => key * self.width,
+        };
+        &self.values[start..start + self.width]
+    }
+
+    fn sample(&self, time: f32) -> Vec<f32> {
+        let next = self.times.partition_point(|t| *t <= time);
+        if next == 0 {
+            return self.key(0).to_vec();
+        }
+        if next == self.times.len() {
+            return self.key(next - 1).to_vec();
+        }
+
+        let prev = next - 1;
+        let span = self.times[next] - self.times[prev];
+        let progress = if span > 0.0 {
+            (time - self.times[prev]) / span
+        } else {
+            0.0
+        };
+        let (a, b) = (self.key(prev), self.key(next));
+
+        match self.interpolation {
+            Interpolation::Step => a.to_vec(),
+            Interpolation::Linear if self.path == AnimationPath::Rotation => {
+                let a = Quat::from_slice(a);
+                let b = Quat::from_slice(b);
+                a.slerp(b, progress).to_array().to_vec()
+            }
+            Interpolation::Linear => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (b - a).mul_add(progress, *a))
+                .collect(),
+            Interpolation::CubicSpline => {
+                let width = self.width;
+                let out_tangent = &self.values[(prev * 3 + 2) * width..(prev * 3 + 3) * width];
+                let in_tangent = &self.values[next * 3 * width..(next * 3 + 1) * width];
+                // The Hermite basis, with tangents scaled to the span.
+                let (p, p2) = (progress, progress * progress);
+                let h00 = p2.mul_add(2.0f32.mul_add(p, -3.0), 1.0);
+                let h10 = p * (p - 1.0) * (p - 1.0) * span;
+                let h01 = p2 * (-2.0f32).mul_add(p, 3.0);
+                let h11 = p2 * (p - 1.0) * span;
+                let value = (0..width)
+                    .map(|i| {
+                        h00.mul_add(
+                            a[i],
+                            h10.mul_add(out_tangent[i], h01.mul_add(b[i], h11 * in_tangent[i])),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if self.path == AnimationPath::Rotation {
+                    Quat::from_slice(&value).normalize().to_array().to_vec()
+                } else {
+                    value
+                }
+            }
+        }
+    }
+}
+
+pub fn advance_animations(
+    time: Res<Time>,
+    mut clips: Query<(&mut AnimationPlayback, &AnimationCurves)>,
+) {
+    let delta = time.delta_secs();
+    for (mut playback, curves) in &mut clips {
+        if playback.paused {
+            continue;
+        }
+        let duration = curves.duration();
+        let next = playback.speed.mul_add(delta, playback.time.min(duration));
+        playback.time = if playback.looping && duration > 0.0 {
+            next.rem_euclid(duration)
+        } else {
+            next.clamp(0.0, duration)
+        };
+    }
+}
+
+/// Weighted sums of every clip driving one target. A clip at weight 1 alone
+/// replaces the pose; clips summing under 1 leave some of the target's own.
+#[derive(Default)]
+struct Blend {
+    translation: Option<(Vec3, f32)>,
+    rotation:    Option<(Vec4, f32)>,
+    scale:       Option<(Vec3, f32)>,
+}
+
+fn accumulate<T: std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T>>(
+    slot: &mut Option<(T, f32)>,
+    value: T,
+    weight: f32,
+) {
+    *slot = Some(match slot.take() {
+        Some((sum, total)) => (sum + value * weight, total + weight),
+        None => (value * weight, weight),
+    });
+}
+
+/// The pose a target's `xform` gives it, which clips blend from.
+fn rest_pose(xform: Option<&XformData>) -> Transform {
+    xform
+        .and_then(|data| {
+            Some(Transform {
+                translation: finite::vec3(data.0.translation)?,
+                rotation:    finite::quat(data.0.rotation)?,
+                scale:       finite::vec3(data.0.scale)?,
+            })
+        })
+        .unwrap_or_default()
+}
+
+pub fn sample_animations(
+    clips: Query<(Entity, &AnimationPlayback, &AnimationCurves)>,
+    ctx: AnimationCtx,
+    animated: Query<Entity, With<Animated>>,
+    rest: Query<Option<&XformData>>,
+    mut transforms: Query<&mut Transform>,
+    mut commands: Commands,
+) {
+    let mut blends = HashMap::<Entity, Blend>::default();
+    for (clip, playback, curves) in &clips {
+        if playback.weight <= 0.0 {
+            continue;
+        }
+        for curve in &curves.curves {
+            let Some(target) = ctx.target_of(clip, curve.target) else {
+                continue;
+            };
+            let value = curve.sample(playback.time);
+            let blend = blends.entry(target).or_default();
+            let weight = playback.weight;
+            match curve.path {
+                AnimationPath::Translation => {
+                    accumulate(&mut blend.translation, Vec3::from_slice(&value), weight);
+                }
+                AnimationPath::Scale => {
+                    accumulate(&mut blend.scale, Vec3::from_slice(&value), weight);
+                }
+                AnimationPath::Rotation => {
+                    // q and -q are one rotation; summing across the two
+                    // hemispheres would cancel them out.
+                    let mut value = Vec4::from_slice(&value);
+                    let reference = blend.rotation.map_or_else(
+                        || Vec4::from(rest_pose(rest.get(target).ok().flatten()).rotation),
+                        |(sum, _)| sum,
+                    );
+                    if value.dot(reference) < 0.0 {
+                        value = -value;
+                    }
+                    accumulate(&mut blend.rotation, value, weight);
+                }
+                // Never built into a curve.
+                AnimationPath::MorphWeights => {}
+            }
+        }
+    }
+
+    let driven = blends.keys().copied().collect::<HashSet<_>>();
+    for target in &animated {
+        if !driven.contains(&target) {
+            if let Ok(mut transform) = transforms.get_mut(target) {
+                *transform = rest_pose(rest.get(target).ok().flatten());
+            }
+            commands.entity(target).remove::<Animated>();
+        }
+    }
+
+    for (target, blend) in blends {
+        let pose = rest_pose(rest.get(target).ok().flatten());
+        if let Ok(mut transform) = transforms.get_mut(target) {
+            let mut next = pose;
+            if let Some((sum, total)) = blend.translation {
+                next.translation = pose.translation.lerp(sum / total, total.min(1.0));
+            }
+            if let Some((sum, total)) = blend.rotation {
+                let mean = Quat::from_vec4(sum);
+                if mean.length_squared() > f32::EPSILON {
+                    next.rotation = pose.rotation.slerp(mean.normalize(), total.min(1.0));
+                }
+            }
+            if let Some((sum, total)) = blend.scale {
+                next.scale = pose.scale.lerp(sum / total, total.min(1.0));
+            }
+            // Finite keyframes can still overflow once a cubic spline sums
+            // them with their tangents; such a pose falls back to the rest one.
+            *transform = match (
+                finite::vec3(next.translation.to_array()),
+                finite::quat(next.rotation.to_array()),
+                finite::vec3(next.scale.to_array()),
+            ) {
+                (Some(translation), Some(rotation), Some(scale)) => Transform {
+                    translation,
+                    rotation,
+                    scale,
+                },
+                _ => pose,
+            };
+        }
+        if !animated.contains(target) {
+            commands.entity(target).insert(Animated);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use bevy::{
+    audio::{
+        AudioPlayer,
+        AudioSink,
+        AudioSource,
+        PlaybackMode,
+        PlaybackSettings,
+        SpatialAudioSink,
+        SpatialScale,
+        Volume,
+    },
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use hsd::attributes::{
+    Attribute,
+    audio::{
+        self,
+        AudioAttr,
+    },
+    slots,
+};
+
+use crate::{
+    HsdChild,
+    HsdPrimIndex,
+    HsdRelationships,
+    HsdSlots,
+    attributes::{
+        AttributeParser,
+        ParseError,
+    },
+};
+
+/// Ceiling on an emitter's gain. Past this a clip is clipping, and a peer
+/// who wrote it is after the listener's ears rather than their attention.
+const MAX_VOLUME: f32 = 4.0;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AudioData(pub AudioAttr);
+
+/// The prim whose `audio:data` an emitter last resolved to, so a clip written
+/// after its emitter still reaches it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioClipRef(pub Entity);
+
+pub struct AudioParser;
+
+impl AttributeParser for AudioParser {
+    fn key(&self) -> &'static str {
+        AudioAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                commands
+                    .entity(prim)
+                    .insert(AudioData(AudioAttr::decode(payload)?));
+            }
+            None => {
+                commands.entity(prim).remove::<(
+                    AudioData,
+                    AudioClipRef,
+                    AudioPlayer,
+                    PlaybackSettings,
+                    AudioSink,
+                    SpatialAudioSink,
+                )>();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(SystemParam)]
+pub struct AudioCtx<'w, 's> {
+    pub children:      Query<'w, 's, &'static HsdChild>,
+    pub indices:       Query<'w, 's, &'static HsdPrimIndex>,
+    pub relationships: Query<'w, 's, &'static HsdRelationships>,
+    pub slots:         Query<'w, 's, &'static HsdSlots>,
+}
+
+impl AudioCtx<'_, '_> {
+    /// The `audio:clip` target, or the emitter itself when it names none.
+    fn clip_of(&self, emitter: Entity) -> Option<Entity> {
+        let Some(target) = self
+            .relationships
+            .get(emitter)
+            .ok()
+            .and_then(|rels| rels.0.get(audio::CLIP))
+        else {
+            return Some(emitter);
+        };
+        let doc = self.children.get(emitter).ok()?;
+        self.indices.get(doc.0).ok()?.0.get(target).copied()
+    }
+}
+
+/// Rebuilds on the settings, the clip relationship, or the emitter's own
+/// slots.
+pub fn rebuild_audio(
+    changed: Query<
+        (Entity, &AudioData),
+        Or<(
+            Changed<AudioData>,
+            Changed<HsdRelationships>,
+            Changed<HsdSlots>,
+        )>,
+    >,
+    ctx: AudioCtx,
+    mut sources: ResMut<Assets<AudioSource>>,
+    mut commands: Commands,
+) {
+    for (emitter, data) in &changed {
+        build(emitter, &data.0, &ctx, &mut sources, &mut commands);
+    }
+}
+
+/// An emitter whose clip lives on another prim picks up that prim's new bytes.
+pub fn propagate_clip_to_emitters(
+    changed: Query<Entity, Changed<HsdSlots>>,
+    emitters: Query<(Entity, &AudioClipRef, &AudioData)>,
+    ctx: AudioCtx,
+    mut sources: ResMut<Assets<AudioSource>>,
+    mut commands: Commands,
+) {
+    for clip in &changed {
+        for (emitter, clip_ref, data) in &emitters {
+            if clip_ref.0 == clip && emitter != clip {
+                build(emitter, &data.0, &ctx, &mut sources, &mut commands);
+            }
+        }
+    }
+}
+
+/// Restarts the emitter from the top: a playing sink keeps the clip and the
+/// settings it started with, so it is dropped for Bevy to queue a fresh one.
+fn build(
+    emitter: Entity,
+    attr: &AudioAttr,
+    ctx: &AudioCtx,
+    sources: &mut Assets<AudioSource>,
+    commands: &mut Commands,
+) {
+    let mut entity = commands.entity(emitter);
+    entity.remove::<(AudioPlayer, AudioSink, SpatialAudioSink)>();
+
+    let Some(clip) = ctx.clip_of(emitter) else {
+        entity.remove::<AudioClipRef>();
+        return;
+    };
+    entity.insert(AudioClipRef(clip));
+
+    let Some(bytes) = ctx
+        .slots
+        .get(clip)
+        .ok()
+        .and_then(|slots| slots.0.get(slots::AUDIO_DATA))
+    else {
+        return;
+    };
+
+    let handle = sources.add(AudioSource {
+        bytes: Arc::from(bytes.as_slice()),
+    });
+    entity.insert((AudioPlayer(handle), playback_settings(attr)));
+}
+
+/// A number a peer wrote, held to what the mixer can use: a NaN gain would
+/// reach the output device as a NaN on every sample.
+fn finite(value: Option<f64>, fallback: f32, max: f32) -> f32 {
+    value
+        .map(|value| value as f32)
+        .filter(|value| value.is_finite())
+        .map_or(fallback, |value| value.clamp(0.0, max))
+}
+
+#[must_use]
+pub fn playback_settings(attr: &AudioAttr) -> PlaybackSettings {
+    let rolloff = finite(attr.rolloff, 1.0, f32::MAX);
+    PlaybackSettings {
+        mode: if attr.looping.unwrap_or(false) {
+            PlaybackMode::Loop
+        } else {
+            PlaybackMode::Once
+        },
+        volume: Volume::Linear(finite(attr.volume, 1.0, MAX_VOLUME)),
+        paused: !attr.autoplay.unwrap_or(true),
+        spatial: rolloff > 0.0,
+        spatial_scale: (rolloff > 0.0).then(|| SpatialScale::new(rolloff)),
+        ..PlaybackSettings::ONCE
+    }
+}
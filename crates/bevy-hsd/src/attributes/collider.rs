@@ -1,4 +1,7 @@
-use avian3d::prelude::Collider;
+use avian3d::prelude::{
+    Collider,
+    CollisionEventsEnabled,
+};
 use bevy::prelude::*;
 use bytemuck::{
     PodCastError,
@@ -48,14 +51,23 @@ impl AttributeParser for ColliderParser {
     ) -> Result<(), ParseError> {
         match payload {
             Some(payload) => {
-                commands
-                    .entity(prim)
-                    .insert((ColliderData(ColliderAttr::decode(payload)?), HsdCollider));
+                // Events are enabled on every scene collider: which ones a
+                // script listens to is not known here, and a listener added
+                // later should not miss the next contact.
+                commands.entity(prim).insert((
+                    ColliderData(ColliderAttr::decode(payload)?),
+                    HsdCollider,
+                    CollisionEventsEnabled,
+                ));
             }
             None => {
-                commands
-                    .entity(prim)
-                    .remove::<(ColliderData, HsdCollider, Collider, DisabledCollider)>();
+                commands.entity(prim).remove::<(
+                    ColliderData,
+                    HsdCollider,
+                    Collider,
+                    DisabledCollider,
+                    CollisionEventsEnabled,
+                )>();
             }
         }
         Ok(())
@@ -0,0 +1,139 @@
+use avian3d::prelude::{
+    CollisionLayers,
+    LayerMask,
+};
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+};
+use hsd::{
+    attributes::{
+        Attribute,
+        collision_layers::CollisionLayersAttr,
+    },
+    layers::{
+        FIRST_CUSTOM,
+        compatible,
+    },
+};
+use unavi_physics::layers;
+
+use crate::{
+    Hsd,
+    HsdChild,
+    attributes::{
+        AttributeParser,
+        ParseError,
+    },
+};
+
+/// The engine's bits; a collider refused its document's own layers keeps
+/// only these.
+const ENGINE_BITS: u32 = (1 << FIRST_CUSTOM) - 1;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CollisionLayersData(pub CollisionLayersAttr);
+
+pub struct CollisionLayersParser;
+
+impl AttributeParser for CollisionLayersParser {
+    fn key(&self) -> &'static str {
+        CollisionLayersAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload.map(CollisionLayersAttr::decode).transpose()? {
+            Some(attr) => {
+                commands.entity(prim).insert(CollisionLayersData(attr));
+            }
+            None => {
+                commands
+                    .entity(prim)
+                    .remove::<(CollisionLayersData, CollisionLayers)>();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The custom layers of each document whose colliders use them.
+///
+/// Global rather than per document, unlike the shadow budget: every loaded
+/// document shares one physics world, and a custom bit means whatever name
+/// its own document gave it. A document is held to its names until it
+/// unloads.
+#[derive(Resource, Default)]
+pub struct LayerClaims {
+    held: HashMap<Entity, Vec<String>>,
+}
+
+impl LayerClaims {
+    /// Whether `doc` may use `custom` alongside every other document's
+    /// claimed layers, claiming them if so.
+    fn claim(&mut self, doc: Entity, custom: &[String]) -> bool {
+        if self.held.get(&doc).is_some_and(|held| held == custom) {
+            return true;
+        }
+        let fits = self
+            .held
+            .iter()
+            .filter(|(other, _)| **other != doc)
+            .all(|(_, held)| compatible(held, custom));
+        if fits {
+            self.held.insert(doc, custom.to_vec());
+        }
+        fits
+    }
+}
+
+/// A collider whose document's layers clash with another's keeps only the
+/// engine's bits, rather than colliding with whatever the other document
+/// named the same bit.
+pub fn apply_collision_layers(
+    changed: Query<(Entity, &CollisionLayersData), Changed<CollisionLayersData>>,
+    doc_of: Query<&HsdChild>,
+    docs: Query<&Hsd>,
+    mut claims: ResMut<LayerClaims>,
+    mut commands: Commands,
+) {
+    for (entity, data) in &changed {
+        let mut attr = data.0;
+
+        let uses_custom =
+            (attr.memberships.unwrap_or(0) | attr.filters.unwrap_or(0)) & !ENGINE_BITS != 0;
+        if uses_custom {
+            let custom = doc_of
+                .get(entity)
+                .ok()
+                .and_then(|doc| Some((doc.0, docs.get(doc.0).ok()?)))
+                .and_then(|(doc, hsd)| Some((doc, hsd.0.lock().ok()?.meta().layers.clone())));
+            let claimed = custom.is_some_and(|(doc, custom)| claims.claim(doc, &custom));
+            if !claimed {
+                warn!(
+                    "document's collision layers clash with another loaded document's; this collider keeps only the engine's"
+                );
+                attr.memberships = attr.memberships.map(|mask| mask & ENGINE_BITS);
+                attr.filters = attr.filters.map(|mask| mask & ENGINE_BITS);
+            }
+        }
+
+        commands.entity(entity).insert(collision_layers(attr));
+    }
+}
+
+pub fn release_layer_claims(trigger: On<Remove, Hsd>, mut claims: ResMut<LayerClaims>) {
+    claims.held.remove(&trigger.entity);
+}
+
+/// Unset masks fall back to avian's own defaults.
+fn collision_layers(attr: CollisionLayersAttr) -> CollisionLayers {
+    CollisionLayers::new(
+        attr.memberships.map_or(layers::DEFAULT, LayerMask),
+        attr.filters.map_or(LayerMask::ALL, LayerMask),
+    )
+}
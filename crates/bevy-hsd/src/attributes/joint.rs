@@ -0,0 +1,318 @@
+use avian3d::prelude::{
+    AngularMotor,
+    DistanceJoint,
+    FixedJoint,
+    LinearMotor,
+    PrismaticJoint,
+    RevoluteJoint,
+    SphericalJoint,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use hsd::attributes::{
+    Attribute,
+    joint::{
+        self,
+        JointAttr,
+        JointKind,
+        JointLimits,
+        JointMotor,
+    },
+};
+use unavi_physics::finite;
+
+use crate::{
+    HsdChild,
+    HsdPrimIndex,
+    HsdRelationships,
+    attributes::{
+        AttributeParser,
+        ParseError,
+    },
+};
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct JointData(pub JointAttr);
+
+/// The avian joint realizing a prim's `joint`. It is an entity of its own,
+/// so a prim changing joint kind swaps one entity rather than juggling five
+/// component types, and it is despawned with the prim.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = PrimJoint)]
+pub struct JointOf(pub Entity);
+
+#[derive(Component, Debug)]
+#[relationship_target(relationship = JointOf, linked_spawn)]
+pub struct PrimJoint(Entity);
+
+impl PrimJoint {
+    #[must_use]
+    pub const fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+/// A joint whose other body is not in the world yet. Retried each frame, so
+/// a joint loaded before the prim it connects to still finds it.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct JointPending;
+
+pub struct JointParser;
+
+impl AttributeParser for JointParser {
+    fn key(&self) -> &'static str {
+        JointAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                commands
+                    .entity(prim)
+                    .insert(JointData(JointAttr::decode(payload)?));
+            }
+            None => {
+                commands
+                    .entity(prim)
+                    .despawn_related::<PrimJoint>()
+                    .remove::<(JointData, JointPending)>();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(SystemParam)]
+pub struct JointCtx<'w, 's> {
+    pub children:      Query<'w, 's, &'static HsdChild>,
+    pub indices:       Query<'w, 's, &'static HsdPrimIndex>,
+    pub relationships: Query<'w, 's, &'static HsdRelationships>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Other {
+    Found(Entity),
+    /// Named, but not in the world yet.
+    Missing,
+    Unnamed,
+}
+
+impl JointCtx<'_, '_> {
+    fn other_of(&self, prim: Entity) -> Other {
+        let Some(target) = self
+            .relationships
+            .get(prim)
+            .ok()
+            .and_then(|rels| rels.0.get(joint::BODY))
+        else {
+            return Other::Unnamed;
+        };
+        self.children
+            .get(prim)
+            .ok()
+            .and_then(|doc| self.indices.get(doc.0).ok())
+            .and_then(|index| index.0.get(target).copied())
+            .map_or(Other::Missing, Other::Found)
+    }
+}
+
+/// Rebuilds on the attribute or the body relationship, and retries any joint
+/// still waiting on its other body.
+pub fn rebuild_joint(
+    changed: Query<
+        (Entity, &JointData),
+        Or<(
+            Changed<JointData>,
+            Changed<HsdRelationships>,
+            With<JointPending>,
+        )>,
+    >,
+    ctx: JointCtx,
+    mut commands: Commands,
+) {
+    for (prim, data) in &changed {
+        let other = ctx.other_of(prim);
+        let mut entity = commands.entity(prim);
+        entity.despawn_related::<PrimJoint>();
+
+        let other = match other {
+            Other::Found(other) if other != prim => other,
+            Other::Found(_) => {
+                warn!("joint connects a body to itself; ignoring");
+                entity.remove::<JointPending>();
+                continue;
+            }
+            Other::Missing => {
+                entity.insert(JointPending);
+                continue;
+            }
+            Other::Unnamed => {
+                entity.remove::<JointPending>();
+                continue;
+            }
+        };
+        entity.remove::<JointPending>();
+
+        if let Err(reason) = build(&mut commands, prim, other, &data.0) {
+            warn!("joint: {reason}; ignoring");
+        }
+    }
+}
+
+/// Checks every number a peer wrote before it reaches the solver, where a NaN
+/// anchor or an inverted limit would spread to both bodies' islands.
+fn build(
+    commands: &mut Commands,
+    prim: Entity,
+    other: Entity,
+    attr: &JointAttr,
+) -> Result<(), String> {
+    let point = |name: &str, value: Option<[f32; 3]>| {
+        value
+            .map_or(Some(Vec3::ZERO), finite::vec3)
+            .ok_or_else(|| format!("{name} must be finite"))
+    };
+    let anchor = point("anchor", attr.anchor)?;
+    let other_anchor = point("other_anchor", attr.other_anchor)?;
+    let axis = attr
+        .axis
+        .map_or(Some(Vec3::Y), finite::direction)
+        .ok_or("axis must be finite and non-zero")?;
+    let limits = attr.limits.map(limits).transpose()?;
+    let motor = attr.motor.map(motor).transpose()?;
+
+    let owner = JointOf(prim);
+    match attr.kind {
+        JointKind::Fixed => {
+            commands.spawn((
+                owner,
+                FixedJoint::new(prim, other)
+                    .with_local_anchor1(anchor)
+                    .with_local_anchor2(other_anchor),
+            ));
+        }
+        JointKind::Revolute => {
+            let mut joint = RevoluteJoint::new(prim, other)
+                .with_local_anchor1(anchor)
+                .with_local_anchor2(other_anchor)
+                .with_hinge_axis(axis);
+            if let Some((min, max)) = limits {
+                joint = joint.with_angle_limits(min, max);
+            }
+            if let Some(motor) = motor {
+                joint = joint.with_motor(motor.angular());
+            }
+            commands.spawn((owner, joint));
+        }
+        JointKind::Prismatic => {
+            let mut joint = PrismaticJoint::new(prim, other)
+                .with_local_anchor1(anchor)
+                .with_local_anchor2(other_anchor)
+                .with_slider_axis(axis);
+            if let Some((min, max)) = limits {
+                joint = joint.with_limits(min, max);
+            }
+            if let Some(motor) = motor {
+                joint = joint.with_motor(motor.linear());
+            }
+            commands.spawn((owner, joint));
+        }
+        JointKind::Spherical => {
+            let mut joint = SphericalJoint::new(prim, other)
+                .with_local_anchor1(anchor)
+                .with_local_anchor2(other_anchor);
+            if let Some((min, max)) = limits {
+                joint = joint.with_swing_limits(min, max);
+            }
+            commands.spawn((owner, joint));
+        }
+        JointKind::Distance => {
+            let mut joint = DistanceJoint::new(prim, other)
+                .with_local_anchor1(anchor)
+                .with_local_anchor2(other_anchor);
+            if let Some((min, max)) = limits {
+                if !finite::nonneg(min) {
+                    return Err(format!("distance limits must be >= 0 (got {min})"));
+                }
+                joint = joint.with_limits(min, max);
+            }
+            commands.spawn((owner, joint));
+        }
+    }
+    Ok(())
+}
+
+fn limits(limits: JointLimits) -> Result<(f32, f32), String> {
+    let (min, max) = (limits.min as f32, limits.max as f32);
+    if !(min.is_finite() && max.is_finite()) {
+        return Err(format!(
+            "limits must be finite (got {}..{})",
+            limits.min, limits.max
+        ));
+    }
+    if min > max {
+        return Err(format!("limits are inverted (got {min}..{max})"));
+    }
+    Ok((min, max))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Motor {
+    target_velocity: f32,
+    target_position: Option<f32>,
+    max_force:       Option<f32>,
+}
+
+impl Motor {
+    fn angular(self) -> AngularMotor {
+        let mut motor = AngularMotor::default().with_target_velocity(self.target_velocity);
+        if let Some(position) = self.target_position {
+            motor = motor.with_target_position(position);
+        }
+        if let Some(max) = self.max_force {
+            motor = motor.with_max_torque(max);
+        }
+        motor
+    }
+
+    fn linear(self) -> LinearMotor {
+        let mut motor = LinearMotor::default().with_target_velocity(self.target_velocity);
+        if let Some(position) = self.target_position {
+            motor = motor.with_target_position(position);
+        }
+        if let Some(max) = self.max_force {
+            motor = motor.with_max_force(max);
+        }
+        motor
+    }
+}
+
+fn motor(motor: JointMotor) -> Result<Motor, String> {
+    let scalar = |name: &str, value: Option<f64>| {
+        value
+            .map(|value| {
+                let narrowed = value as f32;
+                narrowed
+                    .is_finite()
+                    .then_some(narrowed)
+                    .ok_or_else(|| format!("motor {name} must be finite (got {value})"))
+            })
+            .transpose()
+    };
+    let max_force = scalar("max_force", motor.max_force)?;
+    if max_force.is_some_and(|max| !finite::nonneg(max)) {
+        return Err("motor max_force must be >= 0".to_owned());
+    }
+    Ok(Motor {
+        target_velocity: scalar("target_velocity", motor.target_velocity)?.unwrap_or(0.0),
+        target_position: scalar("target_position", motor.target_position)?,
+        max_force,
+    })
+}
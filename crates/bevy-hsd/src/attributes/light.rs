@@ -0,0 +1,221 @@
+use std::f32::consts::{
+    FRAC_PI_2,
+    FRAC_PI_4,
+    PI,
+};
+
+use bevy::{
+    light::light_consts::lux,
+    platform::collections::{
+        HashMap,
+        HashSet,
+    },
+    prelude::*,
+};
+use hsd::attributes::{
+    Attribute,
+    light::{
+        LightAttr,
+        LightKind,
+    },
+    material::ColorVec,
+};
+use unavi_quota::limits::MAX_SHADOW_LIGHTS;
+
+use crate::{
+    Hsd,
+    HsdChild,
+    attributes::{
+        AttributeParser,
+        ParseError,
+    },
+};
+
+/// A household bulb, in candela, rather than Bevy's stage-light default: a
+/// lamp whose author omitted a brightness should light a room, not flood it.
+const DEFAULT_CANDELA: f32 = 80.0;
+
+/// Ceiling on a point or spot light's candela and a sun's lux. Direct
+/// sunlight is around 100 000 lux; past this the author is not lighting
+/// anything.
+const MAX_INTENSITY: f32 = 1_000_000.0;
+
+const DEFAULT_RANGE: f32 = 20.0;
+
+/// Metres a point or spot light may reach. Every mesh inside the range is
+/// shaded by the light, so an unbounded one costs like a sun that is not
+/// culled like one.
+const MAX_RANGE: f32 = 1_000.0;
+
+#[derive(Component, Debug, Clone)]
+pub struct LightData(pub LightAttr);
+
+pub struct LightParser;
+
+impl AttributeParser for LightParser {
+    fn key(&self) -> &'static str {
+        LightAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                commands
+                    .entity(prim)
+                    .insert(LightData(LightAttr::decode(payload)?));
+            }
+            None => {
+                commands
+                    .entity(prim)
+                    .remove::<(LightData, PointLight, SpotLight, DirectionalLight)>();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which prims hold each document's shadow maps.
+///
+/// Per document rather than global, like the shader-program cap: a global
+/// count would let one document's lamps take every other document's shadows.
+#[derive(Resource, Default)]
+pub struct ShadowBudget {
+    casting: HashMap<Entity, HashSet<Entity>>,
+}
+
+impl ShadowBudget {
+    /// Whether `prim` may cast shadows for `doc`, claiming one of its
+    /// [`MAX_SHADOW_LIGHTS`] if it does not hold one already.
+    fn claim(&mut self, doc: Entity, prim: Entity) -> bool {
+        let held = self.casting.entry(doc).or_default();
+        if held.contains(&prim) {
+            return true;
+        }
+        if held.len() >= MAX_SHADOW_LIGHTS {
+            return false;
+        }
+        held.insert(prim);
+        true
+    }
+
+    fn release(&mut self, prim: Entity) {
+        for held in self.casting.values_mut() {
+            held.remove(&prim);
+        }
+    }
+
+    #[must_use]
+    pub fn casting(&self, doc: Entity) -> usize {
+        self.casting.get(&doc).map_or(0, HashSet::len)
+    }
+}
+
+/// Linear RGB, each channel held to 0..=1; a short vector pads with white, as
+/// text colour does, and a non-finite channel is white too.
+fn color(value: Option<&ColorVec>) -> Color {
+    value.map_or(Color::WHITE, |value| {
+        // Colour multiplies intensity, so a channel past 1 would get around
+        // `MAX_INTENSITY`.
+        let channel = |index: usize| scalar(value.0.get(index).copied(), 1.0, 0.0..=1.0);
+        Color::linear_rgb(channel(0), channel(1), channel(2))
+    })
+}
+
+/// A number a peer wrote, held to a range the renderer can use. A NaN
+/// intensity would reach the shader as a NaN on every lit pixel.
+fn scalar(value: Option<f64>, fallback: f32, range: std::ops::RangeInclusive<f32>) -> f32 {
+    value
+        .map(|value| value as f32)
+        .filter(|value| value.is_finite())
+        .unwrap_or(fallback)
+        .clamp(*range.start(), *range.end())
+}
+
+/// Bevy takes a point or spot light's power in lumens; the attribute carries
+/// candela, which is lumens over the whole sphere.
+const fn lumens(candela: f32) -> f32 {
+    candela * 4.0 * PI
+}
+
+/// A light refused a shadow map shines without one, and is not granted one
+/// later when another frees up until its attribute is written again.
+pub fn apply_light(
+    changed: Query<(Entity, &LightData), Changed<LightData>>,
+    doc_of: Query<&HsdChild>,
+    mut budget: ResMut<ShadowBudget>,
+    mut commands: Commands,
+) {
+    for (entity, data) in &changed {
+        let attr = &data.0;
+
+        let wants_shadows = attr.shadows.unwrap_or(false);
+        let shadows = wants_shadows
+            && doc_of
+                .get(entity)
+                .is_ok_and(|doc| budget.claim(doc.0, entity));
+        if !shadows {
+            budget.release(entity);
+        }
+        if wants_shadows && !shadows {
+            warn!(
+                "document is at its cap of {MAX_SHADOW_LIGHTS} shadow-casting lights; this one casts none"
+            );
+        }
+
+        let color = color(attr.color.as_ref());
+        let range = scalar(attr.range, DEFAULT_RANGE, 0.0..=MAX_RANGE);
+        let intensity = lumens(scalar(attr.intensity, DEFAULT_CANDELA, 0.0..=MAX_INTENSITY));
+
+        let mut prim = commands.entity(entity);
+        prim.remove::<(PointLight, SpotLight, DirectionalLight)>();
+        match attr.kind {
+            LightKind::Directional => {
+                prim.insert(DirectionalLight {
+                    color,
+                    illuminance: scalar(attr.intensity, lux::AMBIENT_DAYLIGHT, 0.0..=MAX_INTENSITY),
+                    shadows_enabled: shadows,
+                    ..default()
+                });
+            }
+            LightKind::Point => {
+                prim.insert(PointLight {
+                    color,
+                    intensity,
+                    range,
+                    shadows_enabled: shadows,
+                    ..default()
+                });
+            }
+            LightKind::Spot => {
+                let outer_angle = scalar(attr.outer_angle, FRAC_PI_4, 0.0..=FRAC_PI_2);
+                prim.insert(SpotLight {
+                    color,
+                    intensity,
+                    range,
+                    inner_angle: scalar(attr.inner_angle, 0.0, 0.0..=outer_angle),
+                    outer_angle,
+                    shadows_enabled: shadows,
+                    ..default()
+                });
+            }
+        }
+    }
+}
+
+pub fn release_shadows(
+    mut removed: RemovedComponents<LightData>,
+    mut budget: ResMut<ShadowBudget>,
+) {
+    for prim in removed.read() {
+        budget.release(prim);
+    }
+}
+
+pub fn evict_document_shadows(trigger: On<Remove, Hsd>, mut budget: ResMut<ShadowBudget>) {
+    budget.casting.remove(&trigger.entity);
+}
@@ -6,9 +6,14 @@ use bevy::{
 };
 use thiserror::Error;
 
+pub mod animation;
+pub mod audio;
 pub mod collider;
+pub mod collision_layers;
 pub mod gravity_scale;
 pub mod image;
+pub mod joint;
+pub mod light;
 pub mod material;
 pub mod material_graph;
 pub mod material_source;
@@ -16,8 +21,10 @@ pub mod mesh;
 pub mod name;
 pub mod portal;
 pub mod prefab;
+pub mod relay;
 pub mod rigid_body;
 pub mod script;
+pub mod sensor;
 pub mod spawn;
 pub mod text;
 pub mod util;
@@ -26,15 +33,22 @@ pub mod xform;
 pub static PARSERS: LazyLock<HashMap<&'static str, Box<dyn AttributeParser>>> =
     LazyLock::new(|| {
         let parsers: [Box<dyn AttributeParser>; _] = [
+            Box::new(animation::AnimationParser),
+            Box::new(audio::AudioParser),
             Box::new(collider::ColliderParser),
+            Box::new(collision_layers::CollisionLayersParser),
             Box::new(gravity_scale::GravityScaleParser),
             Box::new(image::ImageParser),
+            Box::new(joint::JointParser),
+            Box::new(light::LightParser),
             Box::new(material::MaterialParser),
             Box::new(material_graph::ShaderGraphOverridesParser),
             Box::new(mesh::MeshParser),
             Box::new(name::NameParser),
             Box::new(portal::PortalParser),
+            Box::new(relay::RelayParser),
             Box::new(rigid_body::RigidBodyParser),
+            Box::new(sensor::SensorParser),
             Box::new(spawn::SpawnParser),
             Box::new(text::TextParser),
             Box::new(xform::XformParser),
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use hsd::attributes::{
+    Attribute,
+    relay::RelayAttr,
+};
+
+use crate::attributes::{
+    AttributeParser,
+    ParseError,
+};
+
+/// A relay named by the document, for whichever space it is.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RelayData(pub RelayAttr);
+
+pub struct RelayParser;
+
+impl AttributeParser for RelayParser {
+    fn key(&self) -> &'static str {
+        RelayAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                commands
+                    .entity(prim)
+                    .insert(RelayData(RelayAttr::decode(payload)?));
+            }
+            None => {
+                commands.entity(prim).remove::<RelayData>();
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use avian3d::prelude::Sensor;
+use bevy::prelude::*;
+use hsd::attributes::{
+    Attribute,
+    sensor::SensorAttr,
+};
+
+use crate::attributes::{
+    AttributeParser,
+    ParseError,
+};
+
+pub struct SensorParser;
+
+impl AttributeParser for SensorParser {
+    fn key(&self) -> &'static str {
+        SensorAttr::KEY
+    }
+
+    fn lifecycle(
+        &self,
+        commands: &mut Commands,
+        prim: Entity,
+        payload: Option<&[u8]>,
+    ) -> Result<(), ParseError> {
+        match payload {
+            Some(payload) => {
+                SensorAttr::decode(payload)?;
+                commands.entity(prim).insert(Sensor);
+            }
+            None => {
+                commands.entity(prim).remove::<Sensor>();
+            }
+        }
+        Ok(())
+    }
+}
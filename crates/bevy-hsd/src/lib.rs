@@ -48,9 +48,13 @@ impl Plugin for HsdPlugin {
         ))
             .init_asset::<load::HsdAsset>()
             .init_resource::<attributes::material_graph::ShaderGraphCache>()
+            .init_resource::<attributes::light::ShadowBudget>()
+            .init_resource::<attributes::collision_layers::LayerClaims>()
             .register_asset_loader(load::HsdLoader)
             .add_observer(diff::resync_on_spawn)
             .add_observer(attributes::material_graph::evict_document_shaders)
+            .add_observer(attributes::light::evict_document_shadows)
+            .add_observer(attributes::collision_layers::release_layer_claims)
             .add_systems(
                 Startup,
                 attributes::material_graph::register_fallback_shader,
@@ -84,6 +88,15 @@ impl Plugin for HsdPlugin {
                     )
                         .chain(),
                     (
+                        attributes::joint::rebuild_joint,
+                        attributes::collision_layers::apply_collision_layers,
+                        attributes::light::release_shadows,
+                        attributes::light::apply_light,
+                        attributes::audio::rebuild_audio,
+                        attributes::audio::propagate_clip_to_emitters,
+                        attributes::animation::rebuild_animation,
+                        attributes::animation::advance_animations,
+                        attributes::animation::sample_animations,
                         attributes::material_graph::rebuild_material_graph,
                         attributes::material_graph::apply_graph_overrides,
                         load::instance_hsd,
@@ -0,0 +1,286 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_hsd::{
+    Prim,
+    attributes::animation::{
+        AnimationCurves,
+        AnimationPlayback,
+    },
+};
+use hsd::{
+    attributes::{
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationChannel,
+            AnimationPath,
+            Interpolation,
+        },
+        slots,
+        xform::XformAttr,
+    },
+    id::PrimId,
+};
+use rstest::rstest;
+use tracing_test::traced_test;
+
+use crate::common::*;
+
+mod common;
+
+fn floats(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// A clip on its own prim sliding `target` from the origin to x = 2 over two
+/// seconds, on a loop.
+fn slide(ctx: &TestContext, target: PrimId, autoplay: bool) -> PrimId {
+    let clip = ctx.create_prim();
+    ctx.set_attr(
+        clip,
+        &AnimationAttr {
+            channels: vec![AnimationChannel {
+                path:          AnimationPath::Translation,
+                interpolation: Interpolation::Linear,
+            }],
+            autoplay: Some(autoplay),
+            looping: Some(true),
+            ..Default::default()
+        },
+    );
+    ctx.set_relationship(clip, &animation::target(0), target);
+    ctx.set_slot(clip, &slots::animation_times(0), floats(&[0.0, 2.0]));
+    ctx.set_slot(
+        clip,
+        &slots::animation_values(0),
+        floats(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0]),
+    );
+    clip
+}
+
+fn entity(ctx: &mut TestContext, prim: PrimId) -> Entity {
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<(Entity, &Prim)>();
+    query
+        .iter(world)
+        .find_map(|(entity, p)| (p.0 == prim).then_some(entity))
+        .expect("prim entity")
+}
+
+fn translation(ctx: &mut TestContext, prim: PrimId) -> Vec3 {
+    let entity = entity(ctx, prim);
+    ctx.app
+        .world()
+        .get::<Transform>(entity)
+        .expect("transform")
+        .translation
+}
+
+/// Parks a clip at `time`, so a pose can be asserted without depending on
+/// frame timing.
+fn hold(ctx: &mut TestContext, clip: PrimId, time: f32, weight: f32) {
+    let entity = entity(ctx, clip);
+    ctx.app
+        .world_mut()
+        .entity_mut(entity)
+        .insert(AnimationPlayback {
+            time,
+            weight,
+            paused: true,
+            ..AnimationPlayback::playing(1.0, 1.0, false)
+        });
+    ctx.app.update();
+}
+
+#[traced_test]
+#[rstest]
+fn test_a_clip_poses_its_target(mut ctx: TestContext) {
+    let target = ctx.create_prim();
+    let clip = slide(&ctx, target, false);
+    ctx.app.update();
+
+    assert_eq!(
+        translation(&mut ctx, target),
+        Vec3::ZERO,
+        "a clip without autoplay waits to be started"
+    );
+
+    hold(&mut ctx, clip, 1.0, 1.0);
+    assert!(
+        translation(&mut ctx, target).abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1.0e-5),
+        "halfway through, a linear channel is halfway between its keys"
+    );
+
+    hold(&mut ctx, clip, 5.0, 1.0);
+    assert!(
+        translation(&mut ctx, target).abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1.0e-5),
+        "past the last key, the last key holds"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_weight_blends_against_the_rest_pose(mut ctx: TestContext) {
+    let target = ctx.create_prim();
+    ctx.set_attr(
+        target,
+        &XformAttr {
+            translation: [0.0, 4.0, 0.0],
+            ..Default::default()
+        },
+    );
+    let clip = slide(&ctx, target, false);
+    ctx.app.update();
+
+    hold(&mut ctx, clip, 2.0, 0.5);
+    assert!(
+        translation(&mut ctx, target).abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1.0e-5),
+        "half weight lands halfway between the xform and the clip"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_stopping_returns_the_target_to_its_xform(mut ctx: TestContext) {
+    let target = ctx.create_prim();
+    ctx.set_attr(
+        target,
+        &XformAttr {
+            translation: [0.0, 4.0, 0.0],
+            ..Default::default()
+        },
+    );
+    let clip = slide(&ctx, target, false);
+    ctx.app.update();
+    hold(&mut ctx, clip, 2.0, 1.0);
+
+    let clip_entity = entity(&mut ctx, clip);
+    ctx.app
+        .world_mut()
+        .entity_mut(clip_entity)
+        .remove::<AnimationPlayback>();
+    ctx.app.update();
+
+    assert!(
+        translation(&mut ctx, target).abs_diff_eq(Vec3::new(0.0, 4.0, 0.0), 1.0e-5),
+        "a stopped clip lets go of its target"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_autoplay_advances_and_loops(mut ctx: TestContext) {
+    ctx.app
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            500,
+        )));
+    let target = ctx.create_prim();
+    let clip = slide(&ctx, target, true);
+    ctx.app.update();
+    let clip_entity = entity(&mut ctx, clip);
+
+    let mut times = Vec::new();
+    for _ in 0..8 {
+        ctx.app.update();
+        let playback = ctx
+            .app
+            .world()
+            .get::<AnimationPlayback>(clip_entity)
+            .expect("an autoplaying clip is playing");
+        times.push(playback.time);
+    }
+
+    assert!(times.iter().all(|t| (0.0..2.0).contains(t)));
+    assert!(
+        times.windows(2).any(|pair| pair[1] < pair[0]),
+        "a looping clip wraps: {times:?}"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_mismatched_keyframes_are_skipped(mut ctx: TestContext) {
+    let target = ctx.create_prim();
+    let clip = slide(&ctx, target, false);
+    ctx.set_slot(clip, &slots::animation_values(0), floats(&[0.0, 0.0, 0.0]));
+    ctx.app.update();
+
+    let entity = entity(&mut ctx, clip);
+    let curves = ctx
+        .app
+        .world()
+        .get::<AnimationCurves>(entity)
+        .expect("curves");
+    assert!(curves.is_empty());
+    assert!(logs_contain("do not add up"));
+}
+
+#[traced_test]
+#[rstest]
+fn test_a_reversed_clip_starts_at_its_end(mut ctx: TestContext) {
+    ctx.app
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            500,
+        )));
+    let target = ctx.create_prim();
+    let clip = slide(&ctx, target, false);
+    ctx.app.update();
+
+    let clip_entity = entity(&mut ctx, clip);
+    ctx.app
+        .world_mut()
+        .entity_mut(clip_entity)
+        .insert(AnimationPlayback::playing(-1.0, 1.0, false));
+    ctx.app.update();
+
+    let time = ctx
+        .app
+        .world()
+        .get::<AnimationPlayback>(clip_entity)
+        .expect("playing")
+        .time;
+    assert!(
+        (time - 1.5).abs() < 1.0e-5,
+        "one step back from the end, not pinned at the start: {time}"
+    );
+
+    for _ in 0..8 {
+        ctx.app.update();
+    }
+    assert!(
+        translation(&mut ctx, target).abs_diff_eq(Vec3::ZERO, 1.0e-5),
+        "played backwards, a clip ends on its first key"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_morph_weight_channels_are_skipped(mut ctx: TestContext) {
+    let clip = ctx.create_prim();
+    ctx.set_attr(
+        clip,
+        &AnimationAttr {
+            channels: vec![AnimationChannel {
+                path:          AnimationPath::MorphWeights,
+                interpolation: Interpolation::Linear,
+            }],
+            ..Default::default()
+        },
+    );
+    ctx.set_slot(clip, &slots::animation_times(0), floats(&[0.0, 1.0]));
+    ctx.set_slot(clip, &slots::animation_values(0), floats(&[0.0, 1.0]));
+    ctx.app.update();
+
+    let entity = entity(&mut ctx, clip);
+    let curves = ctx
+        .app
+        .world()
+        .get::<AnimationCurves>(entity)
+        .expect("curves");
+    assert!(curves.is_empty());
+    assert!(logs_contain("morph targets"));
+}
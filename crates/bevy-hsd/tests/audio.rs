@@ -0,0 +1,169 @@
+use bevy::{
+    audio::{
+        Decodable,
+        PlaybackMode,
+        Source,
+    },
+    prelude::*,
+};
+use bevy_hsd::attributes::audio::{
+    AudioClipRef,
+    AudioData,
+};
+use hsd::attributes::{
+    audio::{
+        self,
+        AudioAttr,
+    },
+    slots,
+};
+use rstest::rstest;
+use tracing_test::traced_test;
+
+use crate::common::*;
+
+mod common;
+
+const RATE: u32 = 8_000;
+const FRAMES: usize = 800;
+
+/// A mono 16-bit PCM WAV of a square wave, built by hand so the test needs no
+/// encoder.
+fn wav() -> Vec<u8> {
+    let samples = (0..FRAMES)
+        .map(|i| {
+            if (i / 20) % 2 == 0 {
+                i16::MAX / 2
+            } else {
+                -i16::MAX / 2
+            }
+        })
+        .flat_map(i16::to_le_bytes)
+        .collect::<Vec<_>>();
+    let data_len = u32::try_from(samples.len()).expect("clip length");
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&RATE.to_le_bytes());
+    out.extend_from_slice(&(RATE * 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out.extend_from_slice(&samples);
+    out
+}
+
+fn players(ctx: &mut TestContext) -> Vec<(AudioPlayer, PlaybackSettings)> {
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<(&AudioPlayer, &PlaybackSettings)>();
+    query
+        .query(world)
+        .into_iter()
+        .map(|(player, settings)| (player.clone(), *settings))
+        .collect()
+}
+
+#[traced_test]
+#[rstest]
+fn test_audio_lifecycle(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &AudioAttr::default());
+    ctx.set_slot(root, slots::AUDIO_DATA, wav());
+    ctx.app.update();
+
+    let found = players(&mut ctx);
+    assert_eq!(found.len(), 1);
+    let settings = found[0].1;
+    assert!(matches!(settings.mode, PlaybackMode::Once));
+    assert!(!settings.paused, "an emitter plays on its own by default");
+    assert!(settings.spatial);
+
+    ctx.remove_attr::<AudioAttr>(root);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<(Option<&AudioPlayer>, Option<&AudioData>)>();
+    let remaining = query
+        .query(world)
+        .into_iter()
+        .filter(|(player, data)| player.is_some() || data.is_some())
+        .count();
+    assert_eq!(
+        remaining, 0,
+        "clearing the attribute takes the emitter with it"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_settings_reach_the_player(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(
+        root,
+        &AudioAttr {
+            volume:   Some(f64::NAN),
+            looping:  Some(true),
+            rolloff:  Some(0.0),
+            autoplay: Some(false),
+        },
+    );
+    ctx.set_slot(root, slots::AUDIO_DATA, wav());
+    ctx.app.update();
+
+    let found = players(&mut ctx);
+    let settings = found[0].1;
+    assert!(matches!(settings.mode, PlaybackMode::Loop));
+    assert!(settings.paused);
+    assert!(!settings.spatial, "no rolloff plays without position");
+    assert!(
+        (settings.volume.to_linear() - 1.0).abs() < f32::EPSILON,
+        "a NaN gain falls back to unity"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_a_clip_on_another_prim_reaches_its_emitter(mut ctx: TestContext) {
+    let clip = ctx.create_prim();
+    let emitter = ctx.create_prim();
+    ctx.set_attr(emitter, &AudioAttr::default());
+    ctx.set_relationship(emitter, audio::CLIP, clip);
+    ctx.app.update();
+    assert!(players(&mut ctx).is_empty(), "nothing to play yet");
+
+    ctx.set_slot(clip, slots::AUDIO_DATA, wav());
+    ctx.app.update();
+    ctx.app.update();
+
+    assert_eq!(players(&mut ctx).len(), 1);
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&AudioClipRef>();
+    assert_eq!(query.query(world).into_iter().count(), 1);
+}
+
+/// Decoding is what the output device would pull; rendering it to a buffer
+/// checks the clip a peer wrote is the clip that plays, with no device.
+#[traced_test]
+#[rstest]
+fn test_the_clip_renders_to_a_buffer(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &AudioAttr::default());
+    ctx.set_slot(root, slots::AUDIO_DATA, wav());
+    ctx.app.update();
+
+    let found = players(&mut ctx);
+    let sources = ctx.app.world().resource::<Assets<AudioSource>>();
+    let source = sources.get(&found[0].0.0).expect("audio source");
+
+    let decoder = source.decoder();
+    assert_eq!(u32::from(decoder.sample_rate()), RATE);
+    let rendered = decoder.collect::<Vec<_>>();
+    assert_eq!(rendered.len(), FRAMES);
+    assert!(rendered.iter().any(|sample| *sample != Default::default()));
+}
@@ -1,10 +1,26 @@
-use avian3d::prelude::Collider;
+use avian3d::prelude::{
+    Collider,
+    CollisionEventsEnabled,
+    CollisionLayers,
+    LayerMask,
+    Sensor,
+};
 use bevy::prelude::*;
 use bevy_hsd::attributes::collider::HsdCollider;
 use bytemuck::cast_slice;
-use hsd::attributes::{
-    collider::ColliderAttr,
-    slots,
+use hsd::{
+    attributes::{
+        collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
+        sensor::SensorAttr,
+        slots,
+    },
+    layers::{
+        self,
+        BUILTIN,
+        FIRST_CUSTOM,
+    },
+    meta::DocMeta,
 };
 use rstest::rstest;
 use tracing_test::traced_test;
@@ -44,6 +60,123 @@ fn test_collider_lifecycle(mut ctx: TestContext) {
     );
 }
 
+#[traced_test]
+#[rstest]
+fn test_collider_sensor_toggles(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &ColliderAttr::Sphere(0.5));
+    ctx.set_attr(root, &SensorAttr);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut q =
+        world.query_filtered::<(), (With<Collider>, With<Sensor>, With<CollisionEventsEnabled>)>();
+    assert_eq!(q.iter(world).count(), 1, "a sensor reports its contacts");
+
+    ctx.remove_attr::<SensorAttr>(root);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut q = world.query_filtered::<(), (With<Collider>, With<Sensor>)>();
+    assert_eq!(q.iter(world).count(), 0, "clearing the flag makes it solid");
+    let mut q = world.query_filtered::<(), (With<Collider>, With<CollisionEventsEnabled>)>();
+    assert_eq!(q.iter(world).count(), 1, "a solid collider reports too");
+}
+
+#[traced_test]
+#[rstest]
+fn test_collider_layers_toggle(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &ColliderAttr::Sphere(0.5));
+    ctx.set_attr(
+        root,
+        &CollisionLayersAttr {
+            memberships: Some(unavi_physics::layers::TOOL.0),
+            filters:     Some(0),
+        },
+    );
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut q = world.query_filtered::<&CollisionLayers, With<Collider>>();
+    let layers = q.single(world).expect("layers on the collider");
+    assert_eq!(layers.memberships, unavi_physics::layers::TOOL);
+    assert_eq!(
+        layers.filters,
+        LayerMask::NONE,
+        "a ghost collides with nothing"
+    );
+
+    ctx.remove_attr::<CollisionLayersAttr>(root);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut q = world.query_filtered::<(), (With<Collider>, With<CollisionLayers>)>();
+    assert_eq!(
+        q.iter(world).count(),
+        0,
+        "clearing the masks restores the default"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_clashing_custom_layers_keep_only_the_engines(mut ctx: TestContext) {
+    let meta = |name: &str| DocMeta {
+        layers: vec![name.to_owned()],
+        ..DocMeta::default()
+    };
+    let custom = 1 << FIRST_CUSTOM;
+    let attr = CollisionLayersAttr {
+        memberships: Some(custom | unavi_physics::layers::DEFAULT.0),
+        filters:     Some(custom),
+    };
+
+    ctx.set_meta(&meta("ghost"));
+    let ghost = ctx.create_prim();
+    ctx.set_attr(ghost, &ColliderAttr::Sphere(0.5));
+    ctx.set_attr(ghost, &attr);
+    ctx.app.update();
+
+    let water_doc = ctx.spawn_document();
+    water_doc.set_meta(&meta("water"));
+    let water = water_doc.create_prim();
+    water_doc.set_attr(water, &ColliderAttr::Sphere(0.5));
+    water_doc.set_attr(water, &attr);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut q = world.query_filtered::<&CollisionLayers, With<Collider>>();
+    let mut masks = q
+        .iter(world)
+        .map(|layers| layers.memberships.0)
+        .collect::<Vec<_>>();
+    masks.sort_unstable();
+    assert_eq!(
+        masks,
+        [
+            unavi_physics::layers::DEFAULT.0,
+            attr.memberships.expect("set")
+        ],
+        "the second document's bit means another name, so it is dropped"
+    );
+    assert!(logs_contain("clash with another loaded document"));
+}
+
+/// The engine's masks and the names a document resolves against are kept in
+/// two crates; a drift between them would put every agent on a custom layer.
+#[test]
+fn test_builtin_layers_match_the_engine() {
+    let engine = [
+        unavi_physics::layers::DEFAULT,
+        unavi_physics::layers::AGENT,
+        unavi_physics::layers::TOOL,
+    ];
+    for (name, mask) in BUILTIN.iter().zip(engine) {
+        assert_eq!(layers::bit(&[], name), Some(mask.0), "{name}");
+    }
+}
+
 #[traced_test]
 #[rstest]
 fn test_collider_invalid_sphere(mut ctx: TestContext) {
@@ -44,7 +44,12 @@ use hsd::{
         },
     },
     id::PrimId,
-    state::SceneState,
+    key,
+    meta::DocMeta,
+    state::{
+        SceneState,
+        entry::Entry,
+    },
 };
 use iroh_blobs::{
     api::blobs::Blobs,
@@ -84,6 +89,7 @@ impl Default for TestContext {
             TransformPlugin,
             bevy_hsd::HsdPlugin,
         ))
+        .init_asset::<AudioSource>()
         .init_asset::<Image>()
         .init_asset::<Mesh>()
         .init_asset::<StandardMaterial>()
@@ -119,6 +125,7 @@ impl TestContext {
             unavi_physics::PhysicsPlugin,
             bevy_hsd::HsdPlugin,
         ))
+        .init_asset::<AudioSource>()
         .init_asset::<Image>()
         .init_asset::<Mesh>()
         .init_asset::<StandardMaterial>()
@@ -154,6 +161,7 @@ impl TestContext {
             WdsPlugin,
             bevy_hsd::HsdPlugin,
         ))
+        .init_asset::<AudioSource>()
         .init_asset::<Image>()
         .init_asset::<Mesh>()
         .init_asset::<StandardMaterial>()
@@ -206,6 +214,14 @@ impl TestContext {
         self.with_state(|state| state.create_prim(None))
     }
 
+    pub fn set_meta(&self, meta: &DocMeta) {
+        self.with_state(|state| {
+            state
+                .apply(&Entry::bytes(key::META, meta.encode().expect("encode"), 0))
+                .expect("apply meta");
+        });
+    }
+
     pub fn create_child(&self, parent: PrimId) -> PrimId {
         self.with_state(|state| state.create_prim(Some(parent)))
     }
@@ -266,6 +282,18 @@ impl TestDocument {
         self.with_state(|state| state.create_prim(None))
     }
 
+    pub fn set_meta(&self, meta: &DocMeta) {
+        self.with_state(|state| {
+            state
+                .apply(&Entry::bytes(key::META, meta.encode().expect("encode"), 0))
+                .expect("apply meta");
+        });
+    }
+
+    pub fn set_attr<A: Attribute>(&self, prim: PrimId, value: &A) {
+        self.with_state(|state| state.set_attribute(prim, value).expect("set attribute"));
+    }
+
     pub fn set_slot(&self, prim: PrimId, slot: &str, bytes: Vec<u8>) {
         self.with_state(|state| state.set_slot(prim, slot, bytes).expect("set slot"));
     }
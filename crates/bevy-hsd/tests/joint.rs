@@ -0,0 +1,134 @@
+use avian3d::prelude::{
+    FixedJoint,
+    RevoluteJoint,
+};
+use bevy::prelude::*;
+use bevy_hsd::attributes::joint::{
+    JointOf,
+    JointPending,
+};
+use hsd::attributes::joint::{
+    self,
+    JointAttr,
+    JointKind,
+    JointLimits,
+};
+use rstest::rstest;
+use tracing_test::traced_test;
+
+use crate::common::*;
+
+mod common;
+
+fn hinge() -> JointAttr {
+    JointAttr {
+        kind: JointKind::Revolute,
+        anchor: Some([-0.5, 0.0, 0.0]),
+        limits: Some(JointLimits { min: 0.0, max: 1.5 }),
+        ..Default::default()
+    }
+}
+
+fn joints(ctx: &mut TestContext) -> usize {
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&JointOf>();
+    query.iter(world).count()
+}
+
+#[traced_test]
+#[rstest]
+fn test_joint_lifecycle(mut ctx: TestContext) {
+    let frame = ctx.create_prim();
+    let door = ctx.create_prim();
+    ctx.set_attr(door, &hinge());
+    ctx.set_relationship(door, joint::BODY, frame);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<(&JointOf, &RevoluteJoint)>();
+    assert_eq!(query.iter(world).count(), 1);
+
+    ctx.remove_attr::<JointAttr>(door);
+    ctx.app.update();
+    assert_eq!(
+        joints(&mut ctx),
+        0,
+        "clearing the attribute takes the joint"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_kind_change_replaces_the_joint(mut ctx: TestContext) {
+    let frame = ctx.create_prim();
+    let door = ctx.create_prim();
+    ctx.set_attr(door, &hinge());
+    ctx.set_relationship(door, joint::BODY, frame);
+    ctx.app.update();
+
+    ctx.set_attr(
+        door,
+        &JointAttr {
+            kind: JointKind::Fixed,
+            ..Default::default()
+        },
+    );
+    ctx.app.update();
+
+    assert_eq!(joints(&mut ctx), 1);
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&FixedJoint>();
+    assert_eq!(query.iter(world).count(), 1);
+}
+
+#[traced_test]
+#[rstest]
+fn test_a_joint_builds_once_its_body_is_named(mut ctx: TestContext) {
+    let door = ctx.create_prim();
+    ctx.set_attr(door, &hinge());
+    ctx.app.update();
+    assert_eq!(joints(&mut ctx), 0, "a joint naming no body holds nothing");
+
+    let frame = ctx.create_prim();
+    ctx.set_relationship(door, joint::BODY, frame);
+    ctx.app.update();
+    ctx.app.update();
+
+    assert_eq!(joints(&mut ctx), 1);
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&JointPending>();
+    assert_eq!(query.iter(world).count(), 0);
+}
+
+#[traced_test]
+#[rstest]
+fn test_non_finite_parameters_never_reach_the_solver(mut ctx: TestContext) {
+    let frame = ctx.create_prim();
+    for bad in [
+        JointAttr {
+            anchor: Some([f32::NAN, 0.0, 0.0]),
+            ..hinge()
+        },
+        JointAttr {
+            axis: Some([0.0; 3]),
+            ..hinge()
+        },
+        JointAttr {
+            limits: Some(JointLimits {
+                min: 1.0,
+                max: -1.0,
+            }),
+            ..hinge()
+        },
+    ] {
+        let door = ctx.create_prim();
+        ctx.set_attr(door, &bad);
+        ctx.set_relationship(door, joint::BODY, frame);
+    }
+    ctx.app.update();
+
+    assert_eq!(joints(&mut ctx), 0);
+    assert!(logs_contain("anchor must be finite"));
+    assert!(logs_contain("axis must be finite and non-zero"));
+    assert!(logs_contain("limits are inverted"));
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_hsd::attributes::light::LightData;
+use hsd::attributes::light::{
+    LightAttr,
+    LightKind,
+};
+use rstest::rstest;
+use tracing_test::traced_test;
+use unavi_quota::limits::MAX_SHADOW_LIGHTS;
+
+use crate::common::*;
+
+mod common;
+
+fn lamp(kind: LightKind, shadows: bool) -> LightAttr {
+    LightAttr {
+        kind,
+        shadows: Some(shadows),
+        ..Default::default()
+    }
+}
+
+#[traced_test]
+#[rstest]
+fn test_light_lifecycle(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &lamp(LightKind::Point, false));
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&PointLight>();
+    assert_eq!(query.query(world).into_iter().count(), 1);
+
+    ctx.remove_attr::<LightAttr>(root);
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<(Option<&PointLight>, Option<&LightData>)>();
+    let remaining = query
+        .query(world)
+        .into_iter()
+        .filter(|(light, data)| light.is_some() || data.is_some())
+        .count();
+    assert_eq!(
+        remaining, 0,
+        "clearing the attribute takes the light with it"
+    );
+}
+
+#[traced_test]
+#[rstest]
+fn test_light_kind_change_replaces_the_component(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(root, &lamp(LightKind::Point, false));
+    ctx.app.update();
+
+    ctx.set_attr(
+        root,
+        &LightAttr {
+            outer_angle: Some(0.5),
+            ..lamp(LightKind::Spot, false)
+        },
+    );
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut points = world.query::<&PointLight>();
+    assert_eq!(points.query(world).into_iter().count(), 0);
+    let mut spots = world.query::<&SpotLight>();
+    let found = spots.query(world).into_iter().collect::<Vec<_>>();
+    assert_eq!(found.len(), 1);
+    assert!((found[0].outer_angle - 0.5).abs() < f32::EPSILON);
+}
+
+#[traced_test]
+#[rstest]
+fn test_nonfinite_settings_fall_back(mut ctx: TestContext) {
+    let root = ctx.create_prim();
+    ctx.set_attr(
+        root,
+        &LightAttr {
+            intensity: Some(f64::NAN),
+            range: Some(f64::INFINITY),
+            ..lamp(LightKind::Point, false)
+        },
+    );
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&PointLight>();
+    let light = query.query(world).into_iter().next().expect("light");
+    assert!(light.intensity.is_finite());
+    assert!(light.range.is_finite());
+}
+
+#[traced_test]
+#[rstest]
+fn test_shadow_casting_lights_are_capped_per_document(mut ctx: TestContext) {
+    for _ in 0..=MAX_SHADOW_LIGHTS {
+        let prim = ctx.create_prim();
+        ctx.set_attr(prim, &lamp(LightKind::Point, true));
+    }
+    let other = ctx.spawn_document();
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&PointLight>();
+    let lights = query.query(world).into_iter().collect::<Vec<_>>();
+    assert_eq!(
+        lights.len(),
+        MAX_SHADOW_LIGHTS + 1,
+        "every light still shines"
+    );
+    assert_eq!(
+        lights.iter().filter(|light| light.shadows_enabled).count(),
+        MAX_SHADOW_LIGHTS
+    );
+
+    let prim = other.create_prim();
+    other.set_attr(prim, &lamp(LightKind::Point, true));
+    ctx.app.update();
+
+    let world = ctx.app.world_mut();
+    let mut query = world.query::<&PointLight>();
+    assert_eq!(
+        query
+            .query(world)
+            .into_iter()
+            .filter(|light| light.shadows_enabled)
+            .count(),
+        MAX_SHADOW_LIGHTS + 1,
+        "another document has a budget of its own"
+    );
+}
@@ -19,7 +19,9 @@ use unavi_util::async_task::spawn_async_task;
 use crate::{
     LocalBlobs,
     LocalDownloader,
+    Mirrors,
     SyncTargets,
+    blob::health,
 };
 
 const MB: u64 = 1024 * 1024;
@@ -85,6 +87,7 @@ pub(crate) fn on_get_blob(
     blobs: Query<&LocalBlobs>,
     downloaders: Query<&LocalDownloader>,
     targets: Query<&SyncTargets>,
+    mirrors: Query<&Mirrors>,
 ) {
     let Ok(blobs) = blobs.single().map(|x| x.0.clone()) else {
         warn!("Unable to get blob: no LocalBlobs");
@@ -92,10 +95,17 @@ pub(crate) fn on_get_blob(
     };
 
     let downloader = downloaders.single().ok().map(|x| x.0.clone());
-    let providers = targets
+    // Sync targets ahead of their mirrors, which serve the same content
+    // read-only; health ranking reorders them once one starts failing.
+    let mut providers = targets
         .iter()
         .flat_map(|x| x.0.iter().map(|actor| actor.host().id))
         .collect::<Vec<_>>();
+    for id in mirrors.iter().flat_map(|x| x.0.iter().copied()) {
+        if !providers.contains(&id) {
+            providers.push(id);
+        }
+    }
 
     let event = req.event_mut();
     let hash = event.hash;
@@ -188,15 +198,35 @@ async fn get_blob(
     read_bounded(hash, blobs).await
 }
 
+/// Tries one provider at a time, healthiest first, recording how each does so
+/// the next fetch starts from one that answers.
 async fn pull(
     downloader: &Downloader,
     hash: Hash,
     providers: &[EndpointId],
 ) -> Result<(), BlobError> {
-    downloader
-        .download(iroh_blobs::Hash::from(hash), providers.to_vec())
-        .await
-        .map_err(|err| BlobError::Io(anyhow::Error::from(err)))
+    let mut last_err = None;
+
+    for provider in health::rank(providers) {
+        match downloader
+            .download(iroh_blobs::Hash::from(hash), vec![provider])
+            .await
+        {
+            Ok(()) => {
+                health::record_success(&provider);
+                return Ok(());
+            }
+            Err(err) => {
+                debug!(%hash, %provider, ?err, "provider failed, failing over");
+                health::record_failure(provider);
+                last_err = Some(anyhow::Error::from(err));
+            }
+        }
+    }
+
+    Err(BlobError::Io(
+        last_err.unwrap_or_else(|| anyhow::anyhow!("no providers")),
+    ))
 }
 
 async fn watch_until_complete(hash: Hash, blobs: &Blobs) -> Result<(), BlobError> {
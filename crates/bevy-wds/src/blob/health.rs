@@ -0,0 +1,110 @@
+//! Per-provider health, so a fetch tries the providers that have been
+//! answering first and fails over to the rest.
+//!
+//! Shared across every fetch in the process: a sync target that just failed
+//! one blob is tried last for the next, rather than timing out again.
+
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::Duration,
+};
+
+use iroh::EndpointId;
+use n0_future::time::Instant;
+use parking_lot::Mutex;
+
+/// A provider's failures stop counting against it once this long has passed
+/// since the last, so one that recovers is back at the front without needing
+/// a success first.
+const FAILURE_MEMORY: Duration = Duration::from_mins(5);
+
+static HEALTH: LazyLock<Mutex<ProviderHealth>> = LazyLock::new(Mutex::default);
+
+#[derive(Default)]
+struct ProviderHealth(HashMap<EndpointId, Failures>);
+
+struct Failures {
+    count: u32,
+    last:  Instant,
+}
+
+impl ProviderHealth {
+    fn failures(&self, id: &EndpointId, now: Instant) -> u32 {
+        self.0
+            .get(id)
+            .filter(|f| now.duration_since(f.last) < FAILURE_MEMORY)
+            .map_or(0, |f| f.count)
+    }
+
+    fn rank(&self, providers: &[EndpointId], now: Instant) -> Vec<EndpointId> {
+        let mut ranked = providers.to_vec();
+        // Stable, so equally healthy providers keep the caller's order.
+        ranked.sort_by_key(|id| self.failures(id, now));
+        ranked
+    }
+
+    fn succeeded(&mut self, id: &EndpointId) {
+        self.0.remove(id);
+    }
+
+    fn failed(&mut self, id: EndpointId, now: Instant) {
+        let count = self.failures(&id, now).saturating_add(1);
+        self.0.insert(id, Failures { count, last: now });
+    }
+}
+
+/// `providers` in the order to try them: fewest recent failures first.
+pub fn rank(providers: &[EndpointId]) -> Vec<EndpointId> {
+    HEALTH.lock().rank(providers, Instant::now())
+}
+
+pub fn record_success(id: &EndpointId) {
+    HEALTH.lock().succeeded(id);
+}
+
+pub fn record_failure(id: EndpointId) {
+    HEALTH.lock().failed(id, Instant::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use iroh::SecretKey;
+
+    use super::*;
+
+    fn id(seed: u8) -> EndpointId {
+        SecretKey::from_bytes(&[seed; 32]).public()
+    }
+
+    #[test]
+    fn failing_providers_are_tried_last() {
+        let (a, b, c) = (id(1), id(2), id(3));
+        let now = Instant::now();
+        let mut health = ProviderHealth::default();
+
+        health.failed(a, now);
+        health.failed(a, now);
+        health.failed(b, now);
+        assert_eq!(health.rank(&[a, b, c], now), [c, b, a]);
+
+        health.succeeded(&a);
+        assert_eq!(health.rank(&[a, b, c], now), [a, c, b]);
+    }
+
+    #[test]
+    fn old_failures_are_forgotten() {
+        let (a, b) = (id(1), id(2));
+        let now = Instant::now();
+        let mut health = ProviderHealth::default();
+
+        health.failed(a, now);
+        assert_eq!(health.rank(&[a, b], now + FAILURE_MEMORY), [a, b]);
+        health.failed(a, now + FAILURE_MEMORY);
+        assert_eq!(
+            health.failures(&a, now + FAILURE_MEMORY),
+            1,
+            "a failure after the window starts a new count"
+        );
+    }
+}
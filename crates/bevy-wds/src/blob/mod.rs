@@ -1,3 +1,4 @@
 pub mod deps;
 pub mod get;
+pub mod health;
 pub mod request;
@@ -1,6 +1,7 @@
 use std::sync::RwLock;
 
 use bevy::prelude::*;
+use iroh::EndpointId;
 use iroh_blobs::api::{
     blobs::Blobs,
     downloader::Downloader,
@@ -118,8 +119,13 @@ pub struct LocalDocs(pub Docs);
 pub struct LocalGossip(pub Gossip);
 
 #[derive(Component)]
-#[require(SyncTargets)]
+#[require(SyncTargets, Mirrors)]
 pub struct LocalActor(pub Actor);
 
 #[derive(Component, Default)]
 pub struct SyncTargets(pub Vec<Actor>);
+
+/// Endpoints re-hosting the sync targets' docs read-only, tried for content
+/// when a sync target fails.
+#[derive(Component, Default)]
+pub struct Mirrors(pub Vec<EndpointId>);
@@ -24,8 +24,17 @@ use hsd::{
     attributes::{
         Attribute,
         collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
         gravity_scale::GravityScaleAttr,
         image::ImageAttr,
+        joint::{
+            JointAttr,
+            JointKind,
+        },
+        light::{
+            LightAttr,
+            LightKind,
+        },
         material::{
             self,
             ColorVec,
@@ -40,16 +49,19 @@ use hsd::{
             validate::validate,
         },
         name::NameAttr,
+        relay::RelayAttr,
         rigid_body::{
             RigidBodyAttr,
             RigidBodyKind,
         },
+        sensor::SensorAttr,
         slots,
         spawn::SpawnAttr,
         xform::XformAttr,
     },
     id::PrimId,
     key,
+    layers,
     meta::DocMeta,
     package::Package,
     property::{
@@ -61,6 +73,8 @@ use hsd::{
         SourceAttributes,
         SourceCollider,
         SourceImage,
+        SourceJoint,
+        SourceLight,
         SourceMaterial,
         SourceMaterialGraph,
         SourcePrim,
@@ -69,11 +83,14 @@ use hsd::{
     },
 };
 
-use crate::wasm::build_wasm_for_crate;
+use crate::{
+    import::import_gltf,
+    wasm::build_wasm_for_crate,
+};
 
 /// Identifies a source file in a way that is the same on every machine, so a
 /// prim id derived from it does not depend on where the repo is checked out.
-fn source_identity(input_abs: &Path) -> Result<String> {
+pub(crate) fn source_identity(input_abs: &Path) -> Result<String> {
     let dir = input_abs.parent().context("input has no parent dir")?;
     let crate_name = dir
         .file_name()
@@ -130,16 +147,20 @@ pub fn compile_file<S: std::hash::BuildHasher>(
     let mut names = HashMap::new();
     index_names(&doc.0, &source, &mut Vec::new(), &mut names)?;
 
+    let mut meta = DocMeta::default();
+    index_layers(&doc.0, &mut meta.layers)?;
+
     let mut compiler = Compiler {
         source,
         input_dir,
         names,
+        layers: meta.layers.clone(),
         entries: BTreeMap::new(),
         built,
     };
     compiler.entries.insert(
         key::META.to_owned(),
-        DocMeta::default().encode().context("encoding meta")?,
+        meta.encode().context("encoding meta")?,
     );
     compiler.emit(&doc.0, Parent::Root, &mut Vec::new())?;
 
@@ -166,10 +187,24 @@ fn index_names(
     Ok(())
 }
 
+/// Gives the document a layer for every custom name its colliders use, in
+/// the order they first appear, so a rebuild assigns the same bits.
+fn index_layers(prims: &[SourcePrim], custom: &mut Vec<String>) -> Result<()> {
+    for prim in prims {
+        let attrs = &prim.attributes;
+        for name in attrs.layers.iter().chain(&attrs.collides_with).flatten() {
+            layers::declare(custom, name)?;
+        }
+        index_layers(&prim.children, custom)?;
+    }
+    Ok(())
+}
+
 struct Compiler<'a, S: std::hash::BuildHasher> {
     source:    String,
     input_dir: PathBuf,
     names:     HashMap<String, PrimId>,
+    layers:    Vec<String>,
     entries:   BTreeMap<String, Vec<u8>>,
     built:     &'a mut HashMap<String, Vec<u8>, S>,
 }
@@ -209,15 +244,47 @@ impl<S: std::hash::BuildHasher> Compiler<'_, S> {
                 },
             )?;
         }
+        if let Some(relay) = &attrs.relay {
+            let endpoint = blake3::Hash::from_hex(relay)
+                .with_context(|| format!("relay {relay:?} is not a hex endpoint id"))?;
+            self.set_attribute(
+                id,
+                &RelayAttr {
+                    endpoint: *endpoint.as_bytes(),
+                },
+            )?;
+        }
         if let Some(xform) = &attrs.xform {
             self.set_attribute(id, &compile_xform(xform)?)?;
         }
         if let Some(collider) = &attrs.collider {
             self.set_attribute(id, &compile_collider(collider))?;
+            if attrs.sensor == Some(true) {
+                self.set_attribute(id, &SensorAttr)?;
+            }
+            if attrs.layers.is_some() || attrs.collides_with.is_some() {
+                self.set_attribute(
+                    id,
+                    &CollisionLayersAttr {
+                        memberships: self.layer_mask(attrs.layers.as_deref())?,
+                        filters:     self.layer_mask(attrs.collides_with.as_deref())?,
+                    },
+                )?;
+            }
+        } else if attrs.sensor.is_some() {
+            bail!("sensor is set without a collider");
+        } else if attrs.layers.is_some() || attrs.collides_with.is_some() {
+            bail!("collision layers are set without a collider");
         }
         if let Some(rigid_body) = &attrs.rigid_body {
             self.set_attribute(id, &compile_rigid_body(rigid_body)?)?;
         }
+        if let Some(joint) = &attrs.joint {
+            self.set_attribute(id, &compile_joint(joint)?)?;
+        }
+        if let Some(light) = &attrs.light {
+            self.set_attribute(id, &compile_light(light)?)?;
+        }
         if let Some(image) = &attrs.image {
             self.emit_image(id, image)?;
         }
@@ -227,6 +294,11 @@ impl<S: std::hash::BuildHasher> Compiler<'_, S> {
         if let Some(graph) = &attrs.material_graph {
             self.emit_material_graph(id, graph)?;
         }
+        if let Some(rel) = &attrs.mesh {
+            let path = self.input_dir.join(rel);
+            import_gltf(&path, id, &mut self.entries)
+                .with_context(|| format!("importing mesh {}", path.display()))?;
+        }
         if let Some(rel) = &attrs.script {
             let bytes = self.compile_script(rel)?;
             self.set_slot(id, slots::SCRIPT, bytes);
@@ -331,6 +403,13 @@ impl<S: std::hash::BuildHasher> Compiler<'_, S> {
 
     /// A dangling reference in hand-written source is an author bug, so it
     /// fails the build rather than passing through as a literal.
+    fn layer_mask(&self, names: Option<&[String]>) -> Result<Option<u32>> {
+        names
+            .map(|names| layers::mask(&self.layers, names))
+            .transpose()
+            .map_err(Into::into)
+    }
+
     fn resolve(&self, name: &str) -> Result<PrimId> {
         self.names
             .get(name)
@@ -381,6 +460,52 @@ fn compile_rigid_body(rb: &SourceRigidBody) -> Result<RigidBodyAttr> {
     })
 }
 
+fn compile_joint(joint: &SourceJoint) -> Result<JointAttr> {
+    let kind = match joint.kind.as_str() {
+        "Fixed" => JointKind::Fixed,
+        "Revolute" => JointKind::Revolute,
+        "Prismatic" => JointKind::Prismatic,
+        "Spherical" => JointKind::Spherical,
+        "Distance" => JointKind::Distance,
+        other => bail!(
+            "unknown joint kind {other:?}; expected Fixed, Revolute, Prismatic, Spherical, or Distance"
+        ),
+    };
+    let vec3 = |name: &str, v: Option<&Vec<f32>>| -> Result<Option<[f32; 3]>> {
+        v.map(|v| {
+            <[f32; 3]>::try_from(v.as_slice())
+                .with_context(|| format!("{name} must have 3 components, got {}", v.len()))
+        })
+        .transpose()
+    };
+    Ok(JointAttr {
+        kind,
+        anchor: vec3("anchor", joint.anchor.as_ref())?,
+        other_anchor: vec3("other_anchor", joint.other_anchor.as_ref())?,
+        axis: vec3("axis", joint.axis.as_ref())?,
+        limits: joint.limits,
+        motor: joint.motor,
+    })
+}
+
+fn compile_light(light: &SourceLight) -> Result<LightAttr> {
+    let kind = match light.kind.as_str() {
+        "Directional" => LightKind::Directional,
+        "Point" => LightKind::Point,
+        "Spot" => LightKind::Spot,
+        other => bail!("unknown light kind {other:?}; expected Directional, Point, or Spot"),
+    };
+    Ok(LightAttr {
+        kind,
+        color: light.color.clone().map(ColorVec),
+        intensity: light.intensity,
+        range: light.range,
+        inner_angle: light.inner_angle,
+        outer_angle: light.outer_angle,
+        shadows: light.shadows,
+    })
+}
+
 fn compile_xform(x: &SourceXform) -> Result<XformAttr> {
     let mut out = XformAttr::default();
     if let Some(t) = &x.translation {
@@ -16,15 +16,22 @@ use anyhow::{
 use hsd::{
     attributes::{
         Attribute,
+        animation::AnimationAttr,
+        audio::AudioAttr,
         collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
         gravity_scale::GravityScaleAttr,
         image::ImageAttr,
+        joint::JointAttr,
+        light::LightAttr,
         material::MaterialAttr,
         material_graph::overrides::GraphOverridesAttr,
         mesh::MeshAttr,
         name::NameAttr,
         portal::PortalAttr,
+        relay::RelayAttr,
         rigid_body::RigidBodyAttr,
+        sensor::SensorAttr,
         slots,
         spawn::SpawnAttr,
         xform::XformAttr,
@@ -126,15 +133,22 @@ fn render(name: &str, payload: &[u8]) -> String {
     }
 
     match name {
+        AnimationAttr::KEY => show::<AnimationAttr>(payload),
+        AudioAttr::KEY => show::<AudioAttr>(payload),
         ColliderAttr::KEY => show::<ColliderAttr>(payload),
+        CollisionLayersAttr::KEY => show::<CollisionLayersAttr>(payload),
         GravityScaleAttr::KEY => show::<GravityScaleAttr>(payload),
         ImageAttr::KEY => show::<ImageAttr>(payload),
+        JointAttr::KEY => show::<JointAttr>(payload),
+        LightAttr::KEY => show::<LightAttr>(payload),
         MaterialAttr::KEY => show::<MaterialAttr>(payload),
         GraphOverridesAttr::KEY => show::<GraphOverridesAttr>(payload),
         MeshAttr::KEY => show::<MeshAttr>(payload),
         NameAttr::KEY => show::<NameAttr>(payload),
         PortalAttr::KEY => show::<PortalAttr>(payload),
+        RelayAttr::KEY => show::<RelayAttr>(payload),
         RigidBodyAttr::KEY => show::<RigidBodyAttr>(payload),
+        SensorAttr::KEY => show::<SensorAttr>(payload),
         SpawnAttr::KEY => show::<SpawnAttr>(payload),
         XformAttr::KEY => show::<XformAttr>(payload),
         _ => format!("<unknown, {} bytes>", payload.len()),
@@ -0,0 +1,783 @@
+//! Prims → GLB.
+//!
+//! The inverse of [`crate::import`]. Every prim that draws or has children is
+//! a node; a prim that only holds a material, an image or a clip becomes that
+//! glTF material, texture or animation instead, so a round trip does not grow
+//! the tree by a node per material. Whatever glTF cannot express stays under
+//! `extras.hsd` (see [`crate::extras`]), including where such a prim sat.
+
+use std::{
+    borrow::Cow,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    path::Path,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use gltf::binary::{
+    Glb,
+    Header,
+};
+use hsd::{
+    attributes::{
+        Attribute,
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationChannel,
+            AnimationPath,
+            Interpolation,
+        },
+        image::{
+            AddressMode,
+            FilterMode,
+            ImageAttr,
+        },
+        material::{
+            self,
+            ColorVec,
+            MaterialAttr,
+        },
+        mesh::{
+            MeshAttr,
+            Topology,
+        },
+        name::NameAttr,
+        slots,
+        xform::XformAttr,
+    },
+    id::PrimId,
+    package::Package,
+    property::Property,
+    state::{
+        SceneState,
+        entry::Entry,
+    },
+};
+use serde_json::{
+    Map,
+    Value,
+    json,
+};
+
+use crate::extras::{
+    HsdExtras,
+    to_hex,
+};
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// Reads a compiled `.hsdz` and exports it.
+pub fn export_file(input: &Path) -> Result<Vec<u8>> {
+    let bytes = std::fs::read(input).with_context(|| format!("reading {}", input.display()))?;
+    let package =
+        Package::decode(&bytes).with_context(|| format!("decoding {}", input.display()))?;
+
+    let mut state = SceneState::new();
+    for (key, value) in package.entries {
+        state
+            .apply(&Entry::new(key, value, 0))
+            .with_context(|| format!("applying {}", input.display()))?;
+    }
+    export_state(&state)
+}
+
+/// Exports the realized prims of a document as GLB bytes.
+pub fn export_state(state: &SceneState) -> Result<Vec<u8>> {
+    let mut exporter = Exporter {
+        state,
+        bin: Vec::new(),
+        views: Vec::new(),
+        accessors: Vec::new(),
+        images: Vec::new(),
+        samplers: Vec::new(),
+        textures: Vec::new(),
+        materials: Vec::new(),
+        meshes: Vec::new(),
+        nodes: Vec::new(),
+        animations: Vec::new(),
+        texture_index: HashMap::new(),
+        material_index: HashMap::new(),
+        node_index: HashMap::new(),
+        animation_index: HashMap::new(),
+        clips: HashMap::new(),
+        mapped: HashMap::new(),
+    };
+
+    let mut prims = state.prims().collect::<Vec<_>>();
+    prims.sort_unstable();
+
+    for prim in &prims {
+        exporter.texture(*prim);
+    }
+    for prim in &prims {
+        exporter.material(*prim);
+    }
+    for prim in &prims {
+        exporter.clip(*prim);
+    }
+    exporter.strand_clips();
+    let roots = exporter.nodes(state.roots());
+    for prim in &prims {
+        exporter.animation(*prim);
+    }
+    for prim in &prims {
+        exporter.extras(*prim);
+    }
+
+    exporter.finish(&roots)
+}
+
+struct Exporter<'a> {
+    state:           &'a SceneState,
+    bin:             Vec<u8>,
+    views:           Vec<Value>,
+    accessors:       Vec<Value>,
+    images:          Vec<Value>,
+    samplers:        Vec<Value>,
+    textures:        Vec<Value>,
+    materials:       Vec<Value>,
+    meshes:          Vec<Value>,
+    nodes:           Vec<Value>,
+    animations:      Vec<Value>,
+    texture_index:   HashMap<PrimId, usize>,
+    material_index:  HashMap<PrimId, usize>,
+    node_index:      HashMap<PrimId, usize>,
+    animation_index: HashMap<PrimId, usize>,
+    /// Prims exported as animations rather than nodes → each channel's target.
+    clips:           HashMap<PrimId, Vec<PrimId>>,
+    /// Properties each prim's glTF elements already express.
+    mapped:          HashMap<PrimId, HashSet<String>>,
+}
+
+impl Exporter<'_> {
+    fn attribute<A: Attribute>(&self, prim: PrimId) -> Option<A> {
+        self.state.attribute::<A>(prim).and_then(Result::ok)
+    }
+
+    fn slot(&self, prim: PrimId, name: &str) -> Option<&[u8]> {
+        self.state.get(prim)?.slot(name)
+    }
+
+    fn map(&mut self, prim: PrimId, name: &str) {
+        self.mapped.entry(prim).or_default().insert(name.to_owned());
+    }
+
+    fn is_node(&self, prim: PrimId) -> bool {
+        self.attribute::<MeshAttr>(prim).is_some()
+            || !self.state.children(prim).is_empty()
+            || !(self.material_index.contains_key(&prim)
+                || self.texture_index.contains_key(&prim)
+                || self.clips.contains_key(&prim))
+    }
+
+    fn named(&mut self, prim: PrimId, out: &mut Map<String, Value>) {
+        if let Some(name) = self.attribute::<NameAttr>(prim) {
+            out.insert("name".to_owned(), json!(name.0));
+            self.map(prim, NameAttr::KEY);
+        }
+    }
+
+    fn push_view(&mut self, bytes: &[u8]) -> usize {
+        self.bin.resize(self.bin.len().next_multiple_of(4), 0);
+        self.views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.bin.len(),
+            "byteLength": bytes.len(),
+        }));
+        self.bin.extend_from_slice(bytes);
+        self.views.len() - 1
+    }
+
+    fn texture(&mut self, prim: PrimId) {
+        let Some(attr) = self.attribute::<ImageAttr>(prim) else {
+            return;
+        };
+        let Some(data) = self.slot(prim, slots::IMAGE_DATA).map(<[u8]>::to_vec) else {
+            return;
+        };
+
+        let view = self.push_view(&data);
+        let mut image = Map::new();
+        image.insert("bufferView".to_owned(), json!(view));
+        image.insert("mimeType".to_owned(), json!(mime_type(&data)));
+        self.images.push(Value::Object(image));
+
+        let mut sampler = Map::new();
+        if let Some(mode) = attr.address_mode_u {
+            sampler.insert("wrapS".to_owned(), json!(wrap(mode)));
+        }
+        if let Some(mode) = attr.address_mode_v {
+            sampler.insert("wrapT".to_owned(), json!(wrap(mode)));
+        }
+        if let Some(filter) = attr.mag_filter {
+            sampler.insert("magFilter".to_owned(), json!(mag_filter(filter)));
+        }
+        if let Some(filter) = attr.min_filter {
+            sampler.insert(
+                "minFilter".to_owned(),
+                json!(min_filter(filter, attr.mipmap_filter)),
+            );
+        }
+        self.samplers.push(Value::Object(sampler));
+
+        let mut texture = Map::new();
+        self.named(prim, &mut texture);
+        texture.insert("sampler".to_owned(), json!(self.samplers.len() - 1));
+        texture.insert("source".to_owned(), json!(self.images.len() - 1));
+        self.textures.push(Value::Object(texture));
+
+        // The attribute stays unmapped: glTF has no field for `srgb` or a
+        // third address mode, so it also rides in extras.
+        self.texture_index.insert(prim, self.textures.len() - 1);
+        self.map(prim, slots::IMAGE_DATA);
+    }
+
+    fn material(&mut self, prim: PrimId) {
+        let Some(attr) = self.attribute::<MaterialAttr>(prim) else {
+            return;
+        };
+
+        let texture = |exporter: &mut Self, name: &str| {
+            let target = exporter.state.relationship(prim, name)?;
+            let index = *exporter.texture_index.get(&target)?;
+            exporter.map(prim, name);
+            Some(json!({ "index": index }))
+        };
+
+        let mut pbr = Map::new();
+        if let Some(color) = attr.base_color.as_ref().and_then(rgba) {
+            pbr.insert("baseColorFactor".to_owned(), json!(color));
+        }
+        if let Some(metallic) = attr.metallic {
+            pbr.insert("metallicFactor".to_owned(), json!(metallic));
+        }
+        if let Some(roughness) = attr.roughness {
+            pbr.insert("roughnessFactor".to_owned(), json!(roughness));
+        }
+        if let Some(info) = texture(self, material::BASE_COLOR_TEXTURE) {
+            pbr.insert("baseColorTexture".to_owned(), info);
+        }
+        if let Some(info) = texture(self, material::METALLIC_ROUGHNESS_TEXTURE) {
+            pbr.insert("metallicRoughnessTexture".to_owned(), info);
+        }
+
+        let mut out = Map::new();
+        self.named(prim, &mut out);
+        out.insert("pbrMetallicRoughness".to_owned(), Value::Object(pbr));
+        if let Some(emissive) = attr.emissive.as_ref().and_then(rgba) {
+            out.insert("emissiveFactor".to_owned(), json!(emissive[..3]));
+        }
+        for (name, key) in [
+            (material::EMISSIVE_TEXTURE, "emissiveTexture"),
+            (material::NORMAL_TEXTURE, "normalTexture"),
+            (material::OCCLUSION_TEXTURE, "occlusionTexture"),
+        ] {
+            if let Some(info) = texture(self, name) {
+                out.insert(key.to_owned(), info);
+            }
+        }
+
+        // Mirrors the client's reading of an unset mode: translucent base
+        // colour blends.
+        let translucent = attr
+            .base_color
+            .as_ref()
+            .and_then(rgba)
+            .is_some_and(|c| c[3] < 1.0);
+        let alpha_mode = match attr.alpha_mode.as_deref() {
+            Some("Opaque") => "OPAQUE",
+            Some("Mask") => "MASK",
+            Some(_) => "BLEND",
+            None if translucent => "BLEND",
+            None => "OPAQUE",
+        };
+        out.insert("alphaMode".to_owned(), json!(alpha_mode));
+        if let Some(cutoff) = attr.alpha_cutoff {
+            out.insert("alphaCutoff".to_owned(), json!(cutoff));
+        }
+        if let Some(double_sided) = attr.double_sided {
+            out.insert("doubleSided".to_owned(), json!(double_sided));
+        }
+        self.materials.push(Value::Object(out));
+
+        self.material_index.insert(prim, self.materials.len() - 1);
+        self.map(prim, MaterialAttr::KEY);
+    }
+
+    fn nodes(&mut self, prims: Vec<PrimId>) -> Vec<usize> {
+        prims
+            .into_iter()
+            .filter(|prim| self.is_node(*prim))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|prim| self.node(prim))
+            .collect()
+    }
+
+    fn node(&mut self, prim: PrimId) -> usize {
+        let mut out = Map::new();
+        self.named(prim, &mut out);
+
+        if let Some(xform) = self.attribute::<XformAttr>(prim) {
+            out.insert("translation".to_owned(), json!(xform.translation));
+            out.insert("rotation".to_owned(), json!(xform.rotation));
+            out.insert("scale".to_owned(), json!(xform.scale));
+            self.map(prim, XformAttr::KEY);
+        }
+
+        if let Some(mesh) = self.mesh(prim) {
+            out.insert("mesh".to_owned(), json!(mesh));
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Value::Null);
+        self.node_index.insert(prim, index);
+
+        let children = self.nodes(self.state.children(prim));
+        if !children.is_empty() {
+            out.insert("children".to_owned(), json!(children));
+        }
+
+        self.nodes[index] = Value::Object(out);
+        index
+    }
+
+    /// A mesh without a usable `POSITION` is not a glTF mesh; its attribute
+    /// and buffers fall through to extras instead.
+    fn mesh(&mut self, prim: PrimId) -> Option<usize> {
+        let attr = self.attribute::<MeshAttr>(prim)?;
+        let positions = self.slot(prim, &slots::mesh_attribute("POSITION"))?;
+        let floats = f32s(positions)?;
+        if floats.is_empty() || !floats.len().is_multiple_of(3) {
+            return None;
+        }
+        let count = floats.len() / 3;
+
+        let mut attributes = Map::new();
+        let buffers = self
+            .state
+            .get(prim)?
+            .slots()
+            .filter_map(|(name, bytes)| {
+                let name = slots::mesh_attribute_name(name)?;
+                let (semantic, kind, width) = semantic(name)?;
+                Some((name.to_owned(), semantic, kind, width, bytes.to_vec()))
+            })
+            .collect::<Vec<_>>();
+        for (name, semantic, kind, width, bytes) in buffers {
+            let Some(floats) = f32s(&bytes) else {
+                continue;
+            };
+            if floats.len() != count * width {
+                continue;
+            }
+
+            let view = self.push_view(&bytes);
+            let mut accessor = json!({
+                "bufferView": view,
+                "componentType": FLOAT,
+                "count": count,
+                "type": kind,
+            });
+            if semantic == "POSITION" {
+                let (min, max) = bounds(&floats);
+                accessor["min"] = json!(min);
+                accessor["max"] = json!(max);
+            }
+            self.accessors.push(accessor);
+            attributes.insert(semantic.to_owned(), json!(self.accessors.len() - 1));
+            self.map(prim, &slots::mesh_attribute(&name));
+        }
+
+        let mut primitive = Map::new();
+        primitive.insert("attributes".to_owned(), Value::Object(attributes));
+        primitive.insert("mode".to_owned(), json!(mode(attr.topology)));
+
+        if let Some(bytes) = self
+            .slot(prim, slots::MESH_INDICES)
+            .map(<[u8]>::to_vec)
+            .filter(|bytes| bytes.len().is_multiple_of(4))
+        {
+            let view = self.push_view(&bytes);
+            self.accessors.push(json!({
+                "bufferView": view,
+                "componentType": UNSIGNED_INT,
+                "count": bytes.len() / 4,
+                "type": "SCALAR",
+            }));
+            primitive.insert("indices".to_owned(), json!(self.accessors.len() - 1));
+            self.map(prim, slots::MESH_INDICES);
+        }
+
+        let bound = if self.material_index.contains_key(&prim) {
+            Some(prim)
+        } else {
+            self.state
+                .relationship(prim, material::BINDING)
+                .filter(|target| self.material_index.contains_key(target))
+        };
+        if let Some(target) = bound {
+            primitive.insert("material".to_owned(), json!(self.material_index[&target]));
+            if target != prim {
+                self.map(prim, material::BINDING);
+            }
+        }
+
+        self.meshes
+            .push(json!({ "primitives": [Value::Object(primitive)] }));
+        self.map(prim, MeshAttr::KEY);
+        Some(self.meshes.len() - 1)
+    }
+
+    /// Marks a prim that only holds a clip to become a glTF animation, if glTF
+    /// can say every channel: each must move another prim, along a path glTF
+    /// has, with keyframes that fit it.
+    fn clip(&mut self, prim: PrimId) {
+        let Some(attr) = self.attribute::<AnimationAttr>(prim) else {
+            return;
+        };
+        if attr.channels.is_empty()
+            || self.attribute::<MeshAttr>(prim).is_some()
+            || !self.state.children(prim).is_empty()
+            || self.material_index.contains_key(&prim)
+            || self.texture_index.contains_key(&prim)
+        {
+            return;
+        }
+
+        let targets = attr
+            .channels
+            .iter()
+            .enumerate()
+            .map(|(index, channel)| {
+                self.keyframes(prim, index, *channel)?;
+                self.state
+                    .relationship(prim, &animation::target(index))
+                    .filter(|target| *target != prim)
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(targets) = targets {
+            self.clips.insert(prim, targets);
+        }
+    }
+
+    /// Drops the clips moving a prim that will not be a node, which no glTF
+    /// channel can target. They stay nodes with everything in extras.
+    fn strand_clips(&mut self) {
+        let stranded = self
+            .clips
+            .iter()
+            .filter(|(_, targets)| targets.iter().any(|target| !self.is_node(*target)))
+            .map(|(prim, _)| *prim)
+            .collect::<Vec<_>>();
+        for prim in stranded {
+            self.clips.remove(&prim);
+        }
+    }
+
+    /// A channel's keyframe times and values, checked against the shape glTF
+    /// gives its path.
+    fn keyframes(
+        &self,
+        prim: PrimId,
+        index: usize,
+        channel: AnimationChannel,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (_, _, width) = channel_path(channel.path)?;
+        let times = self.slot(prim, &slots::animation_times(index))?;
+        let values = self.slot(prim, &slots::animation_values(index))?;
+        let per_key = match channel.interpolation {
+            Interpolation::CubicSpline => 3 * width,
+            Interpolation::Step | Interpolation::Linear => width,
+        };
+        let count = f32s(times)?.len();
+        (count > 0 && f32s(values)?.len() == count * per_key)
+            .then(|| (times.to_vec(), values.to_vec()))
+    }
+
+    fn animation(&mut self, prim: PrimId) {
+        let Some(targets) = self.clips.get(&prim).cloned() else {
+            return;
+        };
+        let Some(attr) = self.attribute::<AnimationAttr>(prim) else {
+            return;
+        };
+
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for (index, (channel, target)) in attr.channels.iter().zip(targets).enumerate() {
+            let (Some((path, kind, width)), Some((times, values)), Some(node)) = (
+                channel_path(channel.path),
+                self.keyframes(prim, index, *channel),
+                self.node_index.get(&target).copied(),
+            ) else {
+                continue;
+            };
+            let count = times.len() / 4;
+            let (min, max) = f32s(&times)
+                .unwrap_or_default()
+                .into_iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), t| {
+                    (min.min(t), max.max(t))
+                });
+
+            let view = self.push_view(&times);
+            self.accessors.push(json!({
+                "bufferView": view,
+                "componentType": FLOAT,
+                "count": count,
+                "type": "SCALAR",
+                "min": [min],
+                "max": [max],
+            }));
+            let input = self.accessors.len() - 1;
+
+            let view = self.push_view(&values);
+            self.accessors.push(json!({
+                "bufferView": view,
+                "componentType": FLOAT,
+                "count": values.len() / 4 / width,
+                "type": kind,
+            }));
+            let output = self.accessors.len() - 1;
+
+            samplers.push(json!({
+                "input": input,
+                "output": output,
+                "interpolation": match channel.interpolation {
+                    Interpolation::Step => "STEP",
+                    Interpolation::Linear => "LINEAR",
+                    Interpolation::CubicSpline => "CUBICSPLINE",
+                },
+            }));
+            channels.push(json!({
+                "sampler": samplers.len() - 1,
+                "target": { "node": node, "path": path },
+            }));
+            self.map(prim, &animation::target(index));
+            self.map(prim, &slots::animation_times(index));
+            self.map(prim, &slots::animation_values(index));
+        }
+
+        let mut out = Map::new();
+        self.named(prim, &mut out);
+        out.insert("channels".to_owned(), Value::Array(channels));
+        out.insert("samplers".to_owned(), Value::Array(samplers));
+        self.animations.push(Value::Object(out));
+
+        // The attribute stays unmapped: glTF has no field for autoplay, looping
+        // or speed, so it also rides in extras.
+        self.animation_index.insert(prim, self.animations.len() - 1);
+    }
+
+    /// Attaches a prim's id and unmapped properties to its first element: the
+    /// node, else the material, the texture or the animation.
+    fn extras(&mut self, prim: PrimId) {
+        let Some(state) = self.state.get(prim) else {
+            return;
+        };
+        let mapped = self.mapped.remove(&prim).unwrap_or_default();
+
+        let mut extras = HsdExtras {
+            id: prim.to_string(),
+            ..HsdExtras::default()
+        };
+        if !self.node_index.contains_key(&prim) {
+            extras.parent = self.state.parent(prim).map(|parent| parent.to_string());
+        }
+        for (name, value) in state.properties() {
+            if mapped.contains(name.as_str()) {
+                continue;
+            }
+            match value {
+                Property::Attribute(payload) => {
+                    extras.attributes.insert(name.to_string(), to_hex(payload));
+                }
+                Property::Relationship(target) => {
+                    extras
+                        .relationships
+                        .insert(name.to_string(), target.to_string());
+                }
+            }
+        }
+        let unmapped = state
+            .slots()
+            .filter(|(name, _)| !mapped.contains(name.as_str()))
+            .map(|(name, bytes)| (name.to_string(), bytes.to_vec()))
+            .collect::<Vec<_>>();
+        for (name, bytes) in unmapped {
+            let view = self.push_view(&bytes);
+            extras.slots.insert(name, view);
+        }
+
+        let element = if let Some(index) = self.node_index.get(&prim) {
+            &mut self.nodes[*index]
+        } else if let Some(index) = self.material_index.get(&prim) {
+            &mut self.materials[*index]
+        } else if let Some(index) = self.texture_index.get(&prim) {
+            &mut self.textures[*index]
+        } else if let Some(index) = self.animation_index.get(&prim) {
+            &mut self.animations[*index]
+        } else {
+            return;
+        };
+        element["extras"] = extras.to_value();
+    }
+
+    fn finish(self, roots: &[usize]) -> Result<Vec<u8>> {
+        let mut root = Map::new();
+        root.insert(
+            "asset".to_owned(),
+            json!({ "version": "2.0", "generator": "hsd-cli" }),
+        );
+        root.insert("scene".to_owned(), json!(0));
+        root.insert("scenes".to_owned(), json!([{ "nodes": roots }]));
+        for (key, values) in [
+            ("nodes", self.nodes),
+            ("meshes", self.meshes),
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("samplers", self.samplers),
+            ("images", self.images),
+            ("animations", self.animations),
+            ("accessors", self.accessors),
+            ("bufferViews", self.views),
+        ] {
+            if !values.is_empty() {
+                root.insert(key.to_owned(), Value::Array(values));
+            }
+        }
+        if !self.bin.is_empty() {
+            root.insert(
+                "buffers".to_owned(),
+                json!([{ "byteLength": self.bin.len() }]),
+            );
+        }
+
+        let json = serde_json::to_vec(&Value::Object(root)).context("serializing glTF json")?;
+        let glb = Glb {
+            // `to_vec` writes its own header from the chunk lengths.
+            header: Header {
+                magic:   *b"glTF",
+                version: 2,
+                length:  0,
+            },
+            json:   Cow::Owned(json),
+            bin:    (!self.bin.is_empty()).then_some(Cow::Owned(self.bin)),
+        };
+        glb.to_vec().context("writing GLB")
+    }
+}
+
+fn f32s(bytes: &[u8]) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+    )
+}
+
+fn bounds(floats: &[f32]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for vertex in floats.chunks_exact(3) {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(vertex[axis]);
+            max[axis] = max[axis].max(vertex[axis]);
+        }
+    }
+    (min, max)
+}
+
+fn rgba(color: &ColorVec) -> Option<[f64; 4]> {
+    match color.0.as_slice() {
+        [r, g, b, a] => Some([*r, *g, *b, *a]),
+        [r, g, b] => Some([*r, *g, *b, 1.0]),
+        _ => None,
+    }
+}
+
+/// The glTF target path of an animation channel, its output accessor type and
+/// width. Morph weights have none: export writes no morph targets for them to
+/// drive.
+const fn channel_path(path: AnimationPath) -> Option<(&'static str, &'static str, usize)> {
+    match path {
+        AnimationPath::Translation => Some(("translation", "VEC3", 3)),
+        AnimationPath::Rotation => Some(("rotation", "VEC4", 4)),
+        AnimationPath::Scale => Some(("scale", "VEC3", 3)),
+        AnimationPath::MorphWeights => None,
+    }
+}
+
+/// The HSD mesh attribute name's glTF semantic, accessor type and width.
+fn semantic(name: &str) -> Option<(&'static str, &'static str, usize)> {
+    match name {
+        "POSITION" => Some(("POSITION", "VEC3", 3)),
+        "NORMAL" => Some(("NORMAL", "VEC3", 3)),
+        "TANGENT" => Some(("TANGENT", "VEC4", 4)),
+        "COLOR" => Some(("COLOR_0", "VEC4", 4)),
+        "UV_0" => Some(("TEXCOORD_0", "VEC2", 2)),
+        "UV_1" => Some(("TEXCOORD_1", "VEC2", 2)),
+        _ => None,
+    }
+}
+
+const fn mode(topology: Topology) -> u32 {
+    match topology {
+        Topology::PointList => 0,
+        Topology::LineList => 1,
+        Topology::LineStrip => 3,
+        Topology::TriangleList => 4,
+        Topology::TriangleStrip => 5,
+    }
+}
+
+const fn wrap(mode: AddressMode) -> u32 {
+    match mode {
+        AddressMode::ClampToEdge => 33071,
+        AddressMode::MirrorRepeat => 33648,
+        AddressMode::Repeat => 10497,
+    }
+}
+
+const fn mag_filter(filter: FilterMode) -> u32 {
+    match filter {
+        FilterMode::Nearest => 9728,
+        FilterMode::Linear => 9729,
+    }
+}
+
+const fn min_filter(filter: FilterMode, mipmap: Option<FilterMode>) -> u32 {
+    match (filter, mipmap) {
+        (FilterMode::Nearest, None) => 9728,
+        (FilterMode::Linear, None) => 9729,
+        (FilterMode::Nearest, Some(FilterMode::Nearest)) => 9984,
+        (FilterMode::Linear, Some(FilterMode::Nearest)) => 9985,
+        (FilterMode::Nearest, Some(FilterMode::Linear)) => 9986,
+        (FilterMode::Linear, Some(FilterMode::Linear)) => 9987,
+    }
+}
+
+/// glTF requires a MIME type on buffer-view images; the slot only holds bytes.
+fn mime_type(data: &[u8]) -> &'static str {
+    if data.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if data.get(8..12) == Some(b"WEBP") {
+        "image/webp"
+    } else {
+        "application/octet-stream"
+    }
+}
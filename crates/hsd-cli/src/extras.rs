@@ -0,0 +1,92 @@
+//! What a glTF round trip cannot express, carried under `extras.hsd`.
+//!
+//! glTF has no slot for a collider, a script or a portal, so export parks every
+//! property it did not map on the node, material or texture it came from, and
+//! import puts them back. The prim id rides along too, so a relationship that
+//! glTF cannot spell still names the same prim afterwards.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::value::RawValue;
+
+#[derive(Serialize, Deserialize)]
+struct Extras {
+    hsd: HsdExtras,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HsdExtras {
+    /// The prim this element was exported from.
+    pub id:            String,
+    /// The prim it sat under, for an element that is not a node and so has no
+    /// place in the node tree. Absent for a root prim.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent:        Option<String>,
+    /// Attribute payloads, hex-encoded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes:    BTreeMap<String, String>,
+    /// Relationship targets by prim id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub relationships: BTreeMap<String, String>,
+    /// Slot contents, as indices into the file's buffer views.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub slots:         BTreeMap<String, usize>,
+}
+
+impl HsdExtras {
+    /// Reads `extras.hsd`, treating extras another tool wrote as absent.
+    #[must_use]
+    pub fn read(raw: Option<&RawValue>) -> Option<Self> {
+        serde_json::from_str::<Extras>(raw?.get())
+            .ok()
+            .map(|extras| extras.hsd)
+    }
+
+    #[must_use]
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::json!({ "hsd": self })
+    }
+}
+
+#[must_use]
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+#[must_use]
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = vec![0x00, 0x7F, 0xAB, 0xFF];
+        assert_eq!(from_hex(&to_hex(&bytes)), Some(bytes));
+    }
+
+    #[test]
+    fn foreign_extras_are_absent() {
+        let raw = RawValue::from_string(r#"{"blender": 1}"#.to_owned()).expect("raw");
+        assert_eq!(HsdExtras::read(Some(&raw)), None);
+    }
+}
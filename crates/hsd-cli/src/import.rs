@@ -0,0 +1,728 @@
+//! glTF / GLB → prims.
+//!
+//! Walks the default scene and writes the same entries hand-written source
+//! would compile to: a prim per node, mesh buffers as `mesh:*` slots, one
+//! material prim per glTF material bound by `material:binding`, and one image
+//! prim per texture use, and one clip prim per animation. Skins and morph
+//! targets are not carried, so morph-weight channels are dropped.
+//!
+//! A file written by [`crate::export`] also carries `extras.hsd`, which puts
+//! back the prim ids and whatever glTF had no field for.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    ops::Range,
+    path::{
+        Component,
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+    bail,
+    ensure,
+};
+use gltf::{
+    Gltf,
+    animation::{
+        Property as GltfProperty,
+        util::ReadOutputs,
+    },
+    material::AlphaMode,
+    mesh::Mode,
+    texture::{
+        MagFilter,
+        MinFilter,
+        WrappingMode,
+    },
+};
+use hsd::{
+    attributes::{
+        Attribute,
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationChannel,
+            AnimationPath,
+            Interpolation,
+        },
+        image::{
+            AddressMode,
+            FilterMode,
+            ImageAttr,
+        },
+        material::{
+            self,
+            ColorVec,
+            MaterialAttr,
+        },
+        mesh::{
+            MeshAttr,
+            Topology,
+        },
+        name::NameAttr,
+        slots,
+        xform::XformAttr,
+    },
+    id::PrimId,
+    key,
+    meta::DocMeta,
+    package::Package,
+    property::{
+        Parent,
+        Property,
+    },
+};
+
+use crate::{
+    compile::source_identity,
+    extras::{
+        HsdExtras,
+        from_hex,
+    },
+};
+
+/// Imports a glTF or GLB file as a package of its own, rooted at one prim
+/// named after the file.
+pub fn import_file(input: &Path) -> Result<Package> {
+    let input_abs =
+        std::fs::canonicalize(input).with_context(|| format!("resolving {}", input.display()))?;
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"hsd:gltf-root");
+    hasher.update(source_identity(&input_abs)?.as_bytes());
+    let root = PrimId::from_digest(hasher.finalize().as_bytes());
+
+    let mut entries = BTreeMap::new();
+    entries.insert(
+        key::META.to_owned(),
+        DocMeta::default().encode().context("encoding meta")?,
+    );
+    entries.insert(key::parent(root), Parent::Root.encode());
+    if let Some(stem) = input_abs.file_stem() {
+        let name = NameAttr(stem.to_string_lossy().into_owned());
+        entries.insert(
+            key::prop(root, NameAttr::KEY),
+            Property::Attribute(name.encode().context("encoding name attribute")?).encode(),
+        );
+    }
+
+    import(&input_abs, root, true, &mut entries)?;
+    Ok(Package::new(entries))
+}
+
+/// Imports a glTF or GLB file's default scene beneath `root`, which must
+/// already exist in `entries`.
+///
+/// Ids derive from `root` and the glTF indices, so the same file imported
+/// under the same prim compiles to the same entries.
+pub fn import_gltf(
+    path: &Path,
+    root: PrimId,
+    entries: &mut BTreeMap<String, Vec<u8>>,
+) -> Result<()> {
+    import(path, root, false, entries)
+}
+
+fn import(
+    path: &Path,
+    root: PrimId,
+    keep_ids: bool,
+    entries: &mut BTreeMap<String, Vec<u8>>,
+) -> Result<()> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let gltf = Gltf::from_slice(&bytes).with_context(|| format!("parsing {}", path.display()))?;
+    let dir = path.parent().context("input has no parent dir")?;
+    let buffers = load_buffers(&gltf, dir)?;
+
+    let scene = gltf
+        .default_scene()
+        .or_else(|| gltf.scenes().next())
+        .with_context(|| format!("{} has no scene", path.display()))?;
+
+    let mut importer = Importer {
+        root,
+        dir,
+        keep_ids,
+        buffers,
+        views: gltf
+            .views()
+            .map(|view| {
+                (
+                    view.buffer().index(),
+                    view.offset()..view.offset() + view.length(),
+                )
+            })
+            .collect(),
+        entries,
+        nodes: HashMap::new(),
+        materials: HashMap::new(),
+        textures: HashMap::new(),
+    };
+    for node in scene.nodes() {
+        importer.emit_node(&node, Parent::Prim(root))?;
+    }
+    for anim in gltf.animations() {
+        importer.animation(&anim)?;
+    }
+
+    // An exported document's materials and images are not always drawn by a
+    // node, but they are still its prims.
+    for mat in gltf.materials() {
+        if let Some(index) = mat.index() {
+            importer.material(index, &mat)?;
+        }
+    }
+    for texture in gltf.textures() {
+        let tagged = HsdExtras::read(texture.extras().as_deref()).is_some();
+        let seen = importer.textures.keys().any(|(i, _)| *i == texture.index());
+        if tagged && !seen {
+            importer.texture(&texture, true)?;
+        }
+    }
+    Ok(())
+}
+
+fn load_buffers(gltf: &Gltf, dir: &Path) -> Result<Vec<Vec<u8>>> {
+    gltf.buffers()
+        .map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Bin => gltf.blob.clone().context("GLB has no binary chunk"),
+            gltf::buffer::Source::Uri(uri) => read_uri(dir, uri),
+        })
+        .collect()
+}
+
+fn read_uri(dir: &Path, uri: &str) -> Result<Vec<u8>> {
+    ensure!(
+        !uri.starts_with("data:"),
+        "embedded data URIs are not supported; export as .glb or with separate files"
+    );
+    let path = resolve_uri(dir, uri)?;
+    std::fs::read(&path).with_context(|| format!("reading {}", path.display()))
+}
+
+/// A file's relative URI, percent-decoded as glTF requires, as a path under
+/// its directory. An imported file is often someone else's, so one naming a
+/// path outside that directory is refused rather than read.
+fn resolve_uri(dir: &Path, uri: &str) -> Result<PathBuf> {
+    let decoded = percent_decode(uri).with_context(|| format!("malformed URI {uri:?}"))?;
+    let relative = Path::new(&decoded);
+    ensure!(
+        relative
+            .components()
+            .all(|part| matches!(part, Component::Normal(_) | Component::CurDir)),
+        "URI {uri:?} points outside the file's directory"
+    );
+    Ok(dir.join(relative))
+}
+
+/// `None` for a stray `%` or an escape that is not UTF-8.
+fn percent_decode(uri: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut rest = uri.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = tail.get(2..)?;
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+struct Importer<'a> {
+    root:      PrimId,
+    dir:       &'a Path,
+    /// Whether ids from `extras.hsd` are used as they are. A standalone
+    /// import keeps them; one nested under a `mesh` source derives from them,
+    /// so two prims importing the same file do not collide.
+    keep_ids:  bool,
+    buffers:   Vec<Vec<u8>>,
+    /// glTF buffer view → (buffer index, byte range).
+    views:     Vec<(usize, Range<usize>)>,
+    entries:   &'a mut BTreeMap<String, Vec<u8>>,
+    /// glTF node index → its prim.
+    nodes:     HashMap<usize, PrimId>,
+    /// glTF material index → its prim.
+    materials: HashMap<usize, PrimId>,
+    /// (glTF texture index, sRGB) → its image prim. One texture sampled both
+    /// as colour and as data becomes two prims, since `srgb` is per image.
+    textures:  HashMap<(usize, bool), PrimId>,
+}
+
+impl Importer<'_> {
+    fn id(&self, parts: &[&str]) -> PrimId {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"hsd:gltf");
+        hasher.update(&self.root.0);
+        for part in parts {
+            hasher.update(b"/");
+            hasher.update(part.as_bytes());
+        }
+        PrimId::from_digest(hasher.finalize().as_bytes())
+    }
+
+    fn restored(&self, original: PrimId) -> PrimId {
+        if self.keep_ids {
+            original
+        } else {
+            self.id(&["prim", &original.to_string()])
+        }
+    }
+
+    /// The id an element's extras name, else one derived from `fallback`.
+    fn element_id(&self, extras: Option<&HsdExtras>, fallback: &[&str]) -> PrimId {
+        extras
+            .and_then(|extras| extras.id.parse().ok())
+            .map_or_else(|| self.id(fallback), |id| self.restored(id))
+    }
+
+    /// Where an element that is not a node goes: back under the prim its
+    /// extras name, else under the root.
+    fn placement(&self, extras: Option<&HsdExtras>) -> Result<Parent> {
+        let Some(parent) = extras.and_then(|extras| extras.parent.as_deref()) else {
+            return Ok(Parent::Prim(self.root));
+        };
+        let parent = parent.parse().context("parent in extras names no prim")?;
+        Ok(Parent::Prim(self.restored(parent)))
+    }
+
+    /// Writes back the properties and slots export could not map.
+    fn restore(&mut self, id: PrimId, extras: Option<&HsdExtras>) -> Result<()> {
+        let Some(extras) = extras else {
+            return Ok(());
+        };
+        for (name, hex) in &extras.attributes {
+            let payload = from_hex(hex).with_context(|| format!("{name} in extras is not hex"))?;
+            self.set_property(id, name, Property::Attribute(payload));
+        }
+        for (name, target) in &extras.relationships {
+            let target = target
+                .parse()
+                .with_context(|| format!("{name} in extras names no prim"))?;
+            let target = self.restored(target);
+            self.set_property(id, name, Property::Relationship(target));
+        }
+        for (name, view) in &extras.slots {
+            let bytes = self.view(*view)?;
+            self.set_slot(id, name, bytes);
+        }
+        Ok(())
+    }
+
+    fn view(&self, index: usize) -> Result<Vec<u8>> {
+        let (buffer, range) = self.views.get(index).context("missing buffer view")?;
+        let bytes = self
+            .buffers
+            .get(*buffer)
+            .context("buffer view names a missing buffer")?
+            .get(range.clone())
+            .context("buffer view is out of bounds")?;
+        Ok(bytes.to_vec())
+    }
+
+    fn emit_node(&mut self, node: &gltf::Node, parent: Parent) -> Result<()> {
+        let extras = HsdExtras::read(node.extras().as_deref());
+        let id = self.element_id(extras.as_ref(), &["node", &node.index().to_string()]);
+        self.nodes.insert(node.index(), id);
+        self.entries.insert(key::parent(id), parent.encode());
+
+        if let Some(name) = node.name() {
+            self.set_attribute(id, &NameAttr(name.to_owned()))?;
+        }
+
+        let (translation, rotation, scale) = node.transform().decomposed();
+        self.set_attribute(
+            id,
+            &XformAttr {
+                rotation,
+                scale,
+                translation,
+            },
+        )?;
+
+        if let Some(mesh) = node.mesh() {
+            let primitives = mesh.primitives().collect::<Vec<_>>();
+            if let [primitive] = primitives.as_slice() {
+                self.emit_primitive(id, primitive)?;
+            } else {
+                // A prim holds one mesh and one binding, so each primitive of
+                // a multi-material mesh is its own child.
+                for primitive in &primitives {
+                    let child = self.id(&[
+                        "node",
+                        &node.index().to_string(),
+                        "primitive",
+                        &primitive.index().to_string(),
+                    ]);
+                    self.entries
+                        .insert(key::parent(child), Parent::Prim(id).encode());
+                    if let Some(name) = mesh.name() {
+                        self.set_attribute(
+                            child,
+                            &NameAttr(format!("{name}.{}", primitive.index())),
+                        )?;
+                    }
+                    self.emit_primitive(child, primitive)?;
+                }
+            }
+        }
+
+        for child in node.children() {
+            self.emit_node(&child, Parent::Prim(id))?;
+        }
+        self.restore(id, extras.as_ref())
+    }
+
+    fn emit_primitive(&mut self, id: PrimId, primitive: &gltf::Primitive) -> Result<()> {
+        let topology = match primitive.mode() {
+            Mode::Points => Topology::PointList,
+            Mode::Lines => Topology::LineList,
+            Mode::LineStrip => Topology::LineStrip,
+            Mode::Triangles => Topology::TriangleList,
+            Mode::TriangleStrip => Topology::TriangleStrip,
+            other => bail!("primitive mode {other:?} has no HSD topology"),
+        };
+        self.set_attribute(id, &MeshAttr { topology })?;
+
+        let buffers = &self.buffers;
+        let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+
+        let positions = reader
+            .read_positions()
+            .context("mesh primitive has no POSITION")?
+            .flatten()
+            .collect::<Vec<_>>();
+        let normals = reader
+            .read_normals()
+            .map(|iter| iter.flatten().collect::<Vec<_>>());
+        let tangents = reader
+            .read_tangents()
+            .map(|iter| iter.flatten().collect::<Vec<_>>());
+        let colors = reader
+            .read_colors(0)
+            .map(|iter| iter.into_rgba_f32().flatten().collect::<Vec<_>>());
+        let uv_0 = reader
+            .read_tex_coords(0)
+            .map(|iter| iter.into_f32().flatten().collect::<Vec<_>>());
+        let uv_1 = reader
+            .read_tex_coords(1)
+            .map(|iter| iter.into_f32().flatten().collect::<Vec<_>>());
+        let indices = reader
+            .read_indices()
+            .map(|iter| iter.into_u32().collect::<Vec<_>>());
+
+        self.set_slot(id, &slots::mesh_attribute("POSITION"), le_bytes(&positions));
+        for (name, values) in [
+            ("NORMAL", normals),
+            ("TANGENT", tangents),
+            ("COLOR", colors),
+            ("UV_0", uv_0),
+            ("UV_1", uv_1),
+        ] {
+            if let Some(values) = values {
+                self.set_slot(id, &slots::mesh_attribute(name), le_bytes(&values));
+            }
+        }
+        if let Some(indices) = indices {
+            let bytes = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+            self.set_slot(id, slots::MESH_INDICES, bytes);
+        }
+
+        let mat = primitive.material();
+        if let Some(index) = mat.index() {
+            let target = self.material(index, &mat)?;
+            // A prim holding its own material needs no binding to itself.
+            if target != id {
+                self.set_property(id, material::BINDING, Property::Relationship(target));
+            }
+        }
+        Ok(())
+    }
+
+    fn material(&mut self, index: usize, mat: &gltf::Material) -> Result<PrimId> {
+        if let Some(id) = self.materials.get(&index) {
+            return Ok(*id);
+        }
+
+        let extras = HsdExtras::read(mat.extras().as_deref());
+        let id = self.element_id(extras.as_ref(), &["material", &index.to_string()]);
+        self.materials.insert(index, id);
+        // A node sharing the prim places it instead.
+        let parent = self.placement(extras.as_ref())?.encode();
+        self.entries.entry(key::parent(id)).or_insert(parent);
+
+        if let Some(name) = mat.name() {
+            self.set_attribute(id, &NameAttr(name.to_owned()))?;
+        }
+
+        let pbr = mat.pbr_metallic_roughness();
+        let alpha_mode = match mat.alpha_mode() {
+            AlphaMode::Opaque => "Opaque",
+            AlphaMode::Mask => "Mask",
+            AlphaMode::Blend => "Blend",
+        };
+        self.set_attribute(
+            id,
+            &MaterialAttr {
+                alpha_cutoff: mat.alpha_cutoff().map(f64::from),
+                alpha_mode:   Some(alpha_mode.to_owned()),
+                base_color:   Some(color_vec(&pbr.base_color_factor())),
+                double_sided: Some(mat.double_sided()),
+                emissive:     Some(color_vec(&mat.emissive_factor())),
+                metallic:     Some(f64::from(pbr.metallic_factor())),
+                roughness:    Some(f64::from(pbr.roughness_factor())),
+            },
+        )?;
+
+        for (name, texture, srgb) in [
+            (
+                material::BASE_COLOR_TEXTURE,
+                pbr.base_color_texture().map(|info| info.texture()),
+                true,
+            ),
+            (
+                material::EMISSIVE_TEXTURE,
+                mat.emissive_texture().map(|info| info.texture()),
+                true,
+            ),
+            (
+                material::METALLIC_ROUGHNESS_TEXTURE,
+                pbr.metallic_roughness_texture().map(|info| info.texture()),
+                false,
+            ),
+            (
+                material::NORMAL_TEXTURE,
+                mat.normal_texture().map(|info| info.texture()),
+                false,
+            ),
+            (
+                material::OCCLUSION_TEXTURE,
+                mat.occlusion_texture().map(|info| info.texture()),
+                false,
+            ),
+        ] {
+            if let Some(texture) = texture {
+                let target = self.texture(&texture, srgb)?;
+                self.set_property(id, name, Property::Relationship(target));
+            }
+        }
+        self.restore(id, extras.as_ref())?;
+        Ok(id)
+    }
+
+    fn texture(&mut self, texture: &gltf::Texture, srgb: bool) -> Result<PrimId> {
+        let index = texture.index();
+        if let Some(id) = self.textures.get(&(index, srgb)) {
+            return Ok(*id);
+        }
+
+        let extras = HsdExtras::read(texture.extras().as_deref());
+        let id = self.element_id(
+            extras.as_ref(),
+            &[
+                "texture",
+                &index.to_string(),
+                if srgb { "srgb" } else { "linear" },
+            ],
+        );
+        self.textures.insert((index, srgb), id);
+        let parent = self.placement(extras.as_ref())?.encode();
+        self.entries.entry(key::parent(id)).or_insert(parent);
+
+        let image = texture.source();
+        if let Some(name) = texture.name().or_else(|| image.name()) {
+            self.set_attribute(id, &NameAttr(name.to_owned()))?;
+        }
+
+        let bytes = match image.source() {
+            gltf::image::Source::View { view, .. } => self.view(view.index())?,
+            gltf::image::Source::Uri { uri, .. } => read_uri(self.dir, uri)?,
+        };
+        self.set_slot(id, slots::IMAGE_DATA, bytes);
+
+        let sampler = texture.sampler();
+        let (min_filter, mipmap_filter) = match sampler.min_filter() {
+            Some(MinFilter::Nearest) => (Some(FilterMode::Nearest), None),
+            Some(MinFilter::Linear) => (Some(FilterMode::Linear), None),
+            Some(MinFilter::NearestMipmapNearest) => {
+                (Some(FilterMode::Nearest), Some(FilterMode::Nearest))
+            }
+            Some(MinFilter::LinearMipmapNearest) => {
+                (Some(FilterMode::Linear), Some(FilterMode::Nearest))
+            }
+            Some(MinFilter::NearestMipmapLinear) => {
+                (Some(FilterMode::Nearest), Some(FilterMode::Linear))
+            }
+            Some(MinFilter::LinearMipmapLinear) => {
+                (Some(FilterMode::Linear), Some(FilterMode::Linear))
+            }
+            None => (None, None),
+        };
+        self.set_attribute(
+            id,
+            &ImageAttr {
+                address_mode_u: Some(address_mode(sampler.wrap_s())),
+                address_mode_v: Some(address_mode(sampler.wrap_t())),
+                address_mode_w: None,
+                mag_filter: sampler.mag_filter().map(|filter| match filter {
+                    MagFilter::Nearest => FilterMode::Nearest,
+                    MagFilter::Linear => FilterMode::Linear,
+                }),
+                min_filter,
+                mipmap_filter,
+                srgb: Some(srgb),
+            },
+        )?;
+        self.restore(id, extras.as_ref())?;
+        Ok(id)
+    }
+
+    /// A clip prim under the root, or where its extras place it. A channel
+    /// whose node is outside the imported scene, or that weights morph
+    /// targets, has nothing to move and is dropped.
+    fn animation(&mut self, anim: &gltf::Animation) -> Result<()> {
+        let extras = HsdExtras::read(anim.extras().as_deref());
+        let id = self.element_id(extras.as_ref(), &["animation", &anim.index().to_string()]);
+        let parent = self.placement(extras.as_ref())?;
+        self.entries.insert(key::parent(id), parent.encode());
+
+        if let Some(name) = anim.name() {
+            self.set_attribute(id, &NameAttr(name.to_owned()))?;
+        }
+
+        let mut channels = Vec::new();
+        for channel in anim.channels() {
+            let Some(target) = self.nodes.get(&channel.target().node().index()).copied() else {
+                continue;
+            };
+            let path = match channel.target().property() {
+                GltfProperty::Translation => AnimationPath::Translation,
+                GltfProperty::Rotation => AnimationPath::Rotation,
+                GltfProperty::Scale => AnimationPath::Scale,
+                GltfProperty::MorphTargetWeights => continue,
+            };
+
+            let buffers = &self.buffers;
+            let reader = channel.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+            let times = reader
+                .read_inputs()
+                .context("animation sampler has no input")?
+                .collect::<Vec<_>>();
+            let values = match reader
+                .read_outputs()
+                .context("animation sampler has no output")?
+            {
+                ReadOutputs::Translations(iter) | ReadOutputs::Scales(iter) => {
+                    iter.flatten().collect::<Vec<_>>()
+                }
+                ReadOutputs::Rotations(iter) => iter.into_f32().flatten().collect(),
+                ReadOutputs::MorphTargetWeights(_) => continue,
+            };
+
+            let index = channels.len();
+            channels.push(AnimationChannel {
+                path,
+                interpolation: match channel.sampler().interpolation() {
+                    gltf::animation::Interpolation::Step => Interpolation::Step,
+                    gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                    gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+                },
+            });
+            self.set_property(
+                id,
+                &animation::target(index),
+                Property::Relationship(target),
+            );
+            self.set_slot(id, &slots::animation_times(index), le_bytes(&times));
+            self.set_slot(id, &slots::animation_values(index), le_bytes(&values));
+        }
+
+        self.set_attribute(
+            id,
+            &AnimationAttr {
+                channels,
+                ..Default::default()
+            },
+        )?;
+        self.restore(id, extras.as_ref())
+    }
+
+    fn set_attribute<A: Attribute>(&mut self, id: PrimId, value: &A) -> Result<()> {
+        let payload = value
+            .encode()
+            .with_context(|| format!("encoding {} attribute", A::KEY))?;
+        self.set_property(id, A::KEY, Property::Attribute(payload));
+        Ok(())
+    }
+
+    fn set_property(&mut self, id: PrimId, name: &str, value: Property) {
+        self.entries.insert(key::prop(id, name), value.encode());
+    }
+
+    fn set_slot(&mut self, id: PrimId, slot: &str, bytes: Vec<u8>) {
+        self.entries.insert(key::prop(id, slot), bytes);
+    }
+}
+
+fn le_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn color_vec(values: &[f32]) -> ColorVec {
+    ColorVec(values.iter().copied().map(f64::from).collect())
+}
+
+const fn address_mode(mode: WrappingMode) -> AddressMode {
+    match mode {
+        WrappingMode::ClampToEdge => AddressMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => AddressMode::MirrorRepeat,
+        WrappingMode::Repeat => AddressMode::Repeat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uris_are_percent_decoded() {
+        let dir = Path::new("assets");
+        assert_eq!(
+            resolve_uri(dir, "my%20mesh.bin").expect("resolve"),
+            dir.join("my mesh.bin")
+        );
+        assert_eq!(
+            resolve_uri(dir, "./textures/caf%C3%A9.png").expect("resolve"),
+            dir.join("textures/café.png")
+        );
+        assert!(resolve_uri(dir, "half%2").is_err());
+        assert!(resolve_uri(dir, "bad%zzescape").is_err());
+    }
+
+    #[test]
+    fn uris_may_not_leave_the_directory() {
+        let dir = Path::new("assets");
+        for uri in [
+            "../secret.bin",
+            "textures/../../secret.bin",
+            "%2E%2E/secret.bin",
+            "..%2Fsecret.bin",
+            "/etc/passwd",
+            "%2Fetc%2Fpasswd",
+        ] {
+            assert!(resolve_uri(dir, uri).is_err(), "{uri} was allowed");
+        }
+    }
+}
@@ -1,4 +1,7 @@
 pub mod cargo;
 pub mod compile;
 pub mod dump;
+pub mod export;
+pub mod extras;
+pub mod import;
 pub mod wasm;
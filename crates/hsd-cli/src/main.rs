@@ -15,6 +15,8 @@ use hsd::source::Source;
 use hsd_cli::{
     compile,
     dump,
+    export,
+    import,
 };
 
 #[derive(Parser, Debug)]
@@ -22,7 +24,9 @@ use hsd_cli::{
 enum HsdCli {
     Build(Build),
     Dump(Dump),
+    Export(Export),
     Format(Format),
+    Import(Import),
 }
 
 /// Compile a `.hsda` source into a single `.hsdz` package.
@@ -45,6 +49,19 @@ struct Dump {
     input: PathBuf,
 }
 
+/// Export a compiled `.hsdz` as a GLB.
+///
+/// What glTF cannot express is kept in `extras`, so `import` restores it.
+#[derive(Args, Debug)]
+struct Export {
+    /// Input `.hsdz` file path
+    #[arg(short, long)]
+    input:   PathBuf,
+    /// Output directory for the `.glb`
+    #[arg(short, long)]
+    out_dir: PathBuf,
+}
+
 /// Pretty-print a `.hsda` source file in place.
 #[derive(Args, Debug)]
 struct Format {
@@ -52,6 +69,17 @@ struct Format {
     input: PathBuf,
 }
 
+/// Import a glTF or GLB scene into a single `.hsdz` package.
+#[derive(Args, Debug)]
+struct Import {
+    /// Input `.gltf` or `.glb` file path
+    #[arg(short, long)]
+    input:   PathBuf,
+    /// Output directory for the compiled `.hsdz`
+    #[arg(short, long)]
+    out_dir: PathBuf,
+}
+
 fn main() -> Result<()> {
     match HsdCli::parse() {
         HsdCli::Build(Build { input, out_dir }) => {
@@ -73,6 +101,17 @@ fn main() -> Result<()> {
         HsdCli::Dump(Dump { input }) => {
             println!("{}", dump::dump_file(&input)?);
         }
+        HsdCli::Export(Export { input, out_dir }) => {
+            std::fs::create_dir_all(&out_dir)
+                .with_context(|| format!("creating {}", out_dir.display()))?;
+
+            let glb = export::export_file(&input)?;
+
+            let name = input.file_stem().context("input has no file stem")?;
+            let out = out_dir.join(name).with_extension("glb");
+            std::fs::write(&out, glb).with_context(|| format!("writing {}", out.display()))?;
+            println!("wrote {}", out.display());
+        }
         HsdCli::Format(Format { input }) => {
             let src = std::fs::read_to_string(&input)
                 .with_context(|| format!("reading {}", input.display()))?;
@@ -81,6 +120,22 @@ fn main() -> Result<()> {
             std::fs::write(&input, doc.to_ron()?)
                 .with_context(|| format!("writing {}", input.display()))?;
         }
+        HsdCli::Import(Import { input, out_dir }) => {
+            std::fs::create_dir_all(&out_dir)
+                .with_context(|| format!("creating {}", out_dir.display()))?;
+
+            let package = import::import_file(&input)?;
+
+            let name = input
+                .file_stem()
+                .context("input has no file stem")?
+                .to_string_lossy()
+                .replace('-', "_");
+            let out = out_dir.join(format!("{name}.{}", hsd::package::EXTENSION));
+            std::fs::write(&out, package.encode()?)
+                .with_context(|| format!("writing {}", out.display()))?;
+            println!("wrote {}", out.display());
+        }
     }
     Ok(())
 }
@@ -8,11 +8,26 @@ use std::{
 
 use hsd::{
     attributes::{
+        collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
+        joint::{
+            self,
+            JointAttr,
+            JointKind,
+            JointLimits,
+        },
+        light::{
+            LightAttr,
+            LightKind,
+        },
         material,
         name::NameAttr,
+        relay::RelayAttr,
+        sensor::SensorAttr,
         slots,
     },
     id::PrimId,
+    layers,
     package::Package,
     state::{
         SceneState,
@@ -136,3 +151,145 @@ fn a_duplicate_name_fails_the_build() {
     let err = compile(&write_source("duplicate", &source)).expect_err("should fail");
     assert!(err.to_string().contains("duplicate"), "{err}");
 }
+
+#[test]
+fn a_light_compiles_to_its_attribute() {
+    let source =
+        r#"[(attributes: (name: "lamp", light: (kind: "Spot", intensity: 400.0, shadows: true)))]"#;
+    let state = realize(&compile(&write_source("light", source)).expect("compile"));
+
+    let light = state
+        .attribute::<LightAttr>(prim_named(&state, "lamp"))
+        .expect("light")
+        .expect("decode");
+    assert_eq!(light.kind, LightKind::Spot);
+    assert_eq!(light.intensity, Some(400.0));
+    assert_eq!(light.shadows, Some(true));
+}
+
+#[test]
+fn a_joint_compiles_to_its_attribute_and_body() {
+    let source = r#"[
+        (attributes: (name: "frame", rigid_body: (kind: "Static"))),
+        (
+            attributes: (
+                name: "door",
+                rigid_body: (kind: "Dynamic"),
+                joint: (kind: "Revolute", anchor: [-0.5, 0.0, 0.0], limits: (min: 0.0, max: 1.5)),
+            ),
+            relationships: { "joint:body": "frame" },
+        ),
+    ]"#;
+    let state = realize(&compile(&write_source("joint", source)).expect("compile"));
+    let door = prim_named(&state, "door");
+
+    let joint = state
+        .attribute::<JointAttr>(door)
+        .expect("joint")
+        .expect("decode");
+    assert_eq!(joint.kind, JointKind::Revolute);
+    assert_eq!(joint.anchor, Some([-0.5, 0.0, 0.0]));
+    assert_eq!(joint.limits, Some(JointLimits { min: 0.0, max: 1.5 }));
+    assert_eq!(
+        state.relationship(door, joint::BODY),
+        Some(prim_named(&state, "frame"))
+    );
+}
+
+#[test]
+fn a_joint_anchor_of_the_wrong_length_fails_the_build() {
+    let source = r#"[(attributes: (joint: (kind: "Fixed", anchor: [1.0, 2.0])))]"#;
+    let err = compile(&write_source("joint-anchor", source)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("anchor"), "{err:#}");
+}
+
+#[test]
+fn a_sensor_compiles_onto_its_collider() {
+    let source =
+        r#"[(attributes: (name: "goal", collider: Cuboid(x: 4.0, y: 2.0, z: 1.0), sensor: true))]"#;
+    let state = realize(&compile(&write_source("sensor", source)).expect("compile"));
+
+    let goal = prim_named(&state, "goal");
+    assert_eq!(
+        state.attribute::<ColliderAttr>(goal).and_then(Result::ok),
+        Some(ColliderAttr::Cuboid {
+            x: 4.0,
+            y: 2.0,
+            z: 1.0,
+        })
+    );
+    assert_eq!(
+        state.attribute::<SensorAttr>(goal).and_then(Result::ok),
+        Some(SensorAttr)
+    );
+}
+
+#[test]
+fn a_sensor_without_a_collider_fails_the_build() {
+    let source = r"[(attributes: (sensor: true))]";
+    let err = compile(&write_source("sensor-alone", source)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("sensor"), "{err:#}");
+}
+
+#[test]
+fn collision_layers_compile_to_masks_named_by_the_document() {
+    let source = r#"[
+        (attributes: (name: "ghost", collider: Sphere(0.5), layers: ["ghost"], collides_with: [])),
+        (attributes: (name: "blocker", collider: Sphere(0.5), collides_with: ["agent", "ghost"])),
+    ]"#;
+    let state = realize(&compile(&write_source("layers", source)).expect("compile"));
+    let layers_of = |name| {
+        state
+            .attribute::<CollisionLayersAttr>(prim_named(&state, name))
+            .expect("layers")
+            .expect("decode")
+    };
+
+    assert_eq!(state.meta().layers, ["ghost"]);
+    let ghost = 1 << layers::FIRST_CUSTOM;
+    assert_eq!(
+        layers_of("ghost"),
+        CollisionLayersAttr {
+            memberships: Some(ghost),
+            filters:     Some(0),
+        }
+    );
+    assert_eq!(
+        layers_of("blocker"),
+        CollisionLayersAttr {
+            memberships: None,
+            filters:     Some((1 << 1) | ghost),
+        }
+    );
+}
+
+#[test]
+fn collision_layers_without_a_collider_fail_the_build() {
+    let source = r#"[(attributes: (layers: ["ghost"]))]"#;
+    let err = compile(&write_source("layers-alone", source)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("collision layers"), "{err:#}");
+}
+
+#[test]
+fn an_unknown_light_kind_fails_the_build() {
+    let source = r#"[(attributes: (light: (kind: "Area")))]"#;
+    let err = compile(&write_source("light-kind", source)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("Area"), "{err:#}");
+}
+
+#[test]
+fn a_relay_compiles_from_its_hex_endpoint_id() {
+    let hex = "ab".repeat(32);
+    let source = format!(r#"[(attributes: (name: "space", relay: "{hex}"))]"#);
+    let state = realize(&compile(&write_source("relay", &source)).expect("compile"));
+
+    let relay = state
+        .attribute::<RelayAttr>(prim_named(&state, "space"))
+        .expect("relay")
+        .expect("decode");
+    assert_eq!(relay.endpoint, [0xAB; 32]);
+
+    let source = r#"[(attributes: (relay: "not hex"))]"#;
+    let err = compile(&write_source("relay-bad", source)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("relay"), "{err:#}");
+}
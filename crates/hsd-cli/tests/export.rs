@@ -0,0 +1,335 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use hsd::{
+    attributes::{
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationChannel,
+            AnimationPath,
+            Interpolation,
+        },
+        collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
+        image::ImageAttr,
+        material::{
+            self,
+            ColorVec,
+            MaterialAttr,
+        },
+        mesh::{
+            MeshAttr,
+            Topology,
+        },
+        name::NameAttr,
+        sensor::SensorAttr,
+        slots,
+        xform::XformAttr,
+    },
+    id::PrimId,
+    package::Package,
+    property::Parent,
+    state::{
+        SceneState,
+        entry::Entry,
+    },
+};
+use hsd_cli::{
+    export,
+    import,
+};
+
+const BODY: PrimId = PrimId([1; 16]);
+const TRI: PrimId = PrimId([2; 16]);
+const PAINT: PrimId = PrimId([3; 16]);
+const CHECKER: PrimId = PrimId([4; 16]);
+const BOB: PrimId = PrimId([5; 16]);
+
+const IMAGE: &[u8] = b"\x89PNG not really";
+const SCRIPT: &[u8] = b"\0asm not really";
+
+fn positions() -> Vec<u8> {
+    [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+        .iter()
+        .flatten()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+}
+
+fn indices() -> Vec<u8> {
+    [0u32, 1, 2].iter().flat_map(|i| i.to_le_bytes()).collect()
+}
+
+fn bob_times() -> Vec<u8> {
+    [0.0f32, 1.0].iter().flat_map(|t| t.to_le_bytes()).collect()
+}
+
+fn bob_values() -> Vec<u8> {
+    [[0.0f32, 0.0, 0.0], [0.0, 0.5, 0.0]]
+        .iter()
+        .flatten()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+}
+
+fn bob() -> AnimationAttr {
+    AnimationAttr {
+        channels: vec![AnimationChannel {
+            path:          AnimationPath::Translation,
+            interpolation: Interpolation::Linear,
+        }],
+        looping: Some(true),
+        ..Default::default()
+    }
+}
+
+/// A scripted body with a collider, holding a triangle bound to a textured
+/// material, the material and its image on prims of their own, and a clip
+/// bobbing the triangle.
+fn scene() -> SceneState {
+    let mut state = SceneState::new();
+    state.insert_prim(BODY, Parent::Root);
+    state.insert_prim(TRI, Parent::Prim(BODY));
+    state.insert_prim(PAINT, Parent::Prim(BODY));
+    state.insert_prim(CHECKER, Parent::Prim(BODY));
+    state.insert_prim(BOB, Parent::Prim(BODY));
+
+    state
+        .set_attribute(BODY, &NameAttr("body".to_owned()))
+        .expect("name");
+    state
+        .set_attribute(
+            BODY,
+            &XformAttr {
+                translation: [0.0, 1.0, 0.0],
+                ..Default::default()
+            },
+        )
+        .expect("xform");
+    state
+        .set_attribute(BODY, &ColliderAttr::Sphere(0.5))
+        .expect("collider");
+    state.set_attribute(BODY, &SensorAttr).expect("sensor");
+    state
+        .set_attribute(
+            BODY,
+            &CollisionLayersAttr {
+                memberships: Some(1 << 2),
+                filters:     Some(0),
+            },
+        )
+        .expect("layers");
+    state
+        .set_slot(BODY, slots::SCRIPT, SCRIPT.to_vec())
+        .expect("script");
+
+    state
+        .set_attribute(TRI, &NameAttr("tri".to_owned()))
+        .expect("name");
+    state
+        .set_attribute(
+            TRI,
+            &MeshAttr {
+                topology: Topology::TriangleList,
+            },
+        )
+        .expect("mesh");
+    state
+        .set_slot(TRI, &slots::mesh_attribute("POSITION"), positions())
+        .expect("positions");
+    state
+        .set_slot(TRI, slots::MESH_INDICES, indices())
+        .expect("indices");
+    state
+        .set_relationship(TRI, material::BINDING, PAINT)
+        .expect("binding");
+
+    state
+        .set_attribute(PAINT, &NameAttr("paint".to_owned()))
+        .expect("name");
+    state
+        .set_attribute(
+            PAINT,
+            &MaterialAttr {
+                base_color: Some(ColorVec(vec![1.0, 0.0, 0.0, 1.0])),
+                roughness: Some(0.5),
+                ..Default::default()
+            },
+        )
+        .expect("material");
+    state
+        .set_relationship(PAINT, material::NORMAL_TEXTURE, CHECKER)
+        .expect("texture");
+
+    state
+        .set_attribute(
+            CHECKER,
+            &ImageAttr {
+                srgb: Some(false),
+                ..Default::default()
+            },
+        )
+        .expect("image");
+    state
+        .set_slot(CHECKER, slots::IMAGE_DATA, IMAGE.to_vec())
+        .expect("image data");
+
+    state
+        .set_attribute(BOB, &NameAttr("bob".to_owned()))
+        .expect("name");
+    state.set_attribute(BOB, &bob()).expect("animation");
+    state
+        .set_relationship(BOB, &animation::target(0), TRI)
+        .expect("target");
+    state
+        .set_slot(BOB, &slots::animation_times(0), bob_times())
+        .expect("times");
+    state
+        .set_slot(BOB, &slots::animation_values(0), bob_values())
+        .expect("values");
+
+    state
+}
+
+fn write_export(case: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("export")
+        .join(case);
+    std::fs::create_dir_all(&dir).expect("create case dir");
+    let path = dir.join("model.glb");
+    std::fs::write(&path, export::export_state(&scene()).expect("export")).expect("write glb");
+    path
+}
+
+fn realize(package: &Package) -> SceneState {
+    let mut state = SceneState::new();
+    for (key, value) in package.entries.clone() {
+        state.apply(&Entry::new(key, value, 1)).expect("apply");
+    }
+    state
+}
+
+#[test]
+fn only_drawn_or_parent_prims_become_nodes() {
+    let glb = std::fs::read(write_export("nodes")).expect("read glb");
+    let gltf = gltf::Gltf::from_slice(&glb).expect("valid glTF");
+
+    let names = gltf.nodes().filter_map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["body", "tri"]);
+    assert_eq!(gltf.materials().count(), 1);
+    assert_eq!(gltf.textures().count(), 1);
+
+    let anim = gltf.animations().next().expect("animation");
+    assert_eq!(anim.name(), Some("bob"));
+    let channel = anim.channels().next().expect("channel");
+    assert_eq!(channel.target().node().name(), Some("tri"));
+
+    let image = gltf.images().next().expect("image");
+    assert!(matches!(
+        image.source(),
+        gltf::image::Source::View {
+            mime_type: "image/png",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn a_round_trip_keeps_ids_and_relationships() {
+    let state = realize(&import::import_file(&write_export("ids")).expect("import"));
+
+    assert_eq!(state.parent(TRI), Some(BODY));
+    assert_eq!(state.relationship(TRI, material::BINDING), Some(PAINT));
+    assert_eq!(
+        state.relationship(PAINT, material::NORMAL_TEXTURE),
+        Some(CHECKER)
+    );
+    assert_eq!(
+        state.attribute::<NameAttr>(PAINT).and_then(Result::ok),
+        Some(NameAttr("paint".to_owned()))
+    );
+}
+
+#[test]
+fn a_round_trip_puts_nested_non_node_prims_back() {
+    let state = realize(&import::import_file(&write_export("nested")).expect("import"));
+
+    assert_eq!(state.parent(PAINT), Some(BODY));
+    assert_eq!(state.parent(CHECKER), Some(BODY));
+    assert_eq!(state.parent(BOB), Some(BODY));
+}
+
+#[test]
+fn a_round_trip_keeps_clips() {
+    let state = realize(&import::import_file(&write_export("clips")).expect("import"));
+
+    assert_eq!(
+        state.attribute::<AnimationAttr>(BOB).and_then(Result::ok),
+        Some(bob())
+    );
+    assert_eq!(state.relationship(BOB, &animation::target(0)), Some(TRI));
+    let clip = state.get(BOB).expect("clip");
+    assert_eq!(
+        clip.slot(&slots::animation_times(0)),
+        Some(bob_times().as_slice())
+    );
+    assert_eq!(
+        clip.slot(&slots::animation_values(0)),
+        Some(bob_values().as_slice())
+    );
+}
+
+#[test]
+fn a_round_trip_keeps_what_gltf_cannot_express() {
+    let state = realize(&import::import_file(&write_export("extras")).expect("import"));
+
+    assert_eq!(
+        state.attribute::<ColliderAttr>(BODY).and_then(Result::ok),
+        Some(ColliderAttr::Sphere(0.5))
+    );
+    assert_eq!(
+        state.attribute::<SensorAttr>(BODY).and_then(Result::ok),
+        Some(SensorAttr)
+    );
+    assert_eq!(
+        state
+            .attribute::<CollisionLayersAttr>(BODY)
+            .and_then(Result::ok),
+        Some(CollisionLayersAttr {
+            memberships: Some(1 << 2),
+            filters:     Some(0),
+        })
+    );
+    assert_eq!(
+        state.get(BODY).and_then(|prim| prim.slot(slots::SCRIPT)),
+        Some(SCRIPT)
+    );
+    assert_eq!(
+        state
+            .attribute::<ImageAttr>(CHECKER)
+            .and_then(Result::ok)
+            .and_then(|image| image.srgb),
+        Some(false)
+    );
+}
+
+#[test]
+fn a_round_trip_keeps_mesh_and_image_bytes() {
+    let state = realize(&import::import_file(&write_export("bytes")).expect("import"));
+
+    let tri = state.get(TRI).expect("tri");
+    assert_eq!(
+        tri.slot(&slots::mesh_attribute("POSITION")),
+        Some(positions().as_slice())
+    );
+    assert_eq!(tri.slot(slots::MESH_INDICES), Some(indices().as_slice()));
+    assert_eq!(
+        state
+            .get(CHECKER)
+            .and_then(|prim| prim.slot(slots::IMAGE_DATA)),
+        Some(IMAGE)
+    );
+}
@@ -0,0 +1,367 @@
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use hsd::{
+    attributes::{
+        animation::{
+            self,
+            AnimationAttr,
+            AnimationPath,
+            Interpolation,
+        },
+        image::ImageAttr,
+        material::{
+            self,
+            ColorVec,
+            MaterialAttr,
+        },
+        mesh::{
+            MeshAttr,
+            Topology,
+        },
+        name::NameAttr,
+        slots,
+        xform::XformAttr,
+    },
+    id::PrimId,
+    package::Package,
+    state::{
+        SceneState,
+        entry::Entry,
+    },
+};
+use hsd_cli::{
+    compile,
+    import,
+};
+
+const POSITIONS: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+const NORMALS: [[f32; 3]; 3] = [[0.0, 0.0, 1.0]; 3];
+const INDICES: [u32; 3] = [0, 1, 2];
+const IMAGE: &[u8] = b"not really a png";
+const TIMES: [f32; 2] = [0.0, 1.0];
+const RISE: [[f32; 3]; 2] = [[0.0, 1.0, 0.0], [0.0, 2.0, 0.0]];
+
+/// One red triangle under a translated parent, textured from an image held
+/// in the binary chunk, and a clip that slides the parent up and weights the
+/// triangle's (absent) morph targets.
+const JSON: &str = r#"{
+    "asset": { "version": "2.0" },
+    "scene": 0,
+    "scenes": [{ "nodes": [0] }],
+    "nodes": [
+        { "name": "body", "translation": [0.0, 1.0, 0.0], "children": [1] },
+        { "name": "tri", "mesh": 0, "scale": [2.0, 2.0, 2.0] }
+    ],
+    "meshes": [{
+        "name": "tri",
+        "primitives": [{
+            "attributes": { "POSITION": 0, "NORMAL": 1 },
+            "indices": 2,
+            "material": 0
+        }]
+    }],
+    "materials": [{
+        "name": "paint",
+        "pbrMetallicRoughness": {
+            "baseColorFactor": [1.0, 0.0, 0.0, 1.0],
+            "baseColorTexture": { "index": 0 },
+            "metallicFactor": 0.0,
+            "roughnessFactor": 0.75
+        }
+    }],
+    "textures": [{ "sampler": 0, "source": 0 }],
+    "samplers": [{ "magFilter": 9728, "minFilter": 9987, "wrapS": 33071 }],
+    "images": [{ "name": "checker", "bufferView": 3, "mimeType": "image/png" }],
+    "animations": [{
+        "name": "rise",
+        "channels": [
+            { "sampler": 0, "target": { "node": 0, "path": "translation" } },
+            { "sampler": 1, "target": { "node": 1, "path": "weights" } }
+        ],
+        "samplers": [
+            { "input": 3, "output": 4, "interpolation": "STEP" },
+            { "input": 3, "output": 3, "interpolation": "LINEAR" }
+        ]
+    }],
+    "accessors": [
+        {
+            "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+            "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+        },
+        { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+        { "bufferView": 2, "componentType": 5125, "count": 3, "type": "SCALAR" },
+        {
+            "bufferView": 4, "componentType": 5126, "count": 2, "type": "SCALAR",
+            "min": [0.0], "max": [1.0]
+        },
+        { "bufferView": 5, "componentType": 5126, "count": 2, "type": "VEC3" }
+    ],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 72, "byteLength": 12 },
+        { "buffer": 0, "byteOffset": 84, "byteLength": 16 },
+        { "buffer": 0, "byteOffset": 100, "byteLength": 8 },
+        { "buffer": 0, "byteOffset": 108, "byteLength": 24 }
+    ],
+    "buffers": [{ "byteLength": 132 }]
+}"#;
+
+fn floats(values: &[[f32; 3]]) -> Vec<u8> {
+    values
+        .iter()
+        .flatten()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+}
+
+fn glb() -> Vec<u8> {
+    let mut bin = floats(&POSITIONS);
+    bin.extend(floats(&NORMALS));
+    bin.extend(INDICES.iter().flat_map(|i| i.to_le_bytes()));
+    bin.extend_from_slice(IMAGE);
+    bin.extend(TIMES.iter().flat_map(|t| t.to_le_bytes()));
+    bin.extend(floats(&RISE));
+
+    let mut json = JSON.as_bytes().to_vec();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let chunk = |kind: &[u8; 4], data: &[u8]| {
+        let mut out = u32::try_from(data.len())
+            .expect("chunk length")
+            .to_le_bytes()
+            .to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        out
+    };
+    let body = [chunk(b"JSON", &json), chunk(b"BIN\0", &bin)].concat();
+
+    let mut out = b"glTF".to_vec();
+    out.extend(2u32.to_le_bytes());
+    out.extend(
+        u32::try_from(12 + body.len())
+            .expect("glb length")
+            .to_le_bytes(),
+    );
+    out.extend(body);
+    out
+}
+
+fn write_model(case: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("import")
+        .join(case);
+    std::fs::create_dir_all(&dir).expect("create case dir");
+    let path = dir.join("model.glb");
+    std::fs::write(&path, glb()).expect("write model");
+    path
+}
+
+fn realize(package: &Package) -> SceneState {
+    let bytes = package.encode().expect("encode");
+    let package = Package::decode(&bytes).expect("decode");
+
+    let mut state = SceneState::new();
+    for (key, value) in package.entries {
+        state.apply(&Entry::new(key, value, 1)).expect("apply");
+    }
+    state
+}
+
+fn prim_named(state: &SceneState, name: &str) -> PrimId {
+    state
+        .prims()
+        .find(|prim| {
+            state
+                .attribute::<NameAttr>(*prim)
+                .and_then(Result::ok)
+                .is_some_and(|n| n.0 == name)
+        })
+        .unwrap_or_else(|| panic!("no prim named {name}"))
+}
+
+#[test]
+fn the_scene_graph_realizes_under_one_root() {
+    let state = realize(&import::import_file(&write_model("tree")).expect("import"));
+
+    let root = prim_named(&state, "model");
+    let body = prim_named(&state, "body");
+    let tri = prim_named(&state, "tri");
+
+    assert_eq!(state.roots(), vec![root]);
+    assert_eq!(state.parent(body), Some(root));
+    assert_eq!(state.parent(tri), Some(body));
+
+    let xform = state
+        .attribute::<XformAttr>(body)
+        .expect("xform")
+        .expect("decode");
+    assert_eq!(
+        xform,
+        XformAttr {
+            translation: [0.0, 1.0, 0.0],
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn mesh_buffers_compile_to_slots() {
+    let state = realize(&import::import_file(&write_model("mesh")).expect("import"));
+    let tri = prim_named(&state, "tri");
+
+    let mesh = state
+        .attribute::<MeshAttr>(tri)
+        .expect("mesh")
+        .expect("decode");
+    assert_eq!(mesh.topology, Topology::TriangleList);
+
+    let prim = state.get(tri).expect("prim");
+    assert_eq!(
+        prim.slot(&slots::mesh_attribute("POSITION")),
+        Some(floats(&POSITIONS).as_slice())
+    );
+    assert_eq!(
+        prim.slot(&slots::mesh_attribute("NORMAL")),
+        Some(floats(&NORMALS).as_slice())
+    );
+    assert_eq!(
+        prim.slot(slots::MESH_INDICES),
+        Some(
+            INDICES
+                .iter()
+                .flat_map(|i| i.to_le_bytes())
+                .collect::<Vec<_>>()
+                .as_slice()
+        )
+    );
+}
+
+#[test]
+fn a_material_binds_and_references_its_texture() {
+    let state = realize(&import::import_file(&write_model("material")).expect("import"));
+    let paint = prim_named(&state, "paint");
+    let checker = prim_named(&state, "checker");
+
+    assert_eq!(
+        state.relationship(prim_named(&state, "tri"), material::BINDING),
+        Some(paint)
+    );
+    assert_eq!(
+        state.relationship(paint, material::BASE_COLOR_TEXTURE),
+        Some(checker)
+    );
+
+    let mat = state
+        .attribute::<MaterialAttr>(paint)
+        .expect("material")
+        .expect("decode");
+    assert_eq!(mat.base_color, Some(ColorVec(vec![1.0, 0.0, 0.0, 1.0])));
+    assert_eq!(mat.roughness, Some(0.75));
+
+    let image = state
+        .attribute::<ImageAttr>(checker)
+        .expect("image")
+        .expect("decode");
+    assert_eq!(image.srgb, Some(true));
+    assert_eq!(
+        state
+            .get(checker)
+            .and_then(|prim| prim.slot(slots::IMAGE_DATA)),
+        Some(IMAGE)
+    );
+}
+
+#[test]
+fn an_animation_becomes_a_clip_targeting_its_node() {
+    let state = realize(&import::import_file(&write_model("animation")).expect("import"));
+    let rise = prim_named(&state, "rise");
+
+    assert_eq!(state.parent(rise), Some(prim_named(&state, "model")));
+    assert_eq!(
+        state.relationship(rise, &animation::target(0)),
+        Some(prim_named(&state, "body"))
+    );
+
+    let clip = state
+        .attribute::<AnimationAttr>(rise)
+        .expect("animation")
+        .expect("decode");
+    assert_eq!(clip.channels.len(), 1);
+    assert_eq!(clip.channels[0].path, AnimationPath::Translation);
+    assert_eq!(clip.channels[0].interpolation, Interpolation::Step);
+
+    let prim = state.get(rise).expect("prim");
+    assert_eq!(
+        prim.slot(&slots::animation_times(0)),
+        Some(
+            TIMES
+                .iter()
+                .flat_map(|t| t.to_le_bytes())
+                .collect::<Vec<_>>()
+                .as_slice()
+        )
+    );
+    assert_eq!(
+        prim.slot(&slots::animation_values(0)),
+        Some(floats(&RISE).as_slice())
+    );
+}
+
+#[test]
+fn a_morph_weight_channel_is_dropped() {
+    let state = realize(&import::import_file(&write_model("morph")).expect("import"));
+    let rise = prim_named(&state, "rise");
+
+    let clip = state
+        .attribute::<AnimationAttr>(rise)
+        .expect("animation")
+        .expect("decode");
+    assert!(
+        clip.channels
+            .iter()
+            .all(|channel| channel.path != AnimationPath::MorphWeights)
+    );
+    assert_eq!(state.relationship(rise, &animation::target(1)), None);
+}
+
+#[test]
+fn a_mesh_source_attribute_imports_beneath_its_prim() {
+    let model = write_model("source");
+    let input = model.with_file_name("asset.hsda");
+    std::fs::write(
+        &input,
+        r#"[(attributes: (name: "statue", mesh: "model.glb"))]"#,
+    )
+    .expect("write source");
+
+    let package = compile::compile_file(&input, &mut HashMap::new()).expect("compile");
+    let state = realize(&package);
+
+    let host = prim_named(&state, "statue");
+    assert_eq!(state.roots(), vec![host]);
+    assert_eq!(state.parent(prim_named(&state, "body")), Some(host));
+}
+
+#[test]
+fn importing_is_reproducible() {
+    let model = write_model("reproducible");
+    assert_eq!(
+        import::import_file(&model)
+            .expect("import")
+            .encode()
+            .expect("encode"),
+        import::import_file(&model)
+            .expect("import")
+            .encode()
+            .expect("encode")
+    );
+}
@@ -0,0 +1,104 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// The relationship naming the prim a channel moves.
+#[must_use]
+pub fn target(channel: usize) -> String {
+    format!("animation:target:{channel}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimationPath {
+    /// Three floats per keyframe.
+    Translation,
+    /// Four floats per keyframe, a quaternion as `[x, y, z, w]`.
+    Rotation,
+    /// Three floats per keyframe.
+    Scale,
+    /// One float per morph target per keyframe. Reserved: no mesh carries
+    /// morph targets yet, so players skip these channels and importers drop
+    /// them.
+    MorphWeights,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolation {
+    Step,
+    #[default]
+    Linear,
+    /// Each keyframe is an in-tangent, a value and an out-tangent, in that
+    /// order, as in glTF.
+    CubicSpline,
+}
+
+/// One animated property of one prim.
+///
+/// Its keyframe times are the
+/// [`animation_times`](super::slots::animation_times) slot, in seconds and
+/// ascending; its values are the
+/// [`animation_values`](super::slots::animation_values) slot. Both are
+/// little-endian `f32`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnimationChannel {
+    pub path:          AnimationPath,
+    pub interpolation: Interpolation,
+}
+
+/// A clip: keyframed channels driving other prims' transforms, played by the
+/// client rather than stepped by a script.
+///
+/// Channel `i` moves the prim named by the [`target`] relationship `i`; a
+/// channel without one moves the clip's own prim. The prim's `xform` is the
+/// pose a channel blends from and returns to when the clip stops.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnimationAttr {
+    pub channels: Vec<AnimationChannel>,
+    /// Whether the clip plays as soon as it loads. Defaults to false: most
+    /// clips are started by a script.
+    pub autoplay: Option<bool>,
+    pub looping:  Option<bool>,
+    /// Playback rate, where 1 is authored speed. A negative rate plays
+    /// backwards from the clip's end.
+    pub speed:    Option<f64>,
+}
+
+impl Attribute for AnimationAttr {
+    const KEY: &'static str = "animation";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_clip_carries_no_channels() {
+        let attr = AnimationAttr::default();
+        let decoded = AnimationAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+
+    #[test]
+    fn channels_survive_a_round_trip() {
+        let attr = AnimationAttr {
+            channels: vec![
+                AnimationChannel {
+                    path:          AnimationPath::Rotation,
+                    interpolation: Interpolation::CubicSpline,
+                },
+                AnimationChannel {
+                    path:          AnimationPath::MorphWeights,
+                    interpolation: Interpolation::Step,
+                },
+            ],
+            autoplay: Some(true),
+            looping:  Some(true),
+            speed:    Some(0.5),
+        };
+        let decoded = AnimationAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// The relationship naming the prim whose `audio:data` slot an emitter plays.
+/// Without one, the emitter plays its own.
+pub const CLIP: &str = "audio:clip";
+
+/// A sound emitter at the prim's origin.
+///
+/// Playback settings only; the encoded clip is an `audio:data` slot, like an
+/// image's pixels. Whether it is playing right now is per-peer runtime state
+/// and is not stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioAttr {
+    /// Linear gain; 1 plays the clip as it was mastered.
+    pub volume:   Option<f64>,
+    pub looping:  Option<bool>,
+    /// Multiplier on the listener's distance before inverse-square falloff:
+    /// at 1 the emitter is at full volume within a metre and a quarter of it
+    /// at two. Smaller carries further; 0 plays the clip without position.
+    pub rolloff:  Option<f64>,
+    /// Whether the clip starts on its own once realized, rather than waiting
+    /// for a script to play it. Defaults to true.
+    pub autoplay: Option<bool>,
+}
+
+impl Attribute for AudioAttr {
+    const KEY: &'static str = "audio";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_bare_emitter_carries_no_settings() {
+        let attr = AudioAttr::default();
+        let decoded = AudioAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+
+    #[test]
+    fn a_fully_specified_emitter_survives_a_round_trip() {
+        let attr = AudioAttr {
+            volume:   Some(0.5),
+            looping:  Some(true),
+            rolloff:  Some(0.25),
+            autoplay: Some(false),
+        };
+        let decoded = AudioAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+}
@@ -20,3 +20,25 @@ pub enum ColliderAttr {
 impl Attribute for ColliderAttr {
     const KEY: &'static str = "collider";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stored documents hold colliders as this enum; its bytes are fixed.
+    #[test]
+    fn a_stored_cuboid_still_decodes() {
+        let mut stored = vec![2];
+        for v in [1.0f64, 2.0, 0.5] {
+            stored.extend(v.to_le_bytes());
+        }
+        assert_eq!(
+            ColliderAttr::decode(&stored).expect("decode"),
+            ColliderAttr::Cuboid {
+                x: 1.0,
+                y: 2.0,
+                z: 0.5,
+            }
+        );
+    }
+}
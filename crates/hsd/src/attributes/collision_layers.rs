@@ -0,0 +1,20 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// Collision layers for the prim's collider, as masks; see [`crate::layers`].
+/// Two colliders touch only if each is on a layer the other's filter names.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionLayersAttr {
+    /// Layers the collider is on. Defaults to `default` alone.
+    pub memberships: Option<u32>,
+    /// Layers the collider collides with. Defaults to every layer.
+    pub filters:     Option<u32>,
+}
+
+impl Attribute for CollisionLayersAttr {
+    const KEY: &'static str = "collision_layers";
+}
@@ -0,0 +1,103 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// The relationship naming the body a joint connects this prim's body to.
+/// A joint without one holds nothing; to pin a body in place, connect it to
+/// a static one.
+pub const BODY: &str = "joint:body";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JointKind {
+    /// Welds the two bodies together.
+    #[default]
+    Fixed,
+    /// A hinge: rotation about `axis` only.
+    Revolute,
+    /// A slider: translation along `axis` only.
+    Prismatic,
+    /// A ball and socket: any rotation about the anchors.
+    Spherical,
+    /// Keeps the anchors within `limits` metres of each other, like a rope
+    /// or a rod.
+    Distance,
+}
+
+/// A joint's range. Radians for a revolute joint's angle or a spherical
+/// joint's swing, metres for a prismatic joint's travel or a distance
+/// joint's length.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointLimits {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Drives a revolute or prismatic joint along its one free axis, in the
+/// joint's units per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct JointMotor {
+    pub target_velocity: Option<f64>,
+    /// A position the motor pulls toward, as a servo, rather than spinning.
+    pub target_position: Option<f64>,
+    /// Torque or force ceiling. A motor without one is as strong as the
+    /// solver allows.
+    pub max_force:       Option<f64>,
+}
+
+/// Connects this prim's body to the [`BODY`] relationship's.
+///
+/// Anchors and the axis are in each body's own frame, so a joint authored
+/// once keeps working wherever the bodies are placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct JointAttr {
+    pub kind:         JointKind,
+    /// Where the joint sits on this prim's body. Defaults to its origin.
+    pub anchor:       Option<[f32; 3]>,
+    /// Where the joint sits on the other body. Defaults to its origin.
+    pub other_anchor: Option<[f32; 3]>,
+    /// Hinge or slide axis, in this prim's frame. Defaults to +Y.
+    pub axis:         Option<[f32; 3]>,
+    pub limits:       Option<JointLimits>,
+    pub motor:        Option<JointMotor>,
+}
+
+impl Attribute for JointAttr {
+    const KEY: &'static str = "joint";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_bare_weld_carries_no_settings() {
+        let attr = JointAttr::default();
+        let decoded = JointAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+        assert_eq!(decoded.kind, JointKind::Fixed);
+    }
+
+    #[test]
+    fn a_motorized_hinge_survives_a_round_trip() {
+        let attr = JointAttr {
+            kind:         JointKind::Revolute,
+            anchor:       Some([0.5, 0.0, 0.0]),
+            other_anchor: Some([-0.5, 0.0, 0.0]),
+            axis:         Some([0.0, 0.0, 1.0]),
+            limits:       Some(JointLimits {
+                min: -1.5,
+                max: 1.5,
+            }),
+            motor:        Some(JointMotor {
+                target_velocity: Some(2.0),
+                target_position: None,
+                max_force:       Some(50.0),
+            }),
+        };
+        let decoded = JointAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::{
+    Attribute,
+    material::ColorVec,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightKind {
+    /// Parallel rays along the prim's -Z, from infinitely far away: a sun.
+    Directional,
+    #[default]
+    Point,
+    /// A cone along the prim's -Z.
+    Spot,
+}
+
+/// A punctual light, shining from the prim's origin.
+///
+/// Units follow glTF's `KHR_lights_punctual`, so an imported lamp keeps its
+/// brightness: point and spot intensity is candela, directional is lux.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LightAttr {
+    pub kind:        LightKind,
+    /// Linear RGB; alpha is ignored.
+    pub color:       Option<ColorVec>,
+    pub intensity:   Option<f64>,
+    /// Metres past which a point or spot light contributes nothing.
+    pub range:       Option<f64>,
+    /// Spot only: radians from the axis where falloff begins.
+    pub inner_angle: Option<f64>,
+    /// Spot only: radians from the axis where the cone ends.
+    pub outer_angle: Option<f64>,
+    /// Whether the light casts shadows. A shadow map is the expensive part of
+    /// a light, so each document realizes only a few that do.
+    pub shadows:     Option<bool>,
+}
+
+impl Attribute for LightAttr {
+    const KEY: &'static str = "light";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_bare_point_light_carries_no_settings() {
+        let attr = LightAttr::default();
+        let decoded = LightAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+        assert_eq!(decoded.kind, LightKind::Point);
+    }
+
+    #[test]
+    fn a_fully_specified_spot_survives_a_round_trip() {
+        let attr = LightAttr {
+            kind:        LightKind::Spot,
+            color:       Some(ColorVec(vec![1.0, 0.8, 0.6])),
+            intensity:   Some(400.0),
+            range:       Some(12.0),
+            inner_angle: Some(0.2),
+            outer_angle: Some(0.6),
+            shadows:     Some(true),
+        };
+        let decoded = LightAttr::decode(&attr.encode().expect("encode")).expect("decode");
+        assert_eq!(decoded, attr);
+    }
+}
@@ -3,15 +3,22 @@ use serde::{
     de::DeserializeOwned,
 };
 
+pub mod animation;
+pub mod audio;
 pub mod collider;
+pub mod collision_layers;
 pub mod gravity_scale;
 pub mod image;
+pub mod joint;
+pub mod light;
 pub mod material;
 pub mod material_graph;
 pub mod mesh;
 pub mod name;
 pub mod portal;
+pub mod relay;
 pub mod rigid_body;
+pub mod sensor;
 pub mod spawn;
 pub mod text;
 pub mod xform;
@@ -38,6 +45,7 @@ pub mod slots {
     pub const PREFAB: &str = "prefab";
     pub const SCRIPT: &str = "script";
     pub const IMAGE_DATA: &str = "image:data";
+    pub const AUDIO_DATA: &str = "audio:data";
     pub const MESH_INDICES: &str = "mesh:indices";
     pub const COLLIDER_INDICES: &str = "collider:indices";
     pub const COLLIDER_VERTICES: &str = "collider:vertices";
@@ -49,6 +57,18 @@ pub mod slots {
         format!("material:graph_texture:{slot}")
     }
 
+    /// An animation channel's keyframe times.
+    #[must_use]
+    pub fn animation_times(channel: usize) -> String {
+        format!("animation:times:{channel}")
+    }
+
+    /// An animation channel's keyframe values.
+    #[must_use]
+    pub fn animation_values(channel: usize) -> String {
+        format!("animation:values:{channel}")
+    }
+
     #[must_use]
     pub fn mesh_attribute(name: &str) -> String {
         format!("mesh:{name}")
@@ -69,11 +89,15 @@ pub mod slots {
             PREFAB,
             SCRIPT,
             IMAGE_DATA,
+            AUDIO_DATA,
             MESH_INDICES,
             COLLIDER_INDICES,
             COLLIDER_VERTICES,
             MATERIAL_GRAPH_DATA,
         ];
-        STATIC.contains(&name) || name.starts_with("mesh:")
+        STATIC.contains(&name)
+            || name.starts_with("mesh:")
+            || name.starts_with("animation:times:")
+            || name.starts_with("animation:values:")
     }
 }
@@ -0,0 +1,19 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// Names a relay for the space whose document carries it. Clients that can
+/// reach it send their agent, object and state streams through it once, rather
+/// than to every other peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayAttr {
+    /// The relay's endpoint id.
+    pub endpoint: [u8; 32],
+}
+
+impl Attribute for RelayAttr {
+    const KEY: &'static str = "relay";
+}
@@ -0,0 +1,15 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::attributes::Attribute;
+
+/// Makes the prim's collider a sensor: it reports what enters and leaves it
+/// but pushes nothing back, as a goal line, a pressure plate or a pickup does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensorAttr;
+
+impl Attribute for SensorAttr {
+    const KEY: &'static str = "sensor";
+}
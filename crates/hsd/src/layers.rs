@@ -0,0 +1,134 @@
+//! Collision layers.
+//!
+//! A collider's memberships and filters are 32-bit masks. The low bits are the
+//! engine's, spelled the same in every document; the rest belong to the names
+//! a document lists in its meta, in that order. A collider stores the bits
+//! rather than the names, so realizing one needs no lookup, and two documents
+//! that list the same custom names in the same order interact as one. Two
+//! whose names disagree over a bit would collide by accident; see
+//! [`compatible`].
+
+use thiserror::Error;
+
+/// The engine's layers, in bit order. `default` is where a collider with no
+/// memberships sits, `agent` is every agent's body and `tool` is for volumes
+/// only a pointer or script query should find.
+pub const BUILTIN: [&str; 3] = ["default", "agent", "tool"];
+
+/// Bits below this are the engine's, used or not, so adding a built-in layer
+/// does not move a document's own.
+pub const FIRST_CUSTOM: u32 = 8;
+
+pub const MAX_CUSTOM: usize = (u32::BITS - FIRST_CUSTOM) as usize;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LayerError {
+    #[error("unknown collision layer {0:?}")]
+    Unknown(String),
+    #[error("a document has room for {MAX_CUSTOM} collision layers of its own")]
+    Full,
+}
+
+/// The bit `name` is, given a document's custom layers.
+#[must_use]
+pub fn bit(custom: &[String], name: &str) -> Option<u32> {
+    let index = BUILTIN
+        .iter()
+        .zip(0..)
+        .find(|(builtin, _)| **builtin == name)
+        .map(|(_, index)| index)
+        .or_else(|| {
+            custom
+                .iter()
+                .take(MAX_CUSTOM)
+                .zip(FIRST_CUSTOM..)
+                .find(|(custom, _)| *custom == name)
+                .map(|(_, index)| index)
+        })?;
+    Some(1 << index)
+}
+
+/// The mask naming every layer in `names`.
+pub fn mask<S: AsRef<str>>(custom: &[String], names: &[S]) -> Result<u32, LayerError> {
+    names.iter().try_fold(0, |mask, name| {
+        let name = name.as_ref();
+        bit(custom, name)
+            .map(|bit| mask | bit)
+            .ok_or_else(|| LayerError::Unknown(name.to_owned()))
+    })
+}
+
+/// Whether two documents' custom layers can share a physics world: every bit
+/// both use must be the same name in each, so one list starts the other.
+#[must_use]
+pub fn compatible(a: &[String], b: &[String]) -> bool {
+    a.iter().zip(b).take(MAX_CUSTOM).all(|(a, b)| a == b)
+}
+
+/// Adds `name` to a document's custom layers unless it is already a layer.
+pub fn declare(custom: &mut Vec<String>, name: &str) -> Result<(), LayerError> {
+    if bit(custom, name).is_some() {
+        return Ok(());
+    }
+    if custom.len() >= MAX_CUSTOM {
+        return Err(LayerError::Full);
+    }
+    custom.push(name.to_owned());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_are_the_low_bits() {
+        assert_eq!(bit(&[], "default"), Some(1));
+        assert_eq!(bit(&[], "agent"), Some(1 << 1));
+        assert_eq!(bit(&[], "tool"), Some(1 << 2));
+    }
+
+    #[test]
+    fn custom_layers_start_past_the_reserved_bits() {
+        let custom = vec!["ghost".to_owned(), "water".to_owned()];
+        assert_eq!(bit(&custom, "water"), Some(1 << (FIRST_CUSTOM + 1)));
+        assert_eq!(
+            mask(&custom, &["agent", "ghost"]),
+            Ok((1 << 1) | (1 << FIRST_CUSTOM))
+        );
+        assert_eq!(
+            mask(&custom, &["lava"]),
+            Err(LayerError::Unknown("lava".to_owned()))
+        );
+    }
+
+    #[test]
+    fn a_full_table_refuses_another_name() {
+        let mut custom = Vec::new();
+        for index in 0..MAX_CUSTOM {
+            declare(&mut custom, &format!("layer{index}")).expect("room");
+        }
+        declare(&mut custom, "layer0").expect("already declared");
+        declare(&mut custom, "agent").expect("built in");
+        assert_eq!(declare(&mut custom, "one-more"), Err(LayerError::Full));
+        assert_eq!(bit(&custom, "layer23"), Some(1 << 31));
+    }
+
+    #[test]
+    fn documents_agreeing_on_every_shared_bit_are_compatible() {
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|&name| name.to_owned())
+                .collect::<Vec<_>>()
+        };
+        let ghost = names(&["ghost"]);
+        let ghost_water = names(&["ghost", "water"]);
+        let water = names(&["water"]);
+
+        assert!(compatible(&[], &water));
+        assert!(compatible(&ghost, &ghost_water));
+        assert!(compatible(&ghost_water, &ghost));
+        assert!(!compatible(&water, &ghost_water));
+    }
+}
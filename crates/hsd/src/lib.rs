@@ -1,6 +1,7 @@
 pub mod attributes;
 pub mod id;
 pub mod key;
+pub mod layers;
 pub mod meta;
 pub mod package;
 pub mod property;
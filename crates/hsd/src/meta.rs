@@ -3,16 +3,23 @@ use serde::{
     Serialize,
 };
 
-pub const VERSION: u16 = 1;
+/// Version 2 added `layers`.
+pub const VERSION: u16 = 2;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DocMeta {
     pub version: u16,
+    /// The document's own collision layers, in bit order; see
+    /// [`crate::layers`].
+    pub layers:  Vec<String>,
 }
 
 impl Default for DocMeta {
     fn default() -> Self {
-        Self { version: VERSION }
+        Self {
+            version: VERSION,
+            layers:  Vec::new(),
+        }
     }
 }
 
@@ -21,7 +28,43 @@ impl DocMeta {
         postcard::to_stdvec(self)
     }
 
+    /// Meta written before `layers` existed ends after the version, and reads
+    /// as having none. Postcard ignores trailing bytes, so older readers take
+    /// the version from newer meta the same way.
     pub fn decode(bytes: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(bytes)
+        let (version, rest) = postcard::take_from_bytes(bytes)?;
+        let layers = if rest.is_empty() {
+            Vec::new()
+        } else {
+            postcard::from_bytes(rest)?
+        };
+        Ok(Self { version, layers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_one_meta_still_decodes() {
+        // Version 1 was the version alone: a single varint byte.
+        assert_eq!(
+            DocMeta::decode(&[1]).expect("decode"),
+            DocMeta {
+                version: 1,
+                layers:  Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn layers_round_trip() {
+        let meta = DocMeta {
+            layers: vec!["ghost".to_owned()],
+            ..DocMeta::default()
+        };
+        let bytes = meta.encode().expect("encode");
+        assert_eq!(DocMeta::decode(&bytes).expect("decode"), meta);
     }
 }
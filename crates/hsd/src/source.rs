@@ -18,6 +18,10 @@ use crate::attributes::{
         AddressMode,
         FilterMode,
     },
+    joint::{
+        JointLimits,
+        JointMotor,
+    },
     material_graph::value::GraphValue,
 };
 
@@ -56,16 +60,31 @@ pub struct SourcePrim {
 #[serde(default)]
 pub struct SourceAttributes {
     pub collider:       Option<SourceCollider>,
+    /// Collision layers `collider` collides with, by name; every layer if
+    /// unset.
+    pub collides_with:  Option<Vec<String>>,
     pub gravity_scale:  Option<f64>,
     pub image:          Option<SourceImage>,
+    pub joint:          Option<SourceJoint>,
+    /// Collision layers `collider` is on, by name. A name that is not built
+    /// in becomes one of the document's own layers.
+    pub layers:         Option<Vec<String>>,
+    pub light:          Option<SourceLight>,
     pub material:       Option<SourceMaterial>,
     pub material_graph: Option<SourceMaterialGraph>,
+    /// Path to a glTF or GLB file, whose default scene is imported as
+    /// children of this prim.
+    pub mesh:           Option<String>,
     pub name:           Option<String>,
     /// Path to another `.hsda`, compiled and inlined as a nested package.
     pub prefab:         Option<String>,
+    /// The endpoint id, in hex, of a relay for the space this document is.
+    pub relay:          Option<String>,
     pub rigid_body:     Option<SourceRigidBody>,
     /// Path to a wasm crate's `Cargo.toml`.
     pub script:         Option<String>,
+    /// Makes `collider` a sensor.
+    pub sensor:         Option<bool>,
     pub spawn:          Option<SourceSpawn>,
     pub xform:          Option<SourceXform>,
 }
@@ -76,6 +95,36 @@ pub struct SourceSpawn {
     pub radius: f64,
 }
 
+/// `kind` is `Directional`, `Point` or `Spot`; the rest follows
+/// [`crate::attributes::light::LightAttr`].
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SourceLight {
+    pub kind:        String,
+    pub color:       Option<Vec<f64>>,
+    pub intensity:   Option<f64>,
+    pub range:       Option<f64>,
+    pub inner_angle: Option<f64>,
+    pub outer_angle: Option<f64>,
+    pub shadows:     Option<bool>,
+}
+
+/// `kind` is `Fixed`, `Revolute`, `Prismatic`, `Spherical` or `Distance`,
+/// and the other body is the `joint:body` relationship; the rest follows
+/// [`crate::attributes::joint::JointAttr`].
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SourceJoint {
+    pub kind:         String,
+    pub anchor:       Option<Vec<f32>>,
+    pub other_anchor: Option<Vec<f32>>,
+    pub axis:         Option<Vec<f32>>,
+    pub limits:       Option<JointLimits>,
+    pub motor:        Option<JointMotor>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum SourceCollider {
     Capsule { height: f64, radius: f64 },
@@ -124,8 +124,8 @@ impl SceneState {
     }
 
     #[must_use]
-    pub const fn meta(&self) -> DocMeta {
-        self.meta
+    pub const fn meta(&self) -> &DocMeta {
+        &self.meta
     }
 
     #[must_use]
@@ -33,6 +33,7 @@ use unavi_manifold::{
     ManifoldViewer,
     visuals::SEAM_RENDER_LAYER,
 };
+use unavi_physics::layers;
 
 use crate::{
     Agent,
@@ -56,6 +57,10 @@ use crate::{
 
 const CAMERA_NEAR_PLANE: f32 = 0.01;
 
+/// Metres between the listener's ears. Emitters pan by the difference in their
+/// distance to each, so this is a head's width rather than Bevy's default.
+const EAR_GAP: f32 = 0.2;
+
 pub fn spawn_local_agent(
     trigger: On<Add, LocalAgent>,
     asset_server: Res<AssetServer>,
@@ -78,6 +83,7 @@ pub fn spawn_local_agent(
             Pickable::IGNORE,
             RigidBody::Dynamic,
             Collider::capsule(config.effective_vrm_radius(), config.effective_vrm_height()),
+            CollisionLayers::new(layers::AGENT, LayerMask::ALL),
             TnuaController::<ControlScheme>::default(),
             TnuaConfig::<ControlScheme>(asset_server.add(ControlSchemeConfig {
                 basis: TnuaBuiltinWalkConfig {
@@ -192,6 +198,7 @@ fn spawn_camera(commands: &mut Commands, is_xr: bool) -> Entity {
         RenderLayers::from_layers(&[0, SEAM_RENDER_LAYER])
             .union(&DEFAULT_RENDER_LAYERS[&FirstPersonFlag::FirstPersonOnly]),
         ManifoldViewer,
+        SpatialListener::new(EAR_GAP),
     ));
 
     camera
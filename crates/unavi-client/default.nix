@@ -76,6 +76,7 @@
             [
               binaryen
               clang
+              cmake
               esbuild
               lld
               pkg-config
@@ -89,6 +90,7 @@
           with pkgs;
           [
             alsa-lib
+            libopus
             libxkbcommon
             openxr-loader
             udev
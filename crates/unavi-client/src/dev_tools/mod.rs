@@ -11,13 +11,21 @@ use bevy_inspector_egui::{
     bevy_egui::EguiPlugin,
     quick::WorldInspectorPlugin,
 };
-use unavi_devtools::DevToolsPlugin;
+use unavi_devtools::{
+    DevToolsPlugin,
+    tabs::panel_active,
+};
 
 mod bevy_panel;
 mod event_gizmos;
+mod scripts_panel;
+mod storage_panel;
 
-/// Client-side dev tools: the shared overlay plus a "Bevy" panel toggling the
-/// engine debug views (FPS, world inspector, physics and event gizmos).
+/// Client-side dev tools: the shared overlay, a "Bevy" panel toggling the
+/// engine debug views (FPS, world inspector, physics and event gizmos), a
+/// "Scripts" panel showing each script's CPU use against its budget and a
+/// "Storage" panel itemising quota use at each host, with unpin and unhost
+/// buttons and, on native, export and import of the local store.
 pub struct ClientDevToolsPlugin;
 
 impl Plugin for ClientDevToolsPlugin {
@@ -43,13 +51,27 @@ impl Plugin for ClientDevToolsPlugin {
         ))
         .init_resource::<bevy_panel::DevToggles>()
         .init_resource::<event_gizmos::EventPings>()
-        .add_systems(Startup, bevy_panel::spawn)
+        .init_resource::<scripts_panel::CpuSampler>()
+        .init_resource::<storage_panel::StorageReports>()
+        .add_systems(
+            Startup,
+            (
+                bevy_panel::spawn,
+                scripts_panel::spawn,
+                storage_panel::spawn,
+            ),
+        )
         .add_observer(bevy_panel::on_toggle)
+        .add_observer(storage_panel::on_release)
         .add_systems(
             Update,
             (
                 bevy_panel::apply_toggles,
                 bevy_panel::apply_fps_display,
+                scripts_panel::update.run_if(panel_active::<scripts_panel::ScriptsPanel>),
+                (storage_panel::refresh, storage_panel::show)
+                    .chain()
+                    .run_if(panel_active::<storage_panel::StoragePanel>),
                 (
                     event_gizmos::update_event_pings,
                     event_gizmos::draw_receptors,
@@ -58,5 +80,8 @@ impl Plugin for ClientDevToolsPlugin {
                     .run_if(bevy_panel::toggled(bevy_panel::Toggle::Events)),
             ),
         );
+
+        #[cfg(not(target_family = "wasm"))]
+        app.add_observer(storage_panel::on_backup);
     }
 }
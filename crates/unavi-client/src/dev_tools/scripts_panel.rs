@@ -0,0 +1,118 @@
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+};
+use unavi_devtools::tabs::DevPanel;
+use unavi_quota::limits::FUEL_PER_MS;
+use unavi_script::quota::cpu::{
+    CpuState,
+    ScriptCpu,
+};
+
+#[derive(Component)]
+pub(super) struct ScriptsPanel;
+
+#[derive(Component)]
+pub(super) struct ScriptsText;
+
+#[derive(Clone, Copy)]
+struct Prev {
+    spent: u64,
+    at:    f32,
+    rate:  f32,
+}
+
+const SAMPLE_INTERVAL: f32 = 1.0;
+
+#[derive(Resource, Default)]
+pub(super) struct CpuSampler {
+    prev: HashMap<Entity, Prev>,
+}
+
+pub(super) fn spawn(mut commands: Commands) {
+    commands
+        .spawn((
+            DevPanel {
+                title: "Scripts".into(),
+            },
+            ScriptsPanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                ScriptsText,
+                Text::new("No metered scripts."),
+                TextFont {
+                    font_size: FontSize::Px(14.0),
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.9, 0.95)),
+            ));
+        });
+}
+
+/// Lists each script's CPU use, in milliseconds of fuel per second, and
+/// whether its budget is holding it back.
+pub(super) fn update(
+    time: Res<Time>,
+    scripts: Query<(Entity, NameOrEntity, &ScriptCpu)>,
+    mut sampler: ResMut<CpuSampler>,
+    mut text: Query<&mut Text, With<ScriptsText>>,
+) {
+    let now = time.elapsed_secs();
+
+    let mut next = HashMap::new();
+    let mut lines = Vec::new();
+    let mut total = 0.0;
+
+    for (entity, name, cpu) in &scripts {
+        let spent = cpu.spent();
+        let entry = match sampler.prev.get(&entity).copied() {
+            Some(p) if now - p.at >= SAMPLE_INTERVAL => Prev {
+                spent,
+                at: now,
+                rate: spent.saturating_sub(p.spent) as f32 / (now - p.at),
+            },
+            Some(p) => p,
+            None => Prev {
+                spent,
+                at: now,
+                rate: 0.0,
+            },
+        };
+        total += entry.rate;
+
+        let state = match cpu.state() {
+            CpuState::Running => String::new(),
+            CpuState::Deferred => format!("  deferred ({} ticks)", cpu.deferred()),
+            CpuState::Suspended => "  suspended".to_owned(),
+            CpuState::Exhausted => "  out of budget".to_owned(),
+        };
+        lines.push(format!(
+            "{name} {:>6.1} ms/s{state}",
+            entry.rate / FUEL_PER_MS as f32,
+        ));
+
+        next.insert(entity, entry);
+    }
+    sampler.prev = next;
+
+    let Ok(mut text) = text.single_mut() else {
+        return;
+    };
+    if lines.is_empty() {
+        text.0 = "No metered scripts.".into();
+        return;
+    }
+    let mut out = format!(
+        "Scripts: {} TOTAL {:.1} ms/s\n",
+        lines.len(),
+        total / FUEL_PER_MS as f32,
+    );
+    out.push_str(&lines.join("\n"));
+    text.0 = out;
+}
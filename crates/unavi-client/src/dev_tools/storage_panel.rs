@@ -0,0 +1,411 @@
+#[cfg(not(target_family = "wasm"))]
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::PathBuf,
+};
+
+use async_channel::{
+    Receiver,
+    Sender,
+};
+use bevy::{
+    ecs::spawn::Spawn,
+    feathers::{
+        controls::ButtonBundleProps,
+        theme::ThemedText,
+    },
+    prelude::*,
+    ui_widgets::Activate,
+};
+use bevy_wds::{
+    LocalActor,
+    SyncTargets,
+};
+use blake3::Hash;
+use iroh_docs::NamespaceId;
+use unavi_devtools::tabs::DevPanel;
+use unavi_util::async_task::spawn_async_task;
+use wds::{
+    actor::Actor,
+    control::QuotaReport,
+};
+use web_time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+#[derive(Component)]
+pub(super) struct StoragePanel;
+
+#[derive(Component)]
+pub(super) struct StorageList;
+
+/// A button taking `item` off `actor`'s host.
+#[derive(Component)]
+pub(super) struct Release {
+    actor: Actor,
+    item:  Item,
+}
+
+#[derive(Clone, Copy)]
+enum Item {
+    Pin(Hash),
+    Doc(NamespaceId),
+}
+
+/// A button moving the local store's data out to an archive file, or back in
+/// from one.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Component, Clone, Copy)]
+pub(super) enum Backup {
+    Export,
+    Import,
+}
+
+const REFRESH_SECS: f32 = 5.0;
+
+/// Usage samples shown per host, most recent last.
+const HISTORY_SHOWN: usize = 7;
+
+type Reports = Vec<(Actor, Result<QuotaReport, String>)>;
+
+#[derive(Resource)]
+pub(super) struct StorageReports {
+    tx:         Sender<Reports>,
+    rx:         Receiver<Reports>,
+    fetched_at: Option<f32>,
+}
+
+impl Default for StorageReports {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self {
+            tx,
+            rx,
+            fetched_at: None,
+        }
+    }
+}
+
+pub(super) fn spawn(mut commands: Commands) {
+    commands
+        .spawn((
+            DevPanel {
+                title: "Storage".into(),
+            },
+            StoragePanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+        ))
+        .with_children(|p| {
+            #[cfg(not(target_family = "wasm"))]
+            spawn_backup_row(p);
+
+            p.spawn((
+                StorageList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+            ))
+            .with_children(|list| {
+                list.spawn(label("Loading..."));
+            });
+        });
+}
+
+/// Asks the local store and every sync target for a quota report, every
+/// [`REFRESH_SECS`] while the panel is open.
+pub(super) fn refresh(
+    time: Res<Time>,
+    actors: Query<(&LocalActor, &SyncTargets)>,
+    mut reports: ResMut<StorageReports>,
+) {
+    let now = time.elapsed_secs();
+    if reports.fetched_at.is_some_and(|at| now - at < REFRESH_SECS) {
+        return;
+    }
+    let Ok((local, targets)) = actors.single() else {
+        return;
+    };
+    reports.fetched_at = Some(now);
+
+    spawn_async_task(fetch(every_host(local, targets), reports.tx.clone()));
+}
+
+fn every_host(local: &LocalActor, targets: &SyncTargets) -> Vec<Actor> {
+    std::iter::once(&local.0)
+        .chain(&targets.0)
+        .cloned()
+        .collect()
+}
+
+async fn fetch(actors: Vec<Actor>, tx: Sender<Reports>) {
+    let mut out = Vec::with_capacity(actors.len());
+    for actor in actors {
+        let report = actor.get_quota().await.map_err(|e| e.to_string());
+        out.push((actor, report));
+    }
+    tx.send(out).await.ok();
+}
+
+/// Rebuilds the list from the newest batch of reports.
+pub(super) fn show(
+    reports: Res<StorageReports>,
+    list: Query<Entity, With<StorageList>>,
+    mut commands: Commands,
+) {
+    let Some(batch) = std::iter::from_fn(|| reports.rx.try_recv().ok()).last() else {
+        return;
+    };
+    let Ok(list) = list.single() else {
+        return;
+    };
+
+    let mut list = commands.entity(list);
+    list.despawn_related::<Children>();
+    list.with_children(|list| {
+        for (actor, report) in batch {
+            let host = actor.host().id.fmt_short();
+            let report = match report {
+                Ok(report) => report,
+                Err(err) => {
+                    list.spawn(label(format!("{host}: {err}")));
+                    continue;
+                }
+            };
+
+            let mut header = format!(
+                "{host}: {} of {}",
+                size(report.bytes_used),
+                size(report.quota_bytes)
+            );
+            if report.uploading > 0 {
+                header += &format!(", {} uploading", size(report.uploading));
+            }
+            list.spawn(label(header));
+
+            let history = &report.history[report.history.len().saturating_sub(HISTORY_SHOWN)..];
+            if history.len() > 1 {
+                let steps = history
+                    .iter()
+                    .map(|s| size(s.bytes_used))
+                    .collect::<Vec<_>>();
+                list.spawn(label(format!("  recent days: {}", steps.join(" > "))));
+            }
+
+            for hosted in report.hosted {
+                let text = format!(
+                    "  doc {} {}",
+                    hosted.ns.fmt_short(),
+                    size(hosted.bytes_used)
+                );
+                spawn_row(
+                    list,
+                    text,
+                    "Unhost",
+                    Release {
+                        actor: actor.clone(),
+                        item:  Item::Doc(hosted.ns),
+                    },
+                );
+            }
+            for pin in report.pins {
+                let text = format!(
+                    "  blob {} {} {}",
+                    &pin.hash.to_hex()[..10],
+                    size(pin.size),
+                    expiry(pin.expires)
+                );
+                spawn_row(
+                    list,
+                    text,
+                    "Unpin",
+                    Release {
+                        actor: actor.clone(),
+                        item:  Item::Pin(pin.hash),
+                    },
+                );
+            }
+        }
+    });
+}
+
+/// Releases the item, then refetches so the list reflects it at once.
+pub(super) fn on_release(
+    act: On<Activate>,
+    releases: Query<&Release>,
+    actors: Query<(&LocalActor, &SyncTargets)>,
+    reports: Res<StorageReports>,
+) {
+    let Ok(release) = releases.get(act.entity) else {
+        return;
+    };
+    let (actor, item) = (release.actor.clone(), release.item);
+    let hosts = actors
+        .single()
+        .map(|(local, targets)| every_host(local, targets))
+        .unwrap_or_default();
+    let tx = reports.tx.clone();
+
+    spawn_async_task(async move {
+        let result = match item {
+            Item::Pin(hash) => actor.unpin_blob(hash).await,
+            Item::Doc(ns) => actor.unhost_doc(ns).await,
+        };
+        if let Err(err) = result {
+            warn!("Failed to release storage: {err:?}");
+        }
+        fetch(hosts, tx).await;
+    });
+}
+
+#[expect(
+    deprecated,
+    reason = "feathers button() BSN requires scene spawning; button_bundle is the transitional API"
+)]
+fn spawn_row(list: &mut ChildSpawnerCommands, text: String, action: &str, release: Release) {
+    list.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        column_gap: Val::Px(8.0),
+        align_items: AlignItems::Center,
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn(label(text));
+        row.spawn(bevy::feathers::controls::button_bundle(
+            ButtonBundleProps::default(),
+            release,
+            Spawn((Text::new(action), ThemedText)),
+        ));
+    });
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[expect(
+    deprecated,
+    reason = "feathers button() BSN requires scene spawning; button_bundle is the transitional API"
+)]
+fn spawn_backup_row(p: &mut ChildSpawnerCommands) {
+    p.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        column_gap: Val::Px(8.0),
+        ..default()
+    })
+    .with_children(|row| {
+        for (backup, text) in [(Backup::Export, "Export"), (Backup::Import, "Import")] {
+            row.spawn(bevy::feathers::controls::button_bundle(
+                ButtonBundleProps::default(),
+                backup,
+                Spawn((Text::new(text), ThemedText)),
+            ));
+        }
+    });
+}
+
+/// Exports the local store to a new archive in [`backups_dir`], or restores
+/// the newest archive there, such as one copied over from another machine.
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn on_backup(act: On<Activate>, buttons: Query<&Backup>, actors: Query<&LocalActor>) {
+    let Ok(&backup) = buttons.get(act.entity) else {
+        return;
+    };
+    let Ok(local) = actors.single() else {
+        return;
+    };
+    let actor = local.0.clone();
+
+    spawn_async_task(async move {
+        let result = match backup {
+            Backup::Export => export(&actor).await,
+            Backup::Import => import(&actor).await,
+        };
+        if let Err(err) = result {
+            warn!("Storage backup failed: {err:?}");
+        }
+    });
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn backups_dir() -> PathBuf {
+    unavi_util::dirs::data_local_dir().join("backups")
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn export(actor: &Actor) -> anyhow::Result<()> {
+    let dir = backups_dir();
+    std::fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = dir.join(format!("{secs}.wdsa"));
+
+    let mut out = BufWriter::new(File::create(&path)?);
+    actor.export_archive(None, &mut out).await?;
+    out.flush()?;
+
+    info!("Exported storage to {}", path.display());
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn import(actor: &Actor) -> anyhow::Result<()> {
+    let dir = backups_dir();
+    let newest = std::fs::read_dir(&dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wdsa"))
+        .max_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+    let Some(path) = newest else {
+        anyhow::bail!("no archive in {}", dir.display());
+    };
+
+    let mut input = BufReader::new(File::open(&path)?);
+    let summary = actor.import_archive(&mut input).await?;
+
+    info!(
+        "Imported {}: {} blobs, {} pins, {} docs, {} entries, {} denied",
+        path.display(),
+        summary.blobs,
+        summary.pins,
+        summary.docs,
+        summary.entries,
+        summary.denied
+    );
+    Ok(())
+}
+
+fn label(text: impl Into<String>) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: FontSize::Px(14.0),
+            ..default()
+        },
+        TextColor(Color::srgb(0.85, 0.9, 0.95)),
+    )
+}
+
+fn size(bytes: i64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    format!("{:.1} MiB", bytes as f64 / MIB)
+}
+
+fn expiry(expires: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs().cast_signed());
+    let hours = (expires - now) / 3600;
+    if hours > 0 {
+        format!("expires in {hours}h")
+    } else {
+        "expiring".to_owned()
+    }
+}
@@ -19,6 +19,7 @@ use bevy_wds::{
     LocalDocs,
     LocalDownloader,
     LocalGossip,
+    Mirrors,
     SyncTargets,
     set_local_actor,
     set_registries,
@@ -43,10 +44,14 @@ use wds::{
     resolve::{
         resolve,
         resolve_allowing_loopback,
+        wds_endpoint,
     },
 };
 use xdid::{
-    core::did::Did,
+    core::{
+        did::Did,
+        document::Document,
+    },
     methods::key::keys::{
         DidKeyPair,
         PublicKey,
@@ -134,7 +139,7 @@ async fn load_store(
         targets,
     } = sync;
 
-    let (sync_targets, unresolved) =
+    let (sync_targets, mirrors, unresolved) =
         resolve_batch(&store, &identity, targets, allow_loopback).await;
     sync_registries(&store, &endpoint, &sync_targets).await;
 
@@ -148,6 +153,7 @@ async fn load_store(
             LocalDocs(store.docs().clone()),
             LocalGossip(store.gossip().clone()),
             SyncTargets(sync_targets.clone()),
+            Mirrors(mirrors.clone()),
         ))
         .await;
 
@@ -156,7 +162,7 @@ async fn load_store(
             store,
             endpoint,
             identity,
-            sync_targets,
+            (sync_targets, mirrors),
             unresolved,
             store_entity,
             allow_loopback,
@@ -216,8 +222,8 @@ async fn sync_registries(store: &DataStore, endpoint: &Endpoint, targets: &[Acto
     set_registry_clients(clients);
 }
 
-/// Resolves every target, returning the ones that answered alongside the DIDs
-/// that did not.
+/// Resolves every target, returning the ones that answered and the mirrors
+/// they list alongside the DIDs that did not.
 ///
 /// An unresolved target is handed to [`retry_sync_targets`] rather than failing
 /// the load: a client with no reachable server still runs peer to peer.
@@ -226,15 +232,17 @@ async fn resolve_batch(
     identity: &Arc<Identity>,
     dids: Vec<String>,
     allow_loopback: bool,
-) -> (Vec<Actor>, Vec<String>) {
+) -> (Vec<Actor>, Vec<EndpointId>, Vec<String>) {
     let mut actors = Vec::new();
+    let mut mirrors = Vec::new();
     let mut unresolved = Vec::new();
 
     for did_str in dids {
         match resolve_sync_target(&did_str, allow_loopback).await {
-            Ok(addr) => {
+            Ok((addr, target_mirrors)) => {
                 info!(target = did_str, "registering WDS sync target");
                 actors.push(store.remote_actor(Arc::clone(identity), addr));
+                mirrors.extend(target_mirrors);
             }
             Err(err) => {
                 warn!(target = did_str, ?err, "failed to resolve WDS sync target");
@@ -243,7 +251,7 @@ async fn resolve_batch(
         }
     }
 
-    (actors, unresolved)
+    (actors, mirrors, unresolved)
 }
 
 /// Keeps resolving the targets that were unreachable at startup, so a server
@@ -252,7 +260,7 @@ async fn retry_sync_targets(
     store: Arc<DataStore>,
     endpoint: Endpoint,
     identity: Arc<Identity>,
-    mut targets: Vec<Actor>,
+    (mut targets, mut mirrors): (Vec<Actor>, Vec<EndpointId>),
     mut unresolved: Vec<String>,
     store_entity: Entity,
     allow_loopback: bool,
@@ -263,22 +271,28 @@ async fn retry_sync_targets(
         n0_future::time::sleep(delay).await;
         delay = (delay * 2).min(MAX_RETRY_DELAY);
 
-        let (actors, pending) = resolve_batch(&store, &identity, unresolved, allow_loopback).await;
+        let (actors, found_mirrors, pending) =
+            resolve_batch(&store, &identity, unresolved, allow_loopback).await;
         unresolved = pending;
 
         if actors.is_empty() {
             continue;
         }
         targets.extend(actors);
+        mirrors.extend(found_mirrors);
 
         sync_registries(&store, &endpoint, &targets).await;
 
         let published = targets.clone();
+        let published_mirrors = mirrors.clone();
         let sent = AsyncCommands::default()
             .push(move |world: &mut World| {
                 if let Some(mut existing) = world.get_mut::<SyncTargets>(store_entity) {
                     existing.0 = published;
                 }
+                if let Some(mut existing) = world.get_mut::<Mirrors>(store_entity) {
+                    existing.0 = published_mirrors;
+                }
             })
             .send()
             .await;
@@ -290,15 +304,18 @@ async fn retry_sync_targets(
     }
 }
 
-async fn resolve_sync_target(did_str: &str, allow_loopback: bool) -> anyhow::Result<EndpointAddr> {
+/// A target's WDS endpoint, and the mirrors its DID document lists for it.
+///
+/// A mirror that fails to resolve is left out rather than failing the target.
+async fn resolve_sync_target(
+    did_str: &str,
+    allow_loopback: bool,
+) -> anyhow::Result<(EndpointAddr, Vec<EndpointId>)> {
     let did = Did::from_str(did_str)?;
 
-    let doc = if allow_loopback {
-        resolve_allowing_loopback(&did).await
-    } else {
-        resolve(&did).await
-    }
-    .ok_or_else(|| anyhow::anyhow!("could not resolve {did}"))?;
+    let doc = resolve_did(&did, allow_loopback)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("could not resolve {did}"))?;
 
     let services = doc.service.unwrap_or_default();
     let wds = services
@@ -312,5 +329,41 @@ async fn resolve_sync_target(did_str: &str, allow_loopback: bool) -> anyhow::Res
         .ok_or_else(|| anyhow::anyhow!("`wds` service has no serviceEndpoint"))?;
 
     let endpoint_id = EndpointId::from_str(endpoint_str)?;
-    Ok(EndpointAddr::from(endpoint_id))
+
+    let mut mirrors = Vec::new();
+    for mirror in wds
+        .service_endpoint
+        .iter()
+        .filter_map(|entry| entry.strip_prefix("mirror:"))
+    {
+        match resolve_mirror(mirror, allow_loopback).await {
+            Ok(id) => mirrors.push(id),
+            Err(err) => warn!(
+                target = did_str,
+                mirror,
+                ?err,
+                "failed to resolve WDS mirror"
+            ),
+        }
+    }
+
+    Ok((EndpointAddr::from(endpoint_id), mirrors))
+}
+
+/// A mirror's endpoint, read from its own DID document rather than trusted
+/// from the target's.
+async fn resolve_mirror(did_str: &str, allow_loopback: bool) -> anyhow::Result<EndpointId> {
+    let did = Did::from_str(did_str)?;
+    let doc = resolve_did(&did, allow_loopback)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("could not resolve {did}"))?;
+    wds_endpoint(&doc).ok_or_else(|| anyhow::anyhow!("no WDS endpoint in DID document"))
+}
+
+async fn resolve_did(did: &Did, allow_loopback: bool) -> Option<Document> {
+    if allow_loopback {
+        resolve_allowing_loopback(did).await
+    } else {
+        resolve(did).await
+    }
 }
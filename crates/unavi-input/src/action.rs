@@ -21,6 +21,10 @@ pub enum Action {
     Look,
     Jump,
     Sprint,
+    /// Held to speak, when voice is set to push-to-talk.
+    Talk,
+    /// Toggles the microphone.
+    Mute,
     Trigger(PointerKind),
     Grip(PointerKind),
     Menu(PointerKind),
@@ -32,6 +36,8 @@ impl Action {
         Self::Look,
         Self::Jump,
         Self::Sprint,
+        Self::Talk,
+        Self::Mute,
         Self::Trigger(PointerKind::Screen),
         Self::Trigger(PointerKind::LeftHand),
         Self::Trigger(PointerKind::RightHand),
@@ -42,7 +48,7 @@ impl Action {
         Self::Menu(PointerKind::LeftHand),
         Self::Menu(PointerKind::RightHand),
     ];
-    pub const COUNT: usize = 4 + 3 * PointerKind::COUNT;
+    pub const COUNT: usize = 6 + 3 * PointerKind::COUNT;
 
     const fn index(self) -> usize {
         match self {
@@ -50,9 +56,11 @@ impl Action {
             Self::Look => 1,
             Self::Jump => 2,
             Self::Sprint => 3,
-            Self::Trigger(kind) => 4 + kind.index(),
-            Self::Grip(kind) => 4 + PointerKind::COUNT + kind.index(),
-            Self::Menu(kind) => 4 + 2 * PointerKind::COUNT + kind.index(),
+            Self::Talk => 4,
+            Self::Mute => 5,
+            Self::Trigger(kind) => 6 + kind.index(),
+            Self::Grip(kind) => 6 + PointerKind::COUNT + kind.index(),
+            Self::Menu(kind) => 6 + 2 * PointerKind::COUNT + kind.index(),
         }
     }
 
@@ -23,6 +23,8 @@ pub struct Bindings {
     pub look:     AxisBinding,
     pub jump:     ButtonBinding,
     pub sprint:   ButtonBinding,
+    pub talk:     ButtonBinding,
+    pub mute:     ButtonBinding,
     pub trigger:  PerPointer<ButtonBinding>,
     pub grip:     PerPointer<ButtonBinding>,
     pub menu:     PerPointer<ButtonBinding>,
@@ -34,23 +36,28 @@ impl Bindings {
     }
 
     pub fn buttons(&self) -> impl Iterator<Item = (Action, &ButtonBinding)> {
-        [(Action::Jump, &self.jump), (Action::Sprint, &self.sprint)]
-            .into_iter()
-            .chain(
-                PointerKind::ALL
-                    .into_iter()
-                    .map(|kind| (Action::Trigger(kind), self.trigger.get(kind))),
-            )
-            .chain(
-                PointerKind::ALL
-                    .into_iter()
-                    .map(|kind| (Action::Grip(kind), self.grip.get(kind))),
-            )
-            .chain(
-                PointerKind::ALL
-                    .into_iter()
-                    .map(|kind| (Action::Menu(kind), self.menu.get(kind))),
-            )
+        [
+            (Action::Jump, &self.jump),
+            (Action::Sprint, &self.sprint),
+            (Action::Talk, &self.talk),
+            (Action::Mute, &self.mute),
+        ]
+        .into_iter()
+        .chain(
+            PointerKind::ALL
+                .into_iter()
+                .map(|kind| (Action::Trigger(kind), self.trigger.get(kind))),
+        )
+        .chain(
+            PointerKind::ALL
+                .into_iter()
+                .map(|kind| (Action::Grip(kind), self.grip.get(kind))),
+        )
+        .chain(
+            PointerKind::ALL
+                .into_iter()
+                .map(|kind| (Action::Menu(kind), self.menu.get(kind))),
+        )
     }
 }
 
@@ -184,6 +191,13 @@ const SPRINT: [(&str, &str); 3] = [
     (WMR_PROFILE, "input/thumbstick/click"),
 ];
 
+/// The left hand's spare face button. A wand has none, as with [`SPRINT`].
+const TALK: [(&str, &str); 3] = [
+    (TOUCH_PROFILE, "input/x/click"),
+    (INDEX_PROFILE, "input/a/click"),
+    (WMR_PROFILE, "input/trackpad/click"),
+];
+
 fn movement() -> AxisBinding {
     AxisBinding {
         dpads: vec![Dpad {
@@ -225,6 +239,25 @@ fn sprint() -> ButtonBinding {
     }
 }
 
+fn talk() -> ButtonBinding {
+    ButtonBinding {
+        keys: vec![KeyCode::KeyV],
+        pad: vec![GamepadButton::DPadUp],
+        xr: xr(LEFT, &TALK),
+        ..default()
+    }
+}
+
+/// Keyboard and pad only: a headset has no button left for a toggle, and a
+/// held [`talk`] covers speaking there.
+fn mute() -> ButtonBinding {
+    ButtonBinding {
+        keys: vec![KeyCode::KeyM],
+        pad: vec![GamepadButton::DPadDown],
+        ..default()
+    }
+}
+
 /// Acting on what is pointed at: the button everything picks with.
 fn trigger() -> PerPointer<ButtonBinding> {
     PerPointer {
@@ -302,6 +335,8 @@ impl Default for Bindings {
             look:     look(),
             jump:     jump(),
             sprint:   sprint(),
+            talk:     talk(),
+            mute:     mute(),
             trigger:  trigger(),
             grip:     grip(),
             menu:     menu(),
@@ -433,8 +468,10 @@ mod tests {
                     // headset binding to find.
                     Action::Menu(_) | Action::Trigger(_) => true,
                     Action::Grip(kind) => kind == PointerKind::Screen,
-                    // Sprint is the one thing a wand has no button left for.
-                    Action::Sprint => profile == VIVE_PROFILE,
+                    // A wand has no button left to sprint or talk with.
+                    Action::Sprint | Action::Talk => profile == VIVE_PROFILE,
+                    // Left to the keyboard and pad.
+                    Action::Mute => true,
                     _ => false,
                 };
                 if skip {
@@ -54,6 +54,8 @@ pub struct BindingsPatch {
     pub look:     AxisPatch,
     pub jump:     ButtonPatch,
     pub sprint:   ButtonPatch,
+    pub talk:     ButtonPatch,
+    pub mute:     ButtonPatch,
     pub trigger:  PerPointer<ButtonPatch>,
     pub grip:     PerPointer<ButtonPatch>,
     pub menu:     PerPointer<ButtonPatch>,
@@ -66,6 +68,8 @@ impl BindingsPatch {
             look:     self.look.apply(base.look),
             jump:     self.jump.apply(base.jump),
             sprint:   self.sprint.apply(base.sprint),
+            talk:     self.talk.apply(base.talk),
+            mute:     self.mute.apply(base.mute),
             trigger:  apply_per_pointer(self.trigger, base.trigger),
             grip:     apply_per_pointer(self.grip, base.grip),
             menu:     apply_per_pointer(self.menu, base.menu),
@@ -80,6 +84,8 @@ impl From<&Bindings> for BindingsPatch {
             look:     (&bindings.look).into(),
             jump:     (&bindings.jump).into(),
             sprint:   (&bindings.sprint).into(),
+            talk:     (&bindings.talk).into(),
+            mute:     (&bindings.mute).into(),
             trigger:  per_pointer_patch(&bindings.trigger),
             grip:     per_pointer_patch(&bindings.grip),
             menu:     per_pointer_patch(&bindings.menu),
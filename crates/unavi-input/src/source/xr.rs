@@ -102,6 +102,8 @@ fn name_of(action: Action) -> String {
         Action::Look => "look".to_owned(),
         Action::Jump => "jump".to_owned(),
         Action::Sprint => "sprint".to_owned(),
+        Action::Talk => "talk".to_owned(),
+        Action::Mute => "mute".to_owned(),
         Action::Trigger(kind) => format!("trigger_{}", kind.name()),
         Action::Grip(kind) => format!("grip_{}", kind.name()),
         Action::Menu(kind) => format!("menu_{}", kind.name()),
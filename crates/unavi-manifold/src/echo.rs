@@ -1,5 +1,6 @@
 use avian3d::prelude::{
     Collider,
+    CollisionLayers,
     RigidBody,
 };
 use bevy::{
@@ -299,9 +300,13 @@ fn spawn_echo_subtree(world: &mut World, body: Entity, seam: Entity, pose: Trans
         if source == body {
             world.entity_mut(clone).insert(SeamEcho { body, seam });
             if let Some(collider) = world.get::<Collider>(source).cloned() {
+                let layers = world
+                    .get::<CollisionLayers>(source)
+                    .copied()
+                    .unwrap_or_default();
                 world
                     .entity_mut(clone)
-                    .insert((collider, RigidBody::Kinematic));
+                    .insert((collider, layers, RigidBody::Kinematic));
             }
         } else if let Some(&parent) = world
             .get::<ChildOf>(source)
@@ -27,6 +27,13 @@ pub fn vec3(v: [f32; 3]) -> Option<Vec3> {
     v.is_finite().then_some(v)
 }
 
+/// An axis, returned normalized, and only if it points somewhere. A zero
+/// axis normalizes to NaN, the same way the zero quaternion does.
+#[must_use]
+pub fn direction(v: [f32; 3]) -> Option<Vec3> {
+    vec3(v)?.try_normalize()
+}
+
 /// A rotation, returned normalized, and only if it names one.
 ///
 /// The zero quaternion is the case worth naming: it is the default a guest
@@ -46,6 +53,7 @@ mod tests {
     };
 
     use super::{
+        direction,
         nonneg,
         positive,
         quat,
@@ -82,6 +90,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn an_axis_comes_back_normalized_or_not_at_all() {
+        assert_eq!(direction([0.0, 3.0, 0.0]), Some(Vec3::Y));
+        assert_eq!(direction([0.0; 3]), None, "the zero axis was accepted");
+        assert_eq!(direction([f32::NAN, 1.0, 0.0]), None);
+    }
+
     #[test]
     fn zero_is_not_positive_but_is_nonneg() {
         assert!(!positive(0.0));
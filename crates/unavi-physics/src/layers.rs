@@ -0,0 +1,13 @@
+//! The engine's collision layers, as avian masks.
+//!
+//! These are the low bits of a scene's layer space and must stay in the order
+//! `hsd::layers::BUILTIN` names them; a document's own layers sit above.
+
+use avian3d::prelude::LayerMask;
+
+/// Where every collider without memberships of its own sits.
+pub const DEFAULT: LayerMask = LayerMask(1);
+/// Agent bodies, so a blocker can stop agents and nothing else.
+pub const AGENT: LayerMask = LayerMask(1 << 1);
+/// Volumes meant for pointers and queries rather than bodies.
+pub const TOOL: LayerMask = LayerMask(1 << 2);
@@ -4,6 +4,7 @@ use bevy::prelude::*;
 pub mod body;
 mod collider_tree;
 pub mod finite;
+pub mod layers;
 pub mod shape;
 
 /// Avian, plus the guards that keep a scene or script from corrupting it.
@@ -8,6 +8,10 @@ use crate::tier::Tier;
 /// a false statement about what the system protects.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ApiName {
+    /// Playing, stopping and seeking emitters in documents it may write.
+    Audio,
+    /// Reading the space's chat, and posting to it signed as the local user.
+    Chat,
     CreateDocument,
     Event,
     /// Reading the local user's own durable identifiers.
@@ -80,6 +84,7 @@ impl DocumentPolicy {
         Self::new(
             Tier::Untrusted,
             ApiSet::none()
+                .with(ApiName::Audio)
                 .with(ApiName::Event)
                 .with(ApiName::Input)
                 .with(ApiName::Kv)
@@ -94,6 +99,8 @@ impl DocumentPolicy {
         Self::new(
             Tier::Space,
             ApiSet::none()
+                .with(ApiName::Audio)
+                .with(ApiName::Chat)
                 .with(ApiName::CreateDocument)
                 .with(ApiName::Event)
                 .with(ApiName::Identity)
@@ -111,6 +118,8 @@ impl DocumentPolicy {
         Self::new(
             Tier::System,
             ApiSet::none()
+                .with(ApiName::Audio)
+                .with(ApiName::Chat)
                 .with(ApiName::CreateDocument)
                 .with(ApiName::Event)
                 .with(ApiName::Identity)
@@ -157,6 +166,7 @@ mod tests {
     fn untrusted_content_reaches_no_privileged_api() {
         let policy = DocumentPolicy::untrusted();
         for name in [
+            ApiName::Chat,
             ApiName::CreateDocument,
             ApiName::Identity,
             ApiName::InputContext,
@@ -187,6 +197,8 @@ mod tests {
     #[test]
     fn each_api_name_occupies_its_own_bit() {
         let all = [
+            ApiName::Audio,
+            ApiName::Chat,
             ApiName::CreateDocument,
             ApiName::Event,
             ApiName::Identity,
@@ -6,12 +6,17 @@ use std::{
 use parking_lot::RwLock;
 use xdid::core::did::Did;
 
+struct Binding {
+    did:     Did,
+    relayed: bool,
+}
+
 /// Maps a peer's endpoint id to the DID it has proven it controls.
 ///
-/// Only a completed challenge-response over that peer's own connection may
-/// write here. A DID announced elsewhere is a claim anyone can make about
-/// anyone, so an unbound peer is indistinguishable from an anonymous one.
-static BINDINGS: LazyLock<RwLock<HashMap<[u8; 32], Did>>> = LazyLock::new(RwLock::default);
+/// Only a completed challenge-response may write here. A DID announced
+/// elsewhere is a claim anyone can make about anyone, so an unbound peer is
+/// indistinguishable from an anonymous one.
+static BINDINGS: LazyLock<RwLock<HashMap<[u8; 32], Binding>>> = LazyLock::new(RwLock::default);
 
 /// The local user's own DID, which is the ego node every trust score is
 /// measured from.
@@ -26,8 +31,28 @@ pub fn self_did() -> Option<Did> {
     SELF.read().clone()
 }
 
+/// Binds a DID proven over the peer's own connection.
 pub fn bind(peer: [u8; 32], did: Did) {
-    BINDINGS.write().insert(peer, did);
+    BINDINGS.write().insert(
+        peer,
+        Binding {
+            did,
+            relayed: false,
+        },
+    );
+}
+
+/// Binds a DID proven through a space relay.
+///
+/// The proof is genuine, but the relay decides which streams it attributes to
+/// `peer`, so it could speak as them. The binding is good enough to block
+/// them by, and to name them, but not to trust them by. A binding already made
+/// directly is left as it is.
+pub fn bind_relayed(peer: [u8; 32], did: Did) {
+    BINDINGS
+        .write()
+        .entry(peer)
+        .or_insert(Binding { did, relayed: true });
 }
 
 pub fn unbind(peer: [u8; 32]) {
@@ -36,5 +61,17 @@ pub fn unbind(peer: [u8; 32]) {
 
 #[must_use]
 pub fn did_of(peer: [u8; 32]) -> Option<Did> {
-    BINDINGS.read().get(&peer).cloned()
+    BINDINGS
+        .read()
+        .get(&peer)
+        .map(|binding| binding.did.clone())
+}
+
+/// Whether `peer`'s DID was only proven through a relay.
+#[must_use]
+pub fn is_relayed(peer: [u8; 32]) -> bool {
+    BINDINGS
+        .read()
+        .get(&peer)
+        .is_some_and(|binding| binding.relayed)
 }
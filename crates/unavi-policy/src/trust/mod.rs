@@ -79,10 +79,16 @@ static COMPUTED: LazyLock<RwLock<HashMap<Did, Trust>>> =
 ///
 /// A peer that has proved no DID cannot rise above [`Trust::Guest`]: an
 /// unproven claim is not an identity, so there is nothing to have an opinion
-/// about.
+/// about. One proven only through a relay cannot either, since the relay
+/// could be speaking as them; a block on their DID still holds.
 #[must_use]
 pub fn of_peer(peer: [u8; 32]) -> Trust {
-    identity::did_of(peer).map_or(Trust::Guest, |did| of_did(&did))
+    let trust = identity::did_of(peer).map_or(Trust::Guest, |did| of_did(&did));
+    if identity::is_relayed(peer) {
+        trust.min(Trust::Guest)
+    } else {
+        trust
+    }
 }
 
 /// What the user said, else what the graph worked out, else the default.
@@ -348,6 +354,32 @@ mod tests {
         identity::unbind([2; 32]);
     }
 
+    #[test]
+    fn a_relayed_proof_can_lower_a_peer_but_not_raise_them() {
+        let _guard = TEST_LOCK.lock().expect("test lock");
+        let friend = Did::from_str("did:web:friend.example").expect("did");
+        let blocked = Did::from_str("did:web:blocked.example").expect("did");
+        set_override(friend.clone(), Trust::Trusted);
+        set_override(blocked.clone(), Trust::Blocked);
+
+        identity::bind_relayed([4; 32], friend.clone());
+        identity::bind_relayed([5; 32], blocked.clone());
+        assert_eq!(of_peer([4; 32]), Trust::Guest);
+        assert_eq!(of_peer([5; 32]), Trust::Blocked);
+
+        identity::bind([4; 32], friend.clone());
+        assert_eq!(
+            of_peer([4; 32]),
+            Trust::Trusted,
+            "a proof over the peer's own connection is trusted"
+        );
+
+        identity::unbind([4; 32]);
+        identity::unbind([5; 32]);
+        clear_override(&friend);
+        clear_override(&blocked);
+    }
+
     #[test]
     fn a_users_own_decision_beats_what_the_graph_worked_out() {
         let _guard = TEST_LOCK.lock().expect("test lock");
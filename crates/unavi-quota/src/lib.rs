@@ -10,7 +10,10 @@ use web_time::Instant;
 pub mod limits;
 pub mod registry;
 
-use crate::limits::Limits;
+use crate::limits::{
+    FlowLimit,
+    Limits,
+};
 
 /// A countable, releasable resource: held while live, refunded when freed.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -40,20 +43,36 @@ impl Stock {
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Flow {
     BlobUpload,
+    /// One message posted to a space's chat.
+    Chat,
+    /// Script execution, in wasmtime fuel: about one unit per wasm operator.
+    /// Charged after a call for what it burned, so a bucket may run negative
+    /// and the script waits out the debt.
+    Cpu,
     CreateDocument,
     CreatePrim,
     Emit,
     PortalOpen,
+    /// Bytes a space relay copies on someone's behalf, counted once per
+    /// member they reach.
+    RelayBytes,
+    /// A member joining a relay room, which has everyone already there
+    /// reopen their streams.
+    RelayJoin,
     SyncDoc,
 }
 
 impl Flow {
-    pub const ALL: [Self; 6] = [
+    pub const ALL: [Self; 10] = [
         Self::BlobUpload,
+        Self::Chat,
+        Self::Cpu,
         Self::CreateDocument,
         Self::CreatePrim,
         Self::Emit,
         Self::PortalOpen,
+        Self::RelayBytes,
+        Self::RelayJoin,
         Self::SyncDoc,
     ];
 }
@@ -264,13 +283,26 @@ impl Quota {
             return Reservation::Never;
         }
 
+        let tokens = self.tokens_local(flow, limit, now);
+        if tokens >= n {
+            Reservation::Ready
+        } else if limit.refill_per_sec <= 0.0 {
+            Reservation::Never
+        } else {
+            Reservation::After(Duration::from_secs_f64((n - tokens) / limit.refill_per_sec))
+        }
+    }
+
+    /// What this level's `flow` bucket holds at `now`.
+    ///
+    /// Materialising elapsed time is not a take: it is what the bucket
+    /// already holds, so doing it under a peek is safe.
+    fn tokens_local(&self, flow: Flow, limit: FlowLimit, now: Instant) -> f64 {
         let mut buckets = self.buckets.lock();
         let bucket = buckets.entry(flow).or_insert(Bucket {
             tokens: limit.capacity,
             last:   now,
         });
-        // Materialising elapsed time is not a take: it is what the bucket
-        // already holds, so doing it under a peek is safe.
         let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
         bucket.tokens = elapsed
             .mul_add(limit.refill_per_sec, bucket.tokens)
@@ -278,20 +310,37 @@ impl Quota {
         bucket.last = now;
         let tokens = bucket.tokens;
         drop(buckets);
+        tokens
+    }
 
-        if tokens >= n {
-            Reservation::Ready
-        } else if limit.refill_per_sec <= 0.0 {
-            Reservation::Never
-        } else {
-            Reservation::After(Duration::from_secs_f64((n - tokens) / limit.refill_per_sec))
+    /// The most of `flow` every level of the chain could give right now, or
+    /// `None` if no level caps it. Negative while a level is in debt.
+    #[must_use]
+    pub fn available(&self, flow: Flow) -> Option<f64> {
+        self.available_inner(flow, Instant::now())
+    }
+
+    fn available_inner(&self, flow: Flow, now: Instant) -> Option<f64> {
+        let here = self
+            .limits
+            .flow
+            .get(&flow)
+            .copied()
+            .map(|limit| self.tokens_local(flow, limit, now));
+        let above = self
+            .owner()
+            .and_then(|owner| owner.available_inner(flow, now));
+        match (here, above) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
         }
     }
 
     /// Takes `n` at every level that caps `flow`.
     ///
     /// Only sound immediately after a [`Reservation::Ready`]; anything else may
-    /// drive a bucket negative.
+    /// drive a bucket negative. [`Flow::Cpu`] does so on purpose, charging
+    /// what a call used once it has returned.
     pub fn commit(&self, flow: Flow, n: f64) {
         if let Some(limit) = self.limits.flow.get(&flow).copied() {
             // Materialised here as well as in `reserve`: a bucket that does not
@@ -677,4 +726,19 @@ mod tests {
             Reservation::After(_)
         ));
     }
+
+    #[test]
+    fn available_is_the_tightest_level_and_may_run_into_debt() {
+        let peer = Quota::root(limits_flow(Flow::Cpu, 100.0, 0.0));
+        let doc = Quota::new(limits_flow(Flow::Cpu, 40.0, 0.0), Some(Arc::clone(&peer)));
+        assert_eq!(doc.available(Flow::Cpu), Some(40.0));
+
+        peer.commit(Flow::Cpu, 90.0);
+        assert_eq!(doc.available(Flow::Cpu), Some(10.0), "the peer governs");
+
+        doc.commit(Flow::Cpu, 30.0);
+        assert_eq!(doc.available(Flow::Cpu), Some(-20.0));
+        assert!(matches!(doc.reserve(Flow::Cpu, 1.0), Reservation::Never));
+        assert_eq!(Quota::unlimited().available(Flow::Cpu), None);
+    }
 }
@@ -55,9 +55,46 @@ pub const MAX_NAME_BYTES: usize = KB;
 /// at all.
 pub const MAX_TEXT_BYTES: usize = 4 * KB;
 
+/// Largest body a single chat message may carry.
+pub const MAX_CHAT_BYTES: usize = KB;
+
 /// Largest vertex/index stream a single mesh write may upload.
 pub const MAX_MESH_ELEMENTS: usize = 4 * MB;
 
+/// Shadow-casting lights one document may realize.
+///
+/// A shadow map is redrawn every frame, six of them for a point light, so this
+/// bounds render cost rather than memory. Lights past it still shine; they
+/// cast nothing.
+pub const MAX_SHADOW_LIGHTS: usize = 4;
+
+/// Wasmtime fuel a script burns in roughly a millisecond.
+///
+/// Fuel counts wasm operators, not cycles, so this is an estimate; it sets the
+/// scale of the [`Flow::Cpu`] limits below rather than promising a wall-clock
+/// bound.
+pub const FUEL_PER_MS: f64 = 1_000_000.0;
+
+/// A [`Flow::Cpu`] limit of `burst_ms` at once and `ms_per_sec` sustained.
+const fn cpu(burst_ms: f64, ms_per_sec: f64) -> FlowLimit {
+    FlowLimit {
+        capacity:       burst_ms * FUEL_PER_MS,
+        refill_per_sec: ms_per_sec * FUEL_PER_MS,
+    }
+}
+
+/// A [`Flow::RelayBytes`] limit of `burst_mb` at once and `mb_per_sec`
+/// sustained.
+///
+/// Every burst must hold the largest frame a relay passes, or a state frame
+/// that size could never be sent at all.
+const fn relay_bytes(burst_mb: f64, mb_per_sec: f64) -> FlowLimit {
+    FlowLimit {
+        capacity:       burst_mb * MB as f64,
+        refill_per_sec: mb_per_sec * MB as f64,
+    }
+}
+
 /// Fraction of host RAM the combined wasm memory of every script may occupy.
 const GLOBAL_WASM_MEMORY_PERCENT: u64 = 30;
 
@@ -91,7 +128,14 @@ impl Limits {
                 | Stock::Slots => None,
             },
             |flow| match flow {
+                Flow::RelayBytes => Some(relay_bytes(256.0, 64.0)),
+                Flow::RelayJoin => Some(FlowLimit {
+                    capacity:       64.0,
+                    refill_per_sec: 8.0,
+                }),
                 Flow::BlobUpload
+                | Flow::Chat
+                | Flow::Cpu
                 | Flow::CreateDocument
                 | Flow::CreatePrim
                 | Flow::Emit
@@ -129,7 +173,14 @@ impl Limits {
                     capacity:       256.0,
                     refill_per_sec: 32.0,
                 }),
-                Flow::PortalOpen | Flow::SyncDoc => None,
+                // A bot that answers, not one that floods.
+                Flow::Chat => Some(FlowLimit {
+                    capacity:       4.0,
+                    refill_per_sec: 0.5,
+                }),
+                // A tenth of a core: at 60 fps, under 2 ms a frame.
+                Flow::Cpu => Some(cpu(20.0, 100.0)),
+                Flow::PortalOpen | Flow::RelayBytes | Flow::RelayJoin | Flow::SyncDoc => None,
             },
         )
     }
@@ -171,6 +222,17 @@ impl Limits {
                     capacity:       4_096.0,
                     refill_per_sec: 256.0,
                 }),
+                Flow::Chat => Some(FlowLimit {
+                    capacity:       60.0,
+                    refill_per_sec: 4.0,
+                }),
+                Flow::Cpu => Some(cpu(120.0, 500.0)),
+                Flow::RelayBytes => Some(relay_bytes(64.0, 16.0)),
+                // Each join resyncs the whole room, so a room churns slowly.
+                Flow::RelayJoin => Some(FlowLimit {
+                    capacity:       16.0,
+                    refill_per_sec: 1.0,
+                }),
             },
         )
     }
@@ -212,6 +274,16 @@ impl Limits {
                     capacity:       2_048.0,
                     refill_per_sec: 128.0,
                 }),
+                // Faster than anyone types, slower than a paste loop.
+                Flow::Chat => Some(FlowLimit {
+                    capacity:       20.0,
+                    refill_per_sec: 2.0,
+                }),
+                Flow::Cpu => Some(cpu(60.0, 250.0)),
+                Flow::RelayBytes => Some(relay_bytes(16.0, 2.0)),
+                // Counted by the room a peer joins, not the peer: an endpoint
+                // is free to mint, so a per-peer count bounds nothing.
+                Flow::RelayJoin => None,
             },
         )
     }
@@ -260,6 +332,21 @@ mod tests {
         );
     }
 
+    /// One busy document must leave most of a frame to everything else.
+    #[test]
+    fn a_document_cannot_spend_a_frame_on_script() {
+        const FRAME_MS: f64 = 1000.0 / 60.0;
+        let cpu = *Limits::document()
+            .flow
+            .get(&Flow::Cpu)
+            .expect("documents meter script CPU");
+        let per_frame_ms = cpu.refill_per_sec / FUEL_PER_MS / 60.0;
+        assert!(
+            per_frame_ms < FRAME_MS / 4.0,
+            "a document sustains {per_frame_ms} ms of script every frame"
+        );
+    }
+
     #[test]
     fn global_caps_wasm_memory_at_a_fraction_of_host_ram() {
         let total = host_total_memory();
@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
 
 use anyhow::Context;
 use iroh::{
@@ -22,28 +28,49 @@ use wds::{
 use crate::{
     control::{
         ALPN,
+        ActAs,
         Announce,
+        Dismiss,
+        Favourites,
+        Flag,
         Occupants,
+        Rate,
         RegistryService,
+        Reports,
         Retract,
+        Search,
         Submit,
         Views,
     },
     entry::{
         Presence,
+        Rating,
+        Report,
         Submission,
     },
+    error::RegistryError,
+    feedback::QueuedReport,
+    search::{
+        SearchPage,
+        SearchQuery,
+    },
     views::ViewIds,
 };
 
 /// Client handle for one registry.
 ///
-/// Authentication rides on the node's existing WDS session.
+/// Authentication rides on the actor's link to the node's WDS: the registry
+/// knows a caller by a DID its endpoint proved there, so the actor must be a
+/// remote one on the same endpoint as this client. This client's own link
+/// then names the actor's DID as the one it acts as.
 #[derive(Clone)]
 pub struct RegistryClient {
     actor:    Actor,
     client:   Client<RegistryService>,
     identity: Arc<Identity>,
+    /// Whether the registry holds this client's link as acting for the actor.
+    /// Shared by clones, which use the same link.
+    acting:   Arc<AtomicBool>,
 }
 
 impl RegistryClient {
@@ -55,18 +82,58 @@ impl RegistryClient {
             actor,
             client,
             identity,
+            acting: Arc::default(),
         }
     }
 
-    /// Wraps an in-process registry, skipping the network entirely.
-    #[must_use]
-    pub fn local(client: Client<RegistryService>, actor: Actor) -> Self {
-        let identity = Arc::clone(actor.identity());
-        Self {
-            actor,
-            client,
-            identity,
+    /// Authenticates the actor with the store, then names its DID on this
+    /// client's link to the registry.
+    async fn authenticate(&self) -> anyhow::Result<()> {
+        self.actor.authenticate().await?;
+        if self.acting.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        self.client
+            .rpc(ActAs {
+                did: self.identity.did().clone(),
+            })
+            .await?
+            .map_err(|e| anyhow::anyhow!("act as failed: {e}"))?;
+
+        self.acting.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Authenticates, then makes the call `send` sends. A registry that no
+    /// longer knows the link refuses with `Unauthenticated`, so the call is
+    /// sent once more after naming the DID afresh.
+    async fn call<T, E, Fut>(&self, what: &str, send: impl Fn() -> Fut) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = Result<Result<T, RegistryError>, E>>,
+        anyhow::Error: From<E>,
+    {
+        self.authenticate().await.context("auth")?;
+        match send().await? {
+            Ok(value) => return Ok(value),
+            Err(RegistryError::Unauthenticated) => {
+                self.acting.store(false, Ordering::Release);
+            }
+            Err(err) => return Err(anyhow::anyhow!("{what} failed: {err}")),
+        }
+
+        self.authenticate().await.context("auth")?;
+        send().await?.map_err(|e| self.failed(what, e))
+    }
+
+    /// The error for a refused `what`. A registry that no longer knows the
+    /// link refuses with `Unauthenticated`, so the next call names the DID
+    /// afresh.
+    fn failed(&self, what: &str, err: RegistryError) -> anyhow::Error {
+        if matches!(err, RegistryError::Unauthenticated) {
+            self.acting.store(false, Ordering::Release);
         }
+        anyhow::anyhow!("{what} failed: {err}")
     }
 
     fn sign<T: Signable>(&self, payload: &T) -> anyhow::Result<SignedBytes<T>> {
@@ -76,69 +143,99 @@ impl RegistryClient {
     }
 
     pub async fn submit(&self, submission: &Submission) -> anyhow::Result<()> {
-        let s = self.actor.session().await.context("auth")?;
         let submission = self.sign(submission)?;
 
-        self.client
-            .rpc(Submit { s, submission })
-            .await?
-            .map_err(|e| anyhow::anyhow!("submit failed: {e}"))?;
+        self.call("submit", || {
+            self.client.rpc(Submit {
+                submission: submission.clone(),
+            })
+        })
+        .await?;
 
         Ok(())
     }
 
     pub async fn retract(&self, ns: NamespaceId) -> anyhow::Result<()> {
-        let s = self.actor.session().await.context("auth")?;
-
-        self.client
-            .rpc(Retract { s, ns })
-            .await?
-            .map_err(|e| anyhow::anyhow!("retract failed: {e}"))?;
+        self.call("retract", || self.client.rpc(Retract { ns }))
+            .await?;
 
         Ok(())
     }
 
     pub async fn announce(&self, presence: &Presence) -> anyhow::Result<()> {
-        let s = self.actor.session().await.context("auth")?;
         let presence = self.sign(presence)?;
 
-        self.client
-            .rpc(Announce { s, presence })
-            .await?
-            .map_err(|e| anyhow::anyhow!("announce failed: {e}"))?;
+        self.call("announce", || {
+            self.client.rpc(Announce {
+                presence: presence.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn rate(&self, rating: &Rating) -> anyhow::Result<()> {
+        let rating = self.sign(rating)?;
+
+        self.call("rate", || {
+            self.client.rpc(Rate {
+                rating: rating.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn report(&self, report: &Report) -> anyhow::Result<()> {
+        let report = self.sign(report)?;
+
+        self.call("report", || {
+            self.client.rpc(Flag {
+                report: report.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn favourites(&self) -> anyhow::Result<Vec<NamespaceId>> {
+        let favourites = self
+            .call("favourites", || self.client.rpc(Favourites))
+            .await?;
+
+        Ok(favourites)
+    }
+
+    /// The moderation queue; the caller must be one of the registry's
+    /// operators. Each report is returned as signed, unverified.
+    pub async fn reports(&self) -> anyhow::Result<Vec<QueuedReport>> {
+        let queue = self.call("reports", || self.client.rpc(Reports)).await?;
+
+        Ok(queue)
+    }
+
+    pub async fn dismiss(&self, ns: NamespaceId) -> anyhow::Result<()> {
+        self.call("dismiss", || self.client.rpc(Dismiss { ns }))
+            .await?;
 
         Ok(())
     }
 
     /// Occupants of a namespace, verified against each announcer's DID.
     pub async fn occupants(&self, ns: NamespaceId) -> anyhow::Result<Vec<Presence>> {
-        let signed = self
-            .client
-            .rpc(Occupants { ns })
-            .await?
-            .map_err(|e| anyhow::anyhow!("occupants failed: {e}"))?;
-
-        let mut out = Vec::new();
-        for entry in signed {
-            let Ok(presence) = entry.payload() else {
-                continue;
-            };
-            if wds::signed_bytes::verify_did_signature(&entry, &presence.did).await {
-                out.push(presence);
-            }
-        }
-
-        Ok(out)
+        occupants(&self.client, ns).await
     }
 
     pub async fn views(&self) -> anyhow::Result<ViewIds> {
-        let ids = self
-            .client
-            .rpc(Views)
-            .await?
-            .map_err(|e| anyhow::anyhow!("views failed: {e}"))?;
+        views(&self.client).await
+    }
 
-        Ok(ids)
+    /// One page of submissions matching `query`, best match first.
+    pub async fn search(&self, query: SearchQuery) -> anyhow::Result<SearchPage> {
+        search(&self.client, query).await
     }
 
     /// Imports this registry's view docs read-only and starts syncing them,
@@ -148,7 +245,13 @@ impl RegistryClient {
         let host = self.actor.host().clone();
         let mut synced = Vec::new();
 
-        for ns in [ids.recent, ids.featured, ids.categories, ids.active] {
+        for ns in [
+            ids.recent,
+            ids.featured,
+            ids.categories,
+            ids.active,
+            ids.top,
+        ] {
             let doc = wds::docs::ensure_open(docs, ns).await?;
             doc.start_sync(vec![host.clone()]).await?;
             synced.push(ns);
@@ -157,3 +260,76 @@ impl RegistryClient {
         Ok(synced)
     }
 }
+
+/// Client handle for an in-process registry, skipping the network entirely.
+///
+/// In-process calls carry no identity, so this offers only the calls that
+/// need none. Its view docs are the node's own, so there is nothing to sync.
+#[derive(Clone)]
+pub struct LocalRegistryClient {
+    client: Client<RegistryService>,
+}
+
+impl LocalRegistryClient {
+    #[must_use]
+    pub const fn new(client: Client<RegistryService>) -> Self {
+        Self { client }
+    }
+
+    /// Occupants of a namespace, verified against each announcer's DID.
+    pub async fn occupants(&self, ns: NamespaceId) -> anyhow::Result<Vec<Presence>> {
+        occupants(&self.client, ns).await
+    }
+
+    pub async fn views(&self) -> anyhow::Result<ViewIds> {
+        views(&self.client).await
+    }
+
+    /// One page of submissions matching `query`, best match first.
+    pub async fn search(&self, query: SearchQuery) -> anyhow::Result<SearchPage> {
+        search(&self.client, query).await
+    }
+}
+
+async fn occupants(
+    client: &Client<RegistryService>,
+    ns: NamespaceId,
+) -> anyhow::Result<Vec<Presence>> {
+    let signed = client
+        .rpc(Occupants { ns })
+        .await?
+        .map_err(|e| anyhow::anyhow!("occupants failed: {e}"))?;
+
+    let mut out = Vec::new();
+    for entry in signed {
+        let Ok(presence) = entry.payload() else {
+            continue;
+        };
+        if wds::signed_bytes::verify_did_signature(&entry, &presence.did).await {
+            out.push(presence);
+        }
+    }
+
+    Ok(out)
+}
+
+async fn views(client: &Client<RegistryService>) -> anyhow::Result<ViewIds> {
+    let ids = client
+        .rpc(Views)
+        .await?
+        .map_err(|e| anyhow::anyhow!("views failed: {e}"))?;
+
+    Ok(ids)
+}
+
+async fn search(
+    client: &Client<RegistryService>,
+    query: SearchQuery,
+) -> anyhow::Result<SearchPage> {
+    let page = client
+        .rpc(Search { query })
+        .await?
+        .map_err(|e| anyhow::anyhow!("search failed: {e}"))?;
+
+    Ok(page)
+}
@@ -31,6 +31,25 @@ pub struct Config {
     pub max_retention:           Duration,
     /// Abuse bound on catalog growth.
     pub max_submissions_per_did: usize,
+    /// DIDs whose ratings and reports the operator counts for more.
+    pub trusted:                 Vec<Did>,
+    /// How much a trusted DID's rating or report counts.
+    pub trusted_weight:          f64,
+    /// How much a rating from a DID not in `trusted` counts towards a
+    /// namespace's standing. Nothing by default, for the same reason as
+    /// `unlisted_report_weight`: a few throwaway raters would otherwise lift
+    /// any space to the top.
+    pub unlisted_rating_weight:  f64,
+    /// How much a report from a DID not in `trusted` counts towards
+    /// `report_threshold`. Nothing by default: a `did:key` costs nothing to
+    /// mint, so a few throwaway reporters would otherwise hide any space.
+    /// Their reports still reach the operator's queue.
+    pub unlisted_report_weight:  f64,
+    /// Ratings and reports one DID may send per hour.
+    pub max_feedback_per_hour:   u32,
+    /// Weighted reports that hold a namespace out of the top view until the
+    /// operator dismisses them.
+    pub report_threshold:        f64,
 }
 
 impl Default for Config {
@@ -43,6 +62,12 @@ impl Default for Config {
             activity_window:         Duration::from_mins(5),
             max_retention:           Duration::from_hours(24 * 30),
             max_submissions_per_did: 64,
+            trusted:                 Vec::new(),
+            trusted_weight:          5.0,
+            unlisted_rating_weight:  0.0,
+            unlisted_report_weight:  0.0,
+            max_feedback_per_hour:   60,
+            report_threshold:        3.0,
         }
     }
 }
@@ -55,4 +80,24 @@ impl Config {
             Submitters::Allowlist(allowed) => allowed.contains(did),
         }
     }
+
+    /// How much `did`'s ratings count towards a namespace's standing.
+    #[must_use]
+    pub fn rating_weight(&self, did: &Did) -> f64 {
+        if self.trusted.contains(did) {
+            self.trusted_weight
+        } else {
+            self.unlisted_rating_weight
+        }
+    }
+
+    /// How much `did`'s reports count towards holding an entry back.
+    #[must_use]
+    pub fn report_weight(&self, did: &Did) -> f64 {
+        if self.trusted.contains(did) {
+            self.trusted_weight
+        } else {
+            self.unlisted_report_weight
+        }
+    }
 }
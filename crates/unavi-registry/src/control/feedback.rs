@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use iroh_docs::NamespaceId;
+use irpc::WithChannels;
+use wds::{
+    denylist::DenyEntry,
+    signed_bytes::{
+        Signable,
+        SignedBytes,
+        verify_did_signature,
+    },
+};
+use xdid::core::did::Did;
+
+use crate::{
+    RegistryContext,
+    control::{
+        Dismiss,
+        Favourites,
+        Flag,
+        Rate,
+        RegistryService,
+        Reports,
+    },
+    entry::{
+        MAX_DETAIL_BYTES,
+        MAX_SCORE,
+    },
+    error::RegistryError,
+};
+
+/// Why a rating or report `did` signed about `ns` is refused, if it is.
+///
+/// Checked last, the caller's hourly allowance is only spent on one that is
+/// otherwise accepted.
+async fn refusal<T: Signable + Sync>(
+    ctx: &RegistryContext,
+    caller: &Did,
+    signed: &SignedBytes<T>,
+    did: &Did,
+    ns: NamespaceId,
+) -> anyhow::Result<Option<RegistryError>> {
+    if did != caller {
+        return Ok(Some(RegistryError::NotPermitted));
+    }
+    if !verify_did_signature(signed, did).await {
+        return Ok(Some(RegistryError::InvalidSignature));
+    }
+    let denied = vec![DenyEntry::Did(did.clone()), DenyEntry::Namespace(ns)];
+    if ctx.store.denies(denied).await? {
+        return Ok(Some(RegistryError::Denied));
+    }
+    if !ctx.index.contains(ns).await? {
+        return Ok(Some(RegistryError::NotListed));
+    }
+    if !ctx
+        .feedback
+        .try_send(did, ctx.config.max_feedback_per_hour)
+        .await?
+    {
+        return Ok(Some(RegistryError::RateLimited));
+    }
+    Ok(None)
+}
+
+pub async fn rate(
+    ctx: Arc<RegistryContext>,
+    did: Did,
+    WithChannels { inner, tx, .. }: WithChannels<Rate, RegistryService>,
+) -> anyhow::Result<()> {
+    let Ok(rating) = inner.rating.payload() else {
+        tx.send(Err(RegistryError::Malformed)).await?;
+        return Ok(());
+    };
+    if !(1..=MAX_SCORE).contains(&rating.score) {
+        tx.send(Err(RegistryError::Malformed)).await?;
+        return Ok(());
+    }
+
+    if let Some(err) = refusal(&ctx, &did, &inner.rating, &rating.did, rating.ns).await? {
+        tx.send(Err(err)).await?;
+        return Ok(());
+    }
+
+    ctx.feedback.rate(&rating).await?;
+    ctx.request_rebuild();
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+pub async fn flag(
+    ctx: Arc<RegistryContext>,
+    did: Did,
+    WithChannels { inner, tx, .. }: WithChannels<Flag, RegistryService>,
+) -> anyhow::Result<()> {
+    let Ok(report) = inner.report.payload() else {
+        tx.send(Err(RegistryError::Malformed)).await?;
+        return Ok(());
+    };
+    if report
+        .detail
+        .as_ref()
+        .is_some_and(|detail| detail.len() > MAX_DETAIL_BYTES)
+    {
+        tx.send(Err(RegistryError::Malformed)).await?;
+        return Ok(());
+    }
+
+    if let Some(err) = refusal(&ctx, &did, &inner.report, &report.did, report.ns).await? {
+        tx.send(Err(err)).await?;
+        return Ok(());
+    }
+
+    ctx.feedback.report(&report, &inner.report).await?;
+    // Enough weight holds the namespace out of the top view.
+    ctx.request_rebuild();
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+pub async fn favourites(
+    ctx: Arc<RegistryContext>,
+    did: Did,
+    WithChannels { tx, .. }: WithChannels<Favourites, RegistryService>,
+) -> anyhow::Result<()> {
+    tx.send(Ok(ctx.feedback.favourites(&did).await?)).await?;
+    Ok(())
+}
+
+/// The moderation queue. Operators only.
+pub async fn reports(
+    ctx: Arc<RegistryContext>,
+    did: Did,
+    WithChannels { tx, .. }: WithChannels<Reports, RegistryService>,
+) -> anyhow::Result<()> {
+    if !ctx.store.is_operator(&did) {
+        tx.send(Err(RegistryError::NotPermitted)).await?;
+        return Ok(());
+    }
+
+    tx.send(Ok(ctx.feedback.queue(&ctx.config).await?)).await?;
+    Ok(())
+}
+
+/// Operators only.
+pub async fn dismiss(
+    ctx: Arc<RegistryContext>,
+    did: Did,
+    WithChannels { inner, tx, .. }: WithChannels<Dismiss, RegistryService>,
+) -> anyhow::Result<()> {
+    if !ctx.store.is_operator(&did) {
+        tx.send(Err(RegistryError::NotPermitted)).await?;
+        return Ok(());
+    }
+
+    if ctx.feedback.dismiss(inner.ns).await? {
+        ctx.request_rebuild();
+    }
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
@@ -1,42 +1,64 @@
 use std::sync::Arc;
 
+use iroh::{
+    endpoint::Connection,
+    protocol::{
+        AcceptError,
+        ProtocolHandler,
+    },
+};
 use iroh_docs::NamespaceId;
 use irpc::{
     Client,
     channel::oneshot,
     rpc_requests,
 };
-use irpc_iroh::IrohProtocol;
 use serde::{
     Deserialize,
     Serialize,
 };
-use tracing::error;
-use wds::{
-    SessionToken,
-    signed_bytes::SignedBytes,
+use tracing::{
+    debug,
+    error,
 };
+use wds::signed_bytes::SignedBytes;
+use xdid::core::did::Did;
 
 use crate::{
     RegistryContext,
     entry::{
         Presence,
+        Rating,
+        Report,
         Submission,
     },
     error::RegistryError,
+    feedback::QueuedReport,
+    search::{
+        SearchPage,
+        SearchQuery,
+    },
     views::ViewIds,
 };
 
+mod feedback;
 mod presence;
+mod search;
 mod submit;
 
-pub const ALPN: &[u8] = b"wired/registry";
+pub const ALPN: &[u8] = b"wired/registry/1";
 
-pub fn protocol(
-    ctx: Arc<RegistryContext>,
-) -> (Client<RegistryService>, IrohProtocol<RegistryService>) {
+/// Returns an in-process client and the handler serving remote connections.
+///
+/// Remote callers are whichever DID their link names, once their endpoint has
+/// authenticated as it with the co-deployed store. In-process calls carry no
+/// identity, so wrap the local client in a
+/// [`LocalRegistryClient`](crate::client::LocalRegistryClient), which offers
+/// only the unauthenticated ones.
+pub fn protocol(ctx: Arc<RegistryContext>) -> (Client<RegistryService>, RegistryProtocol) {
     let (tx, mut rx) = irpc::channel::mpsc::channel(16);
 
+    let local_ctx = Arc::clone(&ctx);
     n0_future::task::spawn(async move {
         while let Some(msg) = match rx.recv().await {
             Ok(msg) => msg,
@@ -45,41 +67,93 @@ pub fn protocol(
                 None
             }
         } {
-            let ctx = Arc::clone(&ctx);
-            n0_future::task::spawn(async move {
-                if let Err(err) = handle_message(ctx, msg).await {
-                    error!("registry request failed: {err:?}");
-                }
-            });
+            spawn_message(&local_ctx, None, msg);
         }
     });
 
-    let client = Client::local(tx);
-    let local_sender = client.as_local().expect("local client");
+    (Client::local(tx), RegistryProtocol(ctx))
+}
+
+pub struct RegistryProtocol(Arc<RegistryContext>);
 
-    (client, IrohProtocol::with_sender(local_sender))
+impl std::fmt::Debug for RegistryProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryProtocol").finish_non_exhaustive()
+    }
+}
+
+impl ProtocolHandler for RegistryProtocol {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let endpoint = connection.remote_id();
+        // Per link, as the store holds it: two actors on one endpoint each
+        // dial their own.
+        let mut acting = None;
+
+        loop {
+            match irpc_iroh::read_request::<RegistryService>(&connection).await {
+                Ok(Some(RegistryMessage::ActAs(channels))) => {
+                    let irpc::WithChannels { inner, tx, .. } = channels;
+                    let res = if self.0.store.endpoint_proves(endpoint, &inner.did).await {
+                        acting = Some(inner.did);
+                        Ok(())
+                    } else {
+                        Err(RegistryError::Unauthenticated)
+                    };
+                    if tx.send(res).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(Some(msg)) => {
+                    // Checked per request: the store drops a binding when the
+                    // endpoint's last link to it closes.
+                    let caller = match &acting {
+                        Some(did) if self.0.store.endpoint_proves(endpoint, did).await => {
+                            Some(did.clone())
+                        }
+                        _ => None,
+                    };
+                    spawn_message(&self.0, caller, msg);
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    debug!(%err, "registry connection closed");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn spawn_message(ctx: &Arc<RegistryContext>, caller: Option<Did>, msg: RegistryMessage) {
+    let ctx = Arc::clone(ctx);
+    n0_future::task::spawn(async move {
+        if let Err(err) = handle_message(ctx, caller, msg).await {
+            error!("registry request failed: {err:?}");
+        }
+    });
 }
 
 #[rpc_requests(message = RegistryMessage)]
 #[derive(Debug, Serialize, Deserialize)]
 pub enum RegistryService {
+    /// Names which DID, of those the endpoint proved to the store, this link
+    /// acts as.
+    #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
+    #[wrap(ActAs)]
+    ActAs { did: Did },
     /// Publish a durable catalog entry.
     #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
     #[wrap(Submit)]
-    Submit {
-        s:          SessionToken,
-        submission: SignedBytes<Submission>,
-    },
+    Submit { submission: SignedBytes<Submission> },
     #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
     #[wrap(Retract)]
-    Retract { s: SessionToken, ns: NamespaceId },
+    Retract { ns: NamespaceId },
     /// Heartbeat live occupancy. Never persisted.
     #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
     #[wrap(Announce)]
-    Announce {
-        s:        SessionToken,
-        presence: SignedBytes<Presence>,
-    },
+    Announce { presence: SignedBytes<Presence> },
     /// Current occupants of a namespace, each individually signed.
     #[rpc(tx=oneshot::Sender<Result<Vec<SignedBytes<Presence>>, RegistryError>>)]
     #[wrap(Occupants)]
@@ -89,33 +163,77 @@ pub enum RegistryService {
     #[rpc(tx=oneshot::Sender<Result<ViewIds, RegistryError>>)]
     #[wrap(Views)]
     Views,
+    /// Full-text search over live submissions, reaching past the capped
+    /// views. Unauthenticated, like `Views`.
+    #[rpc(tx=oneshot::Sender<Result<SearchPage, RegistryError>>)]
+    #[wrap(Search)]
+    Search { query: SearchQuery },
+    /// Rate a listed namespace, replacing the caller's earlier rating.
+    #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
+    #[wrap(Rate)]
+    Rate { rating: SignedBytes<Rating> },
+    /// Report a listed namespace to the operator.
+    #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
+    #[wrap(Flag)]
+    Flag { report: SignedBytes<Report> },
+    /// The namespaces the caller has favourited.
+    #[rpc(tx=oneshot::Sender<Result<Vec<NamespaceId>, RegistryError>>)]
+    #[wrap(Favourites)]
+    Favourites,
+    /// The moderation queue. Operator only.
+    #[rpc(tx=oneshot::Sender<Result<Vec<QueuedReport>, RegistryError>>)]
+    #[wrap(Reports)]
+    Reports,
+    /// Clear the reports against a namespace. Operator only.
+    #[rpc(tx=oneshot::Sender<Result<(), RegistryError>>)]
+    #[wrap(Dismiss)]
+    Dismiss { ns: NamespaceId },
 }
 
-macro_rules! authenticate {
-    ($ctx:tt, $inner:tt, $tx:tt) => {
-        match $ctx.session_did(&$inner.s).await {
-            Some(did) => did,
-            None => {
-                $tx.send(Err($crate::error::RegistryError::Unauthenticated))
-                    .await?;
-                return Ok(());
+async fn handle_message(
+    ctx: Arc<RegistryContext>,
+    caller: Option<Did>,
+    msg: RegistryMessage,
+) -> anyhow::Result<()> {
+    macro_rules! authenticated {
+        ($channels:ident, $handler:path) => {
+            match caller {
+                Some(did) => $handler(ctx, did, $channels).await,
+                None => {
+                    $channels
+                        .tx
+                        .send(Err(RegistryError::Unauthenticated))
+                        .await?;
+                    Ok(())
+                }
             }
-        }
-    };
-}
-
-pub(crate) use authenticate;
+        };
+    }
 
-async fn handle_message(ctx: Arc<RegistryContext>, msg: RegistryMessage) -> anyhow::Result<()> {
     match msg {
-        RegistryMessage::Submit(channels) => submit::submit(ctx, channels).await,
-        RegistryMessage::Retract(channels) => submit::retract(ctx, channels).await,
-        RegistryMessage::Announce(channels) => presence::announce(ctx, channels).await,
+        // Answered by the link itself where it has an endpoint to check; an
+        // in-process call has none.
+        RegistryMessage::ActAs(channels) => {
+            channels
+                .tx
+                .send(Err(RegistryError::Unauthenticated))
+                .await?;
+            Ok(())
+        }
+        RegistryMessage::Submit(channels) => authenticated!(channels, submit::submit),
+        RegistryMessage::Retract(channels) => authenticated!(channels, submit::retract),
+        RegistryMessage::Announce(channels) => authenticated!(channels, presence::announce),
         RegistryMessage::Occupants(channels) => presence::occupants(ctx, channels).await,
         RegistryMessage::Views(channels) => {
             let irpc::WithChannels { tx, .. } = channels;
             tx.send(Ok(ctx.views.ids())).await?;
             Ok(())
         }
+        RegistryMessage::Search(channels) => search::search(ctx, channels).await,
+        RegistryMessage::Rate(channels) => authenticated!(channels, feedback::rate),
+        RegistryMessage::Flag(channels) => authenticated!(channels, feedback::flag),
+        RegistryMessage::Favourites(channels) => authenticated!(channels, feedback::favourites),
+        RegistryMessage::Reports(channels) => authenticated!(channels, feedback::reports),
+        RegistryMessage::Dismiss(channels) => authenticated!(channels, feedback::dismiss),
     }
 }
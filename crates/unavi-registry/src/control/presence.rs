@@ -2,7 +2,11 @@ use std::sync::Arc;
 
 use irpc::WithChannels;
 use time::OffsetDateTime;
-use wds::signed_bytes::verify_did_signature;
+use wds::{
+    denylist::DenyEntry,
+    signed_bytes::verify_did_signature,
+};
+use xdid::core::did::Did;
 
 use crate::{
     RegistryContext,
@@ -10,17 +14,15 @@ use crate::{
         Announce,
         Occupants,
         RegistryService,
-        authenticate,
     },
     error::RegistryError,
 };
 
 pub async fn announce(
     ctx: Arc<RegistryContext>,
+    did: Did,
     WithChannels { inner, tx, .. }: WithChannels<Announce, RegistryService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
-
     let Ok(presence) = inner.presence.payload() else {
         tx.send(Err(RegistryError::Malformed)).await?;
         return Ok(());
@@ -36,6 +38,15 @@ pub async fn announce(
         return Ok(());
     }
 
+    if ctx
+        .store
+        .denies(vec![DenyEntry::Did(did), DenyEntry::Namespace(presence.ns)])
+        .await?
+    {
+        tx.send(Err(RegistryError::Denied)).await?;
+        return Ok(());
+    }
+
     if !verify_did_signature(&inner.presence, &presence.did).await {
         tx.send(Err(RegistryError::InvalidSignature)).await?;
         return Ok(());
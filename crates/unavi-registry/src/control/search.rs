@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use irpc::WithChannels;
+use tracing::warn;
+
+use crate::{
+    RegistryContext,
+    control::{
+        RegistryService,
+        Search,
+    },
+    error::RegistryError,
+    search::MAX_TAGS,
+};
+
+pub async fn search(
+    ctx: Arc<RegistryContext>,
+    WithChannels { inner, tx, .. }: WithChannels<Search, RegistryService>,
+) -> anyhow::Result<()> {
+    // Each tag binds a parameter; past SQLite's limit the query would fail.
+    if inner.query.tags.len() > MAX_TAGS {
+        tx.send(Err(RegistryError::Malformed)).await?;
+        return Ok(());
+    }
+
+    let result = ctx.index.search(&inner.query).await.map_err(|err| {
+        warn!(?err, "search failed");
+        RegistryError::Internal
+    });
+    tx.send(result).await?;
+    Ok(())
+}
@@ -3,7 +3,11 @@ use std::sync::Arc;
 use irpc::WithChannels;
 use time::OffsetDateTime;
 use tracing::warn;
-use wds::signed_bytes::verify_did_signature;
+use wds::{
+    denylist::DenyEntry,
+    signed_bytes::verify_did_signature,
+};
+use xdid::core::did::Did;
 
 use crate::{
     RegistryContext,
@@ -11,17 +15,15 @@ use crate::{
         RegistryService,
         Retract,
         Submit,
-        authenticate,
     },
     error::RegistryError,
 };
 
 pub async fn submit(
     ctx: Arc<RegistryContext>,
+    did: Did,
     WithChannels { inner, tx, .. }: WithChannels<Submit, RegistryService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
-
     if !ctx.config.permits(&did) {
         tx.send(Err(RegistryError::NotPermitted)).await?;
         return Ok(());
@@ -32,7 +34,7 @@ pub async fn submit(
         return Ok(());
     };
 
-    // The session holder must be the announcer; a registry does not let one
+    // The caller must be the announcer; a registry does not let one
     // identity speak for another.
     if submission.did != did {
         tx.send(Err(RegistryError::NotPermitted)).await?;
@@ -44,6 +46,17 @@ pub async fn submit(
         return Ok(());
     }
 
+    // The registry refuses whatever its store does, preview image included.
+    let mut denied = vec![
+        DenyEntry::Did(did.clone()),
+        DenyEntry::Namespace(submission.ns),
+    ];
+    denied.extend(submission.preview.map(|hash| DenyEntry::Blob(hash.into())));
+    if ctx.store.denies(denied).await? {
+        tx.send(Err(RegistryError::Denied)).await?;
+        return Ok(());
+    }
+
     let ceiling = (OffsetDateTime::now_utc() + ctx.config.max_retention).unix_timestamp();
     if submission.expires > ceiling {
         tx.send(Err(RegistryError::RetentionTooLong)).await?;
@@ -72,6 +85,10 @@ pub async fn submit(
         tx.send(Err(RegistryError::Internal)).await?;
         return Ok(());
     }
+    // The catalog is the record; a stale index only costs search results.
+    if let Err(err) = ctx.index.insert(&submission).await {
+        warn!(?err, "failed indexing submission");
+    }
 
     ctx.request_rebuild();
 
@@ -81,10 +98,9 @@ pub async fn submit(
 
 pub async fn retract(
     ctx: Arc<RegistryContext>,
+    did: Did,
     WithChannels { inner, tx, .. }: WithChannels<Retract, RegistryService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
-
     let live = ctx.catalog.live(&ctx.docs, &ctx.blobs).await?;
     let owned = live.iter().any(|s| s.ns == inner.ns && s.did == did);
 
@@ -98,6 +114,12 @@ pub async fn retract(
         tx.send(Err(RegistryError::Internal)).await?;
         return Ok(());
     }
+    if let Err(err) = ctx.index.remove(inner.ns).await {
+        warn!(?err, "failed unindexing submission");
+    }
+    if let Err(err) = ctx.feedback.forget(inner.ns).await {
+        warn!(?err, "failed forgetting feedback");
+    }
 
     ctx.request_rebuild();
 
@@ -38,6 +38,58 @@ impl Signable for Submission {
     const SIGNING_CONTEXT: &'static str = "wired/registry/submission";
 }
 
+/// Highest score a [`Rating`] may give.
+pub const MAX_SCORE: u8 = 5;
+
+/// A user's opinion of a listed namespace. A later rating from the same DID
+/// replaces an earlier one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rating {
+    pub did:       Did,
+    pub ns:        NamespaceId,
+    /// From 1 to [`MAX_SCORE`].
+    pub score:     u8,
+    /// Keeps the namespace in the rater's favourites.
+    pub favourite: bool,
+    /// Unix timestamp of signing; orders ratings from the same DID.
+    pub issued:    i64,
+}
+
+impl Signable for Rating {
+    const SIGNING_CONTEXT: &'static str = "wired/registry/rating";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportReason {
+    Spam,
+    Abusive,
+    Illegal,
+    /// Unreachable, empty, or not what it claims to be.
+    Broken,
+    Other,
+}
+
+/// Longest [`Report::detail`] the registry accepts. The operator reads it;
+/// anything past a paragraph is not a report.
+pub const MAX_DETAIL_BYTES: usize = 1024;
+
+/// A user flagging a listed namespace for the operator's attention. A later
+/// report from the same DID replaces an earlier one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub did:    Did,
+    pub ns:     NamespaceId,
+    pub reason: ReportReason,
+    /// At most [`MAX_DETAIL_BYTES`].
+    pub detail: Option<SmolStr>,
+    /// Unix timestamp of signing.
+    pub issued: i64,
+}
+
+impl Signable for Report {
+    const SIGNING_CONTEXT: &'static str = "wired/registry/report";
+}
+
 /// An ephemeral claim that a DID is reachable in a namespace right now.
 ///
 /// Never persisted: held in memory and expired by clock.
@@ -19,6 +19,12 @@ pub enum RegistryError {
     RetentionTooLong,
     #[error("too many submissions held by this identity")]
     TooManySubmissions,
+    #[error("refused by this registry's denylist")]
+    Denied,
+    #[error("not listed in this registry")]
+    NotListed,
+    #[error("too many ratings and reports from this identity, try again later")]
+    RateLimited,
     #[error("internal error")]
     Internal,
 }
@@ -0,0 +1,426 @@
+//! Ratings and reports on listed namespaces.
+//!
+//! Ratings rank the top view; reports fill the operator's moderation queue.
+//! Both are weighted by the operator's view of who sent them (see
+//! [`Config::rating_weight`] and [`Config::report_weight`]), kept in the
+//! store's database, and start empty each run like the catalog.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+};
+
+use iroh_docs::NamespaceId;
+use rusqlite::params;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use time::OffsetDateTime;
+use wds::{
+    db::Database,
+    signed_bytes::SignedBytes,
+};
+use xdid::core::did::Did;
+
+use crate::{
+    config::Config,
+    entry::{
+        Rating,
+        Report,
+    },
+};
+
+/// Window [`Config::max_feedback_per_hour`] counts over, in seconds.
+const RATE_WINDOW: i64 = 60 * 60;
+
+/// A few imaginary middling ratings every namespace starts with, so one
+/// glowing rating does not outrank many good ones.
+const PRIOR_WEIGHT: f64 = 2.0;
+const PRIOR_SCORE: f64 = 3.0;
+
+/// Reports against one namespace, awaiting the operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedReport {
+    pub ns:      NamespaceId,
+    /// Sum of the reporters' weights, as [`Config::report_weight`] counts
+    /// them.
+    pub weight:  f64,
+    /// Each as its reporter signed it, latest first.
+    pub reports: Vec<SignedBytes<Report>>,
+}
+
+/// Feedback's tables, kept in the store's database but owned by the registry:
+/// ratings and reports on the catalog's namespaces, and when each DID last
+/// sent something, for rate limiting.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS feedback_ratings (
+        did TEXT NOT NULL,
+        ns TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        favourite INTEGER NOT NULL,
+        issued INTEGER NOT NULL,
+        PRIMARY KEY (did, ns)
+    );
+    CREATE INDEX IF NOT EXISTS idx_feedback_ratings_ns ON feedback_ratings(ns);
+
+    CREATE TABLE IF NOT EXISTS feedback_reports (
+        did TEXT NOT NULL,
+        ns TEXT NOT NULL,
+        issued INTEGER NOT NULL,
+        signed BLOB NOT NULL,
+        PRIMARY KEY (did, ns)
+    );
+    CREATE INDEX IF NOT EXISTS idx_feedback_reports_ns ON feedback_reports(ns);
+
+    CREATE TABLE IF NOT EXISTS feedback_sent (
+        did TEXT NOT NULL,
+        at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_feedback_sent_did ON feedback_sent(did, at);
+";
+
+pub struct Feedback {
+    db: Database,
+}
+
+impl Feedback {
+    /// Creates feedback's tables if the store's database lacks them, and
+    /// clears what a previous run held, since its catalog is gone.
+    pub async fn new(db: Database) -> anyhow::Result<Self> {
+        db.call(|conn| {
+            conn.execute_batch(SCHEMA)?;
+            conn.execute_batch(
+                "DELETE FROM feedback_ratings;
+                 DELETE FROM feedback_reports;
+                 DELETE FROM feedback_sent;",
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(Self { db })
+    }
+
+    /// Counts one rating or report from `did` against its hourly allowance.
+    /// Returns `false`, counting nothing, once it is used up.
+    pub async fn try_send(&self, did: &Did, max_per_hour: u32) -> anyhow::Result<bool> {
+        let did = did.to_string();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.db
+            .call(move |conn| {
+                let sent: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM feedback_sent WHERE did = ? AND at > ?",
+                    params![&did, now - RATE_WINDOW],
+                    |row| row.get(0),
+                )?;
+                if sent >= i64::from(max_per_hour) {
+                    return Ok(false);
+                }
+                conn.execute(
+                    "INSERT INTO feedback_sent (did, at) VALUES (?, ?)",
+                    params![&did, now],
+                )?;
+                Ok(true)
+            })
+            .await
+    }
+
+    /// Records `rating`, unless the same DID already rated the namespace more
+    /// recently.
+    pub async fn rate(&self, rating: &Rating) -> anyhow::Result<()> {
+        let (did, ns) = (rating.did.to_string(), rating.ns.to_string());
+        let (score, favourite, issued) = (rating.score, rating.favourite, rating.issued);
+        self.db
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT INTO feedback_ratings (did, ns, score, favourite, issued)
+                     VALUES (?, ?, ?, ?, ?)
+                     ON CONFLICT(did, ns) DO UPDATE SET
+                         score = excluded.score,
+                         favourite = excluded.favourite,
+                         issued = excluded.issued
+                     WHERE excluded.issued >= issued",
+                    params![did, ns, score, favourite, issued],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Queues `report`, unless the same DID already reported the namespace
+    /// more recently.
+    pub async fn report(
+        &self,
+        report: &Report,
+        signed: &SignedBytes<Report>,
+    ) -> anyhow::Result<()> {
+        let (did, ns, issued) = (report.did.to_string(), report.ns.to_string(), report.issued);
+        let signed = postcard::to_stdvec(signed)?;
+        self.db
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT INTO feedback_reports (did, ns, issued, signed) VALUES (?, ?, ?, ?)
+                     ON CONFLICT(did, ns) DO UPDATE SET
+                         issued = excluded.issued,
+                         signed = excluded.signed
+                     WHERE excluded.issued >= issued",
+                    params![did, ns, issued, signed],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Namespaces `did` has favourited, most recently rated first.
+    pub async fn favourites(&self, did: &Did) -> anyhow::Result<Vec<NamespaceId>> {
+        let did = did.to_string();
+        self.db
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT ns FROM feedback_ratings WHERE did = ? AND favourite
+                     ORDER BY issued DESC, ns",
+                )?;
+                let rows = stmt.query_map(params![did], |row| row.get::<_, String>(0))?;
+                rows.map(|ns| Ok(NamespaceId::from_str(&ns?)?)).collect()
+            })
+            .await
+    }
+
+    /// Each rated namespace's standing: its weighted mean score, pulled
+    /// towards the middle while it has few ratings.
+    pub async fn standings(&self, config: &Config) -> anyhow::Result<HashMap<NamespaceId, f64>> {
+        let rows = self
+            .db
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT did, ns, score FROM feedback_ratings")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, u8>(2)?,
+                    ))
+                })?;
+                Ok(rows.collect::<Result<Vec<_>, _>>()?)
+            })
+            .await?;
+
+        let mut sums = HashMap::<NamespaceId, (f64, f64)>::new();
+        for (did, ns, score) in rows {
+            let weight = config.rating_weight(&Did::from_str(&did)?);
+            let (total, weights) = sums.entry(NamespaceId::from_str(&ns)?).or_default();
+            *total += weight * f64::from(score);
+            *weights += weight;
+        }
+
+        Ok(sums
+            .into_iter()
+            .map(|(ns, (total, weights))| {
+                let standing = PRIOR_WEIGHT.mul_add(PRIOR_SCORE, total) / (weights + PRIOR_WEIGHT);
+                (ns, standing)
+            })
+            .collect())
+    }
+
+    /// Every reported namespace, most heavily reported first.
+    pub async fn queue(&self, config: &Config) -> anyhow::Result<Vec<QueuedReport>> {
+        let rows = self
+            .db
+            .call(|conn| {
+                let mut stmt = conn
+                    .prepare("SELECT did, ns, signed FROM feedback_reports ORDER BY issued DESC")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Vec<u8>>(2)?,
+                    ))
+                })?;
+                Ok(rows.collect::<Result<Vec<_>, _>>()?)
+            })
+            .await?;
+
+        let mut queued = HashMap::<NamespaceId, QueuedReport>::new();
+        for (did, ns, signed) in rows {
+            let ns = NamespaceId::from_str(&ns)?;
+            let entry = queued.entry(ns).or_insert_with(|| QueuedReport {
+                ns,
+                weight: 0.0,
+                reports: Vec::new(),
+            });
+            entry.weight += config.report_weight(&Did::from_str(&did)?);
+            entry.reports.push(postcard::from_bytes(&signed)?);
+        }
+
+        let mut queue = queued.into_values().collect::<Vec<_>>();
+        queue.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        Ok(queue)
+    }
+
+    /// Clears the reports against `ns`. Returns `false` if there were none.
+    pub async fn dismiss(&self, ns: NamespaceId) -> anyhow::Result<bool> {
+        self.db
+            .call(move |conn| {
+                let rows = conn.execute(
+                    "DELETE FROM feedback_reports WHERE ns = ?",
+                    params![ns.to_string()],
+                )?;
+                Ok(rows > 0)
+            })
+            .await
+    }
+
+    /// Drops everything held about `ns`, once it leaves the catalog.
+    pub async fn forget(&self, ns: NamespaceId) -> anyhow::Result<()> {
+        let ns = ns.to_string();
+        self.db
+            .call(move |conn| {
+                conn.execute("DELETE FROM feedback_ratings WHERE ns = ?", params![&ns])?;
+                conn.execute("DELETE FROM feedback_reports WHERE ns = ?", params![&ns])?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Drops send times too old to count against anyone.
+    pub async fn sweep(&self) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.db
+            .call(move |conn| {
+                conn.execute(
+                    "DELETE FROM feedback_sent WHERE at <= ?",
+                    params![now - RATE_WINDOW],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wds::signed_bytes::Signable;
+    use xdid::methods::key::keys::{
+        DidKeyPair,
+        p256::P256KeyPair,
+    };
+
+    use super::*;
+    use crate::entry::ReportReason;
+
+    fn did(name: &str) -> Did {
+        Did::from_str(&format!("did:web:{name}.example")).expect("parse did")
+    }
+
+    async fn feedback() -> Feedback {
+        let db = Database::new_in_memory().expect("open db");
+        Feedback::new(db).await.expect("open feedback")
+    }
+
+    fn rating(rater: &str, seed: u8, score: u8) -> Rating {
+        Rating {
+            did: did(rater),
+            ns: NamespaceId::from(&[seed; 32]),
+            score,
+            favourite: score == 5,
+            issued: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn trusted_raters_outweigh_the_rest() {
+        let feedback = feedback().await;
+        let config = Config {
+            trusted: vec![did("curator")],
+            ..Config::default()
+        };
+
+        feedback.rate(&rating("curator", 1, 5)).await.expect("rate");
+        for rater in ["a", "b", "c"] {
+            feedback.rate(&rating(rater, 1, 1)).await.expect("rate");
+            feedback.rate(&rating(rater, 2, 3)).await.expect("rate");
+        }
+
+        let standings = feedback.standings(&config).await.expect("standings");
+        let (first, second) = (NamespaceId::from(&[1; 32]), NamespaceId::from(&[2; 32]));
+        assert!(standings[&first] > standings[&second]);
+
+        assert_eq!(
+            feedback
+                .favourites(&did("curator"))
+                .await
+                .expect("favourites"),
+            [first]
+        );
+    }
+
+    #[tokio::test]
+    async fn throwaway_raters_cannot_lift_an_entry() {
+        let feedback = feedback().await;
+        let config = Config {
+            trusted: vec![did("curator")],
+            ..Config::default()
+        };
+
+        for i in 0..32 {
+            feedback
+                .rate(&rating(&format!("throwaway-{i}"), 1, 5))
+                .await
+                .expect("rate");
+        }
+        feedback.rate(&rating("curator", 2, 4)).await.expect("rate");
+
+        let standings = feedback.standings(&config).await.expect("standings");
+        let (lifted, rated) = (NamespaceId::from(&[1; 32]), NamespaceId::from(&[2; 32]));
+        assert!(
+            standings[&lifted] < standings[&rated],
+            "unlisted DIDs cost nothing to mint, so they must not lift a space"
+        );
+    }
+
+    async fn flag(feedback: &Feedback, key: &P256KeyPair, reporter: &str, seed: u8) {
+        let report = Report {
+            did:    did(reporter),
+            ns:     NamespaceId::from(&[seed; 32]),
+            reason: ReportReason::Spam,
+            detail: None,
+            issued: 0,
+        };
+        let signed = report.sign(key).expect("sign report");
+        feedback.report(&report, &signed).await.expect("report");
+    }
+
+    #[tokio::test]
+    async fn throwaway_reporters_cannot_hold_an_entry() {
+        let feedback = feedback().await;
+        let config = Config {
+            trusted: vec![did("curator")],
+            ..Config::default()
+        };
+        let key = P256KeyPair::generate();
+
+        for i in 0..32 {
+            flag(&feedback, &key, &format!("throwaway-{i}"), 1).await;
+        }
+        let queue = feedback.queue(&config).await.expect("queue");
+        assert_eq!(queue[0].reports.len(), 32, "the operator still sees them");
+        assert!(
+            queue[0].weight < config.report_threshold,
+            "unlisted DIDs cost nothing to mint, so they must not hide a space"
+        );
+
+        flag(&feedback, &key, "curator", 1).await;
+        let queue = feedback.queue(&config).await.expect("queue");
+        assert!(queue[0].weight >= config.report_threshold);
+    }
+
+    #[tokio::test]
+    async fn senders_are_rate_limited() {
+        let feedback = feedback().await;
+        let (a, b) = (did("a"), did("b"));
+
+        assert!(feedback.try_send(&a, 2).await.expect("send"));
+        assert!(feedback.try_send(&a, 2).await.expect("send"));
+        assert!(!feedback.try_send(&a, 2).await.expect("send"));
+        assert!(feedback.try_send(&b, 2).await.expect("send"));
+    }
+}
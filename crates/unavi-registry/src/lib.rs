@@ -14,14 +14,15 @@ use std::{
 use iroh_blobs::api::blobs::Blobs;
 use iroh_docs::protocol::Docs;
 use irpc::Client;
-use irpc_iroh::IrohProtocol;
 use tracing::warn;
 use wds::DataStore;
 
 use crate::{
     catalog::Catalog,
     config::Config,
+    feedback::Feedback,
     presence::PresenceTable,
+    search::SearchIndex,
     views::Views,
 };
 
@@ -31,7 +32,9 @@ pub mod config;
 pub mod control;
 pub mod entry;
 pub mod error;
+pub mod feedback;
 pub mod presence;
+pub mod search;
 pub mod views;
 
 /// Bounds how long a newly occupied space stays invisible to clients.
@@ -42,6 +45,8 @@ pub struct RegistryContext {
     pub(crate) catalog:  Catalog,
     pub(crate) config:   Config,
     pub(crate) docs:     Docs,
+    pub(crate) feedback: Feedback,
+    pub(crate) index:    SearchIndex,
     pub(crate) presence: PresenceTable,
     pub(crate) store:    Arc<DataStore>,
     pub(crate) views:    Views,
@@ -52,13 +57,6 @@ impl RegistryContext {
     pub(crate) fn request_rebuild(&self) {
         self.dirty.store(true, Ordering::Release);
     }
-
-    pub(crate) async fn session_did(
-        &self,
-        token: &wds::SessionToken,
-    ) -> Option<xdid::core::did::Did> {
-        self.store.session_did(token).await
-    }
 }
 
 pub struct Registry {
@@ -72,7 +70,7 @@ impl Registry {
     pub async fn create(
         store: Arc<DataStore>,
         config: Config,
-    ) -> anyhow::Result<(Self, IrohProtocol<control::RegistryService>)> {
+    ) -> anyhow::Result<(Self, control::RegistryProtocol)> {
         let docs = store.docs().clone();
         let blobs = store.blobs().blobs().clone();
 
@@ -85,6 +83,8 @@ impl Registry {
             config,
             dirty: AtomicBool::new(false),
             docs,
+            feedback: Feedback::new(store.db().clone()).await?,
+            index: SearchIndex::new(store.db().clone()).await?,
             presence: PresenceTable::default(),
             store,
             views,
@@ -117,6 +117,12 @@ async fn maintenance(ctx: Arc<RegistryContext>) {
         n0_future::time::sleep(MAINTENANCE_INTERVAL).await;
 
         ctx.presence.sweep(window).await;
+        if let Err(err) = ctx.index.sweep().await {
+            warn!(?err, "search index sweep failed");
+        }
+        if let Err(err) = ctx.feedback.sweep().await {
+            warn!(?err, "feedback sweep failed");
+        }
 
         let active = ctx.presence.active(window).await;
         let ordering = active.iter().map(|s| s.ns).collect::<Vec<_>>();
@@ -134,7 +140,13 @@ async fn maintenance(ctx: Arc<RegistryContext>) {
         if ctx.dirty.swap(false, Ordering::AcqRel)
             && let Err(err) = ctx
                 .views
-                .rebuild(&ctx.docs, &ctx.catalog, &ctx.blobs, &ctx.config)
+                .rebuild(
+                    &ctx.docs,
+                    &ctx.catalog,
+                    &ctx.blobs,
+                    &ctx.feedback,
+                    &ctx.config,
+                )
                 .await
         {
             warn!(?err, "view rebuild failed");
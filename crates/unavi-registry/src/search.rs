@@ -0,0 +1,432 @@
+//! Full-text search over the catalog, past what the capped views reach.
+//!
+//! The index lives in the store's database and is kept in step with the
+//! catalog as entries are submitted and retracted. Like the catalog, it starts
+//! empty each run.
+
+use iroh_docs::NamespaceId;
+use rusqlite::{
+    params,
+    params_from_iter,
+    types::Value,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use smol_str::SmolStr;
+use time::OffsetDateTime;
+use wds::db::Database;
+
+use crate::entry::{
+    Kind,
+    Submission,
+};
+
+/// Most hits one page may carry.
+pub const MAX_PAGE: u32 = 100;
+
+/// Terms past this many in one query are ignored.
+const MAX_TERMS: usize = 16;
+
+/// Most tags one query may filter by; a query with more is refused.
+pub const MAX_TAGS: usize = MAX_TERMS;
+
+const TITLE_WEIGHT: i64 = 3;
+const TAG_WEIGHT: i64 = 2;
+const DESCRIPTION_WEIGHT: i64 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// Free text. Every term must prefix a word of the title, description or
+    /// a tag; empty matches every entry.
+    pub text:   SmolStr,
+    /// Tags an entry must all carry, compared case-insensitively. At most
+    /// [`MAX_TAGS`].
+    pub tags:   Vec<SmolStr>,
+    pub kind:   Option<Kind>,
+    pub offset: u32,
+    /// Capped at [`MAX_PAGE`].
+    pub limit:  u32,
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            text:   SmolStr::default(),
+            tags:   Vec::new(),
+            kind:   None,
+            offset: 0,
+            limit:  20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchPage {
+    /// Best match first; entries matching equally, latest to expire first.
+    pub hits:  Vec<Submission>,
+    /// Matches across every page.
+    pub total: u64,
+}
+
+/// Lowercased alphanumeric runs; everything else separates words.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+const fn kind_column(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Space => "space",
+        Kind::Avatar => "avatar",
+        Kind::Object => "object",
+    }
+}
+
+/// The index's tables, kept in the store's database but owned by the
+/// registry. An entry's score for a query term is the heaviest field the term
+/// prefixes a token of: title, tag or description. Tags are also kept whole,
+/// matched exactly by a tag filter.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS search_entries (
+        ns TEXT PRIMARY KEY,
+        kind TEXT NOT NULL,
+        expires INTEGER NOT NULL,
+        submission BLOB NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_search_entries_expires ON search_entries(expires);
+
+    CREATE TABLE IF NOT EXISTS search_terms (
+        term TEXT NOT NULL,
+        ns TEXT NOT NULL,
+        weight INTEGER NOT NULL,
+        PRIMARY KEY (term, ns)
+    );
+    CREATE INDEX IF NOT EXISTS idx_search_terms_ns ON search_terms(ns);
+
+    CREATE TABLE IF NOT EXISTS search_tags (
+        tag TEXT NOT NULL,
+        ns TEXT NOT NULL,
+        PRIMARY KEY (tag, ns)
+    );
+    CREATE INDEX IF NOT EXISTS idx_search_tags_ns ON search_tags(ns);
+";
+
+pub struct SearchIndex {
+    db: Database,
+}
+
+impl SearchIndex {
+    /// Creates the index's tables if the store's database lacks them, and
+    /// clears whatever a previous run indexed, since its catalog is gone.
+    pub async fn new(db: Database) -> anyhow::Result<Self> {
+        db.call(|conn| {
+            conn.execute_batch(SCHEMA)?;
+            conn.execute_batch(
+                "DELETE FROM search_entries;
+                 DELETE FROM search_terms;
+                 DELETE FROM search_tags;",
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(Self { db })
+    }
+
+    /// Indexes `submission`, replacing whatever was indexed for its namespace.
+    pub async fn insert(&self, submission: &Submission) -> anyhow::Result<()> {
+        let ns = submission.ns.to_string();
+        let kind = kind_column(submission.kind);
+        let expires = submission.expires;
+        let value = postcard::to_stdvec(submission)?;
+
+        let mut terms = Vec::new();
+        terms.extend(tokenize(&submission.title).map(|t| (t, TITLE_WEIGHT)));
+        for tag in &submission.tags {
+            terms.extend(tokenize(tag).map(|t| (t, TAG_WEIGHT)));
+        }
+        if let Some(description) = &submission.description {
+            terms.extend(tokenize(description).map(|t| (t, DESCRIPTION_WEIGHT)));
+        }
+        let tags = submission
+            .tags
+            .iter()
+            .map(|tag| tag.to_lowercase())
+            .collect::<Vec<_>>();
+
+        self.db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                delete(&tx, &ns)?;
+                tx.execute(
+                    "INSERT INTO search_entries (ns, kind, expires, submission) VALUES (?, ?, ?, ?)",
+                    params![&ns, kind, expires, value],
+                )?;
+                for (term, weight) in terms {
+                    tx.execute(
+                        "INSERT INTO search_terms (term, ns, weight) VALUES (?, ?, ?)
+                         ON CONFLICT(term, ns) DO UPDATE SET weight = MAX(weight, excluded.weight)",
+                        params![term, &ns, weight],
+                    )?;
+                }
+                for tag in tags {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO search_tags (tag, ns) VALUES (?, ?)",
+                        params![tag, &ns],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn remove(&self, ns: NamespaceId) -> anyhow::Result<()> {
+        self.db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                delete(&tx, &ns.to_string())?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Whether `ns` is listed and unexpired.
+    pub async fn contains(&self, ns: NamespaceId) -> anyhow::Result<bool> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.db
+            .call(move |conn| {
+                let listed = conn
+                    .prepare_cached("SELECT 1 FROM search_entries WHERE ns = ? AND expires > ?")?
+                    .exists(params![ns.to_string(), now])?;
+                Ok(listed)
+            })
+            .await
+    }
+
+    /// Drops expired entries. Searches skip them regardless; this only
+    /// reclaims the space.
+    pub async fn sweep(&self) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                for table in ["search_terms", "search_tags"] {
+                    tx.execute(
+                        &format!(
+                            "DELETE FROM {table} WHERE ns IN
+                             (SELECT ns FROM search_entries WHERE expires <= ?)"
+                        ),
+                        params![now],
+                    )?;
+                }
+                tx.execute(
+                    "DELETE FROM search_entries WHERE expires <= ?",
+                    params![now],
+                )?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn search(&self, query: &SearchQuery) -> anyhow::Result<SearchPage> {
+        let terms = tokenize(&query.text).take(MAX_TERMS).collect::<Vec<_>>();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        // One arm per term, each tagged with its position so an entry must
+        // match them all. No terms matches every entry, unweighted.
+        let mut args = Vec::<Value>::new();
+        let matches = if terms.is_empty() {
+            "SELECT ns, 0 AS q, 0 AS weight FROM search_entries".to_owned()
+        } else {
+            let arms = terms
+                .iter()
+                .enumerate()
+                .map(|(q, term)| {
+                    // Tokens are alphanumeric, so carry no GLOB metacharacters.
+                    args.push(Value::Text(format!("{term}*")));
+                    format!("SELECT ns, {q} AS q, weight FROM search_terms WHERE term GLOB ?")
+                })
+                .collect::<Vec<_>>();
+            arms.join(" UNION ALL ")
+        };
+        let required = terms.len().max(1);
+
+        let mut filters = vec!["e.expires > ?".to_owned()];
+        args.push(Value::Integer(now));
+        if let Some(kind) = query.kind {
+            filters.push("e.kind = ?".to_owned());
+            args.push(Value::Text(kind_column(kind).to_owned()));
+        }
+        for tag in &query.tags {
+            filters.push("e.ns IN (SELECT ns FROM search_tags WHERE tag = ?)".to_owned());
+            args.push(Value::Text(tag.to_lowercase()));
+        }
+
+        // Per-term weights are summed after taking each term's best field.
+        let matched = format!(
+            "SELECT e.ns, e.expires, e.submission, SUM(m.weight) AS score
+             FROM (
+                 SELECT ns, q, MAX(weight) AS weight FROM ({matches}) GROUP BY ns, q
+             ) m
+             JOIN search_entries e ON e.ns = m.ns
+             WHERE {}
+             GROUP BY e.ns
+             HAVING COUNT(*) = {required}",
+            filters.join(" AND ")
+        );
+
+        let limit = query.limit.min(MAX_PAGE);
+        let offset = query.offset;
+
+        self.db
+            .call(move |conn| {
+                let total: i64 = conn.query_row(
+                    &format!("SELECT COUNT(*) FROM ({matched})"),
+                    params_from_iter(&args),
+                    |row| row.get(0),
+                )?;
+
+                let mut page_args = args;
+                page_args.push(Value::Integer(i64::from(limit)));
+                page_args.push(Value::Integer(i64::from(offset)));
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT submission FROM ({matched})
+                     ORDER BY score DESC, expires DESC, ns
+                     LIMIT ? OFFSET ?"
+                ))?;
+                let rows =
+                    stmt.query_map(params_from_iter(&page_args), |row| row.get::<_, Vec<u8>>(0))?;
+
+                let mut hits = Vec::new();
+                for row in rows {
+                    hits.push(postcard::from_bytes::<Submission>(&row?)?);
+                }
+
+                Ok(SearchPage {
+                    hits,
+                    total: total.cast_unsigned(),
+                })
+            })
+            .await
+    }
+}
+
+/// Drops `ns` from every table, inside the caller's transaction.
+fn delete(tx: &rusqlite::Transaction<'_>, ns: &str) -> rusqlite::Result<()> {
+    for table in ["search_entries", "search_terms", "search_tags"] {
+        tx.execute(&format!("DELETE FROM {table} WHERE ns = ?"), params![ns])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use xdid::core::did::Did;
+
+    use super::*;
+
+    fn submission(seed: u8, kind: Kind, title: &str, tags: &[&str]) -> Submission {
+        Submission {
+            did: Did::from_str("did:web:example.com").expect("parse did"),
+            ns: NamespaceId::from(&[seed; 32]),
+            kind,
+            title: title.into(),
+            description: Some("A quiet place to meet".into()),
+            tags: tags.iter().copied().map(SmolStr::from).collect(),
+            preview: None,
+            expires: i64::MAX,
+        }
+    }
+
+    fn titles(page: &SearchPage) -> Vec<&str> {
+        page.hits.iter().map(|s| s.title.as_str()).collect()
+    }
+
+    async fn index() -> SearchIndex {
+        let db = Database::new_in_memory().expect("open db");
+        SearchIndex::new(db).await.expect("open index")
+    }
+
+    #[tokio::test]
+    async fn terms_must_all_match_and_titles_rank_first() {
+        let index = index().await;
+        index
+            .insert(&submission(1, Kind::Space, "Harbor Market", &["meeting"]))
+            .await
+            .expect("insert");
+        index
+            .insert(&submission(2, Kind::Space, "Meeting Hall", &["market"]))
+            .await
+            .expect("insert");
+        index
+            .insert(&submission(3, Kind::Avatar, "Fox", &[]))
+            .await
+            .expect("insert");
+
+        let found = async |text: &str| {
+            index
+                .search(&SearchQuery {
+                    text: text.into(),
+                    ..SearchQuery::default()
+                })
+                .await
+                .expect("search")
+        };
+
+        assert_eq!(
+            titles(&found("meeting").await),
+            ["Meeting Hall", "Harbor Market"]
+        );
+        assert_eq!(titles(&found("MARKET harbor").await), ["Harbor Market"]);
+        assert_eq!(found("quiet").await.total, 3, "descriptions are searched");
+        assert!(found("lighthouse").await.hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn filters_and_pages() {
+        let index = index().await;
+        for seed in 0..5 {
+            index
+                .insert(&submission(seed, Kind::Space, "Room", &["Music"]))
+                .await
+                .expect("insert");
+        }
+        index
+            .insert(&submission(9, Kind::Object, "Chair", &["music"]))
+            .await
+            .expect("insert");
+
+        let page = index
+            .search(&SearchQuery {
+                tags: vec!["music".into()],
+                kind: Some(Kind::Space),
+                offset: 3,
+                limit: 10,
+                ..SearchQuery::default()
+            })
+            .await
+            .expect("search");
+        assert_eq!(page.total, 5);
+        assert_eq!(page.hits.len(), 2);
+
+        index
+            .remove(NamespaceId::from(&[9; 32]))
+            .await
+            .expect("remove");
+        let page = index
+            .search(&SearchQuery {
+                text: "chair".into(),
+                ..SearchQuery::default()
+            })
+            .await
+            .expect("search");
+        assert_eq!(page.total, 0);
+    }
+}
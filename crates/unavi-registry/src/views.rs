@@ -13,6 +13,7 @@ use crate::{
     catalog::Catalog,
     config::Config,
     entry::Submission,
+    feedback::Feedback,
     presence::ActiveSpace,
 };
 
@@ -26,6 +27,8 @@ pub struct ViewIds {
     pub featured:   NamespaceId,
     pub categories: NamespaceId,
     pub active:     NamespaceId,
+    /// Best rated first.
+    pub top:        NamespaceId,
 }
 
 pub struct Views {
@@ -60,6 +63,7 @@ impl Views {
         let featured = create_view(docs).await?;
         let categories = create_view(docs).await?;
         let active = create_view(docs).await?;
+        let top = create_view(docs).await?;
 
         Ok(Self {
             ids: ViewIds {
@@ -67,6 +71,7 @@ impl Views {
                 featured,
                 categories,
                 active,
+                top,
             },
         })
     }
@@ -101,6 +106,7 @@ impl Views {
         docs: &Docs,
         catalog: &Catalog,
         blobs: &iroh_blobs::api::blobs::Blobs,
+        feedback: &Feedback,
         config: &Config,
     ) -> anyhow::Result<()> {
         let mut live = catalog.live(docs, blobs).await?;
@@ -119,6 +125,7 @@ impl Views {
             .await?;
 
         self.write_categories(docs, &live, config).await?;
+        self.write_top(docs, &live, feedback, config).await?;
 
         Ok(())
     }
@@ -151,6 +158,40 @@ impl Views {
         Ok(())
     }
 
+    /// Rated entries by standing, leaving out those reported past the
+    /// operator's threshold until the reports are dismissed.
+    async fn write_top(
+        &self,
+        docs: &Docs,
+        live: &[Submission],
+        feedback: &Feedback,
+        config: &Config,
+    ) -> anyhow::Result<()> {
+        let standings = feedback.standings(config).await?;
+        let held = feedback
+            .queue(config)
+            .await?
+            .into_iter()
+            .filter(|queued| queued.weight >= config.report_threshold)
+            .map(|queued| queued.ns)
+            .collect::<Vec<_>>();
+
+        let mut top = live
+            .iter()
+            .filter(|s| !held.contains(&s.ns))
+            .filter_map(|s| standings.get(&s.ns).map(|standing| (*standing, s)))
+            .collect::<Vec<_>>();
+        // Stable, so equal standings keep the recent order.
+        top.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let top = top
+            .into_iter()
+            .map(|(_, s)| s)
+            .take(config.view_capacity)
+            .collect::<Vec<_>>();
+
+        self.write(docs, self.ids.top, &top, ranked_key).await
+    }
+
     async fn write_categories(
         &self,
         docs: &Docs,
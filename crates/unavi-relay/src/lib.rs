@@ -0,0 +1,36 @@
+//! A fan-out relay for `wired/space/2`.
+//!
+//! A space is a full mesh by default: every client holds a connection to every
+//! other, and sends each of them its own copy of everything. A space whose
+//! document names a relay lets clients hold one connection instead. Each
+//! sends its streams up once and the relay copies them, a frame at a time, to
+//! every other member of the space's room, tagged with who they came from.
+//!
+//! The relay reads stream framing and nothing else. It does not decode what it
+//! carries, and signs nothing. Members still prove their DIDs to each other
+//! through it, but the relay tags every stream with who sent it and nothing
+//! checks the tag, so members trust it for attribution: a relayed proof is
+//! enough to block a peer by, never to trust them by.
+//!
+//! It does not check that a space names it. Any space may hold a room, and the
+//! relay is bounded by what it will carry instead: rooms, members and streams
+//! are capped, joins are paced, and relayed bytes are charged against
+//! [`unavi_quota::Flow::RelayBytes`].
+//!
+//! Nor does it tailor what each member hears. A member's interest is copied
+//! to the rest like any other stream, but every member still gets every frame
+//! at the rate it was sent, and receivers' pacing feedback never reaches a
+//! sender. A member reached through a relay paces for the room instead: as
+//! often as its most interested member's advertisement asks.
+
+pub mod server;
+pub mod wire;
+
+pub use server::Relay;
+
+/// Versioned with the space protocol it carries, so a member never shares a
+/// room with one whose streams it cannot read.
+pub const ALPN: &[u8] = b"wired/space-relay/2";
+
+/// The space protocol whose streams [`wire::stream_kind`] frames.
+pub const CARRIES: &[u8] = b"wired/space/2";
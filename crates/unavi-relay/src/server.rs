@@ -0,0 +1,505 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+    },
+};
+
+use anyhow::{
+    Context,
+    bail,
+};
+use async_channel::TrySendError;
+use iroh::{
+    EndpointId,
+    endpoint::{
+        Connection,
+        RecvStream,
+        SendStream,
+        VarInt,
+    },
+    protocol::{
+        AcceptError,
+        ProtocolHandler,
+    },
+};
+use n0_future::task::JoinSet;
+use parking_lot::Mutex;
+use tokio::io::{
+    AsyncReadExt,
+    AsyncWriteExt,
+};
+use tracing::{
+    debug,
+    info,
+};
+use unavi_quota::{
+    Flow,
+    Quota,
+    Reservation,
+    limits::Limits,
+};
+
+use crate::wire::{
+    self,
+    Framing,
+    Join,
+    RelayEvent,
+    StreamKind,
+};
+
+/// Rooms served at once.
+const MAX_ROOMS: usize = 1024;
+/// Members of one room. Every frame is copied to each, so this bounds what one
+/// frame costs as much as how many may listen.
+const MAX_MEMBERS: usize = 64;
+/// Streams one member may have relayed at once.
+const MAX_STREAMS: usize = 256;
+
+/// Events queued per member. One pending resync is as good as several.
+const EVENT_QUEUE: usize = 1;
+/// Frames queued per listener on a lossy stream, past which new ones are
+/// skipped for it.
+const LOSSY_QUEUE: usize = 8;
+/// Frames queued per listener on a lossless stream, past which it is cut.
+const LOSSLESS_QUEUE: usize = 256;
+
+/// Largest read a directed stream is charged for at once.
+const COPY_CHUNK: usize = 16 * 1024;
+
+type Frame = Arc<[u8]>;
+
+#[derive(Clone)]
+struct Member {
+    token:      u64,
+    connection: Arc<Connection>,
+    events:     async_channel::Sender<RelayEvent>,
+    /// What the member's streams cost to relay, rolling up into the room's.
+    quota:      Arc<Quota>,
+}
+
+struct Room {
+    members: HashMap<EndpointId, Member>,
+    quota:   Arc<Quota>,
+}
+
+/// Serves [`crate::ALPN`]: one room per space, each member's streams copied to
+/// the rest.
+///
+/// Any space may hold a room here. What bounds a relay is not who may use it
+/// but how much: rooms, members and streams are capped, joins are paced, and
+/// every byte relayed is charged to the member who sent it, their room, and
+/// the relay as a whole.
+#[derive(Clone)]
+pub struct Relay {
+    rooms: Arc<Mutex<HashMap<[u8; 32], Room>>>,
+    token: Arc<AtomicU64>,
+    quota: Arc<Quota>,
+}
+
+impl Default for Relay {
+    fn default() -> Self {
+        Self {
+            rooms: Arc::default(),
+            token: Arc::default(),
+            quota: Quota::root(Limits::global()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Relay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Relay").finish_non_exhaustive()
+    }
+}
+
+impl ProtocolHandler for Relay {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let member = connection.remote_id();
+        if let Err(err) = self.serve(Arc::new(connection)).await {
+            debug!(%member, ?err, "relay member left");
+        }
+        Ok(())
+    }
+}
+
+impl Relay {
+    async fn serve(&self, connection: Arc<Connection>) -> anyhow::Result<()> {
+        let id = connection.remote_id();
+
+        let (mut ctl_tx, mut ctl_rx) = connection.accept_bi().await.context("accept control")?;
+        let Some(Join { space }) = wire::read_msg::<Join, _>(&mut ctl_rx).await? else {
+            return Ok(());
+        };
+
+        let (events_tx, events_rx) = async_channel::bounded(EVENT_QUEUE);
+        let (token, quota) = self.join(space, id, Arc::clone(&connection), events_tx)?;
+        info!(member = %id, "Joined relay room");
+
+        let res = tokio::select! {
+            res = forward_events(&mut ctl_tx, &events_rx) => res,
+            // Closing the control stream is how a member leaves.
+            res = wire::read_msg::<Join, _>(&mut ctl_rx) => res.map(|_| ()),
+            res = self.accept_streams(space, &connection, &quota) => res,
+        };
+
+        self.leave(space, id, token);
+        info!(member = %id, "Left relay room");
+        res
+    }
+
+    /// Adds `id` to `space`'s room, and has everyone already there reopen
+    /// their streams so the newcomer hears them from the start.
+    ///
+    /// Refused when the relay or the room is full, or when the room has seen
+    /// too many joins lately.
+    fn join(
+        &self,
+        space: [u8; 32],
+        id: EndpointId,
+        connection: Arc<Connection>,
+        events: async_channel::Sender<RelayEvent>,
+    ) -> anyhow::Result<(u64, Arc<Quota>)> {
+        let token = self.token.fetch_add(1, Ordering::Relaxed);
+        let mut rooms = self.rooms.lock();
+        if !rooms.contains_key(&space) && rooms.len() >= MAX_ROOMS {
+            bail!("relay is full");
+        }
+        let room = rooms.entry(space).or_insert_with(|| Room {
+            members: HashMap::new(),
+            quota:   Quota::new(Limits::space(), Some(Arc::clone(&self.quota))),
+        });
+
+        let admitted = if !room.members.contains_key(&id) && room.members.len() >= MAX_MEMBERS {
+            Err(anyhow::anyhow!("room is full"))
+        } else {
+            room.quota
+                .spend(Flow::RelayJoin, 1.0)
+                .context("joining too often")
+        };
+        if let Err(err) = admitted {
+            if room.members.is_empty() {
+                rooms.remove(&space);
+            }
+            return Err(err);
+        }
+
+        for member in room.members.values() {
+            let _ = member.events.try_send(RelayEvent::Resync);
+        }
+        let quota = Quota::new(Limits::peer(), Some(Arc::clone(&room.quota)));
+        room.members.insert(
+            id,
+            Member {
+                token,
+                connection,
+                events,
+                quota: Arc::clone(&quota),
+            },
+        );
+        drop(rooms);
+        Ok((token, quota))
+    }
+
+    /// Removes `id` from `space`'s room, unless a newer connection of theirs
+    /// took its place.
+    fn leave(&self, space: [u8; 32], id: EndpointId, token: u64) {
+        let mut rooms = self.rooms.lock();
+        let Some(room) = rooms.get_mut(&space) else {
+            return;
+        };
+        if room.members.get(&id).is_some_and(|m| m.token == token) {
+            room.members.remove(&id);
+        }
+        if room.members.is_empty() {
+            rooms.remove(&space);
+        }
+    }
+
+    fn member(&self, space: [u8; 32], id: EndpointId) -> Option<Member> {
+        self.rooms.lock().get(&space)?.members.get(&id).cloned()
+    }
+
+    fn others(&self, space: [u8; 32], id: EndpointId) -> Vec<Member> {
+        self.rooms.lock().get(&space).map_or_else(Vec::new, |room| {
+            room.members
+                .iter()
+                .filter(|(other, _)| **other != id)
+                .map(|(_, member)| member.clone())
+                .collect()
+        })
+    }
+
+    async fn accept_streams(
+        &self,
+        space: [u8; 32],
+        connection: &Connection,
+        quota: &Arc<Quota>,
+    ) -> anyhow::Result<()> {
+        let origin = connection.remote_id();
+        // Aborted on drop, and reaped as they finish so a long membership does
+        // not keep one handle for every stream it ever opened.
+        let mut streams = JoinSet::new();
+
+        loop {
+            let (tx, mut rx) = tokio::select! {
+                accepted = connection.accept_bi() => accepted.context("accept_bi")?,
+                Some(_) = streams.join_next() => continue,
+            };
+            if streams.len() >= MAX_STREAMS {
+                debug!(%origin, "Refused a stream past the member's limit");
+                let _ = rx.stop(VarInt::from_u32(0));
+                continue;
+            }
+
+            let relay = self.clone();
+            let quota = Arc::clone(quota);
+            streams.spawn(async move {
+                if let Err(err) = relay.relay_stream(space, origin, &quota, tx, rx).await {
+                    debug!(%origin, ?err, "Relayed stream failed");
+                }
+            });
+        }
+    }
+
+    async fn relay_stream(
+        &self,
+        space: [u8; 32],
+        origin: EndpointId,
+        quota: &Quota,
+        tx: SendStream,
+        mut rx: RecvStream,
+    ) -> anyhow::Result<()> {
+        let ident = rx.read_u8().await.context("read ident")?;
+        match wire::stream_kind(ident) {
+            Some(StreamKind::Directed) => self.direct(space, origin, ident, quota, tx, rx).await,
+            Some(StreamKind::FanOut(framing)) => {
+                self.fan_out(space, origin, ident, framing, quota, rx).await
+            }
+            None => bail!("unknown stream ident {ident}"),
+        }
+    }
+
+    /// Passes a stream to the member it is addressed to, and their answer
+    /// back.
+    async fn direct(
+        &self,
+        space: [u8; 32],
+        origin: EndpointId,
+        ident: u8,
+        quota: &Quota,
+        mut tx: SendStream,
+        mut rx: RecvStream,
+    ) -> anyhow::Result<()> {
+        let mut target = [0; 32];
+        rx.read_exact(&mut target).await.context("read target")?;
+        let target = EndpointId::from_bytes(&target)?;
+        let Some(member) = self.member(space, target).filter(|_| target != origin) else {
+            bail!("{target} is not in the room")
+        };
+
+        let (mut down_tx, mut down_rx) = member.connection.open_bi().await?;
+        down_tx.write_u8(ident).await?;
+        down_tx.write_all(origin.as_bytes()).await?;
+
+        // Each side pays for what it says.
+        tokio::try_join!(
+            async {
+                copy_charged(&mut rx, &mut down_tx, quota).await?;
+                down_tx.finish()?;
+                anyhow::Ok(())
+            },
+            async {
+                copy_charged(&mut down_rx, &mut tx, &member.quota).await?;
+                tx.finish()?;
+                anyhow::Ok(())
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Copies a stream, frame by frame, to everyone else in the room when it
+    /// opened. Anyone joining later hears the stream reopened at the resync
+    /// their joining causes.
+    async fn fan_out(
+        &self,
+        space: [u8; 32],
+        origin: EndpointId,
+        ident: u8,
+        framing: Framing,
+        quota: &Quota,
+        mut rx: RecvStream,
+    ) -> anyhow::Result<()> {
+        let mut opening = Vec::with_capacity(1 + 32 + framing.header);
+        opening.push(ident);
+        opening.extend_from_slice(origin.as_bytes());
+        opening.resize(1 + 32 + framing.header, 0);
+        rx.read_exact(&mut opening[33..])
+            .await
+            .context("read header")?;
+        let opening = Frame::from(opening);
+
+        let queue = if framing.lossy {
+            LOSSY_QUEUE
+        } else {
+            LOSSLESS_QUEUE
+        };
+        let mut listeners = self
+            .others(space, origin)
+            .into_iter()
+            .map(|member| listen(member.connection, Arc::clone(&opening), queue))
+            .collect::<Vec<_>>();
+
+        while let Some(frame) = wire::read_frame(&mut rx, framing).await? {
+            let copies = listeners.len();
+            if framing.lossy {
+                // Stale by the next frame anyway, so a sender over budget
+                // loses frames rather than falling behind.
+                if quota.reserve(Flow::RelayBytes, frame.len() as f64) != Reservation::Ready {
+                    continue;
+                }
+                quota.commit(Flow::RelayBytes, (frame.len() * copies) as f64);
+            } else {
+                pace(quota, frame.len(), copies).await?;
+            }
+
+            let frame = Frame::from(frame);
+            let mut lagging = false;
+
+            listeners.retain(|listener| match listener.try_send(Arc::clone(&frame)) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) if framing.lossy => true,
+                Err(TrySendError::Full(_)) => {
+                    lagging = true;
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            });
+
+            // The cut stream ends cleanly after what it had queued; the sender
+            // reopening everything gives the laggard a fresh start.
+            if lagging && let Some(member) = self.member(space, origin) {
+                debug!(%origin, "Listener fell behind a lossless stream");
+                let _ = member.events.try_send(RelayEvent::Resync);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Waits until `quota` can afford one copy of a frame, then charges for every
+/// copy.
+///
+/// Like [`Flow::Cpu`], the bucket may run negative: a frame fanned out to a
+/// full room may cost more than any burst holds, and the sender waits out the
+/// debt on their next frame instead of never sending this one.
+async fn pace(quota: &Quota, len: usize, copies: usize) -> anyhow::Result<()> {
+    loop {
+        match quota.reserve(Flow::RelayBytes, len as f64) {
+            Reservation::Ready => {
+                quota.commit(Flow::RelayBytes, (len * copies) as f64);
+                return Ok(());
+            }
+            Reservation::After(wait) => n0_future::time::sleep(wait).await,
+            Reservation::Never => bail!("a {len} byte frame exceeds every relay budget"),
+        }
+    }
+}
+
+/// Copies `rx` to `tx` as it arrives, charging `quota` for each chunk.
+async fn copy_charged(
+    rx: &mut RecvStream,
+    tx: &mut SendStream,
+    quota: &Quota,
+) -> anyhow::Result<()> {
+    let mut buf = vec![0; COPY_CHUNK];
+    loop {
+        let n = rx.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        pace(quota, n, 1).await?;
+        tx.write_all(&buf[..n]).await?;
+    }
+}
+
+async fn forward_events(
+    tx: &mut SendStream,
+    events: &async_channel::Receiver<RelayEvent>,
+) -> anyhow::Result<()> {
+    while let Ok(event) = events.recv().await {
+        wire::write_msg(tx, &event).await?;
+    }
+    Ok(())
+}
+
+/// Opens a stream to one listener and writes whatever frames are queued for
+/// it, until the queue closes or the listener goes.
+fn listen(
+    connection: Arc<Connection>,
+    opening: Frame,
+    queue: usize,
+) -> async_channel::Sender<Frame> {
+    let (frames_tx, frames_rx) = async_channel::bounded::<Frame>(queue);
+
+    n0_future::task::spawn(async move {
+        let res = async {
+            let (mut tx, _rx) = connection.open_bi().await?;
+            tx.write_all(&opening).await?;
+            while let Ok(frame) = frames_rx.recv().await {
+                tx.write_all(&frame).await?;
+            }
+            tx.finish()?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(err) = res {
+            debug!(listener = %connection.remote_id(), ?err, "Relay listener stream ended");
+        }
+    });
+
+    frames_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn largest_frame() -> usize {
+        (0..=u8::MAX)
+            .filter_map(wire::stream_kind)
+            .filter_map(|kind| match kind {
+                StreamKind::FanOut(framing) => Some(framing.prefix + framing.max),
+                StreamKind::Directed => None,
+            })
+            .max()
+            .expect("some stream fans out")
+    }
+
+    #[tokio::test]
+    async fn the_largest_frame_is_sent_to_a_full_room_without_waiting() {
+        let relay = Relay::default();
+        let room = Quota::new(Limits::space(), Some(Arc::clone(&relay.quota)));
+        let member = Quota::new(Limits::peer(), Some(room));
+
+        n0_future::time::timeout(
+            Duration::from_secs(1),
+            pace(&member, largest_frame(), MAX_MEMBERS - 1),
+        )
+        .await
+        .expect("a full bucket affords one copy of any frame")
+        .expect("no frame is larger than a relay budget");
+
+        assert!(
+            matches!(member.reserve(Flow::RelayBytes, 1.0), Reservation::After(_)),
+            "the copies past the burst are owed, and the next frame waits"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! What goes over a relay connection.
+//!
+//! A member's first stream is its control stream: it writes one [`Join`], and
+//! the relay answers with [`RelayEvent`]s for as long as it stays. Every other
+//! stream it opens is a `wired/space/2` stream, ident byte first, exactly as
+//! it would open one to a peer. Each the relay opens to it carries the same
+//! ident, then the 32-byte endpoint id of the member it came from, then the
+//! stream as that member wrote it.
+
+use anyhow::{
+    Context,
+    bail,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+    de::DeserializeOwned,
+};
+use tokio::io::{
+    AsyncRead,
+    AsyncReadExt,
+    AsyncWrite,
+    AsyncWriteExt,
+};
+
+/// Largest control message.
+const MAX_MSG: usize = 1024;
+
+/// Largest state frame, as `wired/space/2` bounds it.
+const MAX_STATE_FRAME: usize = 8 * 1024 * 1024;
+
+/// Names the room to join: the space's namespace id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Join {
+    pub space: [u8; 32],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelayEvent {
+    /// Reopen every stream. Sent when someone joins, who would otherwise come
+    /// in mid-stream, and when a member fell too far behind on a stream that
+    /// cannot skip.
+    Resync,
+}
+
+/// How the relay treats a stream, by its ident byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    /// Addressed to one member, named after the ident, and passed through
+    /// both ways unread. The identity challenge, whose proof names both ends.
+    Directed,
+    /// Copied to every other member, a frame at a time.
+    FanOut(Framing),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Framing {
+    /// Bytes after the ident that open the stream, as an object stream's
+    /// document id.
+    pub header: usize,
+    /// Width of each frame's big-endian length.
+    pub prefix: usize,
+    /// Largest frame let through.
+    pub max:    usize,
+    /// Whether a frame may be skipped for a member that cannot keep up. Poses
+    /// and voice are stale by the next frame; state deltas are not, so a
+    /// lagging member's state stream is cut instead.
+    pub lossy:  bool,
+}
+
+impl Framing {
+    const fn lossy(header: usize, prefix: usize) -> Self {
+        Self {
+            header,
+            prefix,
+            max: (1 << (prefix * 8)) - 1,
+            lossy: true,
+        }
+    }
+}
+
+/// The `wired/space/2` stream kinds, in the order their idents encode.
+///
+/// Agent and object frames open with a sequence number, which is only more
+/// payload to the relay. Their receivers' feedback is not relayed: a listener's
+/// stream back to the relay goes unread. Interest is fanned out, not filtered
+/// by: every member gets every frame, so senders pace by the room's interest
+/// rather than each listener's.
+#[must_use]
+pub const fn stream_kind(ident: u8) -> Option<StreamKind> {
+    match ident {
+        // Identity.
+        0 => Some(StreamKind::Directed),
+        // Agent.
+        1 => Some(StreamKind::FanOut(Framing::lossy(0, 1))),
+        // Object, opened with its document id.
+        2 => Some(StreamKind::FanOut(Framing::lossy(32, 1))),
+        // State.
+        3 => Some(StreamKind::FanOut(Framing {
+            header: 0,
+            prefix: 4,
+            max:    MAX_STATE_FRAME,
+            lossy:  false,
+        })),
+        // Voice, interest.
+        4 | 5 => Some(StreamKind::FanOut(Framing::lossy(0, 2))),
+        _ => None,
+    }
+}
+
+/// Reads one frame, length and all, or `None` where the stream ends cleanly
+/// between frames.
+pub async fn read_frame<R: AsyncRead + Unpin>(
+    rx: &mut R,
+    framing: Framing,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut prefix = [0; 4];
+    let prefix = &mut prefix[..framing.prefix];
+    match rx.read_exact(prefix).await {
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err).context("read len"),
+    }
+
+    let len = prefix.iter().fold(0, |len, b| (len << 8) | usize::from(*b));
+    if len > framing.max {
+        bail!("frame length {len} exceeds max {}", framing.max);
+    }
+
+    let mut frame = Vec::with_capacity(framing.prefix + len);
+    frame.extend_from_slice(prefix);
+    frame.resize(framing.prefix + len, 0);
+    rx.read_exact(&mut frame[framing.prefix..])
+        .await
+        .context("read frame")?;
+    Ok(Some(frame))
+}
+
+pub async fn write_msg<T: Serialize, W: AsyncWrite + Unpin>(
+    tx: &mut W,
+    msg: &T,
+) -> anyhow::Result<()> {
+    let body = postcard::to_allocvec(msg)?;
+    if body.len() > MAX_MSG {
+        bail!("control message too large")
+    }
+    tx.write_u16(u16::try_from(body.len())?).await?;
+    tx.write_all(&body).await?;
+    Ok(())
+}
+
+/// Reads one control message, or `None` where the stream ends.
+pub async fn read_msg<T: DeserializeOwned, R: AsyncRead + Unpin>(
+    rx: &mut R,
+) -> anyhow::Result<Option<T>> {
+    let len = match rx.read_u16().await {
+        Ok(len) => usize::from(len),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err).context("read len"),
+    };
+    if len > MAX_MSG {
+        bail!("control message length {len} exceeds max {MAX_MSG}");
+    }
+    let mut body = vec![0; len];
+    rx.read_exact(&mut body).await.context("read msg")?;
+    Ok(Some(postcard::from_bytes(&body)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framing(ident: u8) -> Framing {
+        match stream_kind(ident) {
+            Some(StreamKind::FanOut(framing)) => framing,
+            other => panic!("ident {ident} is {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn frames_are_read_whole_with_their_length() {
+        let voice = framing(4);
+        let wire = [0, 3, 7, 8, 9, 0, 1, 5];
+        let mut rx = &wire[..];
+
+        let first = read_frame(&mut rx, voice).await.expect("read");
+        assert_eq!(first.as_deref(), Some(&wire[..5]));
+        let second = read_frame(&mut rx, voice).await.expect("read");
+        assert_eq!(second.as_deref(), Some(&wire[5..]));
+        assert!(read_frame(&mut rx, voice).await.expect("end").is_none());
+    }
+
+    #[tokio::test]
+    async fn oversized_and_truncated_frames_fail() {
+        let state = Framing {
+            max: 4,
+            ..framing(3)
+        };
+        let mut rx = &[0, 0, 0, 5, 1, 2, 3, 4, 5][..];
+        assert!(read_frame(&mut rx, state).await.is_err());
+
+        let mut rx = &[3, 1][..];
+        assert!(read_frame(&mut rx, framing(1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn control_messages_round_trip() {
+        let mut wire = Vec::new();
+        write_msg(&mut wire, &Join { space: [7; 32] })
+            .await
+            .expect("write");
+        write_msg(&mut wire, &RelayEvent::Resync)
+            .await
+            .expect("write");
+
+        let mut rx = &wire[..];
+        assert_eq!(
+            read_msg::<Join, _>(&mut rx).await.expect("read"),
+            Some(Join { space: [7; 32] })
+        );
+        assert_eq!(
+            read_msg::<RelayEvent, _>(&mut rx).await.expect("read"),
+            Some(RelayEvent::Resync)
+        );
+        assert!(
+            read_msg::<RelayEvent, _>(&mut rx)
+                .await
+                .expect("end")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn only_known_idents_are_relayed() {
+        assert_eq!(stream_kind(0), Some(StreamKind::Directed));
+        assert!(framing(2).header == 32 && framing(2).lossy);
+        assert!(!framing(3).lossy);
+        assert_eq!(stream_kind(6), None);
+    }
+}
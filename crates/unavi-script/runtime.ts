@@ -163,6 +163,16 @@ function buildImports(wasi: WASIShim, rt: any) {
     "wired:agent/types": {
       Agent: rt.wiredAgentClass(),
     },
+    "wired:audio/api": {
+      play: rt.wiredAudioPlay.bind(rt),
+      stop: rt.wiredAudioStop.bind(rt),
+      seek: rt.wiredAudioSeek.bind(rt),
+    },
+    "wired:chat/api": {
+      post: rt.wiredChatPost.bind(rt),
+      read: rt.wiredChatRead.bind(rt),
+    },
+    "wired:chat/types": {},
     "wired:event/api": {
       emit: rt.wiredEventEmit.bind(rt),
       listen: rt.wiredEventListen.bind(rt),
@@ -198,12 +208,19 @@ function buildImports(wasi: WASIShim, rt: any) {
     "wired:peer/types": {},
     "wired:physics/api": {
       raycast: rt.wiredPhysicsRaycast.bind(rt),
+      raycastAll: rt.wiredPhysicsRaycastAll.bind(rt),
+      shapeCast: rt.wiredPhysicsShapeCast.bind(rt),
+      overlap: rt.wiredPhysicsOverlap.bind(rt),
       getLinearVelocity: rt.wiredPhysicsGetLinearVelocity.bind(rt),
       setLinearVelocity: rt.wiredPhysicsSetLinearVelocity.bind(rt),
       setAngularVelocity: rt.wiredPhysicsSetAngularVelocity.bind(rt),
       applyForce: rt.wiredPhysicsApplyForce.bind(rt),
       claimAuthority: rt.wiredPhysicsClaimAuthority.bind(rt),
       releaseAuthority: rt.wiredPhysicsReleaseAuthority.bind(rt),
+      registerContactListener: rt.wiredPhysicsRegisterContactListener.bind(rt),
+    },
+    "wired:physics/types": {
+      ContactListener: rt.wiredPhysicsContactListenerClass(),
     },
     "wired:portal/api": {
       open: rt.wiredPortalOpen.bind(rt),
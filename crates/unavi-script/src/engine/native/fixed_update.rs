@@ -27,6 +27,7 @@ use crate::{
                 script_budget,
                 wait_for_scripts,
             },
+            fuel,
             instantiate::{
                 ScriptGuest,
                 ScriptSpan,
@@ -34,6 +35,7 @@ use crate::{
             },
         },
     },
+    quota::cpu::ScriptCpu,
 };
 
 const FIXED_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
@@ -50,6 +52,7 @@ type FixedUpdateQuery<'w, 's> = Query<
         &'static ScriptGuest,
         &'static ScriptStore,
         &'static ScriptSpan,
+        &'static ScriptCpu,
         &'static mut LastFixedUpdate,
     ),
     With<InitializedScript>,
@@ -68,7 +71,7 @@ pub fn fixed_update_scripts(
         let budget = script_budget(&time);
         let now = time.elapsed();
 
-        for (updating, trapped, guest, store, span, mut last) in &mut to_update {
+        for (updating, trapped, guest, store, span, cpu, mut last) in &mut to_update {
             if trapped.get() {
                 continue;
             }
@@ -79,6 +82,10 @@ pub fn fixed_update_scripts(
             if updating.0.swap(true, Ordering::SeqCst) {
                 continue;
             }
+            if !cpu.ready() {
+                updating.0.store(false, Ordering::SeqCst);
+                continue;
+            }
 
             let margin = delta
                 .checked_sub(FIXED_UPDATE_INTERVAL)
@@ -90,6 +97,7 @@ pub fn fixed_update_scripts(
             let trapped = Trapped(Arc::clone(&trapped.0));
             let guest = Arc::clone(&guest.0);
             let store = Arc::clone(&store.0);
+            let cpu = cpu.clone();
             let outstanding = Arc::clone(&outstanding);
             outstanding.fetch_add(1, Ordering::AcqRel);
 
@@ -97,6 +105,7 @@ pub fn fixed_update_scripts(
                 async move {
                     let mut store = store.lock().await;
                     store.set_epoch_deadline(1);
+                    fuel::fill(&mut store);
 
                     let api = Arc::clone(&store.data().api);
                     let tick = api.open_tick().await;
@@ -105,11 +114,18 @@ pub fn fixed_update_scripts(
                         .call_fixed_update(store.as_context_mut())
                         .await;
                     drop(tick);
+                    fuel::settle(&store, &cpu);
 
                     if let Err(err) = result
                         && trapped.set()
                     {
-                        error!(?err, "Script trapped while fixed-updating; it is finished");
+                        if fuel::ran_out(&err) {
+                            error!(
+                                "Script kept overrunning its CPU budget while fixed-updating; it is finished"
+                            );
+                        } else {
+                            error!(?err, "Script trapped while fixed-updating; it is finished");
+                        }
                     }
                     drop(store);
 
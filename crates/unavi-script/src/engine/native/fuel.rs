@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use wasmtime::{
+    Store,
+    Trap,
+    UpdateDeadline,
+};
+
+use crate::{
+    quota::cpu::ScriptCpu,
+    runtime::Runtime,
+};
+
+/// Fuel in the tank at the start of a call and after every charge.
+///
+/// Running dry traps the instance, and a trapped component can never be entered
+/// again, so the tank is never what stops a script. It only measures; the
+/// epoch callback from [`meter`] bills what was burned and pauses the call
+/// while the script is in debt.
+const TANK: u64 = u64::MAX >> 2;
+
+/// Bills the script for each frame it spends inside a call.
+///
+/// Every epoch, what the call burned is charged to `cpu`. A script still in
+/// credit yields and carries on next frame; one in debt is paused until its
+/// bucket refills, and one paused too often is stopped with
+/// [`Trap::OutOfFuel`].
+pub fn meter(store: &mut Store<Runtime>, cpu: &ScriptCpu) {
+    let cpu = cpu.clone();
+    store.epoch_deadline_callback(move |mut ctx| {
+        let left = ctx.get_fuel()?;
+        ctx.set_fuel(TANK)?;
+        cpu.charge(TANK.saturating_sub(left));
+
+        if cpu.ready() {
+            return Ok(UpdateDeadline::Yield(1));
+        }
+        if cpu.pause() {
+            return Err(wasmtime::Error::new(Trap::OutOfFuel));
+        }
+        Ok(UpdateDeadline::YieldCustom(1, Box::pin(cpu.refilled())))
+    });
+}
+
+/// Fills the tank for one call.
+pub fn fill(store: &mut Store<Runtime>) {
+    if let Err(err) = store.set_fuel(TANK) {
+        error!(?err, "Failed to fuel script");
+    }
+}
+
+/// Charges the script for what the call burned since its last charge.
+pub fn settle(store: &Store<Runtime>, cpu: &ScriptCpu) {
+    let left = store.get_fuel().unwrap_or(TANK);
+    cpu.settle(TANK.saturating_sub(left));
+}
+
+/// Whether a call was stopped for overrunning its CPU budget too often.
+#[must_use]
+pub fn ran_out(err: &wasmtime::Error) -> bool {
+    err.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel)
+}
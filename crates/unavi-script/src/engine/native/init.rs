@@ -5,13 +5,19 @@ use tracing::Instrument;
 use unavi_util::async_task::spawn_async_task;
 use wasmtime::AsContextMut;
 
-use crate::engine::{
-    InitializedScript,
-    native::instantiate::{
-        ScriptGuest,
-        ScriptSpan,
-        ScriptStore,
+use crate::{
+    engine::{
+        InitializedScript,
+        native::{
+            fuel,
+            instantiate::{
+                ScriptGuest,
+                ScriptSpan,
+                ScriptStore,
+            },
+        },
     },
+    quota::cpu::ScriptCpu,
 };
 
 #[derive(Component)]
@@ -19,14 +25,18 @@ pub struct InitingScript(tokio::sync::oneshot::Receiver<()>);
 
 pub fn init_scripts(
     to_init: Query<
-        (Entity, &ScriptGuest, &ScriptStore, &ScriptSpan),
+        (Entity, &ScriptGuest, &ScriptStore, &ScriptSpan, &ScriptCpu),
         (Without<InitingScript>, Without<InitializedScript>),
     >,
     mut commands: Commands,
 ) {
-    for (entity, guest, store, span) in to_init {
+    for (entity, guest, store, span, cpu) in to_init {
+        if !cpu.ready() {
+            continue;
+        }
         let guest = Arc::clone(&guest.0);
         let store = Arc::clone(&store.0);
+        let cpu = cpu.clone();
 
         let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -34,6 +44,7 @@ pub fn init_scripts(
             async move {
                 let mut store = store.lock().await;
                 store.set_epoch_deadline(1);
+                fuel::fill(&mut store);
 
                 let api = Arc::clone(&store.data().api);
                 let tick = api.open_tick().await;
@@ -42,12 +53,16 @@ pub fn init_scripts(
                     .call_init(store.as_context_mut())
                     .await;
                 drop(tick);
+                fuel::settle(&store, &cpu);
 
                 match result {
                     Ok(()) => {
                         drop(store);
                         let _ = tx.send(());
                     }
+                    Err(err) if fuel::ran_out(&err) => {
+                        error!("Script kept overrunning its CPU budget while initializing");
+                    }
                     Err(err) => {
                         error!(?err, "Failed to init script");
                     }
@@ -33,6 +33,7 @@ use crate::{
         native::{
             WasmtimeEngine,
             fixed_update::LastFixedUpdate,
+            fuel,
             log::{
                 ScriptStderr,
                 ScriptStdout,
@@ -42,6 +43,7 @@ use crate::{
     load::asset::Wasm,
     quota::{
         QuotaExempt,
+        cpu::ScriptCpu,
         limiter::QuotaLimiter,
     },
     runtime::{
@@ -100,6 +102,18 @@ pub fn instantiate_scripts(
             continue;
         };
 
+        let quota = if exempt {
+            Quota::unlimited()
+        } else {
+            unavi_space::quota::document_quota(doc_id.0)
+        };
+        let cpu = ScriptCpu::new(Arc::clone(&quota));
+        // Instantiation runs start functions, so it is paid for like a tick,
+        // and waits for one.
+        if !cpu.ready() {
+            continue;
+        }
+
         let span = info_span!("", name = name.to_string());
 
         let (stdout, stdout_stream) = ScriptStdout::new();
@@ -114,24 +128,19 @@ pub fn instantiate_scripts(
             .allow_udp(false)
             .build();
 
-        let quota = if exempt {
-            Quota::unlimited()
-        } else {
-            unavi_space::quota::document_quota(doc_id.0)
-        };
-
         let state = Runtime {
             api:    Arc::new(Api {
-                state:       Arc::clone(&doc.0),
-                doc_id:      doc_id.0,
-                prim:        prim.0,
-                quota:       Arc::clone(&quota),
-                wired_agent: Mutex::default(),
-                wired_event: Mutex::default(),
-                wired_input: Mutex::default(),
-                wired_kv:    Mutex::default(),
-                wired_scene: Mutex::default(),
-                wired_wds:   Mutex::default(),
+                state:         Arc::clone(&doc.0),
+                doc_id:        doc_id.0,
+                prim:          prim.0,
+                quota:         Arc::clone(&quota),
+                wired_agent:   Mutex::default(),
+                wired_event:   Mutex::default(),
+                wired_input:   Mutex::default(),
+                wired_kv:      Mutex::default(),
+                wired_physics: Mutex::default(),
+                wired_scene:   Mutex::default(),
+                wired_wds:     Mutex::default(),
             }),
             native: NativeRuntime {
                 table: ResourceTable::default(),
@@ -140,8 +149,9 @@ pub fn instantiate_scripts(
             },
         };
         let mut store = Store::new(&engine.0, state);
-        store.epoch_deadline_async_yield_and_update(1);
+        fuel::meter(&mut store, &cpu);
         store.limiter(|state| &mut state.native.limiter);
+        fuel::fill(&mut store);
         let store = Arc::new(Mutex::new(store));
 
         let engine = engine.0.clone();
@@ -151,9 +161,12 @@ pub fn instantiate_scripts(
 
         spawn_async_task({
             let store = Arc::clone(&store);
+            let cpu = cpu.clone();
             async move {
                 let mut store = store.lock().await;
-                match instantiate_component(&engine, &wasm, &mut store).await {
+                let result = instantiate_component(&engine, &wasm, &mut store).await;
+                fuel::settle(&store, &cpu);
+                match result {
                     Ok(g) => {
                         let _ = tx.send(g);
                     }
@@ -166,6 +179,7 @@ pub fn instantiate_scripts(
         commands.entity(entity).insert((
             InstantiatingScript(rx),
             ScriptStore(store),
+            cpu,
             ScriptSpan(span),
         ));
     }
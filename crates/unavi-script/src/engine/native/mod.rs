@@ -9,6 +9,7 @@ use crate::{
 
 mod drive;
 mod fixed_update;
+mod fuel;
 mod init;
 mod instantiate;
 mod log;
@@ -43,7 +44,13 @@ impl Plugin for NativeEnginePlugin {
 struct WasmtimeEngine(wasmtime::Engine);
 
 fn init_wasmtime_engine(trigger: On<Add, Engine>, mut commands: Commands) {
-    let engine = match wasmtime::Engine::new(Config::default().epoch_interruption(true)) {
+    // Epochs keep a long call from stalling the frame; fuel bills it to the
+    // script's CPU quota.
+    let engine = match wasmtime::Engine::new(
+        Config::default()
+            .epoch_interruption(true)
+            .consume_fuel(true),
+    ) {
         Ok(e) => e,
         Err(err) => {
             error!(?err, "Failed to create Wasmtime engine");
@@ -24,6 +24,7 @@ use crate::{
                 script_budget,
                 wait_for_scripts,
             },
+            fuel,
             instantiate::{
                 ScriptGuest,
                 ScriptSpan,
@@ -31,6 +32,7 @@ use crate::{
             },
         },
     },
+    quota::cpu::ScriptCpu,
 };
 
 type UpdateQuery<'w, 's> = Query<
@@ -42,6 +44,7 @@ type UpdateQuery<'w, 's> = Query<
         &'static ScriptGuest,
         &'static ScriptStore,
         &'static ScriptSpan,
+        &'static ScriptCpu,
     ),
     With<InitializedScript>,
 >;
@@ -58,18 +61,23 @@ pub fn update_scripts(
         };
         let budget = script_budget(&time);
 
-        for (updating, trapped, guest, store, span) in to_update {
+        for (updating, trapped, guest, store, span, cpu) in to_update {
             if trapped.get() {
                 continue;
             }
             if updating.0.swap(true, Ordering::SeqCst) {
                 continue;
             }
+            if !cpu.ready() {
+                updating.0.store(false, Ordering::SeqCst);
+                continue;
+            }
 
             let updating = Arc::clone(&updating.0);
             let trapped = Trapped(Arc::clone(&trapped.0));
             let guest = Arc::clone(&guest.0);
             let store = Arc::clone(&store.0);
+            let cpu = cpu.clone();
             let outstanding = Arc::clone(&outstanding);
             outstanding.fetch_add(1, Ordering::AcqRel);
 
@@ -77,6 +85,7 @@ pub fn update_scripts(
                 async move {
                     let mut store = store.lock().await;
                     store.set_epoch_deadline(1);
+                    fuel::fill(&mut store);
 
                     let api = Arc::clone(&store.data().api);
                     let tick = api.open_tick().await;
@@ -85,11 +94,16 @@ pub fn update_scripts(
                         .call_update(store.as_context_mut())
                         .await;
                     drop(tick);
+                    fuel::settle(&store, &cpu);
 
                     if let Err(err) = result
                         && trapped.set()
                     {
-                        error!(?err, "Script trapped while updating; it is finished");
+                        if fuel::ran_out(&err) {
+                            error!("Script kept overrunning its CPU budget while updating; it is finished");
+                        } else {
+                            error!(?err, "Script trapped while updating; it is finished");
+                        }
                     }
                     drop(store);
 
@@ -68,6 +68,7 @@ pub fn instantiate_scripts(
                 wired_event: Mutex::default(),
                 wired_input: Mutex::default(),
                 wired_kv: Mutex::default(),
+                wired_physics: Mutex::default(),
                 wired_scene: Mutex::default(),
                 wired_wds: Mutex::default(),
             }),
@@ -4,7 +4,10 @@ use unavi_quota::{
     QuotaError,
     Stock,
 };
-use unavi_space::state::replicas::KvError;
+use unavi_space::{
+    chat::ChatError,
+    state::replicas::KvError,
+};
 
 /// Host-side canonical error, mirroring `wired:error/types.error`.
 ///
@@ -52,6 +55,19 @@ impl From<KvError> for ScriptError {
     }
 }
 
+impl From<ChatError> for ScriptError {
+    fn from(err: ChatError) -> Self {
+        match err {
+            ChatError::Quota => Self::QuotaFlow(Flow::Chat),
+            ChatError::TooLong
+            | ChatError::Empty
+            | ChatError::NoIdentity
+            | ChatError::NotJoined
+            | ChatError::Sign(_) => Self::Other(err.to_string()),
+        }
+    }
+}
+
 impl From<PolicyError> for ScriptError {
     fn from(err: PolicyError) -> Self {
         Self::Policy(err)
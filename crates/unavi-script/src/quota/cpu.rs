@@ -0,0 +1,261 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicBool,
+            AtomicU8,
+            AtomicU32,
+            AtomicU64,
+            Ordering,
+        },
+    },
+    time::Duration,
+};
+
+use bevy::ecs::component::Component;
+use unavi_quota::{
+    Flow,
+    Quota,
+    Reservation,
+};
+
+/// Least fuel worth starting a call with. Below it the script waits for its
+/// bucket to refill.
+pub const MIN_CALL_FUEL: u64 = 100_000;
+
+/// Times in a row a script may be paused mid-call for debt before it is
+/// stopped for good. A call that finishes without a pause clears the count, so
+/// only a script that keeps running past its budget is cut off.
+pub const MAX_STRIKES: u32 = 3;
+
+/// Longest sleep while waiting out a budget that currently allows nothing,
+/// after which the chain is asked again.
+const SUSPENDED_POLL: Duration = Duration::from_millis(250);
+
+/// Where a script stands with its CPU budget, as of its last tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CpuState {
+    Running,
+    /// In debt; its ticks are skipped, or its call paused, until the bucket
+    /// refills.
+    Deferred,
+    /// Its chain allows no CPU at all, as for a blocked peer's content.
+    Suspended,
+    /// Paused mid-call [`MAX_STRIKES`] times in a row, and stopped for good.
+    Exhausted,
+}
+
+impl CpuState {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Deferred,
+            2 => Self::Suspended,
+            3 => Self::Exhausted,
+            _ => Self::Running,
+        }
+    }
+}
+
+/// A script's account with [`Flow::Cpu`].
+///
+/// A call starts once every level of the chain holds [`MIN_CALL_FUEL`], and
+/// what it burns is charged each frame it runs and once more when it returns.
+/// Nothing stops a call at the balance, so scripts sharing a chain, or one long
+/// call, may overdraw it; the debt defers later ticks, and pauses a call still
+/// running, until it is repaid.
+///
+/// Only the native engine burns fuel. A web script runs on the browser's wasm
+/// engine, which has no meter, so it has no account.
+#[derive(Component, Clone)]
+pub struct ScriptCpu(Arc<Meter>);
+
+struct Meter {
+    quota:    Arc<Quota>,
+    spent:    AtomicU64,
+    deferred: AtomicU64,
+    state:    AtomicU8,
+    strikes:  AtomicU32,
+    /// Whether the call in flight has been paused for debt.
+    paused:   AtomicBool,
+}
+
+impl ScriptCpu {
+    #[must_use]
+    pub fn new(quota: Arc<Quota>) -> Self {
+        Self(Arc::new(Meter {
+            quota,
+            spent: AtomicU64::new(0),
+            deferred: AtomicU64::new(0),
+            state: AtomicU8::new(CpuState::Running as u8),
+            strikes: AtomicU32::new(0),
+            paused: AtomicBool::new(false),
+        }))
+    }
+
+    /// Whether the script may run now, or sits this tick out.
+    #[must_use]
+    pub fn ready(&self) -> bool {
+        let state = match self.0.quota.reserve(Flow::Cpu, MIN_CALL_FUEL as f64) {
+            Reservation::Ready => {
+                self.set_state(CpuState::Running);
+                return true;
+            }
+            Reservation::After(_) => CpuState::Deferred,
+            Reservation::Never => CpuState::Suspended,
+        };
+        self.set_state(state);
+        self.0.deferred.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    /// Charges fuel burned by a call still running.
+    pub fn charge(&self, used: u64) {
+        self.0.spent.fetch_add(used, Ordering::Relaxed);
+        self.0.quota.commit(Flow::Cpu, used as f64);
+    }
+
+    /// Charges the rest of what a call burned once it returns.
+    pub fn settle(&self, used: u64) {
+        self.charge(used);
+        if !self.0.paused.swap(false, Ordering::Relaxed) {
+            self.0.strikes.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a call paused mid-way for debt, answering whether that was one
+    /// pause too many and the script is finished.
+    #[must_use]
+    pub fn pause(&self) -> bool {
+        self.0.paused.store(true, Ordering::Relaxed);
+        let strikes = self.0.strikes.fetch_add(1, Ordering::Relaxed) + 1;
+        if strikes < MAX_STRIKES {
+            return false;
+        }
+        self.set_state(CpuState::Exhausted);
+        true
+    }
+
+    /// Resolves once the bucket can start a call again.
+    pub fn refilled(&self) -> impl Future<Output = ()> + Send + 'static {
+        let quota = Arc::clone(&self.0.quota);
+        async move {
+            loop {
+                let wait = match quota.reserve(Flow::Cpu, MIN_CALL_FUEL as f64) {
+                    Reservation::Ready => return,
+                    Reservation::After(wait) => wait,
+                    Reservation::Never => SUSPENDED_POLL,
+                };
+                n0_future::time::sleep(wait).await;
+            }
+        }
+    }
+
+    /// Fuel burned over the script's life.
+    #[must_use]
+    pub fn spent(&self) -> u64 {
+        self.0.spent.load(Ordering::Relaxed)
+    }
+
+    /// Ticks skipped for want of budget.
+    #[must_use]
+    pub fn deferred(&self) -> u64 {
+        self.0.deferred.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn state(&self) -> CpuState {
+        CpuState::from_u8(self.0.state.load(Ordering::Relaxed))
+    }
+
+    fn set_state(&self, state: CpuState) {
+        // An exhausted script is finished; nothing it does later changes that.
+        let _ = self
+            .0
+            .state
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                (current != CpuState::Exhausted as u8).then_some(state as u8)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unavi_quota::limits::{
+        FlowLimit,
+        Limits,
+    };
+
+    use super::*;
+
+    fn cpu_quota(capacity: f64, refill_per_sec: f64) -> Arc<Quota> {
+        let mut limits = Limits::default();
+        limits.flow.insert(
+            Flow::Cpu,
+            FlowLimit {
+                capacity,
+                refill_per_sec,
+            },
+        );
+        Quota::root(limits)
+    }
+
+    #[test]
+    fn a_script_in_debt_is_deferred() {
+        let cpu = ScriptCpu::new(cpu_quota(1_000_000.0, 1.0));
+        assert!(cpu.ready());
+
+        cpu.settle(1_500_000);
+        assert!(!cpu.ready());
+        assert_eq!(cpu.state(), CpuState::Deferred);
+        assert_eq!(cpu.deferred(), 1);
+        assert_eq!(cpu.spent(), 1_500_000);
+    }
+
+    #[test]
+    fn a_zero_budget_suspends() {
+        let cpu = ScriptCpu::new(cpu_quota(0.0, 0.0));
+        assert!(!cpu.ready());
+        assert_eq!(cpu.state(), CpuState::Suspended);
+    }
+
+    #[test]
+    fn an_overrun_recovers_once_the_bucket_refills() {
+        let cpu = ScriptCpu::new(cpu_quota(1_000_000.0, 100_000_000.0));
+        assert!(cpu.ready());
+
+        cpu.charge(1_500_000);
+        assert!(!cpu.ready());
+        assert!(!cpu.pause());
+        assert_eq!(cpu.state(), CpuState::Deferred);
+
+        std::thread::sleep(Duration::from_millis(50));
+        cpu.settle(0);
+        assert!(cpu.ready());
+        assert_eq!(cpu.state(), CpuState::Running);
+    }
+
+    #[test]
+    fn a_clean_call_clears_strikes() {
+        let cpu = ScriptCpu::new(Quota::unlimited());
+        for _ in 0..MAX_STRIKES * 2 {
+            assert!(!cpu.pause());
+            cpu.settle(0);
+            cpu.settle(0);
+        }
+        assert_eq!(cpu.state(), CpuState::Running);
+    }
+
+    #[test]
+    fn repeat_offenders_are_stopped_for_good() {
+        let cpu = ScriptCpu::new(Quota::unlimited());
+        for _ in 1..MAX_STRIKES {
+            assert!(!cpu.pause());
+            cpu.settle(0);
+        }
+        assert!(cpu.pause());
+        assert!(cpu.ready());
+        assert_eq!(cpu.state(), CpuState::Exhausted);
+    }
+}
@@ -9,6 +9,7 @@ use unavi_quota::{
     StockGuard,
 };
 
+pub mod cpu;
 #[cfg(not(target_family = "wasm"))] pub mod limiter;
 
 /// How long a script may be slowed down before the ask is called unreasonable.
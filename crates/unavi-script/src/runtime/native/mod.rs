@@ -35,6 +35,11 @@ pub fn add_apis_to_linker(linker: &mut Linker<Runtime>) -> wasmtime::Result<()>
     wired::agent::bindings::wired::agent::types::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
     wired::agent::bindings::wired::agent::api::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
 
+    wired::audio::bindings::wired::audio::api::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
+
+    wired::chat::bindings::wired::chat::api::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
+    wired::chat::bindings::wired::chat::types::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
+
     wired::event::bindings::wired::event::api::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
     wired::event::bindings::wired::event::types::add_to_linker::<_, HasSelf<_>>(linker, |r| r)?;
 
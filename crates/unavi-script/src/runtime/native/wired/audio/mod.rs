@@ -0,0 +1,56 @@
+use unavi_policy::document::ApiName;
+use wasmtime::component::Resource;
+
+use crate::runtime::{
+    Runtime,
+    shared::{
+        self,
+        wired::scene::prim::PrimRes,
+    },
+};
+
+pub mod bindings {
+    pub use crate::runtime::shared::wired::scene::prim::PrimRes;
+
+    wasmtime::component::bindgen!({
+        path: "../../protocol/wit/wired-audio",
+        with: {
+            "wired:scene/types.prim": PrimRes,
+            "wired:error/types": crate::runtime::native::wired::error::bindings::wired::error::types,
+        },
+        imports: { default: async | trappable },
+        exports: { default: async | trappable },
+    });
+}
+
+use crate::runtime::native::wired::error::bindings::wired::error::types::Error;
+
+impl bindings::wired::audio::api::Host for Runtime {
+    async fn play(&mut self, emitter: Resource<PrimRes>) -> wasmtime::Result<Result<(), Error>> {
+        let result = match self.api.require(ApiName::Audio) {
+            Ok(()) => shared::wired::audio::play(&self.api, emitter.rep()).await,
+            Err(err) => Err(err),
+        };
+        Ok(result.map_err(Into::into))
+    }
+
+    async fn stop(&mut self, emitter: Resource<PrimRes>) -> wasmtime::Result<Result<(), Error>> {
+        let result = match self.api.require(ApiName::Audio) {
+            Ok(()) => shared::wired::audio::stop(&self.api, emitter.rep()).await,
+            Err(err) => Err(err),
+        };
+        Ok(result.map_err(Into::into))
+    }
+
+    async fn seek(
+        &mut self,
+        emitter: Resource<PrimRes>,
+        seconds: f32,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let result = match self.api.require(ApiName::Audio) {
+            Ok(()) => shared::wired::audio::seek(&self.api, emitter.rep(), seconds).await,
+            Err(err) => Err(err),
+        };
+        Ok(result.map_err(Into::into))
+    }
+}
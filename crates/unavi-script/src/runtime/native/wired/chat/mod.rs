@@ -0,0 +1,61 @@
+use unavi_policy::document::ApiName;
+
+use crate::runtime::{
+    Runtime,
+    shared,
+};
+
+pub mod bindings {
+    wasmtime::component::bindgen!({
+        path: "../../protocol/wit/wired-chat",
+        with: {
+            "wired:error/types": crate::runtime::native::wired::error::bindings::wired::error::types,
+        },
+        imports: { default: async | trappable },
+        exports: { default: async | trappable },
+    });
+}
+
+use self::bindings::wired::chat::types::Message;
+use crate::runtime::native::wired::error::bindings::wired::error::types::Error;
+
+impl From<shared::wired::chat::Message> for Message {
+    fn from(m: shared::wired::chat::Message) -> Self {
+        Self {
+            id:   m.id,
+            did:  m.did,
+            bot:  m.bot,
+            body: m.body,
+            sent: m.sent,
+        }
+    }
+}
+
+impl bindings::wired::chat::types::Host for Runtime {}
+
+impl bindings::wired::chat::api::Host for Runtime {
+    async fn post(&mut self, body: String) -> wasmtime::Result<Result<(), Error>> {
+        let result = match self.api.require(ApiName::Chat) {
+            Ok(()) => shared::wired::chat::post(&self.api, body).await,
+            Err(err) => Err(err),
+        };
+        Ok(result.map_err(Into::into))
+    }
+
+    async fn read(
+        &mut self,
+        after: u64,
+        limit: u32,
+    ) -> wasmtime::Result<Result<Vec<Message>, Error>> {
+        Ok(self
+            .api
+            .require(ApiName::Chat)
+            .map(|()| {
+                shared::wired::chat::read(&self.api, after, limit)
+                    .into_iter()
+                    .map(Message::from)
+                    .collect()
+            })
+            .map_err(Into::into))
+    }
+}
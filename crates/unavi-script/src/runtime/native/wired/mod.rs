@@ -1,4 +1,6 @@
 pub mod agent;
+pub mod audio;
+pub mod chat;
 pub mod error;
 pub mod event;
 pub mod input;
@@ -0,0 +1,53 @@
+use wasmtime::component::Resource;
+
+use crate::runtime::{
+    Runtime,
+    native::wired::physics::{
+        bindings::{
+            ContactListenerRes,
+            wired::physics::types::{
+                ContactEvent,
+                ContactKind,
+                HostContactListener,
+            },
+        },
+        vec3,
+    },
+    shared::{
+        self,
+        wired::physics::contact,
+    },
+};
+
+impl HostContactListener for Runtime {
+    async fn poll(
+        &mut self,
+        self_: Resource<ContactListenerRes>,
+    ) -> wasmtime::Result<Option<ContactEvent>> {
+        shared::wired::physics::contact::poll(&self.api, self_.rep())
+            .await
+            .map(|event| event.map(into_wit_event))
+            .map_err(wasmtime::Error::from_anyhow)
+    }
+
+    async fn drop(&mut self, rep: Resource<ContactListenerRes>) -> wasmtime::Result<()> {
+        shared::wired::physics::contact::drop(&self.api, rep.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)
+    }
+}
+
+fn into_wit_event(event: contact::ContactEvent) -> ContactEvent {
+    ContactEvent {
+        kind:     match event.kind {
+            contact::ContactKind::Enter => ContactKind::Enter,
+            contact::ContactKind::Exit => ContactKind::Exit,
+            contact::ContactKind::Contact => ContactKind::Contact,
+        },
+        document: event.document.0.to_vec(),
+        prim:     event.prim.to_string(),
+        point:    vec3(event.point.to_array()),
+        normal:   vec3(event.normal.to_array()),
+        impulse:  event.impulse,
+    }
+}
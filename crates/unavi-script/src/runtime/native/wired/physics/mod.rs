@@ -3,21 +3,36 @@ use std::future::Future;
 use unavi_policy::document::ApiName;
 use wasmtime::component::Resource;
 
-use crate::runtime::{
-    Runtime,
-    shared::{
-        self,
-        wired::scene::prim::PrimRes,
+use crate::{
+    error::ScriptError,
+    runtime::{
+        Runtime,
+        shared::{
+            self,
+            wired::{
+                physics::{
+                    contact::ContactListenerRes,
+                    query,
+                },
+                scene::prim::PrimRes,
+            },
+        },
     },
 };
 
+mod listener;
+
 pub mod bindings {
-    pub use crate::runtime::shared::wired::scene::prim::PrimRes;
+    pub use crate::runtime::shared::wired::{
+        physics::contact::ContactListenerRes,
+        scene::prim::PrimRes,
+    };
 
     wasmtime::component::bindgen!({
         path: "../../protocol/wit/wired-physics",
         with: {
             "wired:scene/types.prim": PrimRes,
+            "wired:physics/types.contact-listener": ContactListenerRes,
             "wired:error/types": crate::runtime::native::wired::error::bindings::wired::error::types,
         },
         imports: { default: async | trappable },
@@ -25,7 +40,14 @@ pub mod bindings {
     });
 }
 
-use bindings::wired::physics::types::RayHit;
+use bindings::wired::{
+    math::types::Quat,
+    physics::types::{
+        OverlapHit,
+        QueryShape,
+        RayHit,
+    },
+};
 
 use crate::runtime::native::wired::error::bindings::wired::error::types::Error;
 
@@ -51,6 +73,84 @@ impl bindings::wired::physics::api::Host for Runtime {
         Ok(result.map(|hit| hit.map(into_wit_hit)).map_err(Into::into))
     }
 
+    async fn raycast_all(
+        &mut self,
+        origin: bindings::wired::math::types::Vec3,
+        dir: bindings::wired::math::types::Vec3,
+        max_dist: f32,
+        max_hits: u32,
+        layers: Option<u32>,
+    ) -> wasmtime::Result<Result<Vec<RayHit>, Error>> {
+        if let Err(err) = self.api.require(ApiName::Physics) {
+            return Ok(Err(err.into()));
+        }
+        let result = query::raycast_all(
+            &self.api,
+            [origin.x, origin.y, origin.z],
+            [dir.x, dir.y, dir.z],
+            max_dist,
+            max_hits,
+            layers,
+        )
+        .await;
+        Ok(result
+            .map(|hits| hits.into_iter().map(into_wit_hit).collect())
+            .map_err(Into::into))
+    }
+
+    async fn shape_cast(
+        &mut self,
+        shape: QueryShape,
+        origin: bindings::wired::math::types::Vec3,
+        rotation: Quat,
+        dir: bindings::wired::math::types::Vec3,
+        max_dist: f32,
+        layers: Option<u32>,
+    ) -> wasmtime::Result<Result<Option<RayHit>, Error>> {
+        if let Err(err) = self.api.require(ApiName::Physics) {
+            return Ok(Err(err.into()));
+        }
+        let result = query::shape_cast(
+            &self.api,
+            query_shape(shape),
+            placement(origin, rotation),
+            [dir.x, dir.y, dir.z],
+            max_dist,
+            layers,
+        )
+        .await;
+        Ok(result.map(|hit| hit.map(into_wit_hit)).map_err(Into::into))
+    }
+
+    async fn overlap(
+        &mut self,
+        shape: QueryShape,
+        origin: bindings::wired::math::types::Vec3,
+        rotation: Quat,
+        layers: Option<u32>,
+    ) -> wasmtime::Result<Result<Vec<OverlapHit>, Error>> {
+        if let Err(err) = self.api.require(ApiName::Physics) {
+            return Ok(Err(err.into()));
+        }
+        let result = query::overlap(
+            &self.api,
+            query_shape(shape),
+            placement(origin, rotation),
+            layers,
+        )
+        .await;
+        Ok(result
+            .map(|hits| {
+                hits.into_iter()
+                    .map(|hit| OverlapHit {
+                        document: hit.document,
+                        prim:     hit.prim,
+                    })
+                    .collect()
+            })
+            .map_err(Into::into))
+    }
+
     async fn get_linear_velocity(
         &mut self,
         prim: Resource<PrimRes>,
@@ -111,6 +211,21 @@ impl bindings::wired::physics::api::Host for Runtime {
         Ok(result.map_err(Into::into))
     }
 
+    async fn register_contact_listener(
+        &mut self,
+        target: Resource<PrimRes>,
+    ) -> wasmtime::Result<Result<Resource<ContactListenerRes>, Error>> {
+        if let Err(err) = self.api.require(ApiName::Physics) {
+            return Ok(Err(err.into()));
+        }
+        Ok(
+            shared::wired::physics::contact::register(&self.api, target.rep())
+                .await
+                .map(Resource::new_own)
+                .map_err(|err| ScriptError::from(err).into()),
+        )
+    }
+
     fn claim_authority(
         &mut self,
         doc: Vec<u8>,
@@ -144,6 +259,24 @@ fn into_wit_hit(hit: shared::wired::physics::RayHit) -> RayHit {
     }
 }
 
+const fn query_shape(shape: QueryShape) -> query::QueryShape {
+    match shape {
+        QueryShape::Sphere(radius) => query::QueryShape::Sphere(radius),
+        QueryShape::Capsule(c) => query::QueryShape::Capsule {
+            height: c.height,
+            radius: c.radius,
+        },
+        QueryShape::Cuboid(v) => query::QueryShape::Cuboid([v.x, v.y, v.z]),
+    }
+}
+
+const fn placement(origin: bindings::wired::math::types::Vec3, rotation: Quat) -> query::Placement {
+    query::Placement {
+        origin:   [origin.x, origin.y, origin.z],
+        rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+    }
+}
+
 const fn vec3(v: [f32; 3]) -> bindings::wired::math::types::Vec3 {
     bindings::wired::math::types::Vec3 {
         x: v[0],
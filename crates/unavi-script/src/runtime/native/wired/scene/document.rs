@@ -84,6 +84,24 @@ impl HostDocument for Runtime {
         )
     }
 
+    async fn collision_layers(&mut self, self_: Resource<DocRes>) -> wasmtime::Result<Vec<String>> {
+        shared::wired::scene::document::collision_layers(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)
+    }
+
+    async fn layer_mask(
+        &mut self,
+        self_: Resource<DocRes>,
+        names: Vec<String>,
+    ) -> wasmtime::Result<Result<u32, Error>> {
+        Ok(
+            shared::wired::scene::document::layer_mask(&self.api, self_.rep(), names)
+                .await
+                .map_err(|e| ScriptError::from(e).into()),
+        )
+    }
+
     async fn offset_to(
         &mut self,
         self_: Resource<DocRes>,
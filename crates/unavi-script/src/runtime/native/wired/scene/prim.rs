@@ -24,14 +24,23 @@ use crate::{
                     scene::types::{
                         AddressMode as WitAddressMode,
                         AlphaMode,
+                        AnimationPlayback,
+                        Audio,
                         Collider,
                         ColliderCapsule,
                         ColliderCylinder,
+                        CollisionLayers,
                         Color,
                         FilterMode as WitFilterMode,
                         GraphValue,
                         HostPrim,
                         Image,
+                        Joint,
+                        JointKind,
+                        JointLimits,
+                        JointMotor,
+                        Light,
+                        LightKind,
                         Material,
                         Mesh,
                         Portal,
@@ -55,9 +64,18 @@ use crate::{
             self,
             wired::scene::prim::{
                 PrimAlphaMode,
+                PrimAnimationPlayback,
+                PrimAudio,
                 PrimCollider,
+                PrimCollisionLayers,
                 PrimColor,
                 PrimGraphValue,
+                PrimJoint,
+                PrimJointKind,
+                PrimJointLimits,
+                PrimJointMotor,
+                PrimLight,
+                PrimLightKind,
                 PrimMaterial,
                 PrimMesh,
                 PrimPortal,
@@ -206,6 +224,88 @@ fn text_shared(t: Text) -> PrimText {
     }
 }
 
+fn joint_wit(j: PrimJoint) -> Joint {
+    let vec = |[x, y, z]: [f32; 3]| Vec3 { x, y, z };
+    Joint {
+        kind:         match j.kind {
+            PrimJointKind::Fixed => JointKind::Fixed,
+            PrimJointKind::Revolute => JointKind::Revolute,
+            PrimJointKind::Prismatic => JointKind::Prismatic,
+            PrimJointKind::Spherical => JointKind::Spherical,
+            PrimJointKind::Distance => JointKind::Distance,
+        },
+        anchor:       j.anchor.map(vec),
+        other_anchor: j.other_anchor.map(vec),
+        axis:         j.axis.map(vec),
+        limits:       j.limits.map(|l| JointLimits {
+            min: l.min,
+            max: l.max,
+        }),
+        motor:        j.motor.map(|m| JointMotor {
+            target_velocity: m.target_velocity,
+            target_position: m.target_position,
+            max_force:       m.max_force,
+        }),
+    }
+}
+
+fn joint_shared(j: Joint) -> PrimJoint {
+    let vec = |v: Vec3| [v.x, v.y, v.z];
+    PrimJoint {
+        kind:         match j.kind {
+            JointKind::Fixed => PrimJointKind::Fixed,
+            JointKind::Revolute => PrimJointKind::Revolute,
+            JointKind::Prismatic => PrimJointKind::Prismatic,
+            JointKind::Spherical => PrimJointKind::Spherical,
+            JointKind::Distance => PrimJointKind::Distance,
+        },
+        anchor:       j.anchor.map(vec),
+        other_anchor: j.other_anchor.map(vec),
+        axis:         j.axis.map(vec),
+        limits:       j.limits.map(|l| PrimJointLimits {
+            min: l.min,
+            max: l.max,
+        }),
+        motor:        j.motor.map(|m| PrimJointMotor {
+            target_velocity: m.target_velocity,
+            target_position: m.target_position,
+            max_force:       m.max_force,
+        }),
+    }
+}
+
+fn light_wit(l: PrimLight) -> Light {
+    Light {
+        kind:        match l.kind {
+            PrimLightKind::Directional => LightKind::Directional,
+            PrimLightKind::Point => LightKind::Point,
+            PrimLightKind::Spot => LightKind::Spot,
+        },
+        color:       l.color.map(color_wit),
+        intensity:   l.intensity,
+        range:       l.range,
+        inner_angle: l.inner_angle,
+        outer_angle: l.outer_angle,
+        shadows:     l.shadows,
+    }
+}
+
+fn light_shared(l: Light) -> PrimLight {
+    PrimLight {
+        kind:        match l.kind {
+            LightKind::Directional => PrimLightKind::Directional,
+            LightKind::Point => PrimLightKind::Point,
+            LightKind::Spot => PrimLightKind::Spot,
+        },
+        color:       l.color.map(color_shared),
+        intensity:   l.intensity,
+        range:       l.range,
+        inner_angle: l.inner_angle,
+        outer_angle: l.outer_angle,
+        shadows:     l.shadows,
+    }
+}
+
 const fn color_wit(c: PrimColor) -> Color {
     Color {
         r: c.r,
@@ -769,6 +869,49 @@ impl HostPrim for Runtime {
         ))
     }
 
+    async fn sensor(&mut self, self_: Resource<PrimRes>) -> wasmtime::Result<bool> {
+        shared::wired::scene::prim::sensor(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)
+    }
+
+    async fn set_sensor(
+        &mut self,
+        self_: Resource<PrimRes>,
+        value: bool,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        Ok(lower(
+            shared::wired::scene::prim::set_sensor(&self.api, self_.rep(), value).await,
+        ))
+    }
+
+    async fn collision_layers(
+        &mut self,
+        self_: Resource<PrimRes>,
+    ) -> wasmtime::Result<CollisionLayers> {
+        let layers = shared::wired::scene::prim::collision_layers(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)?;
+        Ok(CollisionLayers {
+            memberships: layers.memberships,
+            filters:     layers.filters,
+        })
+    }
+
+    async fn set_collision_layers(
+        &mut self,
+        self_: Resource<PrimRes>,
+        value: CollisionLayers,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let value = PrimCollisionLayers {
+            memberships: value.memberships,
+            filters:     value.filters,
+        };
+        Ok(lower(
+            shared::wired::scene::prim::set_collision_layers(&self.api, self_.rep(), value).await,
+        ))
+    }
+
     async fn rigid_body(
         &mut self,
         self_: Resource<PrimRes>,
@@ -796,6 +939,24 @@ impl HostPrim for Runtime {
         ))
     }
 
+    async fn joint(&mut self, self_: Resource<PrimRes>) -> wasmtime::Result<Option<Joint>> {
+        Ok(shared::wired::scene::prim::joint(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)?
+            .map(joint_wit))
+    }
+
+    async fn set_joint(
+        &mut self,
+        self_: Resource<PrimRes>,
+        value: Option<Joint>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let value = value.map(joint_shared);
+        Ok(lower(
+            shared::wired::scene::prim::set_joint(&self.api, self_.rep(), value).await,
+        ))
+    }
+
     async fn portal(&mut self, self_: Resource<PrimRes>) -> wasmtime::Result<Option<Portal>> {
         Ok(shared::wired::scene::prim::portal(&self.api, self_.rep())
             .await
@@ -850,6 +1011,95 @@ impl HostPrim for Runtime {
         ))
     }
 
+    async fn light(&mut self, self_: Resource<PrimRes>) -> wasmtime::Result<Option<Light>> {
+        Ok(shared::wired::scene::prim::light(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)?
+            .map(light_wit))
+    }
+
+    async fn set_light(
+        &mut self,
+        self_: Resource<PrimRes>,
+        value: Option<Light>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let value = value.map(light_shared);
+        Ok(lower(
+            shared::wired::scene::prim::set_light(&self.api, self_.rep(), value).await,
+        ))
+    }
+
+    async fn audio(&mut self, self_: Resource<PrimRes>) -> wasmtime::Result<Option<Audio>> {
+        Ok(shared::wired::scene::prim::audio(&self.api, self_.rep())
+            .await
+            .map_err(wasmtime::Error::from_anyhow)?
+            .map(|a| Audio {
+                volume:   a.volume,
+                looping:  a.looping,
+                rolloff:  a.rolloff,
+                autoplay: a.autoplay,
+            }))
+    }
+
+    async fn set_audio(
+        &mut self,
+        self_: Resource<PrimRes>,
+        value: Option<Audio>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let value = value.map(|a| PrimAudio {
+            volume:   a.volume,
+            looping:  a.looping,
+            rolloff:  a.rolloff,
+            autoplay: a.autoplay,
+        });
+        Ok(lower(
+            shared::wired::scene::prim::set_audio(&self.api, self_.rep(), value).await,
+        ))
+    }
+
+    async fn set_audio_data(
+        &mut self,
+        self_: Resource<PrimRes>,
+        bytes: Option<Vec<u8>>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        Ok(lower(
+            shared::wired::scene::prim::set_audio_data(&self.api, self_.rep(), bytes).await,
+        ))
+    }
+
+    async fn play_animation(
+        &mut self,
+        self_: Resource<PrimRes>,
+        playback: AnimationPlayback,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        let playback = PrimAnimationPlayback {
+            speed:   playback.speed,
+            weight:  playback.weight,
+            looping: playback.looping,
+        };
+        Ok(lower(
+            shared::wired::scene::prim::play_animation(&self.api, self_.rep(), playback).await,
+        ))
+    }
+
+    async fn pause_animation(
+        &mut self,
+        self_: Resource<PrimRes>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        Ok(lower(
+            shared::wired::scene::prim::pause_animation(&self.api, self_.rep()).await,
+        ))
+    }
+
+    async fn stop_animation(
+        &mut self,
+        self_: Resource<PrimRes>,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        Ok(lower(
+            shared::wired::scene::prim::stop_animation(&self.api, self_.rep()).await,
+        ))
+    }
+
     async fn relationships(
         &mut self,
         self_: Resource<PrimRes>,
@@ -22,6 +22,7 @@ use crate::{
         event::WiredEventApi,
         input::WiredInputApi,
         kv::WiredKvApi,
+        physics::WiredPhysicsApi,
         scene::WiredSceneApi,
         wds::WiredWdsApi,
     },
@@ -32,16 +33,17 @@ mod slot_map;
 pub mod wired;
 
 pub struct Api {
-    pub state:       Arc<std::sync::Mutex<SceneState>>,
-    pub doc_id:      DocId,
-    pub prim:        PrimId,
-    pub quota:       Arc<Quota>,
-    pub wired_agent: Mutex<WiredAgentApi>,
-    pub wired_event: Mutex<WiredEventApi>,
-    pub wired_input: Mutex<WiredInputApi>,
-    pub wired_kv:    Mutex<WiredKvApi>,
-    pub wired_scene: Mutex<WiredSceneApi>,
-    pub wired_wds:   Mutex<WiredWdsApi>,
+    pub state:         Arc<std::sync::Mutex<SceneState>>,
+    pub doc_id:        DocId,
+    pub prim:          PrimId,
+    pub quota:         Arc<Quota>,
+    pub wired_agent:   Mutex<WiredAgentApi>,
+    pub wired_event:   Mutex<WiredEventApi>,
+    pub wired_input:   Mutex<WiredInputApi>,
+    pub wired_kv:      Mutex<WiredKvApi>,
+    pub wired_physics: Mutex<WiredPhysicsApi>,
+    pub wired_scene:   Mutex<WiredSceneApi>,
+    pub wired_wds:     Mutex<WiredWdsApi>,
 }
 
 impl Api {
@@ -119,6 +121,9 @@ impl Plugin for SharedRuntimePlugin {
                     wired::input::bridge::bridge_global_scroll
                         .run_if(unavi_input::capture::scene_has_input),
                     wired::input::bridge::bridge_menu,
+                    // Without avian there is nothing to hear.
+                    wired::physics::contact::bridge_contacts
+                        .run_if(resource_exists::<avian3d::prelude::ContactGraph>),
                 ),
             )
             .add_systems(
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{
+        AudioPlayer,
+        AudioSink,
+        AudioSinkPlayback,
+        PlaybackSettings,
+        SpatialAudioSink,
+    },
+    prelude::*,
+};
+use bevy_hsd::{
+    HsdDocId,
+    HsdPrimIndex,
+    attributes::audio::AudioData,
+};
+use hsd::id::{
+    DocId,
+    PrimId,
+};
+use unavi_policy::check::{
+    placed,
+    write as check_write,
+};
+use unavi_util::async_commands::AsyncCommands;
+
+use crate::{
+    error::ScriptError,
+    runtime::shared::Api,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Play,
+    Stop,
+    Seek(Duration),
+}
+
+/// Playback is the emitter's, so driving it is gated like writing its
+/// attribute: a script cannot silence or scrub another document's sound.
+async fn emitter_ident(api: &Api, prim_rep: u32) -> Result<(DocId, PrimId), ScriptError> {
+    let scene = api.wired_scene.lock().await;
+    let prim = scene
+        .prims
+        .get(prim_rep)
+        .ok_or_else(|| ScriptError::other(format!("invalid prim rep: {prim_rep}")))?;
+    if prim.is_proxy {
+        return Err(ScriptError::other("cannot drive a proxy prim's audio"));
+    }
+    let ident = (prim.doc_id, prim.id);
+    drop(scene);
+    placed(api.doc_id)?;
+    check_write(api.doc_id, ident.0)?;
+    Ok(ident)
+}
+
+fn entity_for(world: &mut World, doc: DocId, prim: PrimId) -> Option<Entity> {
+    let mut query = world.query::<(&HsdDocId, &HsdPrimIndex)>();
+    for (rec, index) in query.iter(world) {
+        if rec.0 == doc {
+            return index.0.get(&prim).copied();
+        }
+    }
+    None
+}
+
+async fn drive(api: &Api, prim_rep: u32, action: Action) -> Result<(), ScriptError> {
+    let (doc, prim) = emitter_ident(api, prim_rep).await?;
+    let (tx, rx) = async_channel::bounded(1);
+    AsyncCommands::default()
+        .push(move |world: &mut World| {
+            tx.try_send(apply(world, doc, prim, action)).ok();
+        })
+        .send()
+        .await
+        .map_err(|err| ScriptError::other(err.to_string()))?;
+    rx.recv()
+        .await
+        .map_err(|err| ScriptError::other(err.to_string()))?
+}
+
+fn apply(world: &mut World, doc: DocId, prim: PrimId, action: Action) -> Result<(), ScriptError> {
+    let Some(entity) = entity_for(world, doc, prim) else {
+        return Err(ScriptError::other(format!(
+            "prim {prim} of {doc} is not in the world"
+        )));
+    };
+    if world.get::<AudioData>(entity).is_none() {
+        return Err(ScriptError::other(format!(
+            "prim {prim} of {doc} has no audio emitter"
+        )));
+    }
+    if world.get::<AudioPlayer>(entity).is_none() {
+        return Err(ScriptError::other(format!(
+            "prim {prim} of {doc} has no clip to play"
+        )));
+    }
+
+    if let Some(result) = live::<AudioSink>(world, entity, action)
+        .or_else(|| live::<SpatialAudioSink>(world, entity, action))
+    {
+        return result;
+    }
+
+    requeue(world, entity, action);
+    Ok(())
+}
+
+/// Drives a sink that still has clip left to play. A finished one plays
+/// nothing more, so it is left to [`requeue`].
+fn live<S: AudioSinkPlayback + Component>(
+    world: &World,
+    entity: Entity,
+    action: Action,
+) -> Option<Result<(), ScriptError>> {
+    let sink = world.get::<S>(entity)?;
+    if sink.empty() {
+        return None;
+    }
+    Some(match action {
+        Action::Play => {
+            sink.play();
+            Ok(())
+        }
+        Action::Stop => {
+            sink.pause();
+            seek_sink(sink, Duration::ZERO)
+        }
+        Action::Seek(to) => seek_sink(sink, to),
+    })
+}
+
+fn seek_sink(sink: &impl AudioSinkPlayback, to: Duration) -> Result<(), ScriptError> {
+    sink.try_seek(to)
+        .map_err(|err| ScriptError::other(format!("seek failed: {err:?}")))
+}
+
+/// An emitter with no live sink, not yet started or finished, takes the
+/// action as its next start: Bevy queues a fresh sink from the settings once
+/// the old one is gone.
+fn requeue(world: &mut World, entity: Entity, action: Action) {
+    let mut ent = world.entity_mut(entity);
+    if let Some(mut settings) = ent.get_mut::<PlaybackSettings>() {
+        match action {
+            Action::Play => {
+                settings.paused = false;
+                settings.start_position = None;
+            }
+            Action::Stop => {
+                settings.paused = true;
+                settings.start_position = None;
+            }
+            Action::Seek(to) => settings.start_position = Some(to),
+        }
+    }
+    ent.remove::<(AudioSink, SpatialAudioSink)>();
+}
+
+pub async fn play(api: &Api, prim_rep: u32) -> Result<(), ScriptError> {
+    drive(api, prim_rep, Action::Play).await
+}
+
+pub async fn stop(api: &Api, prim_rep: u32) -> Result<(), ScriptError> {
+    drive(api, prim_rep, Action::Stop).await
+}
+
+pub async fn seek(api: &Api, prim_rep: u32, seconds: f32) -> Result<(), ScriptError> {
+    let to = Duration::try_from_secs_f32(seconds).map_err(|_| {
+        ScriptError::other(format!(
+            "seek position must be finite and >= 0, got {seconds}"
+        ))
+    })?;
+    drive(api, prim_rep, Action::Seek(to)).await
+}
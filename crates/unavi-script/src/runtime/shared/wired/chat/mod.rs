@@ -0,0 +1,73 @@
+use hsd::id::DocId;
+use iroh_docs::NamespaceId;
+use unavi_policy::check::space_of;
+use unavi_quota::Flow;
+use unavi_space::{
+    chat::{
+        self,
+        ChatLine,
+        HISTORY_LEN,
+    },
+    state::replicas,
+};
+
+use crate::{
+    error::ScriptError,
+    runtime::shared::Api,
+};
+
+/// A scroll-back line as a script sees it.
+pub struct Message {
+    pub id:   u64,
+    pub did:  String,
+    pub bot:  Option<Vec<u8>>,
+    pub body: String,
+    pub sent: i64,
+}
+
+impl From<ChatLine> for Message {
+    fn from(line: ChatLine) -> Self {
+        Self {
+            id:   line.id,
+            did:  line.message.did.to_string(),
+            bot:  line.message.bot.map(|b| b.to_vec()),
+            body: line.message.body,
+            sent: line.message.sent,
+        }
+    }
+}
+
+fn ns(id: DocId) -> NamespaceId {
+    NamespaceId::from(&id.0)
+}
+
+/// Posts `body` to the chat of the space the script's document is in.
+///
+/// Every peer runs the script, so only the copy on the document's owner
+/// speaks; the rest succeed without sending, and a bot is heard once.
+pub async fn post(api: &Api, body: String) -> Result<(), ScriptError> {
+    let Some(space) = space_of(api.doc_id) else {
+        return Err(ScriptError::other(
+            "script's host document is not in a tracked space",
+        ));
+    };
+    if !replicas::is_self_owner(ns(space), ns(api.doc_id)) {
+        return Ok(());
+    }
+    crate::quota::acquire(&api.quota, Flow::Chat, 1.0).await?;
+    chat::post_as_bot(ns(space), api.doc_id, body)?;
+    Ok(())
+}
+
+/// Up to `limit` lines of the space's scroll-back after the line `after`.
+#[must_use]
+pub fn read(api: &Api, after: u64, limit: u32) -> Vec<Message> {
+    let Some(space) = space_of(api.doc_id) else {
+        return Vec::new();
+    };
+    let limit = usize::try_from(limit).unwrap_or(HISTORY_LEN);
+    chat::history(ns(space), after, limit)
+        .into_iter()
+        .map(Message::from)
+        .collect()
+}
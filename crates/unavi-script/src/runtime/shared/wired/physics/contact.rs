@@ -0,0 +1,315 @@
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+};
+
+use avian3d::prelude::{
+    CollisionEnd,
+    CollisionStart,
+    Collisions,
+    ContactPair,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    log::warn_once,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use bevy_hsd::{
+    HsdChild,
+    HsdDocId,
+    HsdPrimIndex,
+    Prim as HsdPrim,
+};
+use hsd::id::{
+    DocId,
+    PrimId,
+};
+use parking_lot::Mutex;
+use unavi_policy::check::read as check_read;
+use unavi_util::{
+    async_commands::AsyncCommands,
+    hierarchy::ancestors,
+};
+
+use crate::runtime::shared::{
+    Api,
+    wired::physics::resolve_doc,
+};
+
+/// Deeper than an input queue: a resting body sends a `Contact` every frame,
+/// so a listener polled once per tick can be several behind without having
+/// stopped reading.
+const QUEUE_DEPTH: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactKind {
+    Enter,
+    Exit,
+    Contact,
+}
+
+/// A contact with the listener's prim, naming the other collider.
+#[derive(Clone, Copy)]
+pub struct ContactEvent {
+    pub kind:     ContactKind,
+    pub document: DocId,
+    pub prim:     PrimId,
+    pub point:    Vec3,
+    /// From the listener's prim toward the other.
+    pub normal:   Vec3,
+    pub impulse:  f32,
+}
+
+/// A listener's backlog, written by [`bridge_contacts`] and drained by the
+/// script. Overflow drops the oldest event, as an input queue does.
+#[derive(Clone, Default)]
+pub struct ContactQueue(Arc<Mutex<VecDeque<ContactEvent>>>);
+
+impl ContactQueue {
+    pub fn push(&self, event: ContactEvent) {
+        let mut queue = self.0.lock();
+        if queue.len() >= QUEUE_DEPTH {
+            queue.pop_front();
+            warn_once!("a contact listener is not being polled; events are being dropped");
+        }
+        queue.push_back(event);
+    }
+
+    #[must_use]
+    pub fn pop(&self) -> Option<ContactEvent> {
+        self.0.lock().pop_front()
+    }
+
+    /// Whether the script side has let go of this queue, leaving the bridge
+    /// the only holder.
+    fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.0) == 1
+    }
+}
+
+pub struct ContactListenerRes {
+    pub queue: ContactQueue,
+}
+
+#[derive(Component)]
+pub struct ContactListener {
+    pub target_doc:  DocId,
+    pub target_prim: PrimId,
+    pub queue:       ContactQueue,
+}
+
+pub async fn register(api: &Api, node: u32) -> anyhow::Result<u32> {
+    let (target_doc, target_prim) = api
+        .wired_scene
+        .lock()
+        .await
+        .prims
+        .get(node)
+        .map(|prim| (prim.doc_id, prim.id))
+        .ok_or_else(|| anyhow::anyhow!("node not found"))?;
+    // Contacts name the other collider and where it touched, which is as much
+    // a read of the target's document as any of its attributes.
+    check_read(api.doc_id, target_doc)?;
+
+    let queue = ContactQueue::default();
+
+    AsyncCommands::default()
+        .spawn(ContactListener {
+            target_doc,
+            target_prim,
+            queue: queue.clone(),
+        })
+        .send()
+        .await?;
+
+    let rep = api
+        .wired_physics
+        .lock()
+        .await
+        .listeners
+        .insert(ContactListenerRes { queue }, &api.quota)?;
+
+    Ok(rep)
+}
+
+pub async fn poll(api: &Api, listener: u32) -> anyhow::Result<Option<ContactEvent>> {
+    api.wired_physics
+        .lock()
+        .await
+        .listeners
+        .get(listener)
+        .map(|res| res.queue.pop())
+        .ok_or_else(|| anyhow::anyhow!("listener not found"))
+}
+
+pub async fn drop(api: &Api, listener: u32) -> anyhow::Result<()> {
+    api.wired_physics.lock().await.listeners.remove(listener);
+    Ok(())
+}
+
+/// Where a collider sits in the scene: its prim, and the prim entities it
+/// is under.
+#[derive(SystemParam)]
+pub struct ContactScene<'w, 's> {
+    prims:    Query<'w, 's, &'static HsdPrim>,
+    children: Query<'w, 's, &'static HsdChild>,
+    docs:     Query<'w, 's, (Entity, &'static HsdDocId)>,
+    doc_ids:  Query<'w, 's, &'static HsdDocId>,
+    indices:  Query<'w, 's, &'static HsdPrimIndex>,
+    parents:  Query<'w, 's, &'static ChildOf>,
+}
+
+impl ContactScene<'_, '_> {
+    fn entity_of(&self, doc: DocId, prim: PrimId) -> Option<Entity> {
+        let (entity, _) = self.docs.iter().find(|(_, rec)| rec.0 == doc)?;
+        self.indices.get(entity).ok()?.0.get(&prim).copied()
+    }
+
+    fn ident(&self, collider: Entity) -> Option<(DocId, PrimId)> {
+        let prim = self.prims.get(collider).ok()?.0;
+        let doc = resolve_doc(collider, &self.children, &self.doc_ids, &self.parents)?;
+        Some((doc, prim))
+    }
+}
+
+/// The point, normal and summed normal impulse of a touching pair. A pair
+/// with no manifolds is a sensor overlap, which has none of the three.
+fn touch(pair: &ContactPair) -> Option<(Vec3, Vec3, f32)> {
+    let manifold = pair.manifolds.iter().find(|m| !m.points.is_empty())?;
+    let impulse = pair
+        .manifolds
+        .iter()
+        .flat_map(|m| m.points.iter())
+        .map(|p| p.normal_impulse)
+        .sum();
+    Some((manifold.points[0].point, manifold.normal, impulse))
+}
+
+/// Sorts avian's collision messages and live contacts onto listeners.
+///
+/// A listener hears a pair when exactly one of its colliders is under the
+/// listener's prim; a prim's own parts touching each other are not news to
+/// it. Listeners whose script side was dropped are despawned here.
+pub fn bridge_contacts(
+    mut started: MessageReader<CollisionStart>,
+    mut ended: MessageReader<CollisionEnd>,
+    collisions: Collisions,
+    listeners: Query<(Entity, &ContactListener)>,
+    scene: ContactScene,
+    mut commands: Commands,
+    mut targets: Local<HashMap<Entity, Vec<ContactQueue>>>,
+) {
+    targets.clear();
+    for (entity, listener) in &listeners {
+        if listener.queue.is_abandoned() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(target) = scene.entity_of(listener.target_doc, listener.target_prim) {
+            targets
+                .entry(target)
+                .or_default()
+                .push(listener.queue.clone());
+        }
+    }
+    if targets.is_empty() {
+        started.clear();
+        ended.clear();
+        return;
+    }
+
+    let heard = |collider: Entity| -> Vec<Entity> {
+        ancestors(collider, &scene.parents)
+            .filter(|at| targets.contains_key(at))
+            .collect()
+    };
+
+    let deliver = |kind: ContactKind, a: Entity, b: Entity, contact: Option<(Vec3, Vec3, f32)>| {
+        let (heard_a, heard_b) = (heard(a), heard(b));
+        let (point, normal, impulse) = contact.unwrap_or_default();
+        for (mine, theirs, other, sign) in
+            [(&heard_a, &heard_b, b, 1.0), (&heard_b, &heard_a, a, -1.0)]
+        {
+            let Some((document, prim)) = scene.ident(other) else {
+                continue;
+            };
+            let event = ContactEvent {
+                kind,
+                document,
+                prim,
+                point,
+                normal: normal * sign,
+                impulse,
+            };
+            for target in mine.iter().filter(|t| !theirs.contains(t)) {
+                for queue in &targets[target] {
+                    queue.push(event);
+                }
+            }
+        }
+    };
+
+    for event in started.read() {
+        let contact = collisions
+            .get(event.collider1, event.collider2)
+            .and_then(touch);
+        deliver(
+            ContactKind::Enter,
+            event.collider1,
+            event.collider2,
+            contact,
+        );
+    }
+    for event in ended.read() {
+        deliver(ContactKind::Exit, event.collider1, event.collider2, None);
+    }
+    for pair in collisions.iter().filter(|pair| pair.is_touching()) {
+        if let Some(contact) = touch(pair) {
+            deliver(
+                ContactKind::Contact,
+                pair.collider1,
+                pair.collider2,
+                Some(contact),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: ContactKind) -> ContactEvent {
+        ContactEvent {
+            kind,
+            document: DocId::default(),
+            prim: PrimId::default(),
+            point: Vec3::ZERO,
+            normal: Vec3::Y,
+            impulse: 0.0,
+        }
+    }
+
+    #[test]
+    fn a_full_queue_keeps_the_newest_events() {
+        let queue = ContactQueue::default();
+        for _ in 0..QUEUE_DEPTH {
+            queue.push(event(ContactKind::Contact));
+        }
+        queue.push(event(ContactKind::Exit));
+
+        let held = std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>();
+        assert_eq!(held.len(), QUEUE_DEPTH, "the queue stays bounded");
+        assert_eq!(held.last().expect("last").kind, ContactKind::Exit);
+    }
+
+    #[test]
+    fn a_queue_is_abandoned_once_the_script_lets_go() {
+        let bridge = ContactQueue::default();
+        let script = bridge.clone();
+        assert!(!bridge.is_abandoned());
+        std::mem::drop(script);
+        assert!(bridge.is_abandoned());
+    }
+}
@@ -28,9 +28,21 @@ use unavi_util::{
 
 use crate::{
     error::ScriptError,
-    runtime::shared::Api,
+    runtime::shared::{
+        Api,
+        slot_map::SlotMap,
+        wired::physics::contact::ContactListenerRes,
+    },
 };
 
+pub mod contact;
+pub mod query;
+
+#[derive(Default)]
+pub struct WiredPhysicsApi {
+    listeners: SlotMap<ContactListenerRes>,
+}
+
 /// A guest may pass any bit pattern. Every value crossing into avian is checked
 /// here, before it can reach a component the solver reads.
 fn checked_vec3(what: &str, v: [f32; 3]) -> Result<Vec3, ScriptError> {
@@ -0,0 +1,267 @@
+use avian3d::prelude::{
+    Collider,
+    LayerMask,
+    ShapeCastConfig,
+    SpatialQuery,
+    SpatialQueryFilter,
+};
+use bevy::{
+    ecs::system::{
+        RunSystemOnce,
+        SystemParam,
+    },
+    prelude::*,
+};
+use bevy_hsd::{
+    HsdChild,
+    HsdDocId,
+    Prim as HsdPrim,
+};
+use unavi_physics::{
+    finite,
+    shape,
+};
+use unavi_util::async_commands::AsyncCommands;
+
+use crate::{
+    error::ScriptError,
+    runtime::shared::{
+        Api,
+        wired::physics::{
+            RayHit,
+            checked_distance,
+            checked_vec3,
+            resolve_doc,
+        },
+    },
+};
+
+/// Hits one query may return. A ray through a dense scene can cross
+/// thousands of colliders, and every one is a host allocation.
+pub const MAX_QUERY_HITS: u32 = 256;
+
+/// A shape to cast or test for overlaps. Sizes are in metres, with a cuboid
+/// given as full extents, as a prim's collider is.
+#[derive(Clone, Copy, Debug)]
+pub enum QueryShape {
+    Sphere(f32),
+    Capsule { height: f32, radius: f32 },
+    Cuboid([f32; 3]),
+}
+
+/// A collider found by an overlap test.
+pub struct Overlap {
+    pub document: Vec<u8>,
+    pub prim:     String,
+}
+
+/// Where the shape sits, checked before it reaches avian.
+#[derive(Clone, Copy)]
+pub struct Placement {
+    pub origin:   [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+fn checked_shape(shape: QueryShape) -> Result<Collider, ScriptError> {
+    match shape {
+        QueryShape::Sphere(radius) => shape::sphere(radius),
+        QueryShape::Capsule { height, radius } => shape::capsule(radius, height),
+        QueryShape::Cuboid([x, y, z]) => shape::cuboid(x, y, z),
+    }
+    .ok_or_else(|| ScriptError::other(format!("query shape is degenerate: {shape:?}")))
+}
+
+fn checked_placement(at: Placement) -> Result<(Vec3, Quat), ScriptError> {
+    let origin = checked_vec3("query origin", at.origin)?;
+    let rotation = finite::quat(at.rotation).ok_or_else(|| {
+        ScriptError::other(format!(
+            "query rotation must be a rotation, got {:?}",
+            at.rotation
+        ))
+    })?;
+    Ok((origin, rotation))
+}
+
+fn filter(layers: Option<u32>) -> SpatialQueryFilter {
+    layers.map_or_else(SpatialQueryFilter::default, |bits| {
+        SpatialQueryFilter::from_mask(LayerMask(bits))
+    })
+}
+
+/// Names the document and prim a collider belongs to. Colliders outside any
+/// document, such as avatars, are not reported.
+#[derive(SystemParam)]
+struct Located<'w, 's> {
+    prims:    Query<'w, 's, &'static HsdPrim>,
+    children: Query<'w, 's, &'static HsdChild>,
+    docs:     Query<'w, 's, &'static HsdDocId>,
+    parents:  Query<'w, 's, &'static ChildOf>,
+}
+
+impl Located<'_, '_> {
+    fn ident(&self, entity: Entity) -> Option<(Vec<u8>, String)> {
+        let prim = self.prims.get(entity).ok()?.0;
+        let doc = resolve_doc(entity, &self.children, &self.docs, &self.parents)?;
+        Some((doc.0.to_vec(), prim.to_string()))
+    }
+}
+
+async fn run<T, F>(query: F) -> Result<T, ScriptError>
+where
+    T: Default + Send + 'static,
+    F: FnOnce(&mut World) -> Option<T> + Send + 'static,
+{
+    AsyncCommands::default()
+        .send_with(query)
+        .await
+        .ok_or_else(|| ScriptError::other("the world is gone"))
+        .map(Option::unwrap_or_default)
+}
+
+/// Every collider along the ray, nearest first.
+pub async fn raycast_all(
+    _api: &Api,
+    origin: [f32; 3],
+    dir: [f32; 3],
+    max_dist: f32,
+    max_hits: u32,
+    layers: Option<u32>,
+) -> Result<Vec<RayHit>, ScriptError> {
+    let origin = checked_vec3("raycast origin", origin)?;
+    let dir = checked_vec3("raycast direction", dir)?;
+    let max_dist = checked_distance("raycast distance", max_dist)?;
+    let max_hits = max_hits.min(MAX_QUERY_HITS);
+
+    run(move |world: &mut World| {
+        world
+            .run_system_once(move |spatial: SpatialQuery, located: Located| {
+                let direction = Dir3::new(dir).ok()?;
+                let mut hits =
+                    spatial.ray_hits(origin, direction, max_dist, max_hits, true, &filter(layers));
+                hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+                Some(
+                    hits.into_iter()
+                        .filter_map(|hit| {
+                            let (document, prim) = located.ident(hit.entity)?;
+                            Some(RayHit {
+                                document,
+                                prim,
+                                point: (origin + direction.as_vec3() * hit.distance).to_array(),
+                                normal: hit.normal.to_array(),
+                                distance: hit.distance,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .ok()
+            .flatten()
+    })
+    .await
+}
+
+/// The first collider a shape touches when swept along `dir`. The point and
+/// normal are on that collider.
+pub async fn shape_cast(
+    _api: &Api,
+    shape: QueryShape,
+    at: Placement,
+    dir: [f32; 3],
+    max_dist: f32,
+    layers: Option<u32>,
+) -> Result<Option<RayHit>, ScriptError> {
+    let collider = checked_shape(shape)?;
+    let (origin, rotation) = checked_placement(at)?;
+    let dir = checked_vec3("shape cast direction", dir)?;
+    let max_dist = checked_distance("shape cast distance", max_dist)?;
+
+    run(move |world: &mut World| {
+        world
+            .run_system_once(move |spatial: SpatialQuery, located: Located| {
+                let direction = Dir3::new(dir).ok()?;
+                let hit = spatial.cast_shape(
+                    &collider,
+                    origin,
+                    rotation,
+                    direction,
+                    &ShapeCastConfig::from_max_distance(max_dist),
+                    &filter(layers),
+                )?;
+                let (document, prim) = located.ident(hit.entity)?;
+                Some(Some(RayHit {
+                    document,
+                    prim,
+                    point: hit.point1.to_array(),
+                    normal: hit.normal1.to_array(),
+                    distance: hit.distance,
+                }))
+            })
+            .ok()
+            .flatten()
+    })
+    .await
+}
+
+/// Every collider the shape intersects where it stands.
+pub async fn overlap(
+    _api: &Api,
+    shape: QueryShape,
+    at: Placement,
+    layers: Option<u32>,
+) -> Result<Vec<Overlap>, ScriptError> {
+    let collider = checked_shape(shape)?;
+    let (origin, rotation) = checked_placement(at)?;
+
+    run(move |world: &mut World| {
+        world
+            .run_system_once(move |spatial: SpatialQuery, located: Located| {
+                Some(
+                    spatial
+                        .shape_intersections(&collider, origin, rotation, &filter(layers))
+                        .into_iter()
+                        .filter_map(|entity| located.ident(entity))
+                        .take(MAX_QUERY_HITS as usize)
+                        .map(|(document, prim)| Overlap { document, prim })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .ok()
+            .flatten()
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_degenerate_shape_is_refused() {
+        for shape in [
+            QueryShape::Sphere(0.0),
+            QueryShape::Sphere(f32::NAN),
+            QueryShape::Capsule {
+                height: -1.0,
+                radius: 0.5,
+            },
+            QueryShape::Cuboid([1.0, 0.0, 1.0]),
+        ] {
+            assert!(checked_shape(shape).is_err(), "{shape:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn a_zero_rotation_is_refused() {
+        let at = Placement {
+            origin:   [0.0; 3],
+            rotation: [0.0; 4],
+        };
+        assert!(checked_placement(at).is_err());
+    }
+
+    #[test]
+    fn no_layers_means_every_layer() {
+        assert_eq!(filter(None).mask, LayerMask::ALL);
+        assert_eq!(filter(Some(0b10)).mask, LayerMask(0b10));
+    }
+}
@@ -17,6 +17,7 @@ use hsd::{
         DocId,
         PrimId,
     },
+    layers,
     state::SceneState,
 };
 use tokio::sync::MutexGuard;
@@ -178,6 +179,19 @@ pub async fn create_prim(api: &Api, rep: u32) -> anyhow::Result<u32> {
     }
 }
 
+pub async fn collision_layers(api: &Api, rep: u32) -> anyhow::Result<Vec<String>> {
+    get_doc(api, rep)
+        .await?
+        .with(|state| state.meta().layers.clone())
+}
+
+pub async fn layer_mask(api: &Api, rep: u32, names: Vec<String>) -> anyhow::Result<u32> {
+    get_doc(api, rep)
+        .await?
+        .with(|state| layers::mask(&state.meta().layers, &names))?
+        .map_err(Into::into)
+}
+
 pub async fn offset_to(
     api: &Api,
     self_rep: u32,
@@ -329,7 +343,7 @@ fn find_doc(world: &mut World, id: DocId) -> Option<Entity> {
         .find_map(|(e, d)| (d.0 == id).then_some(e))
 }
 
-fn find_prim(world: &mut World, doc: DocId, prim: PrimId) -> Option<Entity> {
+pub(super) fn find_prim(world: &mut World, doc: DocId, prim: PrimId) -> Option<Entity> {
     let doc_ent = find_doc(world, doc)?;
     world
         .get::<HsdPrimIndex>(doc_ent)
@@ -8,6 +8,7 @@ use std::{
 
 use anyhow::bail;
 use bevy::{
+    ecs::world::World,
     math::{
         Affine3A,
         Quat,
@@ -15,12 +16,28 @@ use bevy::{
     },
     transform::components::GlobalTransform,
 };
+use bevy_hsd::attributes::animation::{
+    AnimationData,
+    AnimationPlayback,
+};
 use hsd::{
     attributes::{
         Attribute,
+        audio::AudioAttr,
         collider::ColliderAttr,
+        collision_layers::CollisionLayersAttr,
         gravity_scale::GravityScaleAttr,
         image::ImageAttr,
+        joint::{
+            JointAttr,
+            JointKind,
+            JointLimits,
+            JointMotor,
+        },
+        light::{
+            LightAttr,
+            LightKind,
+        },
         material::{
             ColorVec,
             MaterialAttr,
@@ -49,6 +66,7 @@ use hsd::{
             RigidBodyAttr,
             RigidBodyKind,
         },
+        sensor::SensorAttr,
         slots,
         spawn::SpawnAttr,
         text::TextAttr,
@@ -77,6 +95,7 @@ use unavi_quota::{
         MAX_TEXT_BYTES,
     },
 };
+use unavi_util::async_commands::AsyncCommands;
 
 use crate::runtime::shared::{
     Api,
@@ -85,10 +104,13 @@ use crate::runtime::shared::{
         DOC_ROOT_TRANSFORM_REGISTRY,
         NODE_TRANSFORM_REGISTRY,
     },
-    wired::scene::util::{
-        bytes_to_f32s,
-        f32s_to_bytes,
-        u32s_to_bytes,
+    wired::scene::{
+        document::find_prim,
+        util::{
+            bytes_to_f32s,
+            f32s_to_bytes,
+            u32s_to_bytes,
+        },
     },
 };
 
@@ -162,6 +184,12 @@ pub enum PrimCollider {
     Trimesh,
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct PrimCollisionLayers {
+    pub memberships: Option<u32>,
+    pub filters:     Option<u32>,
+}
+
 #[derive(Default)]
 pub struct PrimRigidBody {
     pub kind:            PrimRigidBodyKind,
@@ -180,6 +208,39 @@ pub enum PrimRigidBodyKind {
     Static,
 }
 
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrimJointKind {
+    #[default]
+    Fixed,
+    Revolute,
+    Prismatic,
+    Spherical,
+    Distance,
+}
+
+#[derive(Clone, Copy)]
+pub struct PrimJointLimits {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PrimJointMotor {
+    pub target_velocity: Option<f32>,
+    pub target_position: Option<f32>,
+    pub max_force:       Option<f32>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PrimJoint {
+    pub kind:         PrimJointKind,
+    pub anchor:       Option<[f32; 3]>,
+    pub other_anchor: Option<[f32; 3]>,
+    pub axis:         Option<[f32; 3]>,
+    pub limits:       Option<PrimJointLimits>,
+    pub motor:        Option<PrimJointMotor>,
+}
+
 #[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub enum PrimTextAlign {
     #[default]
@@ -240,6 +301,47 @@ pub struct PrimSpawn {
     pub radius: f32,
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct PrimAudio {
+    pub volume:   Option<f32>,
+    pub looping:  Option<bool>,
+    pub rolloff:  Option<f32>,
+    pub autoplay: Option<bool>,
+}
+
+#[derive(Clone, Copy)]
+pub struct PrimAnimationPlayback {
+    pub speed:   f32,
+    pub weight:  f32,
+    pub looping: bool,
+}
+
+#[derive(Clone, Copy)]
+enum AnimationAction {
+    Play(PrimAnimationPlayback),
+    Pause,
+    Stop,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrimLightKind {
+    Directional,
+    #[default]
+    Point,
+    Spot,
+}
+
+#[derive(Default)]
+pub struct PrimLight {
+    pub kind:        PrimLightKind,
+    pub color:       Option<PrimColor>,
+    pub intensity:   Option<f32>,
+    pub range:       Option<f32>,
+    pub inner_angle: Option<f32>,
+    pub outer_angle: Option<f32>,
+    pub shadows:     Option<bool>,
+}
+
 async fn get_prim(api: &Api, rep: u32) -> anyhow::Result<PrimRes> {
     api.wired_scene
         .lock()
@@ -979,9 +1081,15 @@ pub async fn collider(api: &Api, rep: u32) -> anyhow::Result<Option<PrimCollider
     }))
 }
 
+/// Reshaping a collider keeps whether it is a sensor and its layers; removing
+/// it drops both.
 pub async fn set_collider(api: &Api, rep: u32, value: Option<PrimCollider>) -> anyhow::Result<()> {
     let prim = get_prim(api, rep).await?;
     ensure_writable(api, &prim)?;
+    if value.is_none() {
+        prim.clear(SensorAttr::KEY)?;
+        prim.clear(CollisionLayersAttr::KEY)?;
+    }
     prim.write_or_clear(value.map(|c| match c {
         PrimCollider::Capsule { height, radius } => ColliderAttr::Capsule {
             height: f64::from(height),
@@ -1002,6 +1110,56 @@ pub async fn set_collider(api: &Api, rep: u32, value: Option<PrimCollider>) -> a
     }))
 }
 
+pub async fn sensor(api: &Api, rep: u32) -> anyhow::Result<bool> {
+    let prim = get_prim(api, rep).await?;
+    if prim.is_proxy {
+        return Ok(false);
+    }
+    Ok(prim.read_attr::<SensorAttr>()?.is_some())
+}
+
+pub async fn set_sensor(api: &Api, rep: u32, value: bool) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    if prim.read_attr::<ColliderAttr>()?.is_none() {
+        bail!("prim has no collider to make a sensor");
+    }
+    prim.write_or_clear(value.then_some(SensorAttr))
+}
+
+pub async fn collision_layers(api: &Api, rep: u32) -> anyhow::Result<PrimCollisionLayers> {
+    let prim = get_prim(api, rep).await?;
+    if prim.is_proxy {
+        return Ok(PrimCollisionLayers::default());
+    }
+    Ok(prim
+        .read_attr::<CollisionLayersAttr>()?
+        .map(|l| PrimCollisionLayers {
+            memberships: l.memberships,
+            filters:     l.filters,
+        })
+        .unwrap_or_default())
+}
+
+pub async fn set_collision_layers(
+    api: &Api,
+    rep: u32,
+    value: PrimCollisionLayers,
+) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    if prim.read_attr::<ColliderAttr>()?.is_none() {
+        bail!("prim has no collider to put on a layer");
+    }
+    if value.memberships.is_none() && value.filters.is_none() {
+        return prim.clear(CollisionLayersAttr::KEY);
+    }
+    prim.write_attr(&CollisionLayersAttr {
+        memberships: value.memberships,
+        filters:     value.filters,
+    })
+}
+
 pub async fn rigid_body(api: &Api, rep: u32) -> anyhow::Result<Option<PrimRigidBody>> {
     let prim = get_prim(api, rep).await?;
     if prim.is_proxy {
@@ -1052,6 +1210,70 @@ fn prim_to_rigid_body_attr(rb: PrimRigidBody) -> RigidBodyAttr {
     }
 }
 
+pub async fn joint(api: &Api, rep: u32) -> anyhow::Result<Option<PrimJoint>> {
+    let prim = get_prim(api, rep).await?;
+    if prim.is_proxy {
+        return Ok(None);
+    }
+    Ok(prim.read_attr::<JointAttr>()?.map(joint_attr_to_prim))
+}
+
+/// Values are stored as written; the client checks them before they reach
+/// the solver, so a bad anchor costs one joint rather than the simulation.
+pub async fn set_joint(api: &Api, rep: u32, value: Option<PrimJoint>) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    prim.write_or_clear(value.map(prim_to_joint_attr))
+}
+
+fn joint_attr_to_prim(attr: JointAttr) -> PrimJoint {
+    PrimJoint {
+        kind:         match attr.kind {
+            JointKind::Fixed => PrimJointKind::Fixed,
+            JointKind::Revolute => PrimJointKind::Revolute,
+            JointKind::Prismatic => PrimJointKind::Prismatic,
+            JointKind::Spherical => PrimJointKind::Spherical,
+            JointKind::Distance => PrimJointKind::Distance,
+        },
+        anchor:       attr.anchor,
+        other_anchor: attr.other_anchor,
+        axis:         attr.axis,
+        limits:       attr.limits.map(|l| PrimJointLimits {
+            min: l.min as f32,
+            max: l.max as f32,
+        }),
+        motor:        attr.motor.map(|m| PrimJointMotor {
+            target_velocity: m.target_velocity.map(|v| v as f32),
+            target_position: m.target_position.map(|v| v as f32),
+            max_force:       m.max_force.map(|v| v as f32),
+        }),
+    }
+}
+
+fn prim_to_joint_attr(joint: PrimJoint) -> JointAttr {
+    JointAttr {
+        kind:         match joint.kind {
+            PrimJointKind::Fixed => JointKind::Fixed,
+            PrimJointKind::Revolute => JointKind::Revolute,
+            PrimJointKind::Prismatic => JointKind::Prismatic,
+            PrimJointKind::Spherical => JointKind::Spherical,
+            PrimJointKind::Distance => JointKind::Distance,
+        },
+        anchor:       joint.anchor,
+        other_anchor: joint.other_anchor,
+        axis:         joint.axis,
+        limits:       joint.limits.map(|l| JointLimits {
+            min: f64::from(l.min),
+            max: f64::from(l.max),
+        }),
+        motor:        joint.motor.map(|m| JointMotor {
+            target_velocity: m.target_velocity.map(f64::from),
+            target_position: m.target_position.map(f64::from),
+            max_force:       m.max_force.map(f64::from),
+        }),
+    }
+}
+
 pub async fn portal(api: &Api, rep: u32) -> anyhow::Result<Option<PrimPortal>> {
     let prim = get_prim(api, rep).await?;
     if prim.is_proxy {
@@ -1128,6 +1350,152 @@ pub async fn set_spawn(api: &Api, rep: u32, value: Option<PrimSpawn>) -> anyhow:
     }))
 }
 
+pub async fn light(api: &Api, rep: u32) -> anyhow::Result<Option<PrimLight>> {
+    let prim = get_prim(api, rep).await?;
+    if prim.is_proxy {
+        return Ok(None);
+    }
+    Ok(prim.read_attr::<LightAttr>()?.map(light_attr_to_prim))
+}
+
+pub async fn set_light(api: &Api, rep: u32, value: Option<PrimLight>) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    prim.write_or_clear(value.map(prim_to_light_attr))
+}
+
+fn light_attr_to_prim(attr: LightAttr) -> PrimLight {
+    PrimLight {
+        kind:        match attr.kind {
+            LightKind::Directional => PrimLightKind::Directional,
+            LightKind::Point => PrimLightKind::Point,
+            LightKind::Spot => PrimLightKind::Spot,
+        },
+        color:       attr.color.map(color_vec_to_prim),
+        intensity:   attr.intensity.map(|v| v as f32),
+        range:       attr.range.map(|v| v as f32),
+        inner_angle: attr.inner_angle.map(|v| v as f32),
+        outer_angle: attr.outer_angle.map(|v| v as f32),
+        shadows:     attr.shadows,
+    }
+}
+
+fn prim_to_light_attr(light: PrimLight) -> LightAttr {
+    LightAttr {
+        kind:        match light.kind {
+            PrimLightKind::Directional => LightKind::Directional,
+            PrimLightKind::Point => LightKind::Point,
+            PrimLightKind::Spot => LightKind::Spot,
+        },
+        color:       light.color.map(prim_color_to_vec),
+        intensity:   light.intensity.map(f64::from),
+        range:       light.range.map(f64::from),
+        inner_angle: light.inner_angle.map(f64::from),
+        outer_angle: light.outer_angle.map(f64::from),
+        shadows:     light.shadows,
+    }
+}
+
+pub async fn audio(api: &Api, rep: u32) -> anyhow::Result<Option<PrimAudio>> {
+    let prim = get_prim(api, rep).await?;
+    if prim.is_proxy {
+        return Ok(None);
+    }
+    Ok(prim.read_attr::<AudioAttr>()?.map(|a| PrimAudio {
+        volume:   a.volume.map(|v| v as f32),
+        looping:  a.looping,
+        rolloff:  a.rolloff.map(|v| v as f32),
+        autoplay: a.autoplay,
+    }))
+}
+
+pub async fn set_audio(api: &Api, rep: u32, value: Option<PrimAudio>) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    prim.write_or_clear(value.map(|a| AudioAttr {
+        volume:   a.volume.map(f64::from),
+        looping:  a.looping,
+        rolloff:  a.rolloff.map(f64::from),
+        autoplay: a.autoplay,
+    }))
+}
+
+pub async fn set_audio_data(api: &Api, rep: u32, bytes: Option<Vec<u8>>) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    set_buffer(api, &prim, slots::AUDIO_DATA, bytes).await
+}
+
+/// Playback is per-peer runtime state, but it is gated like writing the
+/// clip: a script cannot freeze or scrub another document's animation.
+async fn drive_animation(api: &Api, rep: u32, action: AnimationAction) -> anyhow::Result<()> {
+    let prim = get_prim(api, rep).await?;
+    ensure_writable(api, &prim)?;
+    let (doc, id) = (prim.doc_id, prim.id);
+
+    let (tx, rx) = async_channel::bounded(1);
+    AsyncCommands::default()
+        .push(move |world: &mut World| {
+            tx.try_send(apply_animation(world, doc, id, action)).ok();
+        })
+        .send()
+        .await?;
+    rx.recv().await?
+}
+
+fn apply_animation(
+    world: &mut World,
+    doc: DocId,
+    id: PrimId,
+    action: AnimationAction,
+) -> anyhow::Result<()> {
+    let entity = find_prim(world, doc, id)
+        .ok_or_else(|| anyhow::anyhow!("prim {id} of {doc} is not in the world"))?;
+    if world.get::<AnimationData>(entity).is_none() {
+        bail!("prim {id} of {doc} holds no animation clip")
+    }
+
+    let mut ent = world.entity_mut(entity);
+    match action {
+        AnimationAction::Play(p) => {
+            let next = AnimationPlayback::playing(p.speed, p.weight, p.looping);
+            if let Some(mut playback) = ent.get_mut::<AnimationPlayback>() {
+                *playback = AnimationPlayback {
+                    time: playback.time,
+                    ..next
+                };
+            } else {
+                ent.insert(next);
+            }
+        }
+        AnimationAction::Pause => {
+            if let Some(mut playback) = ent.get_mut::<AnimationPlayback>() {
+                playback.paused = true;
+            }
+        }
+        AnimationAction::Stop => {
+            ent.remove::<AnimationPlayback>();
+        }
+    }
+    Ok(())
+}
+
+pub async fn play_animation(
+    api: &Api,
+    rep: u32,
+    playback: PrimAnimationPlayback,
+) -> anyhow::Result<()> {
+    drive_animation(api, rep, AnimationAction::Play(playback)).await
+}
+
+pub async fn pause_animation(api: &Api, rep: u32) -> anyhow::Result<()> {
+    drive_animation(api, rep, AnimationAction::Pause).await
+}
+
+pub async fn stop_animation(api: &Api, rep: u32) -> anyhow::Result<()> {
+    drive_animation(api, rep, AnimationAction::Stop).await
+}
+
 pub async fn relationships(api: &Api, rep: u32) -> anyhow::Result<Vec<(String, String)>> {
     let prim = get_prim(api, rep).await?;
     if prim.is_proxy {
@@ -0,0 +1,42 @@
+use unavi_policy::document::ApiName;
+use wasm_bindgen::prelude::*;
+
+use crate::runtime::{
+    Runtime,
+    shared,
+    web::wired::{
+        raise,
+        scene::prim::PrimHandle,
+    },
+};
+
+#[wasm_bindgen]
+impl Runtime {
+    #[wasm_bindgen(js_name = "wiredAudioPlay")]
+    pub async fn wired_audio_play(&self, emitter: &PrimHandle) -> Result<(), JsValue> {
+        self.api.require(ApiName::Audio).map_err(raise)?;
+        shared::wired::audio::play(&self.api, emitter.rep())
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "wiredAudioStop")]
+    pub async fn wired_audio_stop(&self, emitter: &PrimHandle) -> Result<(), JsValue> {
+        self.api.require(ApiName::Audio).map_err(raise)?;
+        shared::wired::audio::stop(&self.api, emitter.rep())
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "wiredAudioSeek")]
+    pub async fn wired_audio_seek(
+        &self,
+        emitter: &PrimHandle,
+        seconds: f32,
+    ) -> Result<(), JsValue> {
+        self.api.require(ApiName::Audio).map_err(raise)?;
+        shared::wired::audio::seek(&self.api, emitter.rep(), seconds)
+            .await
+            .map_err(raise)
+    }
+}
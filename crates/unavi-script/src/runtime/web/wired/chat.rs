@@ -0,0 +1,46 @@
+use unavi_policy::document::ApiName;
+use wasm_bindgen::prelude::*;
+
+use crate::runtime::{
+    Runtime,
+    shared,
+    web::wired::raise,
+};
+
+fn message_to_js(message: shared::wired::chat::Message) -> JsValue {
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(&obj, &"id".into(), &js_sys::BigInt::from(message.id).into()).ok();
+    js_sys::Reflect::set(&obj, &"did".into(), &JsValue::from_str(&message.did)).ok();
+    let bot = message.bot.map_or(JsValue::UNDEFINED, |bot| {
+        js_sys::Uint8Array::from(bot.as_slice()).into()
+    });
+    js_sys::Reflect::set(&obj, &"bot".into(), &bot).ok();
+    js_sys::Reflect::set(&obj, &"body".into(), &JsValue::from_str(&message.body)).ok();
+    js_sys::Reflect::set(
+        &obj,
+        &"sent".into(),
+        &js_sys::BigInt::from(message.sent).into(),
+    )
+    .ok();
+    obj.into()
+}
+
+#[wasm_bindgen]
+impl Runtime {
+    #[wasm_bindgen(js_name = "wiredChatPost")]
+    pub async fn wired_chat_post(&self, body: String) -> Result<(), JsValue> {
+        self.api.require(ApiName::Chat).map_err(raise)?;
+        shared::wired::chat::post(&self.api, body)
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "wiredChatRead")]
+    pub fn wired_chat_read(&self, after: u64, limit: u32) -> Result<js_sys::Array, JsValue> {
+        self.api.require(ApiName::Chat).map_err(raise)?;
+        Ok(shared::wired::chat::read(&self.api, after, limit)
+            .into_iter()
+            .map(message_to_js)
+            .collect())
+    }
+}
@@ -3,6 +3,8 @@ use wasm_bindgen::JsValue;
 use crate::error::ScriptError;
 
 pub mod agent;
+pub mod audio;
+pub mod chat;
 pub mod event;
 pub mod input;
 pub mod kv;
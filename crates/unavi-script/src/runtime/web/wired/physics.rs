@@ -1,15 +1,37 @@
+use std::sync::Arc;
+
 use unavi_policy::document::ApiName;
+use unavi_util::async_task::spawn_async_task;
 use wasm_bindgen::prelude::*;
 
 use crate::runtime::{
     Runtime,
-    shared,
+    shared::{
+        self,
+        Api,
+        wired::physics::{
+            contact::{
+                ContactEvent,
+                ContactKind,
+            },
+            query::{
+                self,
+                Overlap,
+                Placement,
+                QueryShape,
+            },
+        },
+    },
     web::wired::{
         raise,
         scene::{
             prim::PrimHandle,
             util::{
+                js_to_quat,
                 js_to_vec3,
+                obj_get,
+                obj_get_f32,
+                obj_get_string,
                 vec3_to_js,
             },
         },
@@ -41,8 +63,132 @@ fn ray_hit_to_js(hit: &shared::wired::physics::RayHit) -> JsValue {
     obj.into()
 }
 
+/// A WIT variant arrives from JS as a tag and a value, as `jco` lowers one.
+fn js_to_query_shape(v: &JsValue) -> Result<QueryShape, JsValue> {
+    let val = obj_get(v, "val");
+    match obj_get_string(v, "tag").as_deref() {
+        Some("sphere") => Ok(QueryShape::Sphere(val.as_f64().unwrap_or(0.0) as f32)),
+        Some("capsule") => Ok(QueryShape::Capsule {
+            height: obj_get_f32(&val, "height").unwrap_or(0.0),
+            radius: obj_get_f32(&val, "radius").unwrap_or(0.0),
+        }),
+        Some("cuboid") => Ok(QueryShape::Cuboid(js_to_vec3(&val, [0.0; 3]))),
+        other => Err(JsValue::from_str(&format!("unknown query shape {other:?}"))),
+    }
+}
+
+fn js_to_placement(origin: &JsValue, rotation: &JsValue) -> Placement {
+    Placement {
+        origin:   js_to_vec3(origin, [0.0; 3]),
+        rotation: js_to_quat(rotation, [0.0, 0.0, 0.0, 1.0]),
+    }
+}
+
+fn overlap_to_js(hit: &Overlap) -> JsValue {
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &obj,
+        &"document".into(),
+        &js_sys::Uint8Array::from(hit.document.as_slice()).into(),
+    )
+    .ok();
+    js_sys::Reflect::set(&obj, &"prim".into(), &JsValue::from_str(&hit.prim)).ok();
+    obj.into()
+}
+
+#[wasm_bindgen]
+pub struct ContactListenerHandle {
+    rep: u32,
+    api: Arc<Api>,
+}
+
+impl ContactListenerHandle {
+    pub const fn new(rep: u32, api: Arc<Api>) -> Self {
+        Self { rep, api }
+    }
+}
+
+impl Drop for ContactListenerHandle {
+    fn drop(&mut self) {
+        if self.rep != u32::MAX {
+            let api = Arc::clone(&self.api);
+            let rep = self.rep;
+            spawn_async_task(async move {
+                let _ = shared::wired::physics::contact::drop(&api, rep).await;
+            });
+        }
+    }
+}
+
+fn contact_event_to_js(event: &ContactEvent) -> JsValue {
+    let obj = js_sys::Object::new();
+    let kind = match event.kind {
+        ContactKind::Enter => "enter",
+        ContactKind::Exit => "exit",
+        ContactKind::Contact => "contact",
+    };
+    js_sys::Reflect::set(&obj, &"kind".into(), &kind.into()).ok();
+    js_sys::Reflect::set(
+        &obj,
+        &"document".into(),
+        &js_sys::Uint8Array::from(event.document.0.as_slice()).into(),
+    )
+    .ok();
+    js_sys::Reflect::set(
+        &obj,
+        &"prim".into(),
+        &JsValue::from_str(&event.prim.to_string()),
+    )
+    .ok();
+    js_sys::Reflect::set(
+        &obj,
+        &"point".into(),
+        &vec3_to_js(event.point.x, event.point.y, event.point.z),
+    )
+    .ok();
+    js_sys::Reflect::set(
+        &obj,
+        &"normal".into(),
+        &vec3_to_js(event.normal.x, event.normal.y, event.normal.z),
+    )
+    .ok();
+    js_sys::Reflect::set(&obj, &"impulse".into(), &event.impulse.into()).ok();
+    obj.into()
+}
+
+#[wasm_bindgen]
+impl ContactListenerHandle {
+    pub async fn poll(&self) -> JsValue {
+        let Ok(Some(polled)) = shared::wired::physics::contact::poll(&self.api, self.rep).await
+        else {
+            return JsValue::UNDEFINED;
+        };
+        contact_event_to_js(&polled)
+    }
+}
+
 #[wasm_bindgen]
 impl Runtime {
+    #[wasm_bindgen(js_name = "wiredPhysicsContactListenerClass")]
+    #[must_use]
+    pub fn wired_physics_contact_listener_class(&self) -> JsValue {
+        let handle = ContactListenerHandle::new(u32::MAX, Arc::clone(&self.api));
+        let js = JsValue::from(handle);
+        js_sys::Reflect::get(&js, &"constructor".into()).expect("reflect")
+    }
+
+    #[wasm_bindgen(js_name = "wiredPhysicsRegisterContactListener")]
+    pub async fn wired_physics_register_contact_listener(
+        &self,
+        target: &PrimHandle,
+    ) -> Result<ContactListenerHandle, JsValue> {
+        self.api.require(ApiName::Physics).map_err(raise)?;
+        let rep = shared::wired::physics::contact::register(&self.api, target.rep())
+            .await
+            .map_err(raise)?;
+        Ok(ContactListenerHandle::new(rep, Arc::clone(&self.api)))
+    }
+
     #[wasm_bindgen(js_name = "wiredPhysicsRaycast")]
     pub async fn wired_physics_raycast(
         &self,
@@ -59,6 +205,61 @@ impl Runtime {
         Ok(hit.map_or(JsValue::UNDEFINED, |h| ray_hit_to_js(&h)))
     }
 
+    #[wasm_bindgen(js_name = "wiredPhysicsRaycastAll")]
+    pub async fn wired_physics_raycast_all(
+        &self,
+        origin: JsValue,
+        dir: JsValue,
+        max_dist: f32,
+        max_hits: u32,
+        layers: Option<u32>,
+    ) -> Result<js_sys::Array, JsValue> {
+        self.api.require(ApiName::Physics).map_err(raise)?;
+        let origin = js_to_vec3(&origin, [0.0; 3]);
+        let dir = js_to_vec3(&dir, [0.0; 3]);
+        let hits = query::raycast_all(&self.api, origin, dir, max_dist, max_hits, layers)
+            .await
+            .map_err(raise)?;
+        Ok(hits.iter().map(ray_hit_to_js).collect())
+    }
+
+    #[wasm_bindgen(js_name = "wiredPhysicsShapeCast")]
+    pub async fn wired_physics_shape_cast(
+        &self,
+        shape: JsValue,
+        origin: JsValue,
+        rotation: JsValue,
+        dir: JsValue,
+        max_dist: f32,
+        layers: Option<u32>,
+    ) -> Result<JsValue, JsValue> {
+        self.api.require(ApiName::Physics).map_err(raise)?;
+        let shape = js_to_query_shape(&shape)?;
+        let at = js_to_placement(&origin, &rotation);
+        let dir = js_to_vec3(&dir, [0.0; 3]);
+        let hit = query::shape_cast(&self.api, shape, at, dir, max_dist, layers)
+            .await
+            .map_err(raise)?;
+        Ok(hit.map_or(JsValue::UNDEFINED, |h| ray_hit_to_js(&h)))
+    }
+
+    #[wasm_bindgen(js_name = "wiredPhysicsOverlap")]
+    pub async fn wired_physics_overlap(
+        &self,
+        shape: JsValue,
+        origin: JsValue,
+        rotation: JsValue,
+        layers: Option<u32>,
+    ) -> Result<js_sys::Array, JsValue> {
+        self.api.require(ApiName::Physics).map_err(raise)?;
+        let shape = js_to_query_shape(&shape)?;
+        let at = js_to_placement(&origin, &rotation);
+        let hits = query::overlap(&self.api, shape, at, layers)
+            .await
+            .map_err(raise)?;
+        Ok(hits.iter().map(overlap_to_js).collect())
+    }
+
     #[wasm_bindgen(js_name = "wiredPhysicsGetLinearVelocity")]
     pub async fn wired_physics_get_linear_velocity(
         &self,
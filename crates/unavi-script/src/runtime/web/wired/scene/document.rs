@@ -102,6 +102,20 @@ impl DocHandle {
             .map_err(raise)
     }
 
+    #[wasm_bindgen(js_name = "collisionLayers")]
+    pub async fn collision_layers(&self) -> Vec<String> {
+        shared::wired::scene::document::collision_layers(&self.api, self.rep)
+            .await
+            .unwrap_or_default()
+    }
+
+    #[wasm_bindgen(js_name = "layerMask")]
+    pub async fn layer_mask(&self, names: Vec<String>) -> Result<u32, JsValue> {
+        shared::wired::scene::document::layer_mask(&self.api, self.rep, names)
+            .await
+            .map_err(raise)
+    }
+
     #[wasm_bindgen(js_name = "offsetTo")]
     pub async fn offset_to(&self, other: &Self) -> JsValue {
         match shared::wired::scene::document::offset_to(&self.api, self.rep, other.rep).await {
@@ -24,6 +24,7 @@ use super::{
         obj_get_bool,
         obj_get_f32,
         obj_get_string,
+        obj_get_u32,
         obj_set,
         vec3_to_js,
         xform_to_js,
@@ -35,9 +36,18 @@ use crate::runtime::{
         Api,
         wired::scene::prim::{
             PrimAlphaMode,
+            PrimAnimationPlayback,
+            PrimAudio,
             PrimCollider,
+            PrimCollisionLayers,
             PrimColor,
             PrimGraphValue,
+            PrimJoint,
+            PrimJointKind,
+            PrimJointLimits,
+            PrimJointMotor,
+            PrimLight,
+            PrimLightKind,
             PrimMaterial,
             PrimMesh,
             PrimPortal,
@@ -329,6 +339,47 @@ impl PrimHandle {
             .map_err(raise)
     }
 
+    pub async fn sensor(&self) -> bool {
+        shared::wired::scene::prim::sensor(&self.api, self.rep)
+            .await
+            .unwrap_or(false)
+    }
+
+    #[wasm_bindgen(js_name = "setSensor")]
+    pub async fn set_sensor(&self, value: bool) -> Result<(), JsValue> {
+        shared::wired::scene::prim::set_sensor(&self.api, self.rep, value)
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "collisionLayers")]
+    pub async fn collision_layers(&self) -> JsValue {
+        let layers = shared::wired::scene::prim::collision_layers(&self.api, self.rep)
+            .await
+            .unwrap_or_default();
+        let obj = js_sys::Object::new();
+        for (key, value) in [
+            ("memberships", layers.memberships),
+            ("filters", layers.filters),
+        ] {
+            if let Some(mask) = value {
+                obj_set(&obj, key, &mask.into());
+            }
+        }
+        obj.into()
+    }
+
+    #[wasm_bindgen(js_name = "setCollisionLayers")]
+    pub async fn set_collision_layers(&self, value: JsValue) -> Result<(), JsValue> {
+        let value = PrimCollisionLayers {
+            memberships: obj_get_u32(&value, "memberships"),
+            filters:     obj_get_u32(&value, "filters"),
+        };
+        shared::wired::scene::prim::set_collision_layers(&self.api, self.rep, value)
+            .await
+            .map_err(raise)
+    }
+
     #[wasm_bindgen(js_name = "setColliderVertices")]
     pub async fn set_collider_vertices(&self, values: JsValue) -> Result<(), JsValue> {
         shared::wired::scene::prim::set_collider_vertices(&self.api, self.rep, js_to_f32s(values))
@@ -359,6 +410,21 @@ impl PrimHandle {
             .map_err(raise)
     }
 
+    pub async fn joint(&self) -> JsValue {
+        match shared::wired::scene::prim::joint(&self.api, self.rep).await {
+            Ok(Some(j)) => joint_to_js(&j),
+            _ => JsValue::UNDEFINED,
+        }
+    }
+
+    #[wasm_bindgen(js_name = "setJoint")]
+    pub async fn set_joint(&self, value: JsValue) -> Result<(), JsValue> {
+        let value = js_to_joint(&value);
+        shared::wired::scene::prim::set_joint(&self.api, self.rep, value)
+            .await
+            .map_err(raise)
+    }
+
     pub async fn portal(&self) -> JsValue {
         match shared::wired::scene::prim::portal(&self.api, self.rep).await {
             Ok(Some(p)) => portal_to_js(&p),
@@ -404,6 +470,68 @@ impl PrimHandle {
             .map_err(raise)
     }
 
+    pub async fn light(&self) -> JsValue {
+        match shared::wired::scene::prim::light(&self.api, self.rep).await {
+            Ok(Some(l)) => light_to_js(&l),
+            _ => JsValue::UNDEFINED,
+        }
+    }
+
+    #[wasm_bindgen(js_name = "setLight")]
+    pub async fn set_light(&self, value: JsValue) -> Result<(), JsValue> {
+        let value = js_to_light(&value);
+        shared::wired::scene::prim::set_light(&self.api, self.rep, value)
+            .await
+            .map_err(raise)
+    }
+
+    pub async fn audio(&self) -> JsValue {
+        match shared::wired::scene::prim::audio(&self.api, self.rep).await {
+            Ok(Some(a)) => audio_to_js(a),
+            _ => JsValue::UNDEFINED,
+        }
+    }
+
+    #[wasm_bindgen(js_name = "setAudio")]
+    pub async fn set_audio(&self, value: JsValue) -> Result<(), JsValue> {
+        let value = js_to_audio(&value);
+        shared::wired::scene::prim::set_audio(&self.api, self.rep, value)
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "setAudioData")]
+    pub async fn set_audio_data(&self, bytes: JsValue) -> Result<(), JsValue> {
+        shared::wired::scene::prim::set_audio_data(&self.api, self.rep, js_to_bytes(&bytes))
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "playAnimation")]
+    pub async fn play_animation(&self, playback: JsValue) -> Result<(), JsValue> {
+        shared::wired::scene::prim::play_animation(
+            &self.api,
+            self.rep,
+            js_to_animation_playback(&playback),
+        )
+        .await
+        .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "pauseAnimation")]
+    pub async fn pause_animation(&self) -> Result<(), JsValue> {
+        shared::wired::scene::prim::pause_animation(&self.api, self.rep)
+            .await
+            .map_err(raise)
+    }
+
+    #[wasm_bindgen(js_name = "stopAnimation")]
+    pub async fn stop_animation(&self) -> Result<(), JsValue> {
+        shared::wired::scene::prim::stop_animation(&self.api, self.rep)
+            .await
+            .map_err(raise)
+    }
+
     pub async fn relationships(&self) -> js_sys::Array {
         let Ok(items) = shared::wired::scene::prim::relationships(&self.api, self.rep).await else {
             return js_sys::Array::new();
@@ -614,6 +742,133 @@ fn js_to_text(v: &JsValue) -> Option<PrimText> {
     })
 }
 
+fn joint_to_js(j: &PrimJoint) -> JsValue {
+    let obj = js_sys::Object::new();
+    obj_set(
+        &obj,
+        "kind",
+        &JsValue::from_str(match j.kind {
+            PrimJointKind::Fixed => "fixed",
+            PrimJointKind::Revolute => "revolute",
+            PrimJointKind::Prismatic => "prismatic",
+            PrimJointKind::Spherical => "spherical",
+            PrimJointKind::Distance => "distance",
+        }),
+    );
+    for (key, value) in [
+        ("anchor", j.anchor),
+        ("otherAnchor", j.other_anchor),
+        ("axis", j.axis),
+    ] {
+        if let Some([x, y, z]) = value {
+            obj_set(&obj, key, &vec3_to_js(x, y, z));
+        }
+    }
+    if let Some(l) = j.limits {
+        let limits = js_sys::Object::new();
+        obj_set(&limits, "min", &l.min.into());
+        obj_set(&limits, "max", &l.max.into());
+        obj_set(&obj, "limits", &limits.into());
+    }
+    if let Some(m) = j.motor {
+        let motor = js_sys::Object::new();
+        if let Some(v) = m.target_velocity {
+            obj_set(&motor, "targetVelocity", &v.into());
+        }
+        if let Some(v) = m.target_position {
+            obj_set(&motor, "targetPosition", &v.into());
+        }
+        if let Some(v) = m.max_force {
+            obj_set(&motor, "maxForce", &v.into());
+        }
+        obj_set(&obj, "motor", &motor.into());
+    }
+    obj.into()
+}
+
+fn js_to_joint(v: &JsValue) -> Option<PrimJoint> {
+    if v.is_null() || v.is_undefined() {
+        return None;
+    }
+    let point = |key: &str| {
+        let value = obj_get(v, key);
+        (!value.is_null() && !value.is_undefined()).then(|| js_to_vec3(&value, [0.0; 3]))
+    };
+    let limits = obj_get(v, "limits");
+    let motor = obj_get(v, "motor");
+    Some(PrimJoint {
+        kind:         match obj_get_string(v, "kind").as_deref() {
+            Some("revolute") => PrimJointKind::Revolute,
+            Some("prismatic") => PrimJointKind::Prismatic,
+            Some("spherical") => PrimJointKind::Spherical,
+            Some("distance") => PrimJointKind::Distance,
+            _ => PrimJointKind::Fixed,
+        },
+        anchor:       point("anchor"),
+        other_anchor: point("otherAnchor"),
+        axis:         point("axis"),
+        limits:       obj_get_f32(&limits, "min")
+            .zip(obj_get_f32(&limits, "max"))
+            .map(|(min, max)| PrimJointLimits { min, max }),
+        motor:        (!motor.is_null() && !motor.is_undefined()).then(|| PrimJointMotor {
+            target_velocity: obj_get_f32(&motor, "targetVelocity"),
+            target_position: obj_get_f32(&motor, "targetPosition"),
+            max_force:       obj_get_f32(&motor, "maxForce"),
+        }),
+    })
+}
+
+fn light_to_js(l: &PrimLight) -> JsValue {
+    let obj = js_sys::Object::new();
+    obj_set(
+        &obj,
+        "kind",
+        &JsValue::from_str(match l.kind {
+            PrimLightKind::Directional => "directional",
+            PrimLightKind::Point => "point",
+            PrimLightKind::Spot => "spot",
+        }),
+    );
+    if let Some(v) = &l.color {
+        obj_set(&obj, "color", &color_to_js(v));
+    }
+    if let Some(v) = l.intensity {
+        obj_set(&obj, "intensity", &v.into());
+    }
+    if let Some(v) = l.range {
+        obj_set(&obj, "range", &v.into());
+    }
+    if let Some(v) = l.inner_angle {
+        obj_set(&obj, "innerAngle", &v.into());
+    }
+    if let Some(v) = l.outer_angle {
+        obj_set(&obj, "outerAngle", &v.into());
+    }
+    if let Some(v) = l.shadows {
+        obj_set(&obj, "shadows", &v.into());
+    }
+    obj.into()
+}
+
+fn js_to_light(v: &JsValue) -> Option<PrimLight> {
+    if v.is_null() || v.is_undefined() {
+        return None;
+    }
+    Some(PrimLight {
+        kind:        match obj_get_string(v, "kind").as_deref() {
+            Some("directional") => PrimLightKind::Directional,
+            Some("spot") => PrimLightKind::Spot,
+            _ => PrimLightKind::Point,
+        },
+        color:       js_to_color(&obj_get(v, "color")),
+        intensity:   obj_get_f32(v, "intensity"),
+        range:       obj_get_f32(v, "range"),
+        inner_angle: obj_get_f32(v, "innerAngle"),
+        outer_angle: obj_get_f32(v, "outerAngle"),
+        shadows:     obj_get_bool(v, "shadows"),
+    })
+}
+
 fn material_to_js(m: &PrimMaterial) -> JsValue {
     let obj = js_sys::Object::new();
     if let Some(v) = m.alpha_cutoff {
@@ -856,6 +1111,43 @@ fn portal_to_js(p: &PrimPortal) -> JsValue {
     obj.into()
 }
 
+fn audio_to_js(a: PrimAudio) -> JsValue {
+    let obj = js_sys::Object::new();
+    if let Some(v) = a.volume {
+        obj_set(&obj, "volume", &v.into());
+    }
+    if let Some(v) = a.looping {
+        obj_set(&obj, "looping", &v.into());
+    }
+    if let Some(v) = a.rolloff {
+        obj_set(&obj, "rolloff", &v.into());
+    }
+    if let Some(v) = a.autoplay {
+        obj_set(&obj, "autoplay", &v.into());
+    }
+    obj.into()
+}
+
+fn js_to_audio(v: &JsValue) -> Option<PrimAudio> {
+    if v.is_null() || v.is_undefined() {
+        return None;
+    }
+    Some(PrimAudio {
+        volume:   obj_get_f32(v, "volume"),
+        looping:  obj_get_bool(v, "looping"),
+        rolloff:  obj_get_f32(v, "rolloff"),
+        autoplay: obj_get_bool(v, "autoplay"),
+    })
+}
+
+fn js_to_animation_playback(v: &JsValue) -> PrimAnimationPlayback {
+    PrimAnimationPlayback {
+        speed:   obj_get_f32(v, "speed").unwrap_or(1.0),
+        weight:  obj_get_f32(v, "weight").unwrap_or(1.0),
+        looping: obj_get_bool(v, "looping").unwrap_or(false),
+    }
+}
+
 fn spawn_to_js(s: &PrimSpawn) -> JsValue {
     let obj = js_sys::Object::new();
     obj_set(&obj, "radius", &s.radius.into());
@@ -107,6 +107,13 @@ pub fn obj_get_i32(obj: &JsValue, key: &str) -> Option<i32> {
         .map(|v| v as i32)
 }
 
+pub fn obj_get_u32(obj: &JsValue, key: &str) -> Option<u32> {
+    js_sys::Reflect::get(obj, &key.into())
+        .ok()
+        .and_then(|v| v.as_f64())
+        .map(|v| v as u32)
+}
+
 pub fn obj_get_bool(obj: &JsValue, key: &str) -> Option<bool> {
     js_sys::Reflect::get(obj, &key.into())
         .ok()
@@ -0,0 +1,235 @@
+//! `unavi-server admin`: operator calls against a running server, made as the
+//! server's own DID.
+
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+    sync::Arc,
+};
+
+use anyhow::Context;
+use clap::{
+    Subcommand,
+    ValueEnum,
+};
+use iroh::{
+    Endpoint,
+    EndpointAddr,
+    EndpointId,
+    endpoint::presets::N0,
+};
+use iroh_docs::NamespaceId;
+use unavi_registry::client::RegistryClient;
+use wds::{
+    actor::Actor,
+    denylist::DenyEntry,
+    identity::Identity,
+    resolve::{
+        resolve_allowing_loopback,
+        wds_endpoint,
+    },
+};
+use xdid::core::did::Did;
+
+use crate::{
+    create_did,
+    key_pair,
+    secrets,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum AdminCommand {
+    /// List every user with a quota.
+    Users,
+    /// Show a user's quota and usage.
+    GetQuota { did: String },
+    /// Set a user's quota, in bytes.
+    SetQuota { did: String, bytes: u64 },
+    /// List a user's blob pins.
+    Pins { did: String },
+    /// List the docs hosted on a user's behalf.
+    Hosted { did: String },
+    /// Stop hosting a namespace for everyone, refunding each host.
+    Unhost { ns: String },
+    /// Remove everything a user holds: hosting, pins, quota and authentication.
+    Purge { did: String },
+    /// Write an archive of everything a user holds to a file.
+    Export { did: String, path: PathBuf },
+    /// Write an archive per user into a directory, for moving the whole
+    /// server.
+    Backup { dir: PathBuf },
+    /// Restore archives, each under the user it was exported for.
+    Import {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// List what the server refuses, and where each entry came from.
+    Denylist,
+    /// Refuse a blob, namespace or DID from now on.
+    Deny { kind: DenyKind, value: String },
+    /// Lift one of the server's own denylist entries.
+    Undeny { kind: DenyKind, value: String },
+    /// List the registry's moderation queue, most heavily reported first.
+    Reports,
+    /// Clear the reports against a namespace.
+    Dismiss { ns: String },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum DenyKind {
+    Blob,
+    Ns,
+    Did,
+}
+
+impl DenyKind {
+    fn entry(self, value: &str) -> anyhow::Result<DenyEntry> {
+        Ok(match self {
+            Self::Blob => DenyEntry::Blob(iroh_blobs::Hash::from_str(value)?.into()),
+            Self::Ns => DenyEntry::Namespace(NamespaceId::from_str(value)?),
+            Self::Did => DenyEntry::Did(Did::from_str(value)?),
+        })
+    }
+}
+
+/// Runs one admin command. `host` is the server's endpoint; without it, it is
+/// read from the server's DID document.
+pub async fn run(host: Option<EndpointId>, command: AdminCommand) -> anyhow::Result<()> {
+    let did = create_did(&secrets::Secrets::load().unavi_domain)?;
+    let key = key_pair::get_or_create_key(false)?;
+
+    let host = match host {
+        Some(id) => EndpointAddr::from(id),
+        None => resolve_host(&did).await?,
+    };
+
+    let endpoint = Endpoint::builder(N0).bind().await?;
+    let actor = Actor::remote(&endpoint, Arc::new(Identity::new(did, key)), host.clone());
+    let registry = RegistryClient::new(&endpoint, host, actor.clone());
+
+    match command {
+        AdminCommand::Users => {
+            for user in actor.list_users().await? {
+                println!(
+                    "{}\t{} / {} bytes",
+                    user.owner, user.bytes_used, user.quota_bytes
+                );
+            }
+        }
+        AdminCommand::GetQuota { did } => {
+            let info = actor.user_quota(Did::from_str(&did)?).await?;
+            println!("{} / {} bytes", info.bytes_used, info.quota_bytes);
+        }
+        AdminCommand::SetQuota { did, bytes } => {
+            actor.set_user_quota(Did::from_str(&did)?, bytes).await?
+        }
+        AdminCommand::Pins { did } => {
+            for pin in actor.user_pins(Did::from_str(&did)?).await? {
+                println!("{}\t{} bytes\texpires {}", pin.hash, pin.size, pin.expires);
+            }
+        }
+        AdminCommand::Hosted { did } => {
+            for hosted in actor.user_hosted(Did::from_str(&did)?).await? {
+                println!("{}\t{} bytes", hosted.ns, hosted.bytes_used);
+            }
+        }
+        AdminCommand::Unhost { ns } => actor.force_unhost(NamespaceId::from_str(&ns)?).await?,
+        AdminCommand::Purge { did } => actor.purge_did(Did::from_str(&did)?).await?,
+        AdminCommand::Export { did, path } => {
+            export(&actor, Did::from_str(&did)?, &path).await?;
+        }
+        AdminCommand::Backup { dir } => {
+            std::fs::create_dir_all(&dir)?;
+            for user in actor.list_users().await? {
+                let path = dir.join(archive_name(&user.owner));
+                export(&actor, user.owner, &path).await?;
+                println!("{}", path.display());
+            }
+        }
+        AdminCommand::Import { paths } => {
+            for path in paths {
+                let mut input = BufReader::new(File::open(&path)?);
+                let summary = actor
+                    .import_archive(&mut input)
+                    .await
+                    .with_context(|| format!("import {}", path.display()))?;
+                println!(
+                    "{}\t{} blobs, {} pins, {} docs, {} entries, {} denied",
+                    path.display(),
+                    summary.blobs,
+                    summary.pins,
+                    summary.docs,
+                    summary.entries,
+                    summary.denied
+                );
+            }
+        }
+        AdminCommand::Denylist => {
+            for denied in actor.list_denied().await? {
+                let source = denied
+                    .source
+                    .map_or_else(|| "own".to_string(), |did| did.to_string());
+                println!("{:?}\t{source}", denied.entry);
+            }
+        }
+        AdminCommand::Deny { kind, value } => actor.deny(kind.entry(&value)?).await?,
+        AdminCommand::Undeny { kind, value } => actor.undeny(kind.entry(&value)?).await?,
+        AdminCommand::Reports => {
+            for queued in registry.reports().await? {
+                println!("{}\tweight {:.1}", queued.ns, queued.weight);
+                for signed in queued.reports {
+                    let Ok(report) = signed.payload() else {
+                        continue;
+                    };
+                    let detail = report.detail.unwrap_or_default();
+                    println!("\t{}\t{:?}\t{detail}", report.did, report.reason);
+                }
+            }
+        }
+        AdminCommand::Dismiss { ns } => registry.dismiss(NamespaceId::from_str(&ns)?).await?,
+    }
+
+    endpoint.close().await;
+    Ok(())
+}
+
+async fn export(actor: &Actor, did: Did, path: &Path) -> anyhow::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    actor
+        .export_archive(Some(did), &mut out)
+        .await
+        .with_context(|| format!("export {}", path.display()))?;
+    out.flush()?;
+    Ok(())
+}
+
+/// A file name for `did`'s archive, with the characters a DID may carry that
+/// paths may not replaced.
+fn archive_name(did: &Did) -> String {
+    let name = did.to_string().replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+        "_",
+    );
+    format!("{name}.wdsa")
+}
+
+/// The endpoint the server advertises in its own DID document.
+///
+/// Loopback is allowed: the DID is the operator's own configuration, and a
+/// development server resolves from `localhost`.
+async fn resolve_host(did: &Did) -> anyhow::Result<EndpointAddr> {
+    let doc = resolve_allowing_loopback(did)
+        .await
+        .with_context(|| format!("could not resolve {did}"))?;
+    let endpoint = wds_endpoint(&doc).context("no WDS endpoint in the server's DID document")?;
+    Ok(EndpointAddr::from(endpoint))
+}
@@ -32,6 +32,7 @@ use unavi_registry::{
 use wds::{
     DataStore,
     WDS_SERVICE_TYPE,
+    identity::Identity,
 };
 use xdid::{
     core::{
@@ -56,6 +57,7 @@ use xdid::{
     },
 };
 
+pub mod admin;
 mod files;
 mod key_pair;
 pub mod secrets;
@@ -75,6 +77,9 @@ pub static DIRS: LazyLock<ProjectDirs> = LazyLock::new(|| {
 /// inert when empty.
 pub struct Features {
     pub registry: bool,
+    /// Off by default: a relay carries every member's traffic, and small
+    /// spaces do better as a plain mesh.
+    pub relay:    bool,
     pub wds:      bool,
 }
 
@@ -82,14 +87,23 @@ impl Default for Features {
     fn default() -> Self {
         Self {
             registry: true,
+            relay:    false,
             wds:      true,
         }
     }
 }
 
 pub struct ServerOptions {
+    /// Publishers whose denylists the store and registry refuse content by,
+    /// besides the operators' own.
+    pub denylists: Vec<Did>,
     pub features:  Features,
     pub in_memory: bool,
+    /// Peer servers whose hosted docs this one re-hosts, and which may re-host
+    /// its own.
+    pub mirrors:   Vec<Did>,
+    /// DIDs besides the server's own allowed the WDS admin calls.
+    pub operators: Vec<Did>,
     pub port:      u16,
 }
 
@@ -97,12 +111,21 @@ pub async fn run_server(opts: ServerOptions) -> anyhow::Result<()> {
     let port = opts.port;
 
     let did = create_did(&secrets::Secrets::load().unavi_domain)?;
-    let vc = key_pair::get_or_create_key(opts.in_memory)?;
+    let identity = Arc::new(Identity::new(
+        did.clone(),
+        key_pair::get_or_create_key(opts.in_memory)?,
+    ));
     info!("Running server as {did}");
 
     let endpoint = Endpoint::builder(N0).bind().await?;
 
-    let builder = DataStore::builder(endpoint.clone()).gc_timer(Duration::from_mins(15));
+    let builder = DataStore::builder(endpoint.clone())
+        .gc_timer(Duration::from_mins(15))
+        .denylists(opts.denylists)
+        .denylist_timer(Duration::from_mins(15))
+        .mirror_timer(Duration::from_mins(5))
+        .mirrors(opts.mirrors.clone())
+        .operators(std::iter::once(did.clone()).chain(opts.operators));
     let builder = if opts.in_memory {
         builder
     } else {
@@ -110,6 +133,9 @@ pub async fn run_server(opts: ServerOptions) -> anyhow::Result<()> {
     };
 
     let (store, f) = builder.build().await?;
+    // Mirrors authenticate to their peers as this server, and their copies are
+    // charged to it.
+    store.set_user_identity(Arc::clone(&identity));
     let store = Arc::new(store);
 
     if let Err(err) = files::init_files_dir() {
@@ -137,9 +163,21 @@ pub async fn run_server(opts: ServerOptions) -> anyhow::Result<()> {
         (None, None)
     };
 
+    if opts.features.relay {
+        // Spaces name the relay by this endpoint id, in their `relay` attribute.
+        info!(endpoint = %store.endpoint_id(), "Serving space relay");
+        rb = rb.accept(unavi_relay::ALPN, unavi_relay::Relay::default());
+    }
+
     let router = rb.spawn();
 
-    let app = create_did_document_route(did, &vc, store.endpoint_id(), views)?;
+    let app = create_did_document_route(
+        did,
+        identity.signing_key(),
+        store.endpoint_id(),
+        views,
+        &opts.mirrors,
+    )?;
 
     let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port));
     info!("HTTP listening on port {port}");
@@ -165,13 +203,16 @@ fn create_did_document_route(
     vc: &impl DidKeyPair,
     endpoint_id: EndpointId,
     views: Option<ViewIds>,
+    mirrors: &[Did],
 ) -> anyhow::Result<axum::Router> {
     // Advertise the endpoint always, and the registry's entry view only when
-    // this node runs one, so resolvers learn which roles it serves.
+    // this node runs one, so resolvers learn which roles it serves. Mirrors
+    // are listed so clients can fail over to them for content.
     let mut service_endpoint = vec![endpoint_id.to_string()];
     if let Some(views) = views {
         service_endpoint.push(format!("registry:{}", views.recent));
     }
+    service_endpoint.extend(mirrors.iter().map(|did| format!("mirror:{did}")));
 
     let key_ref = VerificationMethod::RelativeUrl(RelativeDidUrl::new(
         RelativeDidUrlPath::Empty,
@@ -1,4 +1,13 @@
-use clap::Parser;
+use std::{
+    process::ExitCode,
+    str::FromStr,
+};
+
+use clap::{
+    Parser,
+    Subcommand,
+};
+use iroh::EndpointId;
 use tracing::{
     Level,
     error,
@@ -12,7 +21,9 @@ use tracing_subscriber::{
 use unavi_server::{
     Features,
     ServerOptions,
+    admin::AdminCommand,
 };
+use xdid::core::did::Did;
 
 #[derive(Parser, Debug)]
 #[command(version)]
@@ -30,13 +41,43 @@ struct Args {
     /// Do not serve discovery: catalog, curated views, and live presence.
     #[arg(long, default_value_t = false)]
     no_registry: bool,
+    /// Relay space traffic. Any space may join; rooms, members and bytes
+    /// relayed are capped.
+    #[arg(long, default_value_t = false)]
+    relay:       bool,
     /// Do not serve storage: hosted docs, blob pins, and quotas.
     #[arg(long, default_value_t = false)]
     no_wds:      bool,
+    /// A DID allowed the WDS admin calls, besides the server's own.
+    /// Repeatable.
+    #[arg(long = "operator")]
+    operators:   Vec<String>,
+    /// A peer server's DID to mirror: its hosted docs are re-hosted here, and
+    /// it may re-host this server's. Repeatable.
+    #[arg(long = "mirror")]
+    mirrors:     Vec<String>,
+    /// A DID whose published denylist this server also refuses content by.
+    /// Repeatable.
+    #[arg(long = "denylist")]
+    denylists:   Vec<String>,
+    #[command(subcommand)]
+    command:     Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Administer a running server's storage, as the server's own DID.
+    Admin {
+        /// The server's endpoint id; read from its DID document if omitted.
+        #[arg(long)]
+        host:    Option<EndpointId>,
+        #[command(subcommand)]
+        command: AdminCommand,
+    },
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let args = Args::parse();
 
     let registry = tracing_subscriber::registry();
@@ -59,16 +100,56 @@ async fn main() {
 
     registry.init();
 
+    if let Some(Command::Admin { host, command }) = args.command {
+        if let Err(err) = unavi_server::admin::run(host, command).await {
+            error!(?err, "admin command failed");
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let operators = match parse_dids(&args.operators) {
+        Ok(operators) => operators,
+        Err(err) => {
+            error!(?err, "invalid operator DID");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mirrors = match parse_dids(&args.mirrors) {
+        Ok(mirrors) => mirrors,
+        Err(err) => {
+            error!(?err, "invalid mirror DID");
+            return ExitCode::FAILURE;
+        }
+    };
+    let denylists = match parse_dids(&args.denylists) {
+        Ok(denylists) => denylists,
+        Err(err) => {
+            error!(?err, "invalid denylist DID");
+            return ExitCode::FAILURE;
+        }
+    };
+
     if let Err(err) = unavi_server::run_server(ServerOptions {
-        features:  Features {
+        denylists,
+        features: Features {
             registry: !args.no_registry,
+            relay:    args.relay,
             wds:      !args.no_wds,
         },
         in_memory: args.in_memory,
-        port:      args.port,
+        mirrors,
+        operators,
+        port: args.port,
     })
     .await
     {
         error!(?err, "error during run");
+        return ExitCode::FAILURE;
     }
+    ExitCode::SUCCESS
+}
+
+fn parse_dids(dids: &[String]) -> anyhow::Result<Vec<Did>> {
+    dids.iter().map(|did| Ok(Did::from_str(did)?)).collect()
 }
@@ -0,0 +1,108 @@
+use std::collections::VecDeque;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::chat::ChatMessage;
+
+/// Messages kept per space. Older ones fall off the back.
+pub const HISTORY_LEN: usize = 200;
+
+/// A message as the scroll-back holds it, numbered in arrival order.
+///
+/// Ids are local: two peers number the same message differently. They exist so
+/// a reader can ask for what arrived after the last line it saw.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatLine {
+    pub id:      u64,
+    pub message: ChatMessage,
+}
+
+/// One space's scroll-back.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    lines:   VecDeque<ChatLine>,
+    next_id: u64,
+    /// Changed since last written to disk.
+    #[serde(skip)]
+    dirty:   bool,
+}
+
+impl History {
+    /// Appends `message`, unless it is one already held.
+    pub fn insert(&mut self, message: ChatMessage) -> Option<u64> {
+        if self
+            .lines
+            .iter()
+            .any(|l| l.message.did == message.did && l.message.nonce == message.nonce)
+        {
+            return None;
+        }
+
+        self.next_id += 1;
+        let id = self.next_id;
+        self.lines.push_back(ChatLine { id, message });
+        while self.lines.len() > HISTORY_LEN {
+            self.lines.pop_front();
+        }
+        self.dirty = true;
+        Some(id)
+    }
+
+    /// Lines with an id past `after`, oldest first.
+    pub fn after(&self, after: u64) -> impl Iterator<Item = &ChatLine> {
+        let start = self.lines.partition_point(|l| l.id <= after);
+        self.lines.range(start..)
+    }
+
+    /// Whether there is anything to write, clearing the flag.
+    pub const fn take_dirty(&mut self) -> bool {
+        std::mem::replace(&mut self.dirty, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xdid::core::did::Did;
+
+    use super::*;
+
+    fn message(nonce: u64, body: &str) -> ChatMessage {
+        ChatMessage {
+            did: "did:web:alice.example".parse::<Did>().expect("did"),
+            space: [0; 32],
+            bot: None,
+            body: body.to_string(),
+            sent: 0,
+            nonce,
+        }
+    }
+
+    #[test]
+    fn a_message_seen_twice_is_kept_once() {
+        let mut history = History::default();
+        assert_eq!(history.insert(message(1, "hi")), Some(1));
+        assert_eq!(history.insert(message(1, "hi")), None);
+        assert_eq!(history.insert(message(2, "hi")), Some(2));
+        assert_eq!(history.after(0).count(), 2);
+    }
+
+    #[test]
+    fn scroll_back_is_bounded_and_reads_from_an_id() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LEN as u64 + 10 {
+            history.insert(message(i, "spam"));
+        }
+
+        assert_eq!(history.after(0).count(), HISTORY_LEN);
+        assert_eq!(history.after(0).next().map(|l| l.id), Some(11));
+
+        let tail = history.after(HISTORY_LEN as u64).collect::<Vec<_>>();
+        assert_eq!(tail.len(), 10);
+        assert!(tail.windows(2).all(|w| w[0].id < w[1].id));
+        assert!(history.take_dirty());
+        assert!(!history.take_dirty());
+    }
+}
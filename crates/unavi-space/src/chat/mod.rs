@@ -0,0 +1,300 @@
+//! Text chat, carried on each space's gossip topic.
+//!
+//! A message is signed by the sender's DID, so it is attributable to a person
+//! rather than an endpoint, and checked the same way by everyone who hears it:
+//! from a blocked DID, over the sender's trust-scaled rate, outside the replay
+//! window or with a bad signature, it is dropped. What passes lands in a
+//! bounded scroll-back kept per space, and on native written to disk.
+//!
+//! Blocks apply backwards too. The scroll-back keeps what a since-blocked DID
+//! said, but [`history`] no longer returns it.
+
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+};
+
+use bevy::prelude::*;
+use hsd::id::DocId;
+use iroh::EndpointId;
+use iroh_docs::NamespaceId;
+use parking_lot::Mutex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use time::OffsetDateTime;
+use unavi_policy::trust::{
+    self,
+    Trust,
+};
+use unavi_quota::{
+    Flow,
+    limits::MAX_CHAT_BYTES,
+    registry::{
+        peer_quota,
+        peer_quota_with,
+        space_quota,
+    },
+};
+use wds::signed_bytes::{
+    Signable,
+    SignedBytes,
+    verify_did_signature,
+};
+use xdid::core::did::Did;
+
+use crate::peer::{
+    self_identity,
+    self_peer_id,
+};
+
+mod history;
+
+use history::History;
+pub use history::{
+    ChatLine,
+    HISTORY_LEN,
+};
+
+/// How far a message's timestamp may trail the receiver's clock. Older ones
+/// are replays: nobody waits minutes for a chat line.
+const MAX_AGE_SECS: i64 = 5 * 60;
+/// How far ahead of the receiver's clock a sender's may run.
+const MAX_SKEW_SECS: i64 = 60;
+
+/// Messages queued for broadcast before posting is refused.
+const OUTBOX_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub did:   Did,
+    /// Bound into the signature, so a message cannot be replayed into another
+    /// space.
+    pub space: [u8; 32],
+    /// The script document that posted it, if not a person.
+    pub bot:   Option<[u8; 32]>,
+    pub body:  String,
+    /// Unix seconds, by the sender's clock.
+    pub sent:  i64,
+    /// Tells apart two identical lines sent in the same second.
+    pub nonce: u64,
+}
+
+impl Signable for ChatMessage {
+    const SIGNING_CONTEXT: &'static str = "unavi/space/chat";
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChatError {
+    #[error("message exceeds {MAX_CHAT_BYTES} bytes")]
+    TooLong,
+    #[error("message is empty")]
+    Empty,
+    #[error("no local identity to sign with")]
+    NoIdentity,
+    #[error("not joined to the space's topic")]
+    NotJoined,
+    #[error("posting too fast")]
+    Quota,
+    #[error(transparent)]
+    Sign(#[from] anyhow::Error),
+}
+
+static HISTORIES: LazyLock<Mutex<HashMap<NamespaceId, History>>> = LazyLock::new(Mutex::default);
+
+type Outbox = async_channel::Sender<SignedBytes<ChatMessage>>;
+
+/// Where each joined space's topic takes messages to broadcast.
+static OUTBOXES: LazyLock<Mutex<HashMap<NamespaceId, Outbox>>> = LazyLock::new(Mutex::default);
+
+fn with_history<T>(space: NamespaceId, f: impl FnOnce(&mut History) -> T) -> T {
+    let mut histories = HISTORIES.lock();
+    let history = histories.entry(space).or_insert_with(|| load(space));
+    f(history)
+}
+
+/// Posts `body` to `space` as the local user.
+pub fn post(space: NamespaceId, body: String) -> Result<u64, ChatError> {
+    // The user's own budget: the limit everyone else holds them to, felt here
+    // first rather than discovered as silence.
+    let peer = self_peer_id().ok_or(ChatError::NotJoined)?;
+    peer_quota(NamespaceId::from(&peer))
+        .spend(Flow::Chat, 1.0)
+        .map_err(|_| ChatError::Quota)?;
+    publish(space, None, body)
+}
+
+/// Posts `body` to `space` on behalf of the script document `bot`, signed as
+/// the local user and marked as the bot's. The caller has charged the
+/// document's quota, which rolls up into the local user's.
+pub fn post_as_bot(space: NamespaceId, bot: DocId, body: String) -> Result<u64, ChatError> {
+    publish(space, Some(bot.0), body)
+}
+
+fn publish(space: NamespaceId, bot: Option<[u8; 32]>, body: String) -> Result<u64, ChatError> {
+    if body.len() > MAX_CHAT_BYTES {
+        return Err(ChatError::TooLong);
+    }
+    if body.trim().is_empty() {
+        return Err(ChatError::Empty);
+    }
+    let identity = self_identity().ok_or(ChatError::NoIdentity)?;
+    let outbox = OUTBOXES
+        .lock()
+        .get(&space)
+        .cloned()
+        .ok_or(ChatError::NotJoined)?;
+
+    let message = ChatMessage {
+        did: identity.did().clone(),
+        space: *space.as_bytes(),
+        bot,
+        body,
+        sent: OffsetDateTime::now_utc().unix_timestamp(),
+        nonce: rand::random(),
+    };
+    let signed = message.sign(identity.signing_key())?;
+
+    outbox.try_send(signed).map_err(|_| ChatError::Quota)?;
+    Ok(with_history(space, |h| h.insert(message)).unwrap_or_default())
+}
+
+/// Opens `space`'s outbox for its topic to drain. Replaces any earlier one,
+/// whose topic has gone.
+pub(crate) fn open_outbox(space: NamespaceId) -> async_channel::Receiver<SignedBytes<ChatMessage>> {
+    let (tx, rx) = async_channel::bounded(OUTBOX_LEN);
+    OUTBOXES.lock().insert(space, tx);
+    rx
+}
+
+/// Closes an outbox its topic no longer drains. Leaves alone any opened since
+/// for the same space, which a rejoin may have raced in.
+pub(crate) fn close_outbox(outbox: &async_channel::Receiver<SignedBytes<ChatMessage>>) {
+    outbox.close();
+    OUTBOXES.lock().retain(|_, tx| !tx.is_closed());
+}
+
+/// Takes a message heard on `space`'s topic from `sender`, if it passes.
+///
+/// Cheapest checks first: resolving the DID to verify the signature may go to
+/// the network, so nothing already refusable gets that far.
+pub(crate) async fn receive(
+    space: NamespaceId,
+    sender: EndpointId,
+    signed: SignedBytes<ChatMessage>,
+) {
+    let message = match signed.payload() {
+        Ok(message) => message,
+        Err(err) => {
+            debug!(?err, "Undecodable chat message");
+            return;
+        }
+    };
+    if message.space != *space.as_bytes() || message.body.len() > MAX_CHAT_BYTES {
+        return;
+    }
+
+    if trust::of_did(&message.did) == Trust::Blocked {
+        return;
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    if message.sent < now - MAX_AGE_SECS || message.sent > now + MAX_SKEW_SECS {
+        debug!(did = %message.did, "Chat message outside the replay window");
+        return;
+    }
+
+    // The budget is the endpoint's, so it is scaled by what the endpoint has
+    // proved, not by the DID a message claims before its signature is checked.
+    let peer = peer_quota_with(NamespaceId::from(sender.as_bytes()), || {
+        unavi_policy::limits::for_trust(trust::of_peer(*sender.as_bytes()))
+    });
+    if peer.spend(Flow::Chat, 1.0).is_err() {
+        debug!(did = %message.did, "Chat message over quota");
+        return;
+    }
+
+    if !verify_did_signature(&signed, &message.did).await {
+        warn!(did = %message.did, "Chat message not signed by its DID");
+        return;
+    }
+
+    // The space's budget is shared by everyone in it, so only signed messages
+    // draw on it; forgeries from fresh endpoints could otherwise drain it.
+    if space_quota(space).spend(Flow::Chat, 1.0).is_err() {
+        debug!(did = %message.did, "Chat message over the space's quota");
+        return;
+    }
+
+    with_history(space, |h| h.insert(message));
+}
+
+/// Up to `limit` lines of `space`'s scroll-back with an id past `after`,
+/// oldest first, less anything from a DID now blocked.
+#[must_use]
+pub fn history(space: NamespaceId, after: u64, limit: usize) -> Vec<ChatLine> {
+    with_history(space, |h| {
+        h.after(after)
+            .filter(|l| trust::of_did(&l.message.did) != Trust::Blocked)
+            .take(limit)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Writes every scroll-back that changed since the last pass.
+pub fn flush_histories() {
+    let mut histories = HISTORIES.lock();
+    for (space, history) in histories.iter_mut() {
+        if history.take_dirty() {
+            save(*space, history);
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn load(_space: NamespaceId) -> History {
+    History::default()
+}
+
+#[cfg(target_family = "wasm")]
+const fn save(_space: NamespaceId, _history: &History) {}
+
+#[cfg(not(target_family = "wasm"))]
+fn history_path(space: NamespaceId) -> std::path::PathBuf {
+    unavi_util::dirs::data_local_dir()
+        .join("chat")
+        .join(format!("{space}.postcard"))
+}
+
+/// A scroll-back that cannot be read starts empty rather than failing chat.
+#[cfg(not(target_family = "wasm"))]
+fn load(space: NamespaceId) -> History {
+    let path = history_path(space);
+    match std::fs::read(&path) {
+        Ok(bytes) => postcard::from_bytes(&bytes).unwrap_or_else(|err| {
+            warn!(?err, ?path, "Unreadable chat history");
+            History::default()
+        }),
+        Err(_) => History::default(),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn save(space: NamespaceId, history: &History) {
+    fn write(path: &std::path::Path, history: &History) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("postcard.tmp");
+        std::fs::write(&temp, postcard::to_allocvec(history)?)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    let path = history_path(space);
+    if let Err(err) = write(&path, history) {
+        warn!(?err, ?path, "Failed to save chat history");
+    }
+}
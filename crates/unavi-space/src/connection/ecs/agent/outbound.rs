@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
 
 use async_channel::TrySendError;
 use bevy::{
@@ -24,6 +27,7 @@ use crate::{
             PeerStream,
             Tickrate,
         },
+        shared::feedback::Pacing,
         types::{
             IFrame,
             f16_vec3::F16Vec3,
@@ -34,7 +38,13 @@ use crate::{
             rigid_transform::RigidTransform,
         },
     },
-    peer::Peer,
+    interest::{
+        self,
+        FAR_TICKRATE,
+        IDLE_TICKRATE,
+        InterestSettings,
+        Relevance,
+    },
 };
 
 /// Humanoid bones streamed to peers, ordered by visual significance and capped
@@ -62,9 +72,10 @@ pub struct OutgoingPose {
     pub pose:  Pose<IFrame>,
 }
 
+/// A pose stream's handoff, and how its receiver asked to be paced.
 #[derive(Component)]
 #[require(Tickrate)]
-pub struct AgentSender(pub async_channel::Sender<OutgoingPose>);
+pub struct AgentSender(pub async_channel::Sender<OutgoingPose>, pub Arc<Pacing>);
 
 pub fn send_agent_pose(
     time: Res<Time>,
@@ -160,29 +171,75 @@ fn gather_bones(
 const MAX_TICKRATE: Duration = Duration::from_millis(200);
 const MIN_TICKRATE: Duration = Duration::from_millis(50);
 
-const MAX_DIST: f32 = 50.0;
 const MIN_DIST: f32 = 4.0;
 
+/// How often a peer hears this agent, given how much it cares.
+fn tickrate_for(relevance: Relevance, radius: f32) -> Duration {
+    match relevance {
+        Relevance::Near(dist) => {
+            let span = (radius - MIN_DIST).max(f32::EPSILON);
+            let s = ((dist - MIN_DIST) / span).clamp(0.0, 1.0);
+            let secs = MIN_TICKRATE
+                .as_secs_f32()
+                .lerp(MAX_TICKRATE.as_secs_f32(), s);
+            Duration::from_secs_f32(secs)
+        }
+        Relevance::Far => FAR_TICKRATE,
+        Relevance::Hidden => IDLE_TICKRATE,
+    }
+}
+
+/// Paces each peer's pose stream by the interest it advertised, and never
+/// faster than it reported it can keep up with. A stream through a relay is
+/// paced by the room's interest alone, since no feedback comes back.
 pub fn set_agent_tickrates(
-    agent: Query<&Transform, With<LocalAgent>>,
-    peers: Query<(&Peer, &Transform), Without<LocalAgent>>,
-    streams: Query<(&PeerStream, &mut Tickrate)>,
+    settings: Res<InterestSettings>,
+    active: Res<ActiveSpace>,
+    spaces: Query<&Space>,
+    agent: Query<&AgentAvatar, With<LocalAgent>>,
+    globals: Query<&GlobalTransform>,
+    streams: Query<(&PeerStream, &AgentSender, &mut Tickrate)>,
 ) {
-    let Ok(root) = agent.single() else {
+    let Some(root) = agent
+        .single()
+        .ok()
+        .and_then(|avatar| globals.get(avatar.0).ok())
+        .map(GlobalTransform::translation)
+    else {
+        return;
+    };
+    let Some(space) = active.0.and_then(|e| spaces.get(e).ok()) else {
         return;
     };
 
-    for (target, mut tickrate) in streams {
-        let Some((_, transform)) = peers.iter().find(|(p, _)| p.0.id == target.0) else {
-            continue;
-        };
-
-        let dist = root.translation.distance(transform.translation);
-        let dist = dist.abs().clamp(MIN_DIST, MAX_DIST);
-        let s = (dist - MIN_DIST) / (MAX_DIST - MIN_DIST);
-        let secs = MIN_TICKRATE
-            .as_secs_f32()
-            .lerp(MAX_TICKRATE.as_secs_f32(), s);
-        tickrate.0 = Duration::from_secs_f32(secs);
+    for (target, sender, mut tickrate) in streams {
+        let audience = interest::audience(target.0);
+        let relevance = interest::relevance_to_any(&audience, space.0, root, settings.radius);
+        tickrate.0 = tickrate_for(relevance, settings.radius).max(sender.1.interval());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickrate_slows_with_interest() {
+        let close = |a: Duration, b: Duration| a.abs_diff(b) < Duration::from_millis(1);
+
+        assert!(close(
+            tickrate_for(Relevance::Near(0.0), 50.0),
+            MIN_TICKRATE
+        ));
+        assert!(close(
+            tickrate_for(Relevance::Near(50.0), 50.0),
+            MAX_TICKRATE
+        ));
+        assert!(close(
+            tickrate_for(Relevance::Near(10.0), 1.0),
+            MAX_TICKRATE
+        ));
+        assert_eq!(tickrate_for(Relevance::Far, 50.0), FAR_TICKRATE);
+        assert_eq!(tickrate_for(Relevance::Hidden, 50.0), IDLE_TICKRATE);
     }
 }
@@ -3,8 +3,11 @@ use std::time::Duration;
 use bevy::prelude::*;
 use iroh::EndpointId;
 
+use crate::connection::shared::feedback;
+
 pub mod agent;
 pub mod object;
+#[cfg(not(target_family = "wasm"))] pub mod voice;
 
 #[derive(Component)]
 pub struct PeerStream(pub EndpointId);
@@ -21,3 +24,12 @@ impl Default for Tickrate {
 
 #[derive(Component, Default)]
 pub struct LastTick(Duration);
+
+/// Keeps the frame time that receivers report as the most often an update is
+/// any use, smoothed so a single hitch does not slow every peer down.
+pub fn measure_frame_budget(time: Res<Time<Real>>, mut smoothed: Local<Option<f32>>) {
+    let dt = time.delta_secs();
+    let budget = smoothed.map_or(dt, |prev| prev.lerp(dt, 0.1));
+    *smoothed = Some(budget);
+    feedback::set_frame_budget(Duration::from_secs_f32(budget));
+}
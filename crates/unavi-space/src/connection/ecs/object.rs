@@ -37,7 +37,17 @@ use unavi_policy::{
 };
 use web_time::Instant;
 
-use crate::state::replicas;
+use crate::{
+    connection::ecs::PeerStream,
+    interest::{
+        self,
+        FAR_TICKRATE,
+        Interest,
+        InterestSettings,
+        Relevance,
+    },
+    state::replicas,
+};
 
 /// Replicas key by 32 opaque bytes, which a document id equally is.
 fn ns(id: DocId) -> NamespaceId {
@@ -45,7 +55,7 @@ fn ns(id: DocId) -> NamespaceId {
 }
 
 /// One owned dynamic prim's space-relative pose, queued for broadcast. Captured
-/// once per tick and cloned to each [`ObjectSender`].
+/// once per tick and cloned to each [`ObjectSender`] whose peer can see it.
 #[derive(Clone)]
 pub struct OutgoingObject {
     pub doc:   DocId,
@@ -54,6 +64,8 @@ pub struct OutgoingObject {
     pub root:  Transform,
     pub lin:   Vec3,
     pub ang:   Vec3,
+    /// Outside the peer's radius: sent only every [`FAR_TICKRATE`].
+    pub far:   bool,
 }
 
 #[derive(Component)]
@@ -70,8 +82,12 @@ const OBJECT_TICKRATE: Duration = Duration::from_millis(200);
 /// Broadcasts every dynamic prim in documents the local peer has authority
 /// over. Poses are space-relative; velocities are space-invariant (spaces only
 /// translate).
+///
+/// Each peer gets only the prims in spaces it has loaded, with those beyond
+/// its radius marked far.
 pub fn send_object_poses(
     time: Res<Time>,
+    settings: Res<InterestSettings>,
     spaces: Query<(&Space, &GlobalTransform)>,
     roots: Query<&HsdDocId>,
     prims: Query<(
@@ -82,7 +98,7 @@ pub fn send_object_poses(
         Option<&LinearVelocity>,
         Option<&AngularVelocity>,
     )>,
-    mut streams: Query<(Entity, &ObjectSender, &mut LastObjectTick)>,
+    mut streams: Query<(Entity, &PeerStream, &ObjectSender, &mut LastObjectTick)>,
     commands: ParallelCommands,
 ) {
     let now = time.elapsed();
@@ -116,6 +132,7 @@ pub fn send_object_poses(
                 },
                 lin: lin.map_or(Vec3::ZERO, |v| v.0),
                 ang: ang.map_or(Vec3::ZERO, |v| v.0),
+                far: false,
             })
         })
         .collect::<Vec<_>>();
@@ -126,7 +143,7 @@ pub fn send_object_poses(
 
     streams
         .par_iter_mut()
-        .for_each(|(entity, sender, mut last_tick)| {
+        .for_each(|(entity, peer, sender, mut last_tick)| {
             if sender.0.is_full() {
                 if sender.0.is_closed() {
                     commands.command_scope(|mut commands| commands.entity(entity).despawn());
@@ -138,7 +155,11 @@ pub fn send_object_poses(
             }
             last_tick.0 = now;
 
-            match sender.0.try_send(outgoing.clone()) {
+            // A batch may come out empty; it still goes, so the streams of
+            // documents the peer no longer sees are closed.
+            let batch = batch_for(&interest::audience(peer.0), &outgoing, settings.radius);
+
+            match sender.0.try_send(batch) {
                 Ok(()) | Err(TrySendError::Full(_)) => {}
                 Err(TrySendError::Closed(_)) => {
                     commands.command_scope(|mut commands| commands.entity(entity).despawn());
@@ -147,6 +168,29 @@ pub fn send_object_poses(
         });
 }
 
+/// The objects anyone in `audience` can see, with those past everyone's
+/// `radius` marked far.
+fn batch_for(
+    audience: &[Option<Interest>],
+    outgoing: &[OutgoingObject],
+    radius: f32,
+) -> Vec<OutgoingObject> {
+    outgoing
+        .iter()
+        .filter_map(|obj| {
+            match interest::relevance_to_any(audience, ns(obj.space), obj.root.translation, radius)
+            {
+                Relevance::Near(_) => Some(obj.clone()),
+                Relevance::Far => Some(OutgoingObject {
+                    far: true,
+                    ..obj.clone()
+                }),
+                Relevance::Hidden => None,
+            }
+        })
+        .collect()
+}
+
 pub struct ResolvedObject {
     pub doc:   DocId,
     pub space: DocId,
@@ -0,0 +1,65 @@
+use async_channel::TrySendError;
+use bevy::prelude::*;
+use bytes::Bytes;
+use iroh_docs::NamespaceId;
+use unavi_input::action::ActionState;
+use unavi_policy::space::Space;
+
+use crate::{
+    anchor::ActiveSpace,
+    voice::{
+        VoiceSettings,
+        capture::Microphone,
+    },
+};
+
+#[derive(Clone)]
+pub struct OutgoingVoice {
+    pub space:  NamespaceId,
+    pub packet: Bytes,
+}
+
+#[derive(Component)]
+pub struct VoiceSender(pub async_channel::Sender<OutgoingVoice>);
+
+/// Encodes whatever the microphone caught since last frame and hands it to
+/// every peer's voice stream.
+pub fn send_voice(
+    settings: Res<VoiceSettings>,
+    input: Res<ActionState>,
+    active: Res<ActiveSpace>,
+    microphone: Option<ResMut<Microphone>>,
+    spaces: Query<&Space>,
+    senders: Query<(Entity, &VoiceSender)>,
+    mut commands: Commands,
+) {
+    let Some(mut microphone) = microphone else {
+        return;
+    };
+    let space = active.0.and_then(|e| spaces.get(e).ok());
+
+    // Read even when silent, so nothing stale goes out once talking starts.
+    let packets = match microphone.take_packets(space.is_some() && settings.transmitting(&input)) {
+        Ok(packets) => packets,
+        Err(err) => {
+            warn!(?err, "Failed to encode voice");
+            return;
+        }
+    };
+    let Some(space) = space else {
+        return;
+    };
+
+    for packet in packets {
+        let outgoing = OutgoingVoice {
+            space:  space.0,
+            packet: Bytes::from(packet),
+        };
+        for (entity, sender) in &senders {
+            match sender.0.try_send(outgoing.clone()) {
+                Ok(()) | Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Closed(_)) => commands.entity(entity).despawn(),
+            }
+        }
+    }
+}
@@ -25,12 +25,16 @@ use unavi_util::async_task::spawn_async_task;
 
 use crate::{
     connection::ecs::PeerStream,
-    peer::Peer,
+    peer::{
+        ActiveSpaces,
+        Peer,
+    },
 };
 
 pub mod ecs;
 mod inbound;
 mod outbound;
+pub mod relay;
 mod shared;
 mod types;
 
@@ -73,14 +77,28 @@ fn release_connection(peer: EndpointId, token: u64) -> bool {
     }
 }
 
-/// Drops the live connection to `peer`, if there is one.
+/// Drops the live connection to `peer`, if there is one, and turns away
+/// whatever of theirs comes through a relay.
 pub fn disconnect(peer: EndpointId) {
     // Dropping the cancel sender is what the connection task waits on; the
     // recv stream ending despawns the peer's `RemotePeer` entity.
     CONNECTIONS.lock().expect("connections lock").remove(&peer);
+    // Keyed to the DID so the refusal ends with the block. Every caller has
+    // judged a proven DID first, so there is always one here.
+    if let Some(did) = identity::did_of(*peer.as_bytes()) {
+        relay::refuse(peer, did);
+    }
     identity::unbind(*peer.as_bytes());
 }
 
+/// Whether there is a live connection to `peer`.
+fn is_connected(peer: EndpointId) -> bool {
+    CONNECTIONS
+        .lock()
+        .expect("connections lock")
+        .contains_key(&peer)
+}
+
 /// Whether a peer is already known to be blocked before a word is exchanged.
 ///
 /// Only answers for a peer whose DID is still bound from an earlier connection;
@@ -91,7 +109,9 @@ pub fn is_blocked(peer: EndpointId) -> bool {
     unavi_policy::trust::of_peer(*peer.as_bytes()) == unavi_policy::trust::Trust::Blocked
 }
 
-pub const ALPN: &[u8] = b"wired/space/1";
+/// Bumped whenever a stream's encoding changes. Version 2 numbers each agent
+/// and object frame, and has their receivers answer with feedback.
+pub const ALPN: &[u8] = b"wired/space/2";
 
 pub fn register_protocol(
     trigger: On<Add, IrohEndpoint>,
@@ -111,7 +131,7 @@ pub fn register_protocol(
 
 pub fn connect_to_peer(
     trigger: On<Add, Peer>,
-    peers: Query<&Peer>,
+    peers: Query<(&Peer, &ActiveSpaces)>,
     endpoint: Query<&IrohEndpoint>,
 ) {
     let Ok(endpoint) = endpoint.single().map(|e| e.0.clone()) else {
@@ -119,10 +139,14 @@ pub fn connect_to_peer(
         return;
     };
 
-    let peer = peers
-        .get(trigger.entity)
-        .map(|p| p.0.clone())
-        .expect("peer");
+    let (peer, spaces) = peers.get(trigger.entity).expect("peer");
+
+    // Whoever shares a relayed space is heard through its relay.
+    if spaces.0.keys().any(relay::serves) {
+        debug!("Reaching {} through a relay", peer.0.id);
+        return;
+    }
+    let peer = peer.0.clone();
 
     // Dialing one side only would break one-directional discovery;
     // [`claim_connection`] resolves simultaneous opens.
@@ -0,0 +1,465 @@
+//! Space relays: one upstream connection per space instead of one per peer.
+//!
+//! A space whose document names a relay is joined through it. Every stream
+//! that would go to each peer goes to the relay once, and it hands each on to
+//! the rest of the room; streams from the others come back tagged with who
+//! they are from, and are received as if that peer had opened them directly.
+//! Peers in a relayed space are not dialed, for as long as the relay session
+//! lasts. When it ends, the space falls back to the mesh.
+//!
+//! The relay copies every stream to the whole room, so what goes up is paced
+//! for whichever member is most interested in it, by the interest each
+//! advertises through the relay: receivers' pacing feedback is not relayed.
+//!
+//! The relay reads only framing, but it is trusted for attribution: it tags
+//! each stream with who it came from, and nothing here can check the tag. A
+//! peer's DID is still proven over a stream the relay passes through unread,
+//! which is enough to block them by, but a DID proven this way never raises
+//! their trust.
+
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    mem,
+    sync::{
+        Arc,
+        LazyLock,
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use bevy::prelude::*;
+use bevy_hsd::attributes::relay::RelayData;
+use bevy_iroh::endpoint::IrohEndpoint;
+use iroh::{
+    Endpoint,
+    EndpointId,
+    endpoint::{
+        Connection,
+        RecvStream,
+        SendStream,
+        VarInt,
+    },
+};
+use iroh_docs::NamespaceId;
+use n0_future::task::{
+    AbortOnDropHandle,
+    JoinSet,
+};
+use parking_lot::{
+    Mutex,
+    RwLock,
+};
+use tokio::{
+    io::AsyncReadExt,
+    sync::{
+        oneshot,
+        watch,
+    },
+};
+use tracing::{
+    Instrument,
+    error,
+    info_span,
+};
+use unavi_policy::{
+    identity,
+    space::Space,
+    trust::{
+        self,
+        Trust,
+    },
+};
+use unavi_relay::wire::{
+    self,
+    Join,
+    RelayEvent,
+};
+use unavi_util::{
+    async_task::spawn_async_task,
+    hierarchy::ancestors,
+};
+use xdid::core::did::Did;
+
+use crate::{
+    connection::shared::{
+        self,
+        StreamIdent,
+        identity::verify_peer_identity,
+    },
+    peer::{
+        ActiveSpaces,
+        Peer,
+    },
+};
+
+const MAX_BACKOFF: Duration = Duration::from_mins(5);
+
+struct Slot {
+    relay:   EndpointId,
+    /// Dropping it ends the session.
+    _cancel: oneshot::Sender<()>,
+}
+
+/// The session kept for each space naming a relay.
+static SESSIONS: LazyLock<Mutex<HashMap<NamespaceId, Slot>>> = LazyLock::new(Mutex::default);
+
+/// Spaces whose relay session is joined.
+static SERVED: LazyLock<RwLock<HashSet<NamespaceId>>> = LazyLock::new(RwLock::default);
+
+/// Spaces that gained or lost their relay since peers were last reset.
+static CHANGED: LazyLock<Mutex<Vec<NamespaceId>>> = LazyLock::new(Mutex::default);
+
+/// Peers refused, with the DID they were refused as. A relay cannot drop one
+/// peer the way a connection can, so their streams are turned away on arrival
+/// instead, for as long as that DID stays blocked.
+static REFUSED: LazyLock<watch::Sender<HashMap<EndpointId, Did>>> =
+    LazyLock::new(|| watch::Sender::new(HashMap::new()));
+
+/// Who each relay's rooms hold, by relay, each counted once per interest
+/// stream they have open through it. A member that stops advertising has left.
+static ROOMS: LazyLock<RwLock<HashMap<EndpointId, HashMap<EndpointId, usize>>>> =
+    LazyLock::new(RwLock::default);
+
+/// Everyone a stream to `relay` reaches, or `None` if it is not a relay with
+/// anyone advertising through it.
+pub(crate) fn room(relay: EndpointId) -> Option<Vec<EndpointId>> {
+    ROOMS
+        .read()
+        .get(&relay)
+        .map(|members| members.keys().copied().collect())
+}
+
+/// Counts a member into its relay's room for as long as it is held.
+struct InRoom {
+    relay:  EndpointId,
+    member: EndpointId,
+}
+
+impl InRoom {
+    fn new(relay: EndpointId, member: EndpointId) -> Self {
+        *ROOMS
+            .write()
+            .entry(relay)
+            .or_default()
+            .entry(member)
+            .or_default() += 1;
+        Self { relay, member }
+    }
+}
+
+impl Drop for InRoom {
+    fn drop(&mut self) {
+        let mut rooms = ROOMS.write();
+        let Some(members) = rooms.get_mut(&self.relay) else {
+            return;
+        };
+        if let Some(count) = members.get_mut(&self.member) {
+            *count -= 1;
+            if *count == 0 {
+                members.remove(&self.member);
+            }
+        }
+        if members.is_empty() {
+            rooms.remove(&self.relay);
+        }
+    }
+}
+
+/// Whether `space` is currently reached through its relay.
+pub fn serves(space: &NamespaceId) -> bool {
+    SERVED.read().contains(space)
+}
+
+/// Turns away `peer`'s relayed streams, open and future, until `did` is no
+/// longer blocked.
+pub fn refuse(peer: EndpointId, did: Did) {
+    REFUSED.send_modify(|refused| {
+        refused.insert(peer, did);
+    });
+}
+
+/// Whether `peer` is still refused. A block is lifted in the trust table,
+/// which knows nothing of relays, so the refusal is forgotten here instead, the
+/// next time the peer is heard from.
+fn is_refused(peer: EndpointId) -> bool {
+    let mut blocked = false;
+    REFUSED.send_if_modified(|refused| {
+        let Some(did) = refused.get(&peer) else {
+            return false;
+        };
+        blocked = trust::of_did(did) == Trust::Blocked;
+        if !blocked {
+            refused.remove(&peer);
+        }
+        !blocked
+    });
+    blocked
+}
+
+/// Marks a space served while its session is joined.
+struct Served(NamespaceId);
+
+impl Served {
+    fn new(space: NamespaceId) -> Self {
+        SERVED.write().insert(space);
+        CHANGED.lock().push(space);
+        Self(space)
+    }
+}
+
+impl Drop for Served {
+    fn drop(&mut self) {
+        SERVED.write().remove(&self.0);
+        CHANGED.lock().push(self.0);
+    }
+}
+
+/// Keeps one relay session per loaded space whose document names a relay,
+/// and has peers in any space that gained or lost one dialed afresh.
+pub fn follow_space_relays(
+    spaces: Query<&Space>,
+    relays: Query<(Entity, &RelayData)>,
+    parents: Query<&ChildOf>,
+    peers: Query<(Entity, &ActiveSpaces), With<Peer>>,
+    endpoint: Query<&IrohEndpoint>,
+    mut commands: Commands,
+) {
+    let mut named = HashMap::new();
+    for (entity, relay) in relays {
+        let Some(space) = ancestors(entity, &parents).find_map(|e| spaces.get(e).ok()) else {
+            continue;
+        };
+        match EndpointId::from_bytes(&relay.0.endpoint) {
+            Ok(relay) => {
+                named.insert(space.0, relay);
+            }
+            Err(err) => warn!(?err, "Space names an invalid relay"),
+        }
+    }
+
+    let mut sessions = SESSIONS.lock();
+    sessions.retain(|space, slot| named.get(space) == Some(&slot.relay));
+    if let Ok(endpoint) = endpoint.single() {
+        for (space, relay) in named {
+            if sessions.contains_key(&space) {
+                continue;
+            }
+            let (cancel_tx, cancel_rx) = oneshot::channel();
+            sessions.insert(
+                space,
+                Slot {
+                    relay,
+                    _cancel: cancel_tx,
+                },
+            );
+            let endpoint = endpoint.0.clone();
+            let span = info_span!("relay", %relay);
+            spawn_async_task(run_session(endpoint, space, relay, cancel_rx).instrument(span));
+        }
+    }
+    drop(sessions);
+
+    // Despawned peers are respawned by their next presence, and
+    // `connect_to_peer` decides again whether to dial them.
+    let changed = mem::take(&mut *CHANGED.lock());
+    if changed.is_empty() {
+        return;
+    }
+    for (entity, active) in peers {
+        if changed.iter().any(|space| active.0.contains_key(space)) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+async fn run_session(
+    endpoint: Endpoint,
+    space: NamespaceId,
+    relay: EndpointId,
+    cancel: oneshot::Receiver<()>,
+) {
+    let retry = async {
+        let mut delay = Duration::from_secs(2);
+        loop {
+            if let Err(err) = session(&endpoint, space, relay).await {
+                error!(?err, "Relay session ended");
+            }
+            n0_future::time::sleep(delay).await;
+            delay = delay.saturating_mul(2).min(MAX_BACKOFF);
+        }
+    };
+
+    tokio::select! {
+        _ = cancel => {},
+        _ = retry => {},
+    }
+}
+
+async fn session(endpoint: &Endpoint, space: NamespaceId, relay: EndpointId) -> anyhow::Result<()> {
+    let connection = Arc::new(endpoint.connect(relay, unavi_relay::ALPN).await?);
+
+    let (mut ctl_tx, mut ctl_rx) = connection.open_bi().await.context("open control")?;
+    wire::write_msg(
+        &mut ctl_tx,
+        &Join {
+            space: *space.as_bytes(),
+        },
+    )
+    .await?;
+    let _served = Served::new(space);
+
+    let (heard_tx, heard_rx) = async_channel::unbounded();
+    let mut send = send_data(&connection);
+    let mut identities = HashMap::new();
+
+    // Each runs for the whole session: a control message is never left half
+    // read by another branch finishing first.
+    let control = async {
+        while let Some(event) = wire::read_msg::<RelayEvent, _>(&mut ctl_rx).await? {
+            match event {
+                // Reopening starts every stream from the top for whoever just
+                // joined, or whoever fell behind.
+                RelayEvent::Resync => drop(mem::replace(&mut send, send_data(&connection))),
+            }
+        }
+        anyhow::Ok(())
+    };
+    let identify = async {
+        while let Ok(origin) = heard_rx.recv().await {
+            identities.entry(origin).or_insert_with(|| {
+                let connection = Arc::clone(&connection);
+                AbortOnDropHandle::new(n0_future::task::spawn(async move {
+                    verify_peer_identity(&connection, origin).await;
+                }))
+            });
+        }
+    };
+
+    let res = tokio::select! {
+        res = control => res,
+        res = recv_relayed(Arc::clone(&connection), heard_tx) => res,
+        () = identify => Ok(()),
+    };
+
+    connection.close(VarInt::from_u32(0), b"done");
+    for origin in identities.into_keys() {
+        if !super::is_connected(origin) {
+            identity::unbind(*origin.as_bytes());
+        }
+    }
+    res
+}
+
+fn send_data(connection: &Arc<Connection>) -> AbortOnDropHandle<()> {
+    let connection = Arc::clone(connection);
+    AbortOnDropHandle::new(n0_future::task::spawn(async move {
+        if let Err(err) = shared::send_data_streams(connection).await {
+            error!(?err, "Relay send error");
+        }
+    }))
+}
+
+async fn recv_relayed(
+    connection: Arc<Connection>,
+    heard: async_channel::Sender<EndpointId>,
+) -> anyhow::Result<()> {
+    let relay = connection.remote_id();
+    let mut i = 0;
+    // Aborted on drop, and reaped as they finish so a long session does not
+    // keep one handle for every stream it ever heard.
+    let mut streams = JoinSet::new();
+
+    loop {
+        let accepted = tokio::select! {
+            accepted = connection.accept_bi() => accepted,
+            Some(_) = streams.join_next() => continue,
+        };
+        let (tx, rx) = match accepted {
+            Ok(pair) => pair,
+            Err(err) if shared::is_graceful_close(&err) => return Ok(()),
+            Err(err) => return Err(err).context("accept_bi"),
+        };
+
+        let span = info_span!("stream", i);
+        i += 1;
+
+        let heard = heard.clone();
+        streams.spawn(
+            async move {
+                if let Err(err) = recv_relayed_stream(relay, &heard, tx, rx).await {
+                    error!(?err);
+                }
+            }
+            .instrument(span),
+        );
+    }
+}
+
+async fn recv_relayed_stream(
+    relay: EndpointId,
+    heard: &async_channel::Sender<EndpointId>,
+    tx: SendStream,
+    mut rx: RecvStream,
+) -> anyhow::Result<()> {
+    let ident = StreamIdent::read(&mut rx).await.context("read ident")?;
+    let mut origin = [0; 32];
+    rx.read_exact(&mut origin).await.context("read origin")?;
+    let origin = EndpointId::from_bytes(&origin)?;
+
+    // A peer also connected directly is already heard over that connection.
+    if super::is_connected(origin) || super::is_blocked(origin) || is_refused(origin) {
+        return Ok(());
+    }
+    let _ = heard.try_send(origin);
+    let _in_room = matches!(ident, StreamIdent::Interest).then(|| InRoom::new(relay, origin));
+
+    let mut refused = REFUSED.subscribe();
+    tokio::select! {
+        res = shared::dispatch(origin, ident, tx, rx) => res,
+        _ = refused.wait_for(|refused| refused.contains_key(&origin)) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unavi_relay::wire::{
+        StreamKind,
+        stream_kind,
+    };
+
+    use super::*;
+
+    fn ident(ident: &StreamIdent) -> u8 {
+        postcard::to_allocvec(ident).expect("serialize")[0]
+    }
+
+    #[test]
+    fn the_relay_carries_this_protocol_version() {
+        assert_eq!(unavi_relay::CARRIES, crate::connection::ALPN);
+    }
+
+    #[test]
+    fn the_relay_frames_each_stream_as_it_is_written() {
+        assert_eq!(
+            stream_kind(ident(&StreamIdent::Identity)),
+            Some(StreamKind::Directed)
+        );
+        for known in [
+            StreamIdent::Agent,
+            StreamIdent::Object,
+            StreamIdent::State,
+            StreamIdent::Voice,
+            StreamIdent::Interest,
+        ] {
+            assert!(
+                matches!(stream_kind(ident(&known)), Some(StreamKind::FanOut(_))),
+                "{known:?} is not relayed"
+            );
+        }
+        assert_eq!(stream_kind(ident(&StreamIdent::Unknown(0))), None);
+    }
+}
@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context;
 use bevy::{
@@ -41,7 +44,14 @@ use crate::connection::{
             },
         },
     },
-    shared::StreamIdent,
+    shared::{
+        StreamIdent,
+        feedback::{
+            self,
+            Pacing,
+            Reporter,
+        },
+    },
     types::{
         IFrame,
         PFrame,
@@ -67,27 +77,52 @@ enum AgentMsg {
     },
 }
 
+/// A message as written, numbered so the receiver can tell what it missed.
+#[derive(Serialize, Deserialize, MaxSize)]
+struct AgentFrame {
+    seq: u16,
+    msg: AgentMsg,
+}
+
+const _: () = assert!(AgentFrame::POSTCARD_MAX_SIZE <= u8::MAX as usize);
+
 const IFRAME_FREQ: Duration = Duration::from_secs(5);
 
 pub async fn send_agent_stream(connection: &Connection) -> anyhow::Result<()> {
-    let (mut tx, _rx) = connection.open_bi().await?;
+    let (mut tx, rx) = connection.open_bi().await?;
     StreamIdent::Agent.write(&mut tx).await?;
 
-    // TODO Read `rx` for tickrate backpressure
-
     let (pose_tx, pose_rx) = async_channel::bounded::<OutgoingPose>(1);
+    let pacing = Arc::new(Pacing::new(IFRAME_FREQ));
 
     AsyncCommands::default()
-        .spawn((PeerStream(connection.remote_id()), AgentSender(pose_tx)))
+        .spawn((
+            PeerStream(connection.remote_id()),
+            AgentSender(pose_tx, Arc::clone(&pacing)),
+        ))
         .send()
         .await?;
 
+    // The receiver's feedback paces the poses the ECS hands over, and their
+    // i-frames here.
+    tokio::select! {
+        res = send_poses(&mut tx, &pose_rx, &pacing) => res,
+        Err(err) = feedback::read_feedback(rx, &pacing, IFRAME_FREQ) => Err(err),
+    }
+}
+
+async fn send_poses(
+    tx: &mut SendStream,
+    pose_rx: &async_channel::Receiver<OutgoingPose>,
+    pacing: &Pacing,
+) -> anyhow::Result<()> {
+    let mut seq = 0u16;
     let mut iframe_id = 0;
     let mut last_iframe = Pose::default();
     let mut last_iframe_time: Option<Instant> = None;
     let mut last_space = NamespaceId::from(&[0; 32]);
 
-    let mut buf = [0; AgentMsg::POSTCARD_MAX_SIZE];
+    let mut buf = [0; AgentFrame::POSTCARD_MAX_SIZE];
 
     while let Ok(OutgoingPose { space, pose }) = pose_rx.recv().await {
         let now = Instant::now();
@@ -96,7 +131,7 @@ pub async fn send_agent_stream(connection: &Connection) -> anyhow::Result<()> {
         // A p-frame delta is only valid against an i-frame in the same space, so
         // a space change forces a fresh i-frame.
         let new_iframe = last_iframe_time
-            .is_none_or(|last| now.duration_since(last) >= IFRAME_FREQ)
+            .is_none_or(|last| now.duration_since(last) >= pacing.iframe_freq())
             || space != last_space;
 
         let msg = if new_iframe {
@@ -117,10 +152,11 @@ pub async fn send_agent_stream(connection: &Connection) -> anyhow::Result<()> {
             }
         };
 
-        let out = postcard::to_slice(&msg, &mut buf)?;
+        let out = postcard::to_slice(&AgentFrame { seq, msg }, &mut buf)?;
         let len = out.len();
         tx.write_u8(u8::try_from(len).expect("max size")).await?;
         tx.write_all(out).await?;
+        seq = seq.wrapping_add(1);
     }
 
     Ok(())
@@ -208,11 +244,12 @@ fn resolve_msg(msg: AgentMsg, baseline: &mut Option<Baseline>) -> Option<Resolve
 
 pub async fn recv_agent_stream(
     peer: EndpointId,
-    _tx: SendStream,
+    mut tx: SendStream,
     mut rx: RecvStream,
 ) -> anyhow::Result<()> {
-    let mut buf = [0; AgentMsg::POSTCARD_MAX_SIZE];
+    let mut buf = [0; AgentFrame::POSTCARD_MAX_SIZE];
     let mut baseline: Option<Baseline> = None;
+    let mut reporter = Reporter::default();
 
     loop {
         let len = match rx.read_u8().await {
@@ -225,11 +262,14 @@ pub async fn recv_agent_stream(
         }
         let buf = &mut buf[..len];
         rx.read_exact(buf).await?;
-        let msg = postcard::from_bytes::<AgentMsg>(buf)?;
+        let AgentFrame { seq, msg } = postcard::from_bytes(buf)?;
 
-        if let Some(resolved) = resolve_msg(msg, &mut baseline) {
+        let resolved = resolve_msg(msg, &mut baseline);
+        reporter.record(seq, resolved.is_some());
+        if let Some(resolved) = resolved {
             submit_pose(peer, resolved);
         }
+        reporter.report(&mut tx).await;
     }
 }
 
@@ -0,0 +1,297 @@
+//! Receiver feedback on pose streams.
+//!
+//! Agent and object streams are bidirectional, and their receivers write back
+//! on the otherwise unused half: how often they can use an update, and how
+//! many of the last window's frames they lost. A sender paces that stream by
+//! it, and shortens its i-frame interval as loss grows, so a receiver that
+//! missed an i-frame is not left with unusable p-frames for long.
+//!
+//! A receiver that never writes back, as a relay does not, is sent to at the
+//! sender's own pace.
+
+use std::{
+    sync::atomic::{
+        AtomicU32,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use anyhow::{
+    Context,
+    bail,
+};
+use iroh::endpoint::{
+    RecvStream,
+    SendStream,
+};
+use postcard::experimental::max_size::MaxSize;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::io::{
+    AsyncReadExt,
+    AsyncWriteExt,
+};
+use tracing::debug;
+use web_time::Instant;
+
+/// How often a receiver reports.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest interval a receiver may ask for.
+const MIN_INTERVAL: Duration = Duration::from_millis(20);
+/// Longest interval a receiver may ask for, past which an avatar looks frozen
+/// rather than slow.
+const MAX_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Shortest i-frame interval, reached at total loss.
+pub const MIN_IFRAME_FREQ: Duration = Duration::from_secs(1);
+
+/// What a receiver asks of a sender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, MaxSize)]
+pub struct Feedback {
+    /// Shortest gap between updates worth sending, in milliseconds.
+    pub interval_ms: u16,
+    /// Share of the last window's frames lost or unusable, out of 255.
+    pub loss:        u8,
+}
+
+/// How a sender paces one stream, from the receiver's last [`Feedback`].
+/// Shared between the stream's task and whatever schedules its updates.
+#[derive(Debug)]
+pub struct Pacing {
+    interval_ms: AtomicU32,
+    iframe_ms:   AtomicU32,
+}
+
+impl Pacing {
+    pub const fn new(iframe_freq: Duration) -> Self {
+        Self {
+            interval_ms: AtomicU32::new(0),
+            iframe_ms:   AtomicU32::new(iframe_freq.as_millis() as u32),
+        }
+    }
+
+    /// Shortest gap between updates the receiver can use.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed).into())
+    }
+
+    /// How often to send an i-frame.
+    pub fn iframe_freq(&self) -> Duration {
+        Duration::from_millis(self.iframe_ms.load(Ordering::Relaxed).into())
+    }
+
+    fn apply(&self, feedback: Feedback, iframe_freq: Duration) {
+        let (interval, iframe) = paced(feedback, iframe_freq);
+        self.interval_ms
+            .store(interval.as_millis() as u32, Ordering::Relaxed);
+        self.iframe_ms
+            .store(iframe.as_millis() as u32, Ordering::Relaxed);
+    }
+}
+
+/// The update interval and i-frame interval a report calls for. Loss slows
+/// updates down, on the guess that the link is what dropped them, and brings
+/// i-frames closer together so whatever was lost is recovered sooner.
+fn paced(feedback: Feedback, iframe_freq: Duration) -> (Duration, Duration) {
+    let loss = u32::from(feedback.loss);
+    let scale = |d: Duration, num: u32| d * num / u32::from(u8::MAX);
+
+    let interval =
+        Duration::from_millis(feedback.interval_ms.into()).clamp(MIN_INTERVAL, MAX_INTERVAL);
+    let interval = scale(interval, u32::from(u8::MAX) + loss).min(MAX_INTERVAL);
+    let iframe = scale(iframe_freq, u32::from(u8::MAX) - loss).max(MIN_IFRAME_FREQ);
+    (interval, iframe)
+}
+
+/// Applies a receiver's reports to `pacing` as they come.
+///
+/// A receiver that closes its half without a word is one that does not
+/// report, and leaves the pacing as it was.
+pub async fn read_feedback(
+    mut rx: RecvStream,
+    pacing: &Pacing,
+    iframe_freq: Duration,
+) -> anyhow::Result<()> {
+    let mut buf = [0; Feedback::POSTCARD_MAX_SIZE];
+
+    loop {
+        let len = match rx.read_u8().await {
+            Ok(len) => usize::from(len),
+            Err(err) if super::read_disconnected(&err) => return Ok(()),
+            Err(err) => return Err(err).context("read len"),
+        };
+        if len > buf.len() {
+            bail!("feedback length {len} exceeds max {}", buf.len());
+        }
+        let buf = &mut buf[..len];
+        rx.read_exact(buf).await.context("read feedback")?;
+        pacing.apply(postcard::from_bytes(buf)?, iframe_freq);
+    }
+}
+
+/// This client's frame time, in microseconds: how often it can show an update
+/// at all.
+static FRAME_BUDGET_US: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_frame_budget(budget: Duration) {
+    FRAME_BUDGET_US.store(
+        u32::try_from(budget.as_micros()).unwrap_or(u32::MAX),
+        Ordering::Relaxed,
+    );
+}
+
+fn frame_budget() -> Duration {
+    Duration::from_micros(FRAME_BUDGET_US.load(Ordering::Relaxed).into())
+}
+
+/// Counts what a receiver got against what was sent, by sequence number, and
+/// reports it back every [`REPORT_INTERVAL`].
+pub struct Reporter {
+    next:        Option<u16>,
+    received:    u32,
+    lost:        u32,
+    last_report: Instant,
+    /// Set once a report fails to go out, as when the sender is a relay that
+    /// never listens; the stream itself carries on.
+    stopped:     bool,
+}
+
+impl Default for Reporter {
+    fn default() -> Self {
+        Self {
+            next:        None,
+            received:    0,
+            lost:        0,
+            last_report: Instant::now(),
+            stopped:     false,
+        }
+    }
+}
+
+impl Reporter {
+    /// Records frame `seq`, and whether it could be used. Frames skipped
+    /// since the last one count as lost.
+    pub const fn record(&mut self, seq: u16, usable: bool) {
+        if let Some(next) = self.next {
+            let gap = seq.wrapping_sub(next);
+            // Anything further reads as the sender restarting its count.
+            if gap < u16::MAX / 2 {
+                self.lost += gap as u32;
+            }
+        }
+        self.next = Some(seq.wrapping_add(1));
+        if usable {
+            self.received += 1;
+        } else {
+            self.lost += 1;
+        }
+    }
+
+    fn take(&mut self) -> Feedback {
+        let total = self.received + self.lost;
+        let loss = if total == 0 {
+            0
+        } else {
+            (u64::from(self.lost) * u64::from(u8::MAX) / u64::from(total)) as u8
+        };
+        self.received = 0;
+        self.lost = 0;
+
+        Feedback {
+            interval_ms: u16::try_from(frame_budget().as_millis()).unwrap_or(u16::MAX),
+            loss,
+        }
+    }
+
+    /// Writes a report if one is due.
+    pub async fn report(&mut self, tx: &mut SendStream) {
+        if self.stopped || self.last_report.elapsed() < REPORT_INTERVAL {
+            return;
+        }
+        self.last_report = Instant::now();
+
+        if let Err(err) = write_feedback(tx, self.take()).await {
+            debug!(?err, "Sender stopped listening for feedback");
+            self.stopped = true;
+        }
+    }
+}
+
+async fn write_feedback(tx: &mut SendStream, feedback: Feedback) -> anyhow::Result<()> {
+    let mut buf = [0; Feedback::POSTCARD_MAX_SIZE];
+    let out = postcard::to_slice(&feedback, &mut buf)?;
+    tx.write_u8(u8::try_from(out.len())?).await?;
+    tx.write_all(out).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IFRAME_FREQ: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn gaps_and_unusable_frames_count_as_loss() {
+        let mut reporter = Reporter::default();
+        reporter.record(10, true);
+        reporter.record(11, false);
+        // 12 and 13 never came.
+        reporter.record(14, true);
+
+        assert_eq!(reporter.take().loss, (3 * 255 / 5) as u8);
+        assert_eq!(reporter.take().loss, 0);
+    }
+
+    #[test]
+    fn a_restarted_count_is_not_loss() {
+        let mut reporter = Reporter::default();
+        reporter.record(500, true);
+        reporter.record(0, true);
+        assert_eq!(reporter.take().loss, 0);
+
+        reporter.record(u16::MAX, true);
+        reporter.record(0, true);
+        assert_eq!(reporter.take().loss, 0);
+    }
+
+    #[test]
+    fn loss_slows_updates_and_hastens_iframes() {
+        let clean = Feedback {
+            interval_ms: 100,
+            loss:        0,
+        };
+        assert_eq!(
+            paced(clean, IFRAME_FREQ),
+            (Duration::from_millis(100), IFRAME_FREQ)
+        );
+
+        let (interval, iframe) = paced(Feedback { loss: 128, ..clean }, IFRAME_FREQ);
+        assert!(interval > Duration::from_millis(140));
+        assert!(iframe < Duration::from_secs(3));
+
+        let (interval, iframe) = paced(Feedback { loss: 255, ..clean }, IFRAME_FREQ);
+        assert!(interval <= MAX_INTERVAL);
+        assert_eq!(iframe, MIN_IFRAME_FREQ);
+    }
+
+    #[test]
+    fn requested_intervals_are_bounded() {
+        let ask = |interval_ms| {
+            paced(
+                Feedback {
+                    interval_ms,
+                    loss: 0,
+                },
+                IFRAME_FREQ,
+            )
+            .0
+        };
+        assert_eq!(ask(0), MIN_INTERVAL);
+        assert_eq!(ask(u16::MAX), MAX_INTERVAL);
+    }
+}
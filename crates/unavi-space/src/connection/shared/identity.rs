@@ -50,11 +50,11 @@ const PROOF_TIMEOUT: Duration = Duration::from_secs(15);
 
 type Nonce = [u8; 32];
 
-/// A peer's claim to a DID, bound to the one connection it was made on.
+/// A peer's claim to a DID, bound to the pair of endpoints it was made between.
 ///
 /// Both endpoints are covered by the signature: `prover` so a proof collected
 /// from one peer cannot be presented as another peer's, and `verifier` so a
-/// proof cannot be relayed onward to a third party. The nonce is minted per
+/// proof cannot be replayed onward to a third party. The nonce is minted per
 /// stream and answered once, so nothing here is replayable.
 #[derive(Debug, Serialize, Deserialize)]
 struct PeerBinding {
@@ -74,13 +74,20 @@ impl Signable for PeerBinding {
 /// Retried a few times because a peer whose own identity is still loading has
 /// nothing to answer with yet. A peer that never answers keeps no DID, which is
 /// a state the trust model is required to handle anyway.
-pub async fn verify_peer_identity(connection: &Connection) {
-    let peer = connection.remote_id();
+///
+/// Where `peer` is not the far end of `connection`, the connection is to a
+/// space relay, and the challenge is addressed to `peer` through it. The proof
+/// still shows `peer`'s endpoint holds the DID, but not that what the relay
+/// tags as theirs came from them: the relay is trusted for attribution. Such a
+/// binding is made with [`identity::bind_relayed`], which can see a peer
+/// blocked but never raises them above a guest.
+pub async fn verify_peer_identity(connection: &Connection, peer: EndpointId) {
+    let relayed = peer != connection.remote_id();
 
     for attempt in 1..=PROOF_ATTEMPTS {
         // A peer that accepts the stream and never answers would otherwise
         // hold the attempt open for as long as the connection lives.
-        let proof = n0_future::time::timeout(PROOF_TIMEOUT, request_proof(connection))
+        let proof = n0_future::time::timeout(PROOF_TIMEOUT, request_proof(connection, peer))
             .await
             .unwrap_or_else(|_| Err(anyhow::anyhow!("identity proof timed out")));
 
@@ -89,7 +96,11 @@ pub async fn verify_peer_identity(connection: &Connection) {
                 info!("Peer identified as {did}");
                 // Bound before the block is judged, so a refusal has a DID to
                 // key its teardown to.
-                identity::bind(*peer.as_bytes(), did.clone());
+                if relayed {
+                    identity::bind_relayed(*peer.as_bytes(), did.clone());
+                } else {
+                    identity::bind(*peer.as_bytes(), did.clone());
+                }
                 if crate::trust::enforce_block(peer, &did) {
                     identity::unbind(*peer.as_bytes());
                 }
@@ -104,12 +115,16 @@ pub async fn verify_peer_identity(connection: &Connection) {
     }
 }
 
-async fn request_proof(connection: &Connection) -> anyhow::Result<Did> {
-    let peer = connection.remote_id();
+async fn request_proof(connection: &Connection, peer: EndpointId) -> anyhow::Result<Did> {
     let verifier = self_endpoint_id()?;
 
     let (mut tx, mut rx) = connection.open_bi().await.context("open_bi")?;
     StreamIdent::Identity.write(&mut tx).await?;
+    if peer != connection.remote_id() {
+        tx.write_all(peer.as_bytes())
+            .await
+            .context("write target")?;
+    }
 
     let mut nonce = Nonce::default();
     rand::rng().fill_bytes(&mut nonce);
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use anyhow::{
+    Context,
+    bail,
+};
+use iroh::{
+    EndpointId,
+    endpoint::{
+        Connection,
+        RecvStream,
+        SendStream,
+    },
+};
+use tokio::io::{
+    AsyncReadExt,
+    AsyncWriteExt,
+};
+
+use crate::{
+    connection::shared::StreamIdent,
+    interest::{
+        self,
+        Interest,
+        MAX_INTEREST_SPACES,
+    },
+};
+
+/// Largest encoded advertisement: a count, then per space its id, a tag and
+/// three varint cells.
+const MAX_FRAME: usize = 1 + MAX_INTEREST_SPACES * (32 + 1 + 3 * 3);
+
+/// Shortest gap between advertisements, so someone pacing across a cell edge
+/// does not flood their peers.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The advertisement as written: a big-endian length, then the body.
+fn encode_frame(interest: &Interest) -> anyhow::Result<Vec<u8>> {
+    if interest.spaces.len() > MAX_INTEREST_SPACES {
+        bail!("interest names too many spaces")
+    }
+    let body = postcard::to_allocvec(interest)?;
+    let mut out = Vec::with_capacity(2 + body.len());
+    out.extend_from_slice(&u16::try_from(body.len())?.to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+fn decode_frame(body: &[u8]) -> anyhow::Result<Interest> {
+    let interest = postcard::from_bytes::<Interest>(body)?;
+    if interest.spaces.len() > MAX_INTEREST_SPACES {
+        bail!("interest names too many spaces")
+    }
+    Ok(interest)
+}
+
+pub async fn send_interest_stream(connection: &Connection) -> anyhow::Result<()> {
+    let (mut tx, _rx) = connection.open_bi().await?;
+    StreamIdent::Interest.write(&mut tx).await?;
+
+    let mut local = interest::subscribe();
+
+    loop {
+        let frame = encode_frame(&local.borrow_and_update())?;
+        tx.write_all(&frame).await?;
+
+        n0_future::time::sleep(MIN_INTERVAL).await;
+        if local.changed().await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+pub async fn recv_interest_stream(
+    peer: EndpointId,
+    _tx: SendStream,
+    rx: RecvStream,
+) -> anyhow::Result<()> {
+    let res = recv_interest(peer, rx).await;
+    // Whoever stops saying where they are goes back to getting everything.
+    interest::forget_peer(peer);
+    res
+}
+
+async fn recv_interest(peer: EndpointId, mut rx: RecvStream) -> anyhow::Result<()> {
+    let mut buf = [0; MAX_FRAME];
+
+    loop {
+        let len = match rx.read_u16().await {
+            Ok(len) => usize::from(len),
+            Err(err) if super::read_disconnected(&err) => return Ok(()),
+            Err(err) => return Err(err).context("read len"),
+        };
+        if len > MAX_FRAME {
+            bail!("interest frame length {len} exceeds max {MAX_FRAME}");
+        }
+        let buf = &mut buf[..len];
+        rx.read_exact(buf).await.context("read frame")?;
+        interest::set_peer(peer, decode_frame(buf).context("parse frame")?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interest::SpaceInterest;
+
+    #[test]
+    fn fullest_interest_fits_a_frame() {
+        let interest = Interest {
+            spaces: (0..MAX_INTEREST_SPACES)
+                .map(|_| SpaceInterest {
+                    space: [0xFF; 32],
+                    focus: Some([i16::MIN; 3]),
+                })
+                .collect(),
+        };
+        let wire = encode_frame(&interest).expect("encode");
+
+        let len = usize::from(u16::from_be_bytes([wire[0], wire[1]]));
+        assert_eq!(len, wire.len() - 2);
+        assert!(len <= MAX_FRAME);
+        assert_eq!(decode_frame(&wire[2..]).expect("decode"), interest);
+    }
+
+    #[test]
+    fn too_many_spaces_are_refused() {
+        let interest = Interest {
+            spaces: vec![
+                SpaceInterest {
+                    space: [0; 32],
+                    focus: None,
+                };
+                MAX_INTEREST_SPACES + 1
+            ],
+        };
+        assert!(encode_frame(&interest).is_err());
+
+        let body = postcard::to_allocvec(&interest).expect("serialize");
+        assert!(decode_frame(&body).is_err());
+    }
+}
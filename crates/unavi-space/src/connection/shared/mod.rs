@@ -37,9 +37,12 @@ use tracing::{
 };
 
 mod agent;
-mod identity;
+pub mod feedback;
+pub mod identity;
+mod interest;
 mod object;
 mod state;
+#[cfg(not(target_family = "wasm"))] mod voice;
 
 pub async fn handle_connection(
     connection: Connection,
@@ -125,6 +128,17 @@ async fn recv_streams(connection: Arc<Connection>) -> anyhow::Result<()> {
 
 async fn recv_stream(peer: EndpointId, tx: SendStream, mut rx: RecvStream) -> anyhow::Result<()> {
     let ident = StreamIdent::read(&mut rx).await.context("read ident")?;
+    dispatch(peer, ident, tx, rx).await
+}
+
+/// Hands a stream whose ident has been read to its receiver, as coming from
+/// `peer`.
+pub async fn dispatch(
+    peer: EndpointId,
+    ident: StreamIdent,
+    tx: SendStream,
+    rx: RecvStream,
+) -> anyhow::Result<()> {
     info!("Stream ident: {ident:?}");
 
     match ident {
@@ -132,6 +146,12 @@ async fn recv_stream(peer: EndpointId, tx: SendStream, mut rx: RecvStream) -> an
         StreamIdent::Agent => agent::recv_agent_stream(peer, tx, rx).await?,
         StreamIdent::Object => object::recv_object_stream(peer, tx, rx).await?,
         StreamIdent::State => state::recv_state_stream(peer, tx, rx).await?,
+        StreamIdent::Interest => interest::recv_interest_stream(peer, tx, rx).await?,
+        #[cfg(not(target_family = "wasm"))]
+        StreamIdent::Voice => voice::recv_voice_stream(peer, tx, rx).await?,
+        // There is no Opus to decode with on wasm, so voice goes unheard.
+        #[cfg(target_family = "wasm")]
+        StreamIdent::Voice => {}
         StreamIdent::Unknown(_) => {}
     }
 
@@ -143,13 +163,20 @@ const STREAM_LOOP_DELAY: Duration = Duration::from_secs(1);
 async fn send_streams(connection: Arc<Connection>) -> anyhow::Result<()> {
     let task_identity = {
         let connection = Arc::clone(&connection);
-        let handle =
-            n0_future::task::spawn(
-                async move { identity::verify_peer_identity(&connection).await },
-            );
+        let handle = n0_future::task::spawn(async move {
+            let peer = connection.remote_id();
+            identity::verify_peer_identity(&connection, peer).await;
+        });
         AbortOnDropHandle::new(handle)
     };
 
+    let (_, res) = tokio::join!(task_identity, send_data_streams(connection));
+    res
+}
+
+/// Opens, and keeps reopening, every stream that carries the local peer's
+/// agent, state, objects, interest and voice over `connection`.
+pub async fn send_data_streams(connection: Arc<Connection>) -> anyhow::Result<()> {
     let task_agent = {
         let connection = Arc::clone(&connection);
         let handle = n0_future::task::spawn(async move {
@@ -189,12 +216,47 @@ async fn send_streams(connection: Arc<Connection>) -> anyhow::Result<()> {
         AbortOnDropHandle::new(handle)
     };
 
-    n0_future::join_all([task_identity, task_agent, task_state, task_objects]).await;
+    let task_interest = {
+        let connection = Arc::clone(&connection);
+        let handle = n0_future::task::spawn(async move {
+            loop {
+                if let Err(err) = interest::send_interest_stream(&connection).await {
+                    error!(?err, "Interest stream error");
+                }
+                n0_future::time::sleep(STREAM_LOOP_DELAY).await;
+            }
+        });
+        AbortOnDropHandle::new(handle)
+    };
+
+    #[cfg(not(target_family = "wasm"))]
+    let task_voice = {
+        let connection = Arc::clone(&connection);
+        let handle = n0_future::task::spawn(async move {
+            loop {
+                if let Err(err) = voice::send_voice_stream(&connection).await {
+                    error!(?err, "Voice stream error");
+                }
+                n0_future::time::sleep(STREAM_LOOP_DELAY).await;
+            }
+        });
+        AbortOnDropHandle::new(handle)
+    };
+
+    n0_future::join_all([
+        task_agent,
+        task_state,
+        task_objects,
+        task_interest,
+        #[cfg(not(target_family = "wasm"))]
+        task_voice,
+    ])
+    .await;
 
     Ok(())
 }
 
-const fn is_graceful_close(err: &ConnectionError) -> bool {
+pub const fn is_graceful_close(err: &ConnectionError) -> bool {
     matches!(
         err,
         ConnectionError::ConnectionClosed(_)
@@ -219,16 +281,18 @@ fn read_disconnected(err: &std::io::Error) -> bool {
 
 #[derive(Serialize, Deserialize, Debug)]
 #[non_exhaustive]
-enum StreamIdent {
+pub enum StreamIdent {
     Identity,
     Agent,
     Object,
     State,
+    Voice,
+    Interest,
     Unknown(usize),
 }
 
 impl StreamIdent {
-    async fn read(rx: &mut RecvStream) -> anyhow::Result<Self> {
+    pub async fn read(rx: &mut RecvStream) -> anyhow::Result<Self> {
         let buf = [rx.read_u8().await?];
         let ident = postcard::from_bytes::<Self>(&buf)?;
         Ok(ident)
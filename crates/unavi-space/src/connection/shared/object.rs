@@ -4,6 +4,7 @@ use std::{
         HashSet,
         hash_map::Entry,
     },
+    sync::Arc,
     time::Duration,
 };
 
@@ -21,6 +22,7 @@ use iroh::{
         SendStream,
     },
 };
+use n0_future::task::AbortOnDropHandle;
 use postcard::experimental::max_size::MaxSize;
 use serde::{
     Deserialize,
@@ -30,25 +32,36 @@ use tokio::io::{
     AsyncReadExt,
     AsyncWriteExt,
 };
+use tracing::debug;
 use unavi_util::async_commands::AsyncCommands;
 use web_time::Instant;
 
-use crate::connection::{
-    ecs::{
-        PeerStream,
-        object::{
-            ObjectSender,
-            OutgoingObject,
-            ResolvedObject,
-            submit_object,
+use crate::{
+    connection::{
+        ecs::{
+            PeerStream,
+            object::{
+                ObjectSender,
+                OutgoingObject,
+                ResolvedObject,
+                submit_object,
+            },
+        },
+        shared::{
+            StreamIdent,
+            feedback::{
+                self,
+                Pacing,
+                Reporter,
+            },
+        },
+        types::{
+            f16_vec3::F16Vec3,
+            f32_vec3::F32Vec3,
+            rigid_transform::RigidTransform,
         },
     },
-    shared::StreamIdent,
-    types::{
-        f16_vec3::F16Vec3,
-        f32_vec3::F32Vec3,
-        rigid_transform::RigidTransform,
-    },
+    interest::FAR_TICKRATE,
 };
 
 /// A message on a per-document object stream (doc id is fixed in the header).
@@ -75,6 +88,15 @@ enum ObjectMsg {
     },
 }
 
+/// A message as written, numbered so the receiver can tell what it missed.
+#[derive(Serialize, Deserialize, MaxSize)]
+struct ObjectFrame {
+    seq: u16,
+    msg: ObjectMsg,
+}
+
+const _: () = assert!(ObjectFrame::POSTCARD_MAX_SIZE <= u8::MAX as usize);
+
 const IFRAME_FREQ: Duration = Duration::from_secs(5);
 
 /// Per-prim i-frame baseline tracked by a sender, so its p-frame deltas resolve
@@ -84,6 +106,7 @@ struct SendState {
     last_iframe_root: RigidTransform<F32Vec3>,
     last_iframe_time: Option<Instant>,
     last_space:       DocId,
+    last_sent:        Option<Instant>,
 }
 
 /// One open stream carrying every dynamic prim of a single document. The space
@@ -91,8 +114,12 @@ struct SendState {
 /// rather than per frame.
 struct DocStream {
     tx:         SendStream,
+    seq:        u16,
     last_space: Option<DocId>,
     prims:      HashMap<PrimId, SendState>,
+    /// Paced by the receiver's feedback on this stream, read by `_feedback`.
+    pacing:     Arc<Pacing>,
+    _feedback:  AbortOnDropHandle<()>,
 }
 
 pub async fn send_object_stream(connection: &Connection) -> anyhow::Result<()> {
@@ -104,7 +131,7 @@ pub async fn send_object_stream(connection: &Connection) -> anyhow::Result<()> {
         .await?;
 
     let mut streams: HashMap<DocId, DocStream> = HashMap::new();
-    let mut buf = [0; ObjectMsg::POSTCARD_MAX_SIZE];
+    let mut buf = [0; ObjectFrame::POSTCARD_MAX_SIZE];
 
     while let Ok(objects) = obj_rx.recv().await {
         let now = Instant::now();
@@ -131,6 +158,26 @@ pub async fn send_object_stream(connection: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Whether `obj` should go out now, given the receiver wants updates no more
+/// often than `interval`. A far prim stays in every batch, keeping its stream
+/// open, but is only sent every [`FAR_TICKRATE`].
+fn due(
+    prims: &HashMap<PrimId, SendState>,
+    obj: &OutgoingObject,
+    now: Instant,
+    interval: Duration,
+) -> bool {
+    let interval = if obj.far {
+        interval.max(FAR_TICKRATE)
+    } else {
+        interval
+    };
+    prims
+        .get(&obj.prim)
+        .and_then(|state| state.last_sent)
+        .is_none_or(|last| now.duration_since(last) >= interval)
+}
+
 async fn send_object(
     connection: &Connection,
     streams: &mut HashMap<DocId, DocStream>,
@@ -138,16 +185,37 @@ async fn send_object(
     obj: &OutgoingObject,
     now: Instant,
 ) -> anyhow::Result<()> {
+    if streams
+        .get(&obj.doc)
+        .is_some_and(|stream| !due(&stream.prims, obj, now, stream.pacing.interval()))
+    {
+        return Ok(());
+    }
+
     let stream = match streams.entry(obj.doc) {
         Entry::Occupied(e) => e.into_mut(),
         Entry::Vacant(e) => {
-            let mut tx = connection.open_bi().await?.0;
+            let (mut tx, rx) = connection.open_bi().await?;
             StreamIdent::Object.write(&mut tx).await?;
             tx.write_all(&obj.doc.0).await?;
+
+            let pacing = Arc::new(Pacing::new(IFRAME_FREQ));
+            let feedback = {
+                let pacing = Arc::clone(&pacing);
+                n0_future::task::spawn(async move {
+                    if let Err(err) = feedback::read_feedback(rx, &pacing, IFRAME_FREQ).await {
+                        debug!(?err, "Object feedback error");
+                    }
+                })
+            };
+
             e.insert(DocStream {
                 tx,
+                seq: 0,
                 last_space: None,
                 prims: HashMap::new(),
+                pacing,
+                _feedback: AbortOnDropHandle::new(feedback),
             })
         }
     };
@@ -155,18 +223,26 @@ async fn send_object(
     if stream.last_space != Some(obj.space) {
         stream.last_space = Some(obj.space);
         let msg = ObjectMsg::SpaceChange { space: obj.space.0 };
-        write_frame(&mut stream.tx, &msg, buf).await?;
+        write_frame(stream, msg, buf).await?;
     }
 
-    let msg = build_msg(&mut stream.prims, obj, now);
-    write_frame(&mut stream.tx, &msg, buf).await
+    let msg = build_msg(&mut stream.prims, obj, now, stream.pacing.iframe_freq());
+    write_frame(stream, msg, buf).await
 }
 
-async fn write_frame(tx: &mut SendStream, msg: &ObjectMsg, buf: &mut [u8]) -> anyhow::Result<()> {
-    let out = postcard::to_slice(msg, buf)?;
+async fn write_frame(stream: &mut DocStream, msg: ObjectMsg, buf: &mut [u8]) -> anyhow::Result<()> {
+    let frame = ObjectFrame {
+        seq: stream.seq,
+        msg,
+    };
+    let out = postcard::to_slice(&frame, buf)?;
     let len = out.len();
-    tx.write_u8(u8::try_from(len).expect("max size")).await?;
-    tx.write_all(out).await?;
+    stream
+        .tx
+        .write_u8(u8::try_from(len).expect("max size"))
+        .await?;
+    stream.tx.write_all(out).await?;
+    stream.seq = stream.seq.wrapping_add(1);
     Ok(())
 }
 
@@ -174,6 +250,7 @@ fn build_msg(
     prims: &mut HashMap<PrimId, SendState>,
     obj: &OutgoingObject,
     now: Instant,
+    iframe_freq: Duration,
 ) -> ObjectMsg {
     let root = RigidTransform::<F32Vec3>::from(&obj.root);
     let lin = obj.lin.into();
@@ -184,13 +261,15 @@ fn build_msg(
         last_iframe_root: root.clone(),
         last_iframe_time: None,
         last_space:       DocId([0; 32]),
+        last_sent:        None,
     });
+    state.last_sent = Some(now);
 
     // A p-frame delta is only valid against an i-frame in the same space, so a
     // space change forces a fresh i-frame.
     let new_iframe = state
         .last_iframe_time
-        .is_none_or(|last| now.duration_since(last) >= IFRAME_FREQ)
+        .is_none_or(|last| now.duration_since(last) >= iframe_freq)
         || state.last_space != obj.space;
 
     if new_iframe {
@@ -283,16 +362,17 @@ fn resolve_msg(
 
 pub async fn recv_object_stream(
     peer: EndpointId,
-    _tx: SendStream,
+    mut tx: SendStream,
     mut rx: RecvStream,
 ) -> anyhow::Result<()> {
     let mut doc = [0u8; 32];
     rx.read_exact(&mut doc).await.context("read doc header")?;
     let doc = DocId(doc);
 
-    let mut buf = [0; ObjectMsg::POSTCARD_MAX_SIZE];
+    let mut buf = [0; ObjectFrame::POSTCARD_MAX_SIZE];
     let mut baselines: HashMap<PrimId, Baseline> = HashMap::new();
     let mut current_space: Option<DocId> = None;
+    let mut reporter = Reporter::default();
 
     loop {
         let len = match rx.read_u8().await {
@@ -305,19 +385,25 @@ pub async fn recv_object_stream(
         }
         let buf = &mut buf[..len];
         rx.read_exact(buf).await?;
-        let msg = postcard::from_bytes::<ObjectMsg>(buf)?;
+        let ObjectFrame { seq, msg } = postcard::from_bytes(buf)?;
 
-        match msg {
-            ObjectMsg::SpaceChange { space } => current_space = Some(DocId(space)),
+        let usable = match msg {
+            ObjectMsg::SpaceChange { space } => {
+                current_space = Some(DocId(space));
+                true
+            }
             frame => {
-                let Some(space) = current_space else {
-                    continue;
-                };
-                if let Some(resolved) = resolve_msg(frame, doc, space, &mut baselines) {
+                let resolved =
+                    current_space.and_then(|space| resolve_msg(frame, doc, space, &mut baselines));
+                let usable = resolved.is_some();
+                if let Some(resolved) = resolved {
                     submit_object(peer, resolved);
                 }
+                usable
             }
-        }
+        };
+        reporter.record(seq, usable);
+        reporter.report(&mut tx).await;
     }
 }
 
@@ -343,6 +429,7 @@ mod tests {
             root: Transform::from_translation(t),
             lin: Vec3::new(1.0, 0.0, 0.0),
             ang: Vec3::new(0.0, 2.0, 0.0),
+            far: false,
         }
     }
 
@@ -354,7 +441,12 @@ mod tests {
         let space = h(b"space");
         let pos = Vec3::new(1.0, 2.0, 3.0);
 
-        let msg = build_msg(&mut prims, &outgoing(doc, space, pos), Instant::now());
+        let msg = build_msg(
+            &mut prims,
+            &outgoing(doc, space, pos),
+            Instant::now(),
+            IFRAME_FREQ,
+        );
         assert!(matches!(msg, ObjectMsg::IFrame { .. }));
 
         let resolved = resolve_msg(msg, doc, space, &mut baselines).expect("resolved");
@@ -378,11 +470,12 @@ mod tests {
             &mut prims,
             &outgoing(doc, space, Vec3::new(1.0, 2.0, 3.0)),
             now,
+            IFRAME_FREQ,
         );
         resolve_msg(iframe, doc, space, &mut baselines);
 
         let moved = Vec3::new(1.1, 1.8, 3.05);
-        let pframe = build_msg(&mut prims, &outgoing(doc, space, moved), now);
+        let pframe = build_msg(&mut prims, &outgoing(doc, space, moved), now, IFRAME_FREQ);
         assert!(matches!(pframe, ObjectMsg::PFrame { .. }));
 
         let resolved = resolve_msg(pframe, doc, space, &mut baselines).expect("resolved");
@@ -395,10 +488,20 @@ mod tests {
         let doc = h(b"doc");
         let now = Instant::now();
 
-        let first = build_msg(&mut prims, &outgoing(doc, h(b"a"), Vec3::ZERO), now);
+        let first = build_msg(
+            &mut prims,
+            &outgoing(doc, h(b"a"), Vec3::ZERO),
+            now,
+            IFRAME_FREQ,
+        );
         assert!(matches!(first, ObjectMsg::IFrame { .. }));
 
-        let second = build_msg(&mut prims, &outgoing(doc, h(b"b"), Vec3::ZERO), now);
+        let second = build_msg(
+            &mut prims,
+            &outgoing(doc, h(b"b"), Vec3::ZERO),
+            now,
+            IFRAME_FREQ,
+        );
         assert!(matches!(second, ObjectMsg::IFrame { .. }));
     }
 
@@ -426,6 +529,7 @@ mod tests {
             &mut prims,
             &outgoing(doc, space, Vec3::ZERO),
             Instant::now(),
+            IFRAME_FREQ,
         );
         resolve_msg(iframe, doc, space, &mut baselines);
 
@@ -456,12 +560,66 @@ mod tests {
             ..outgoing(doc, space, Vec3::new(-5.0, 0.0, 0.0))
         };
 
-        let ra =
-            resolve_msg(build_msg(&mut prims, &a, now), doc, space, &mut baselines).expect("a");
-        let rb =
-            resolve_msg(build_msg(&mut prims, &b, now), doc, space, &mut baselines).expect("b");
+        let ra = resolve_msg(
+            build_msg(&mut prims, &a, now, IFRAME_FREQ),
+            doc,
+            space,
+            &mut baselines,
+        )
+        .expect("a");
+        let rb = resolve_msg(
+            build_msg(&mut prims, &b, now, IFRAME_FREQ),
+            doc,
+            space,
+            &mut baselines,
+        )
+        .expect("b");
         assert!((ra.root.translation - Vec3::new(5.0, 0.0, 0.0)).length() < 0.01);
         assert!((rb.root.translation - Vec3::new(-5.0, 0.0, 0.0)).length() < 0.01);
         assert_eq!(baselines.len(), 2);
     }
+
+    #[test]
+    fn far_prims_are_paced() {
+        let mut prims = HashMap::new();
+        let now = Instant::now();
+        let far = OutgoingObject {
+            far: true,
+            ..outgoing(h(b"doc"), h(b"space"), Vec3::ZERO)
+        };
+
+        assert!(due(&prims, &far, now, Duration::ZERO));
+        build_msg(&mut prims, &far, now, IFRAME_FREQ);
+        assert!(!due(&prims, &far, now + FAR_TICKRATE / 2, Duration::ZERO));
+        assert!(due(&prims, &far, now + FAR_TICKRATE, Duration::ZERO));
+
+        let near = OutgoingObject { far: false, ..far };
+        assert!(due(&prims, &near, now, Duration::ZERO));
+    }
+
+    #[test]
+    fn prims_are_paced_by_feedback() {
+        let mut prims = HashMap::new();
+        let now = Instant::now();
+        let near = outgoing(h(b"doc"), h(b"space"), Vec3::ZERO);
+        let interval = Duration::from_millis(200);
+
+        build_msg(&mut prims, &near, now, IFRAME_FREQ);
+        assert!(!due(&prims, &near, now + interval / 2, interval));
+        assert!(due(&prims, &near, now + interval, interval));
+    }
+
+    #[test]
+    fn iframes_follow_the_paced_interval() {
+        let mut prims = HashMap::new();
+        let now = Instant::now();
+        let obj = outgoing(h(b"doc"), h(b"space"), Vec3::ZERO);
+        let freq = Duration::from_secs(1);
+
+        build_msg(&mut prims, &obj, now, freq);
+        let soon = build_msg(&mut prims, &obj, now + freq / 2, freq);
+        assert!(matches!(soon, ObjectMsg::PFrame { .. }));
+        let later = build_msg(&mut prims, &obj, now + freq, freq);
+        assert!(matches!(later, ObjectMsg::IFrame { .. }));
+    }
 }
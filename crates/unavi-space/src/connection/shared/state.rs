@@ -41,10 +41,18 @@ pub async fn send_state_stream(connection: &Connection) -> anyhow::Result<()> {
     StreamIdent::State.write(&mut tx).await?;
 
     let (token, rx) = replicas::register_stream();
-    let res = send_loop(&mut tx, &rx).await;
-    replicas::unregister_stream(token);
+    let _registration = Registration(token);
+    send_loop(&mut tx, &rx).await
+}
 
-    res
+/// Unregisters a state stream however sending it ends, including the task
+/// being aborted with its connection or at a relay resync.
+struct Registration(u64);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        replicas::unregister_stream(self.0);
+    }
 }
 
 async fn send_loop(
@@ -0,0 +1,190 @@
+use anyhow::{
+    Context,
+    bail,
+};
+use iroh::{
+    EndpointId,
+    endpoint::{
+        Connection,
+        RecvStream,
+        SendStream,
+    },
+};
+use iroh_docs::NamespaceId;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::io::{
+    AsyncReadExt,
+    AsyncWriteExt,
+};
+use tracing::debug;
+use unavi_util::async_commands::AsyncCommands;
+
+use crate::{
+    connection::{
+        ecs::{
+            PeerStream,
+            voice::{
+                OutgoingVoice,
+                VoiceSender,
+            },
+        },
+        shared::StreamIdent,
+    },
+    peer::self_peer_id,
+    voice::{
+        self,
+        codec::{
+            MAX_PACKET,
+            VoiceDecoder,
+        },
+        playback,
+    },
+};
+
+/// Packets queued per peer. Voice is worth nothing late, so a peer that falls
+/// behind loses packets rather than the queue growing.
+const VOICE_QUEUE: usize = 4;
+
+/// Largest encoded frame: the tags, a length and the biggest Opus packet.
+const MAX_FRAME: usize = 32 + 32 + 2 + MAX_PACKET;
+
+/// One Opus packet, with the space it was spoken in and the agent speaking.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct VoiceFrame {
+    space:  [u8; 32],
+    agent:  [u8; 32],
+    packet: Vec<u8>,
+}
+
+/// The frame as written: a big-endian length, then the frame.
+fn encode_frame(frame: &VoiceFrame) -> anyhow::Result<Vec<u8>> {
+    if frame.packet.len() > MAX_PACKET {
+        bail!("voice packet too large")
+    }
+    let body = postcard::to_allocvec(frame)?;
+    let mut out = Vec::with_capacity(2 + body.len());
+    out.extend_from_slice(&u16::try_from(body.len())?.to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+fn decode_frame(body: &[u8]) -> anyhow::Result<VoiceFrame> {
+    let frame = postcard::from_bytes::<VoiceFrame>(body)?;
+    if frame.packet.len() > MAX_PACKET {
+        bail!("voice packet too large")
+    }
+    Ok(frame)
+}
+
+pub async fn send_voice_stream(connection: &Connection) -> anyhow::Result<()> {
+    let agent = self_peer_id().context("no local peer id")?;
+
+    let (mut tx, _rx) = connection.open_bi().await?;
+    StreamIdent::Voice.write(&mut tx).await?;
+
+    let (voice_tx, voice_rx) = async_channel::bounded::<OutgoingVoice>(VOICE_QUEUE);
+
+    AsyncCommands::default()
+        .spawn((PeerStream(connection.remote_id()), VoiceSender(voice_tx)))
+        .send()
+        .await?;
+
+    while let Ok(OutgoingVoice { space, packet }) = voice_rx.recv().await {
+        let frame = VoiceFrame {
+            space: *space.as_bytes(),
+            agent,
+            packet: packet.to_vec(),
+        };
+        tx.write_all(&encode_frame(&frame)?).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn recv_voice_stream(
+    peer: EndpointId,
+    _tx: SendStream,
+    mut rx: RecvStream,
+) -> anyhow::Result<()> {
+    let mut decoder = VoiceDecoder::new()?;
+    let mut buf = [0; MAX_FRAME];
+
+    loop {
+        let len = match rx.read_u16().await {
+            Ok(len) => usize::from(len),
+            Err(err) if super::read_disconnected(&err) => return Ok(()),
+            Err(err) => return Err(err).context("read len"),
+        };
+        if len > MAX_FRAME {
+            bail!("voice frame length {len} exceeds max {MAX_FRAME}");
+        }
+        let buf = &mut buf[..len];
+        rx.read_exact(buf).await.context("read frame")?;
+        let frame = decode_frame(buf).context("parse frame")?;
+
+        // A peer speaks only for itself; and whoever is not to be heard is
+        // dropped before any decoding is spent on them.
+        if frame.agent != *peer.as_bytes() || !voice::audible(frame.agent) {
+            continue;
+        }
+
+        match decoder.decode(&frame.packet) {
+            Ok(pcm) => playback::submit(peer, NamespaceId::from(&frame.space), &pcm),
+            Err(err) => debug!(?err, "Undecodable voice packet"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voice::codec::{
+        FRAME_SAMPLES,
+        VoiceEncoder,
+    };
+
+    #[test]
+    fn synthetic_voice_round_trips_through_frames() {
+        let mut encoder = VoiceEncoder::new().expect("encoder");
+        let mut decoder = VoiceDecoder::new().expect("decoder");
+
+        #[expect(clippy::cast_precision_loss, reason = "test signal")]
+        let pcm = (0..FRAME_SAMPLES * 3)
+            .map(|i| (i as f32 * 0.05).sin() * 0.3)
+            .collect::<Vec<_>>();
+
+        for packet in encoder.push(&pcm).expect("encode") {
+            let frame = VoiceFrame {
+                space: [1; 32],
+                agent: [2; 32],
+                packet,
+            };
+            let wire = encode_frame(&frame).expect("encode frame");
+
+            let len = usize::from(u16::from_be_bytes([wire[0], wire[1]]));
+            assert_eq!(len, wire.len() - 2);
+            assert!(len <= MAX_FRAME);
+
+            let decoded = decode_frame(&wire[2..]).expect("decode frame");
+            assert_eq!(decoded, frame);
+            let out = decoder.decode(&decoded.packet).expect("decode");
+            assert_eq!(out.len(), FRAME_SAMPLES);
+        }
+    }
+
+    #[test]
+    fn oversized_packets_are_refused() {
+        let frame = VoiceFrame {
+            space:  [0; 32],
+            agent:  [0; 32],
+            packet: vec![0; MAX_PACKET + 1],
+        };
+        assert!(encode_frame(&frame).is_err());
+
+        let body = postcard::to_allocvec(&frame).expect("serialize");
+        assert!(decode_frame(&body).is_err());
+    }
+}
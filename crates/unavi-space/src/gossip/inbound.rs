@@ -18,6 +18,7 @@ use tracing::{
 use wds::signed_bytes::SignedBytes;
 
 use crate::{
+    chat,
     gossip::{
         GossipCtx,
         SpaceBroadcast,
@@ -96,6 +97,9 @@ pub async fn handle_gossip_inbound(
 
                         submit_presence(peer, space);
                     }
+                    SpaceMessage::Chat(message) => {
+                        chat::receive(space, broadcast.sender, message).await;
+                    }
                     SpaceMessage::Unknown(i) => {
                         warn!("Got unknown gossip variant: {i}");
                     }
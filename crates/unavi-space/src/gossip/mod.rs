@@ -19,11 +19,17 @@ use serde::{
 use tokio::sync::oneshot;
 use unavi_policy::space::Space;
 use unavi_util::async_task::spawn_async_task;
-use wds::signed_bytes::Signable;
+use wds::signed_bytes::{
+    Signable,
+    SignedBytes,
+};
 
-use crate::gossip::thread::{
-    GossipCommand,
-    GossipCtx,
+use crate::{
+    chat::ChatMessage,
+    gossip::thread::{
+        GossipCommand,
+        GossipCtx,
+    },
 };
 
 mod bootstrap;
@@ -75,6 +81,7 @@ impl Signable for SpaceBroadcast {
 #[non_exhaustive]
 enum SpaceMessage {
     Presence(EndpointAddr),
+    Chat(SignedBytes<ChatMessage>),
     Unknown(usize),
 }
 
@@ -8,9 +8,11 @@ use tracing::info;
 use wds::signed_bytes::{
     IrohSigner,
     Signable,
+    SignedBytes,
 };
 
 use crate::{
+    chat::ChatMessage,
     gossip::{
         GossipCtx,
         SpaceBroadcast,
@@ -26,6 +28,7 @@ pub async fn handle_gossip_outbound(
     tx: &GossipSender,
     space: NamespaceId,
     wake: &Notify,
+    chat: &async_channel::Receiver<SignedBytes<ChatMessage>>,
 ) -> anyhow::Result<()> {
     let signer = IrohSigner(ctx.endpoint.secret_key());
     let mut watcher = ctx.endpoint.watch_addr();
@@ -39,22 +42,38 @@ pub async fn handle_gossip_outbound(
             let addr = watcher.get();
 
             info!("Broadcasting presence: {:?}", addr);
-            let broadcast = SpaceBroadcast {
-                sender: ctx.endpoint.id(),
-                msg:    SpaceMessage::Presence(addr),
-            };
-
-            let bytes = postcard::to_stdvec(&broadcast.sign(&signer)?)?;
-            tx.broadcast(bytes.into()).await?;
+            broadcast(ctx, tx, &signer, SpaceMessage::Presence(addr)).await?;
         }
 
         // Re-broadcast on the interval, immediately when a neighbor joins the
         // topic, or as soon as this space becomes active, so neither entering
-        // a space nor a peer arriving waits out a full interval.
-        tokio::select! {
-            () = n0_future::time::sleep(PRESENCE_INTERVAL) => {}
-            () = wake.notified() => {}
-            () = active_changed().notified() => {}
+        // a space nor a peer arriving waits out a full interval. Chat goes out
+        // as it is posted, without restarting the heartbeat.
+        let mut heartbeat = std::pin::pin!(n0_future::time::sleep(PRESENCE_INTERVAL));
+        loop {
+            tokio::select! {
+                () = &mut heartbeat => break,
+                () = wake.notified() => break,
+                () = active_changed().notified() => break,
+                Ok(message) = chat.recv() => {
+                    broadcast(ctx, tx, &signer, SpaceMessage::Chat(message)).await?;
+                }
+            }
         }
     }
 }
+
+async fn broadcast(
+    ctx: &GossipCtx,
+    tx: &GossipSender,
+    signer: &IrohSigner<'_>,
+    msg: SpaceMessage,
+) -> anyhow::Result<()> {
+    let broadcast = SpaceBroadcast {
+        sender: ctx.endpoint.id(),
+        msg,
+    };
+    let bytes = postcard::to_stdvec(&broadcast.sign(signer)?)?;
+    tx.broadcast(bytes.into()).await?;
+    Ok(())
+}
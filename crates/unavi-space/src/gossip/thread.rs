@@ -199,8 +199,12 @@ async fn handle_space_topic(
         async move { handle_gossip_bootstrap(&ctx, &tx, space, &neighbors).await }
     });
 
+    let chat = crate::chat::open_outbox(space);
+    let outbox = chat.clone();
+
     let outbound_task = n0_future::task::spawn(async move {
-        while let Err(err) = super::outbound::handle_gossip_outbound(&ctx, &tx, space, &wake).await
+        while let Err(err) =
+            super::outbound::handle_gossip_outbound(&ctx, &tx, space, &wake, &chat).await
         {
             error!(?err, "Error handling outbound gossip");
             n0_future::time::sleep(Duration::from_secs(1)).await;
@@ -222,6 +226,7 @@ async fn handle_space_topic(
     }
 
     bootstrap_task.abort();
+    crate::chat::close_outbox(&outbox);
 
     Ok(())
 }
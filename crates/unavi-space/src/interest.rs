@@ -0,0 +1,314 @@
+//! Area of interest: how often each peer needs this peer's agent and objects.
+//!
+//! Every peer tells each peer it is connected to where it is looking: the
+//! spaces it has loaded, and a coarse position in the one it stands in.
+//! Senders scale to that. Within [`InterestSettings::radius`] of a peer,
+//! agent updates fall off with distance; past it, or in a space the peer only
+//! sees through a portal, everything slows to [`FAR_TICKRATE`]. A space the
+//! peer has not loaded lies behind its anchor boundary: objects there are not
+//! sent at all, and the agent only at [`IDLE_TICKRATE`], which keeps the
+//! connection's presence alive without drawing anything.
+//!
+//! A peer that has never said where it is gets everything at full rate, as
+//! before interest existed. A stream through a space relay reaches the whole
+//! room, so it is sent for whichever member there cares most.
+
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use iroh::EndpointId;
+use iroh_docs::NamespaceId;
+use parking_lot::RwLock;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::sync::watch;
+use unavi_agent::{
+    AgentAvatar,
+    LocalAgent,
+};
+use unavi_policy::space::Space;
+
+use crate::{
+    anchor::ActiveSpace,
+    connection::relay,
+};
+
+/// Side of the grid a focus is snapped to. Coarse enough that walking around
+/// re-advertises rarely, and that peers learn little about exactly where
+/// anyone stands.
+pub const INTEREST_CELL: f32 = 4.0;
+
+/// Most spaces one advertisement names; the active space always comes first.
+pub const MAX_INTEREST_SPACES: usize = 16;
+
+/// Rate for whatever is visible but outside the radius.
+pub const FAR_TICKRATE: Duration = Duration::from_secs(1);
+/// Rate for an agent in a space the peer has not loaded. Well under
+/// [`crate::peer::presence`]'s expiry, so a peer heard only over the
+/// connection is not forgotten.
+pub const IDLE_TICKRATE: Duration = Duration::from_secs(5);
+
+#[derive(Resource, Debug, Clone)]
+pub struct InterestSettings {
+    /// Metres around a peer within which it gets full-rate updates.
+    pub radius: f32,
+}
+
+impl Default for InterestSettings {
+    fn default() -> Self {
+        Self { radius: 50.0 }
+    }
+}
+
+/// One space a peer has loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SpaceInterest {
+    pub space: [u8; 32],
+    /// Where the peer stands in the space's frame, in [`INTEREST_CELL`]s. Only
+    /// the space it is in has one.
+    pub focus: Option<[i16; 3]>,
+}
+
+/// What a peer advertises, as it goes on the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Interest {
+    pub spaces: Vec<SpaceInterest>,
+}
+
+/// How much a peer cares about something at a place in a space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Relevance {
+    /// Within the radius, this many metres away.
+    Near(f32),
+    /// Visible, but past the radius or through a portal.
+    Far,
+    /// In a space the peer has not loaded.
+    Hidden,
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "clamped to the i16 range first"
+)]
+fn quantize(at: Vec3) -> [i16; 3] {
+    let cell = |v: f32| {
+        (v / INTEREST_CELL)
+            .round()
+            .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+    };
+    [cell(at.x), cell(at.y), cell(at.z)]
+}
+
+fn dequantize(cell: [i16; 3]) -> Vec3 {
+    Vec3::new(f32::from(cell[0]), f32::from(cell[1]), f32::from(cell[2])) * INTEREST_CELL
+}
+
+/// How much a peer with `interest` cares about something at `at` in `space`.
+pub(crate) fn relevance(
+    interest: Option<&Interest>,
+    space: NamespaceId,
+    at: Vec3,
+    radius: f32,
+) -> Relevance {
+    let Some(interest) = interest else {
+        return Relevance::Near(0.0);
+    };
+    match interest
+        .spaces
+        .iter()
+        .find(|s| s.space == *space.as_bytes())
+    {
+        None => Relevance::Hidden,
+        Some(SpaceInterest { focus: None, .. }) => Relevance::Far,
+        Some(SpaceInterest {
+            focus: Some(focus), ..
+        }) => {
+            let dist = dequantize(*focus).distance(at);
+            if dist > radius {
+                Relevance::Far
+            } else {
+                Relevance::Near(dist)
+            }
+        }
+    }
+}
+
+impl Relevance {
+    /// Whichever of the two cares more.
+    fn max(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Near(a), Self::Near(b)) => Self::Near(a.min(b)),
+            (near @ Self::Near(_), _) | (_, near @ Self::Near(_)) => near,
+            (Self::Far, _) | (_, Self::Far) => Self::Far,
+            (Self::Hidden, Self::Hidden) => Self::Hidden,
+        }
+    }
+}
+
+/// What everyone a stream to `target` reaches last advertised: `target`'s
+/// own, or through a relay, each room member's.
+pub(crate) fn audience(target: EndpointId) -> Vec<Option<Interest>> {
+    relay::room(target).map_or_else(
+        || vec![of_peer(target)],
+        |members| members.into_iter().map(of_peer).collect(),
+    )
+}
+
+/// How much whoever in `audience` cares most cares about `at` in `space`.
+pub(crate) fn relevance_to_any(
+    audience: &[Option<Interest>],
+    space: NamespaceId,
+    at: Vec3,
+    radius: f32,
+) -> Relevance {
+    audience
+        .iter()
+        .map(|interest| relevance(interest.as_ref(), space, at, radius))
+        .reduce(Relevance::max)
+        .unwrap_or(Relevance::Hidden)
+}
+
+static LOCAL: LazyLock<watch::Sender<Interest>> =
+    LazyLock::new(|| watch::Sender::new(Interest::default()));
+
+static PEERS: LazyLock<RwLock<HashMap<EndpointId, Interest>>> = LazyLock::new(RwLock::default);
+
+/// The local interest, updated as it changes.
+pub(crate) fn subscribe() -> watch::Receiver<Interest> {
+    LOCAL.subscribe()
+}
+
+pub(crate) fn set_peer(peer: EndpointId, interest: Interest) {
+    PEERS.write().insert(peer, interest);
+}
+
+pub(crate) fn forget_peer(peer: EndpointId) {
+    PEERS.write().remove(&peer);
+}
+
+/// What `peer` last advertised, if anything.
+pub(crate) fn of_peer(peer: EndpointId) -> Option<Interest> {
+    PEERS.read().get(&peer).cloned()
+}
+
+/// Recomputes the local interest. Peers only hear of it when it changes,
+/// which, with the focus snapped to the grid, is seldom.
+pub fn publish_interest(
+    active: Res<ActiveSpace>,
+    spaces: Query<(Entity, &Space)>,
+    agent: Query<&AgentAvatar, With<LocalAgent>>,
+    globals: Query<&GlobalTransform>,
+) {
+    // The active space sits at the world origin, so the avatar's world
+    // translation is where it stands in the space's frame.
+    let focus = agent
+        .single()
+        .ok()
+        .and_then(|avatar| globals.get(avatar.0).ok())
+        .map(|global| quantize(global.translation()));
+
+    let mut interest = Interest::default();
+    if let Some((_, space)) = active.0.and_then(|e| spaces.get(e).ok()) {
+        interest.spaces.push(SpaceInterest {
+            space: *space.0.as_bytes(),
+            focus,
+        });
+    }
+    interest.spaces.extend(
+        spaces
+            .iter()
+            .filter(|(entity, _)| Some(*entity) != active.0)
+            .map(|(_, space)| SpaceInterest {
+                space: *space.0.as_bytes(),
+                focus: None,
+            }),
+    );
+    interest.spaces.truncate(MAX_INTEREST_SPACES);
+
+    LOCAL.send_if_modified(|current| {
+        if *current == interest {
+            return false;
+        }
+        *current = interest;
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ns(seed: u8) -> NamespaceId {
+        NamespaceId::from(&[seed; 32])
+    }
+
+    fn interest(focus: Vec3) -> Interest {
+        Interest {
+            spaces: vec![
+                SpaceInterest {
+                    space: [1; 32],
+                    focus: Some(quantize(focus)),
+                },
+                SpaceInterest {
+                    space: [2; 32],
+                    focus: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn focus_snaps_to_the_grid() {
+        let at = Vec3::new(10.3, -1.0, 1000.0);
+        assert!(dequantize(quantize(at)).distance(at) <= INTEREST_CELL);
+        assert_eq!(quantize(Vec3::splat(1.0e9)), [i16::MAX; 3]);
+    }
+
+    #[test]
+    fn relevance_follows_distance_and_space() {
+        let interest = interest(Vec3::ZERO);
+        let near = relevance(Some(&interest), ns(1), Vec3::new(8.0, 0.0, 0.0), 50.0);
+        assert!(matches!(near, Relevance::Near(d) if (d - 8.0).abs() < 0.01));
+        assert_eq!(
+            relevance(Some(&interest), ns(1), Vec3::new(80.0, 0.0, 0.0), 50.0),
+            Relevance::Far
+        );
+        assert_eq!(
+            relevance(Some(&interest), ns(2), Vec3::ZERO, 50.0),
+            Relevance::Far
+        );
+        assert_eq!(
+            relevance(Some(&interest), ns(3), Vec3::ZERO, 50.0),
+            Relevance::Hidden
+        );
+    }
+
+    #[test]
+    fn a_room_is_sent_for_its_most_interested_member() {
+        let room = [
+            Some(interest(Vec3::ZERO)),
+            Some(interest(Vec3::splat(30.0))),
+        ];
+        let at = Vec3::new(28.0, 30.0, 30.0);
+        assert!(matches!(
+            relevance_to_any(&room, ns(1), at, 50.0),
+            Relevance::Near(d) if (d - 2.0).abs() < INTEREST_CELL
+        ));
+        assert_eq!(relevance_to_any(&room, ns(3), at, 50.0), Relevance::Hidden);
+        assert_eq!(relevance_to_any(&[], ns(1), at, 50.0), Relevance::Hidden);
+    }
+
+    #[test]
+    fn unadvertised_peers_get_everything() {
+        assert_eq!(
+            relevance(None, ns(3), Vec3::splat(1000.0), 50.0),
+            Relevance::Near(0.0)
+        );
+    }
+}
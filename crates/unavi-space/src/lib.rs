@@ -11,9 +11,11 @@ use unavi_manifold::{
 };
 
 pub mod anchor;
+pub mod chat;
 mod connection;
 #[cfg(feature = "devtools")] mod devtools;
 mod gossip;
+pub mod interest;
 pub mod peer;
 mod portal;
 mod portal_bridge;
@@ -24,11 +26,14 @@ pub mod spawn;
 pub mod state;
 pub mod travel;
 pub mod trust;
+#[cfg(not(target_family = "wasm"))] pub mod voice;
 
 pub struct SpacePlugin;
 
-const TICKRATE_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+const TICKRATE_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 const VOUCH_PUBLISH_INTERVAL: Duration = Duration::from_mins(5);
+const CHAT_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+const RELAY_FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
 
 impl Plugin for SpacePlugin {
     fn build(&self, app: &mut App) {
@@ -44,12 +49,17 @@ impl Plugin for SpacePlugin {
 
         app.add_systems(
             FixedUpdate,
-            trust::publish_vouches.run_if(on_timer(VOUCH_PUBLISH_INTERVAL)),
+            (
+                trust::publish_vouches.run_if(on_timer(VOUCH_PUBLISH_INTERVAL)),
+                chat::flush_histories.run_if(on_timer(CHAT_FLUSH_INTERVAL)),
+                connection::relay::follow_space_relays.run_if(on_timer(RELAY_FOLLOW_INTERVAL)),
+            ),
         );
 
         app.init_resource::<anchor::SpaceGridAllocator>()
             .init_resource::<anchor::ActiveSpace>()
             .init_resource::<travel::PendingTravel>()
+            .init_resource::<interest::InterestSettings>()
             .add_observer(anchor::assign_anchor)
             .add_observer(quota::registry::reassign_doc_quota)
             .add_observer(quota::registry::forget_document_quota)
@@ -89,6 +99,7 @@ impl Plugin for SpacePlugin {
                 FixedUpdate,
                 (
                     presence::publish_presence,
+                    interest::publish_interest,
                     connection::ecs::agent::outbound::send_agent_pose,
                     connection::ecs::object::send_object_poses,
                     connection::ecs::object::reconcile_object_authority,
@@ -116,6 +127,28 @@ impl Plugin for SpacePlugin {
                     connection::ecs::agent::inbound::advance_remote_lerp,
                 )
                     .chain(),
-            );
+            )
+            .add_systems(Update, connection::ecs::measure_frame_budget);
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            voice::playback::register(app);
+            app.init_resource::<unavi_input::action::ActionState>()
+                .init_resource::<voice::VoiceSettings>()
+                .add_systems(
+                    Update,
+                    (
+                        voice::toggle_mute,
+                        voice::capture::open_microphone.run_if(voice::capture::wants_microphone),
+                        connection::ecs::voice::send_voice,
+                    )
+                        .chain(),
+                )
+                .add_systems(
+                    Update,
+                    voice::playback::attach_voice_emitters
+                        .after(connection::ecs::agent::inbound::apply_remote_poses),
+                );
+        }
     }
 }
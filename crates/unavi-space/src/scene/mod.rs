@@ -12,6 +12,7 @@ use bevy_iroh::endpoint::IrohEndpoint;
 use bevy_wds::{
     LocalBlobs,
     LocalDocs,
+    Mirrors,
     SyncTargets,
     registry_clients,
 };
@@ -43,7 +44,7 @@ pub struct PendingScene {
 pub fn spawn_space_scene(
     trigger: On<Add, Space>,
     spaces: Query<(&Space, Option<&HsdNamespace>)>,
-    stores: Query<(&LocalDocs, &LocalBlobs, &SyncTargets)>,
+    stores: Query<(&LocalDocs, &LocalBlobs, &SyncTargets, &Mirrors)>,
     endpoints: Query<&IrohEndpoint>,
     mut commands: Commands,
 ) {
@@ -52,7 +53,7 @@ pub fn spawn_space_scene(
         .map(|(space, ns)| (space.0, ns.map(|v| v.0)))
         .expect("space");
 
-    let Ok((docs, blobs, sync_targets)) = stores.single() else {
+    let Ok((docs, blobs, sync_targets, mirrors)) = stores.single() else {
         warn!("Cannot read space: no local store");
         return;
     };
@@ -74,10 +75,13 @@ pub fn spawn_space_scene(
     let docs = docs.0.clone();
     let blobs = blobs.0.clone();
     let self_id = endpoints.single().ok().map(|e| e.0.id());
+    // Mirrors follow the targets, so the space still arrives while one of its
+    // servers is down.
     let sync_targets = sync_targets
         .0
         .iter()
         .map(|a| a.host().clone())
+        .chain(mirrors.0.iter().copied().map(EndpointAddr::from))
         .collect::<Vec<_>>();
 
     let (tx, rx) = async_channel::bounded(1);
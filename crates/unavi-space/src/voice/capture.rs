@@ -0,0 +1,164 @@
+//! The microphone, opened the first time there is something to say.
+
+use std::sync::Arc;
+
+use anyhow::{
+    Context,
+    bail,
+};
+use bevy::prelude::*;
+use cpal::{
+    FromSample,
+    SampleFormat,
+    SizedSample,
+    traits::{
+        DeviceTrait,
+        HostTrait,
+        StreamTrait,
+    },
+};
+use parking_lot::Mutex;
+use unavi_input::action::ActionState;
+
+use crate::{
+    connection::ecs::voice::VoiceSender,
+    voice::{
+        VoiceSettings,
+        codec::{
+            Resampler,
+            SAMPLE_RATE,
+            VoiceEncoder,
+        },
+    },
+};
+
+/// About a second of unread audio; anything older is dropped.
+const MAX_CAPTURED: usize = SAMPLE_RATE as usize;
+
+/// Mono PCM the device has captured and nothing has read yet.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<f32>>>);
+
+impl Captured {
+    fn push<T>(&self, data: &[T], channels: usize)
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        #[expect(clippy::cast_precision_loss, reason = "a handful of channels")]
+        let scale = 1.0 / channels as f32;
+
+        let mut captured = self.0.lock();
+        captured.extend(
+            data.chunks_exact(channels)
+                .map(|frame| frame.iter().map(|s| f32::from_sample(*s)).sum::<f32>() * scale),
+        );
+        let excess = captured.len().saturating_sub(MAX_CAPTURED);
+        captured.drain(..excess);
+    }
+}
+
+/// The open input stream. Not `Send` on every platform, so it is held apart
+/// from [`Microphone`].
+struct InputStream(#[expect(dead_code, reason = "kept open")] cpal::Stream);
+
+/// Everything between the device and the wire.
+#[derive(Resource)]
+pub struct Microphone {
+    captured:  Captured,
+    resampler: Resampler,
+    /// Opus's encoder is `Send` but not `Sync`, which a resource must be.
+    encoder:   Mutex<VoiceEncoder>,
+}
+
+impl Microphone {
+    /// Opus packets for everything captured since the last call, or nothing
+    /// (and nothing buffered) while not `transmitting`.
+    pub fn take_packets(&mut self, transmitting: bool) -> anyhow::Result<Vec<Vec<u8>>> {
+        let captured = std::mem::take(&mut *self.captured.0.lock());
+        if !transmitting {
+            self.encoder.get_mut().clear();
+            return Ok(Vec::new());
+        }
+        let mut pcm = Vec::with_capacity(captured.len());
+        self.resampler.process(&captured, &mut pcm);
+        self.encoder.get_mut().push(&pcm)
+    }
+}
+
+/// Set once the microphone could not be opened, so it is not retried every
+/// frame.
+#[derive(Resource)]
+pub struct NoMicrophone;
+
+/// Whether the microphone is wanted and not yet open.
+pub fn wants_microphone(
+    settings: Res<VoiceSettings>,
+    input: Res<ActionState>,
+    microphone: Option<Res<Microphone>>,
+    failed: Option<Res<NoMicrophone>>,
+    senders: Query<(), With<VoiceSender>>,
+) -> bool {
+    microphone.is_none() && failed.is_none() && !senders.is_empty() && settings.transmitting(&input)
+}
+
+pub fn open_microphone(world: &mut World) {
+    match open() {
+        Ok((stream, microphone)) => {
+            info!("Microphone opened");
+            world.insert_non_send_resource(stream);
+            world.insert_resource(microphone);
+        }
+        Err(err) => {
+            warn!("No microphone, voice is listen-only: {err:#}");
+            world.insert_resource(NoMicrophone);
+        }
+    }
+}
+
+fn open() -> anyhow::Result<(InputStream, Microphone)> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .context("no input device")?;
+    let config = device.default_input_config().context("no input config")?;
+
+    let channels = usize::from(config.channels());
+    let rate = config.sample_rate();
+    let captured = Captured::default();
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build::<f32>(&device, &config.config(), captured.clone(), channels),
+        SampleFormat::I16 => build::<i16>(&device, &config.config(), captured.clone(), channels),
+        SampleFormat::U16 => build::<u16>(&device, &config.config(), captured.clone(), channels),
+        other => bail!("unsupported sample format {other}"),
+    }?;
+    stream.play().context("start input stream")?;
+
+    Ok((
+        InputStream(stream),
+        Microphone {
+            captured,
+            resampler: Resampler::new(rate),
+            encoder: Mutex::new(VoiceEncoder::new()?),
+        },
+    ))
+}
+
+fn build<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    captured: Captured,
+    channels: usize,
+) -> anyhow::Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| captured.push(data, channels),
+        |err| warn!(?err, "Microphone stream error"),
+        None,
+    )?;
+    Ok(stream)
+}
@@ -0,0 +1,189 @@
+//! Opus at one fixed shape: 48 kHz mono, 20 ms frames.
+
+use anyhow::Context;
+use opus::{
+    Application,
+    Bitrate,
+    Channels,
+    Decoder,
+    Encoder,
+};
+
+pub const SAMPLE_RATE: u32 = 48_000;
+/// 20 ms, the frame Opus is tuned for on voice.
+pub const FRAME_SAMPLES: usize = 960;
+/// Largest packet one Opus frame can encode to.
+pub const MAX_PACKET: usize = 1275;
+
+const BITRATE: i32 = 24_000;
+
+/// Turns microphone PCM into Opus packets, one per whole frame.
+pub struct VoiceEncoder {
+    opus:    Encoder,
+    pending: Vec<f32>,
+}
+
+impl VoiceEncoder {
+    pub fn new() -> anyhow::Result<Self> {
+        let mut opus = Encoder::new(SAMPLE_RATE, Channels::Mono, Application::Voip)
+            .context("create opus encoder")?;
+        opus.set_bitrate(Bitrate::Bits(BITRATE))
+            .context("set opus bitrate")?;
+        Ok(Self {
+            opus,
+            pending: Vec::with_capacity(FRAME_SAMPLES * 2),
+        })
+    }
+
+    /// Buffers `pcm`, at [`SAMPLE_RATE`], and encodes every whole frame now
+    /// held. A partial frame waits for the next call.
+    pub fn push(&mut self, pcm: &[f32]) -> anyhow::Result<Vec<Vec<u8>>> {
+        self.pending.extend_from_slice(pcm);
+
+        let mut packets = Vec::new();
+        let mut buf = [0; MAX_PACKET];
+        let whole = self.pending.len() - self.pending.len() % FRAME_SAMPLES;
+        for frame in self.pending[..whole].chunks_exact(FRAME_SAMPLES) {
+            let len = self.opus.encode_float(frame, &mut buf).context("encode")?;
+            packets.push(buf[..len].to_vec());
+        }
+        self.pending.drain(..whole);
+        Ok(packets)
+    }
+
+    /// Drops a partial frame, so speech resumes cleanly after a pause rather
+    /// than with the tail of what came before it.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Turns one peer's Opus packets back into PCM at [`SAMPLE_RATE`].
+pub struct VoiceDecoder {
+    opus: Decoder,
+}
+
+impl VoiceDecoder {
+    pub fn new() -> anyhow::Result<Self> {
+        let opus = Decoder::new(SAMPLE_RATE, Channels::Mono).context("create opus decoder")?;
+        Ok(Self { opus })
+    }
+
+    pub fn decode(&mut self, packet: &[u8]) -> anyhow::Result<Vec<f32>> {
+        let mut pcm = vec![0.0; FRAME_SAMPLES];
+        let len = self
+            .opus
+            .decode_float(packet, &mut pcm, false)
+            .context("decode")?;
+        pcm.truncate(len);
+        Ok(pcm)
+    }
+}
+
+/// Linear resampling from a device's rate to [`SAMPLE_RATE`]. Crude, but a
+/// microphone's rate is almost always 48 or 44.1 kHz, and voice loses little
+/// to it.
+pub struct Resampler {
+    /// Input samples per output sample.
+    step: f64,
+    /// Where the next output sample falls, in input samples past `last`.
+    pos:  f64,
+    last: f32,
+}
+
+impl Resampler {
+    #[must_use]
+    pub fn new(from_rate: u32) -> Self {
+        Self {
+            step: f64::from(from_rate) / f64::from(SAMPLE_RATE),
+            pos:  1.0,
+            last: 0.0,
+        }
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "positions are small and non-negative"
+    )]
+    pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        if input.is_empty() {
+            return;
+        }
+
+        // Position 0 is `last`, the sample before `input[0]`.
+        let len = input.len() as f64;
+        while self.pos <= len {
+            let i = self.pos.floor() as usize;
+            let frac = (self.pos - self.pos.floor()) as f32;
+            let a = if i == 0 { self.last } else { input[i - 1] };
+            let b = input.get(i).copied().unwrap_or(a);
+            out.push((b - a).mul_add(frac, a));
+            self.pos += self.step;
+        }
+        self.pos -= len;
+        self.last = input[input.len() - 1];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::*;
+
+    #[expect(clippy::cast_precision_loss, reason = "test signal")]
+    fn sine(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.5 * (TAU * 440.0 * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "test signal")]
+    fn rms(pcm: &[f32]) -> f32 {
+        (pcm.iter().map(|s| s * s).sum::<f32>() / pcm.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn sine_survives_a_round_trip() {
+        let mut encoder = VoiceEncoder::new().expect("encoder");
+        let mut decoder = VoiceDecoder::new().expect("decoder");
+
+        let input = sine(FRAME_SAMPLES * 10 + 100);
+        let mut packets = Vec::new();
+        for chunk in input.chunks(333) {
+            packets.extend(encoder.push(chunk).expect("encode"));
+        }
+        assert_eq!(packets.len(), 10, "the partial frame is held back");
+        assert!(packets.iter().all(|p| p.len() <= MAX_PACKET));
+
+        let output = packets
+            .iter()
+            .flat_map(|p| decoder.decode(p).expect("decode"))
+            .collect::<Vec<_>>();
+        assert_eq!(output.len(), FRAME_SAMPLES * 10);
+
+        // Past the codec's warm-up, the tone comes back at about its level.
+        let (expected, got) = (rms(&input[..output.len()]), rms(&output[FRAME_SAMPLES..]));
+        assert!(
+            (got - expected).abs() < expected * 0.3,
+            "{got} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn resampling_keeps_duration() {
+        let mut resampler = Resampler::new(44_100);
+        let mut out = Vec::new();
+        for _ in 0..10 {
+            resampler.process(&[0.25; 4410], &mut out);
+        }
+        assert!(out.len().abs_diff(48_000) <= 1, "{}", out.len());
+        assert!(out[10..].iter().all(|s| (s - 0.25).abs() < 1.0e-6));
+
+        let mut same = Resampler::new(SAMPLE_RATE);
+        let mut out = Vec::new();
+        same.process(&[1.0, 2.0, 3.0], &mut out);
+        assert_eq!(out, [1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Proximity voice: the microphone, Opus-encoded, to every connected peer, and
+//! each peer's voice played back from their avatar's head.
+//!
+//! What a peer hears is the local user's call. A peer below the hearing floor
+//! is dropped before decoding — [`Trust::Blocked`] clears no floor, so a
+//! blocked peer is never heard — and one muted by hand likewise. Mutes are
+//! keyed by DID, like blocks, but last only the session.
+
+use std::{
+    collections::HashSet,
+    sync::LazyLock,
+};
+
+use bevy::prelude::*;
+use parking_lot::RwLock;
+use unavi_input::action::{
+    Action,
+    ActionState,
+};
+use unavi_policy::{
+    identity,
+    trust::{
+        self,
+        Trust,
+    },
+};
+use xdid::core::did::Did;
+
+use crate::trust::NoIdentity;
+
+pub(crate) mod capture;
+pub mod codec;
+pub(crate) mod playback;
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct VoiceSettings {
+    /// Nothing from the microphone is sent. Toggled by [`Action::Mute`].
+    pub muted:        bool,
+    /// Send only while [`Action::Talk`] is held.
+    pub push_to_talk: bool,
+}
+
+impl VoiceSettings {
+    /// Whether the microphone should be sent this frame.
+    #[must_use]
+    pub const fn transmitting(&self, input: &ActionState) -> bool {
+        !self.muted && (!self.push_to_talk || input.pressed(Action::Talk))
+    }
+}
+
+static HEARING_FLOOR: RwLock<Trust> = RwLock::new(Trust::Guest);
+
+static MUTED: LazyLock<RwLock<HashSet<Did>>> = LazyLock::new(RwLock::default);
+
+/// The lowest rung heard. Raising it to [`Trust::Known`] leaves strangers
+/// silent without muting each one.
+pub fn set_hearing_floor(floor: Trust) {
+    *HEARING_FLOOR.write() = floor;
+}
+
+#[must_use]
+pub fn hearing_floor() -> Trust {
+    *HEARING_FLOOR.read()
+}
+
+/// Silences `peer` for the rest of the session.
+///
+/// A peer that proved no DID cannot be singled out, for the same reason it
+/// cannot be blocked; raise the hearing floor instead.
+pub fn mute_peer(peer: [u8; 32]) -> Result<(), NoIdentity> {
+    let did = identity::did_of(peer).ok_or(NoIdentity)?;
+    MUTED.write().insert(did);
+    if let Ok(peer) = iroh::EndpointId::from_bytes(&peer) {
+        playback::clear(peer);
+    }
+    Ok(())
+}
+
+pub fn unmute_peer(peer: [u8; 32]) -> Result<(), NoIdentity> {
+    let did = identity::did_of(peer).ok_or(NoIdentity)?;
+    MUTED.write().remove(&did);
+    Ok(())
+}
+
+#[must_use]
+pub fn is_peer_muted(peer: [u8; 32]) -> bool {
+    identity::did_of(peer).is_some_and(|did| MUTED.read().contains(&did))
+}
+
+/// Whether `peer`'s voice is played at all.
+#[must_use]
+pub fn audible(peer: [u8; 32]) -> bool {
+    trust::of_peer(peer).clears(hearing_floor()) && !is_peer_muted(peer)
+}
+
+pub fn toggle_mute(input: Res<ActionState>, mut settings: ResMut<VoiceSettings>) {
+    if input.just_pressed(Action::Mute) {
+        settings.muted = !settings.muted;
+        info!(muted = settings.muted, "Microphone toggled");
+    }
+}
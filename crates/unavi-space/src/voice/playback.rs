@@ -0,0 +1,253 @@
+//! Each remote agent's voice, as a streaming audio source at their head.
+
+use std::{
+    collections::VecDeque,
+    num::NonZero,
+    sync::{
+        Arc,
+        LazyLock,
+    },
+    time::Duration,
+};
+
+use bevy::{
+    audio::{
+        AddAudioSource,
+        Decodable,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+};
+use iroh::EndpointId;
+use iroh_docs::NamespaceId;
+use parking_lot::Mutex;
+use serde_vrm::vrm0::BoneName;
+use unavi_avatar::bones::AvatarBones;
+use unavi_policy::space::Space;
+
+use crate::{
+    connection::ecs::agent::inbound::RemoteAgent,
+    voice::codec::{
+        FRAME_SAMPLES,
+        SAMPLE_RATE,
+    },
+};
+
+/// Audio held before a peer's voice starts playing, to ride out packets
+/// arriving unevenly.
+const PREROLL: usize = FRAME_SAMPLES * 3;
+/// Most audio held for one peer. Past this the oldest is dropped, so a stall
+/// never turns into lag.
+const MAX_BUFFERED: usize = FRAME_SAMPLES * 12;
+
+/// Where the voice sits until the avatar's head bone exists.
+const HEAD_HEIGHT: f32 = 1.6;
+
+#[derive(Default)]
+struct Buffer {
+    samples: VecDeque<f32>,
+    /// Where the peer's agent is instanced here; voice from anywhere else, or
+    /// from a peer with no agent, is dropped.
+    space:   Option<NamespaceId>,
+    playing: bool,
+}
+
+#[derive(Clone, Default)]
+struct VoiceBuffer(Arc<Mutex<Buffer>>);
+
+static BUFFERS: LazyLock<Mutex<HashMap<EndpointId, VoiceBuffer>>> = LazyLock::new(Mutex::default);
+
+fn buffer(peer: EndpointId) -> VoiceBuffer {
+    BUFFERS.lock().entry(peer).or_default().clone()
+}
+
+/// Queues decoded voice from `peer`, spoken in `space`.
+pub fn submit(peer: EndpointId, space: NamespaceId, pcm: &[f32]) {
+    let Some(buffer) = BUFFERS.lock().get(&peer).cloned() else {
+        return;
+    };
+    let mut buffer = buffer.0.lock();
+    if buffer.space != Some(space) {
+        return;
+    }
+    buffer.samples.extend(pcm);
+    let excess = buffer.samples.len().saturating_sub(MAX_BUFFERED);
+    buffer.samples.drain(..excess);
+}
+
+/// Drops whatever of `peer`'s voice is waiting to play.
+pub fn clear(peer: EndpointId) {
+    if let Some(buffer) = BUFFERS.lock().get(&peer) {
+        buffer.0.lock().samples.clear();
+    }
+}
+
+/// A peer's voice as Bevy plays it: endless, and silent whenever nothing has
+/// arrived.
+#[derive(Asset, TypePath)]
+pub struct VoiceStream(VoiceBuffer);
+
+impl Decodable for VoiceStream {
+    type Decoder = VoiceSource;
+
+    fn decoder(&self) -> Self::Decoder {
+        VoiceSource {
+            buffer: self.0.clone(),
+            chunk:  Vec::with_capacity(FRAME_SAMPLES),
+            at:     0,
+        }
+    }
+}
+
+/// Takes a frame at a time from the shared buffer, so the audio thread locks
+/// it once per frame rather than once per sample.
+pub struct VoiceSource {
+    buffer: VoiceBuffer,
+    chunk:  Vec<f32>,
+    at:     usize,
+}
+
+impl VoiceSource {
+    fn refill(&mut self) {
+        self.chunk.clear();
+        self.at = 0;
+
+        let mut buffer = self.buffer.0.lock();
+        if !buffer.playing && buffer.samples.len() >= PREROLL {
+            buffer.playing = true;
+        }
+        if buffer.samples.is_empty() {
+            buffer.playing = false;
+        }
+
+        if buffer.playing {
+            let len = buffer.samples.len().min(FRAME_SAMPLES);
+            self.chunk.extend(buffer.samples.drain(..len));
+        } else {
+            self.chunk.resize(FRAME_SAMPLES / 2, 0.0);
+        }
+    }
+}
+
+impl Iterator for VoiceSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.at >= self.chunk.len() {
+            self.refill();
+        }
+        let sample = self.chunk[self.at];
+        self.at += 1;
+        Some(sample)
+    }
+}
+
+impl rodio::Source for VoiceSource {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> rodio::ChannelCount {
+        NonZero::new(1).expect("one channel")
+    }
+
+    fn sample_rate(&self) -> rodio::SampleRate {
+        NonZero::new(SAMPLE_RATE).expect("nonzero sample rate")
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+pub fn register(app: &mut App) {
+    app.add_audio_source::<VoiceStream>();
+}
+
+/// Marks the entity playing a remote agent's voice.
+#[derive(Component)]
+pub struct VoiceEmitter(pub EndpointId);
+
+/// Keeps one voice emitter on each remote agent's head, and lets through only
+/// voice spoken in the space that agent is instanced in.
+pub fn attach_voice_emitters(
+    mut assets: ResMut<Assets<VoiceStream>>,
+    remotes: Query<(Entity, &RemoteAgent, &ChildOf, Option<&AvatarBones>)>,
+    spaces: Query<&Space>,
+    emitters: Query<(Entity, &VoiceEmitter, &ChildOf)>,
+    mut commands: Commands,
+) {
+    let mut placed = HashMap::<EndpointId, Option<NamespaceId>>::default();
+
+    for (entity, remote, child_of, bones) in &remotes {
+        placed.insert(remote.0, spaces.get(child_of.parent()).ok().map(|s| s.0));
+
+        let head = bones.and_then(|bones| bones.get(&BoneName::Head)).copied();
+        let (parent, offset) = head.map_or_else(
+            || (entity, Transform::from_xyz(0.0, HEAD_HEIGHT, 0.0)),
+            |head| (head, Transform::IDENTITY),
+        );
+
+        match emitters.iter().find(|(_, e, _)| e.0 == remote.0) {
+            Some((emitter, _, at)) if at.parent() != parent => {
+                commands.entity(emitter).insert((offset, ChildOf(parent)));
+            }
+            Some(_) => {}
+            None => {
+                let stream = assets.add(VoiceStream(buffer(remote.0)));
+                commands.spawn((
+                    VoiceEmitter(remote.0),
+                    AudioPlayer(stream),
+                    PlaybackSettings {
+                        spatial: true,
+                        ..PlaybackSettings::ONCE
+                    },
+                    offset,
+                    ChildOf(parent),
+                ));
+            }
+        }
+    }
+
+    // A buffer lives as long as the agent, and so the emitter playing it.
+    BUFFERS.lock().retain(|peer, buffer| {
+        let Some(space) = placed.get(peer) else {
+            return false;
+        };
+        let mut buffer = buffer.0.lock();
+        if buffer.space != *space {
+            buffer.space = *space;
+            buffer.samples.clear();
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_waits_for_preroll_then_drains() {
+        let buffer = VoiceBuffer::default();
+        let mut source = VoiceStream(buffer.clone()).decoder();
+
+        buffer.0.lock().samples.extend([0.5; FRAME_SAMPLES]);
+        assert!(
+            source
+                .by_ref()
+                .take(FRAME_SAMPLES)
+                .all(|s| s.abs() < f32::EPSILON),
+            "too little to start"
+        );
+
+        buffer.0.lock().samples.extend([0.5; PREROLL]);
+        let heard = source
+            .by_ref()
+            .take(FRAME_SAMPLES * 4)
+            .filter(|s| (s - 0.5).abs() < f32::EPSILON)
+            .count();
+        assert_eq!(heard, FRAME_SAMPLES + PREROLL);
+        assert!(buffer.0.lock().samples.is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+-- Resumable uploads in progress. Pieces are charged to the owner as they
+-- land; an upload left idle past `expires` is dropped and refunded. `pieces`
+-- holds the chaining value of every piece, concatenated, and is empty for an
+-- upload that fits in one piece.
+CREATE TABLE uploads (
+    owner TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    pieces BLOB NOT NULL,
+    received INTEGER NOT NULL DEFAULT 0,
+    expires INTEGER NOT NULL,
+    PRIMARY KEY (owner, hash)
+);
+
+-- Received pieces, held until the last one arrives and the blob is assembled.
+CREATE TABLE upload_pieces (
+    owner TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    idx INTEGER NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (owner, hash, idx)
+);
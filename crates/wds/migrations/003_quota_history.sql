@@ -0,0 +1,9 @@
+-- Each owner's bytes in use as of the last charge or refund on each UTC day,
+-- for the usage history in a quota report. `day` is the unix timestamp of
+-- the day's midnight. Days past the history window are trimmed by GC.
+CREATE TABLE quota_history (
+    owner TEXT NOT NULL,
+    day INTEGER NOT NULL,
+    bytes_used INTEGER NOT NULL,
+    PRIMARY KEY (owner, day)
+);
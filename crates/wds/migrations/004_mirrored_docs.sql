@@ -0,0 +1,8 @@
+-- Namespaces hosted here because a peer store this one mirrors hosts them.
+-- Each is hosted under the store's own DID, charged to it, and unhosted once
+-- no mirrored peer lists it any longer.
+CREATE TABLE mirrored_docs (
+    ns TEXT NOT NULL,
+    peer TEXT NOT NULL,
+    PRIMARY KEY (ns, peer)
+);
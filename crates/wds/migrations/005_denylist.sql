@@ -0,0 +1,9 @@
+-- Content this store refuses: blob hashes, namespaces and DIDs. `source` is
+-- empty for the operator's own entries, or the DID of the published denylist
+-- an entry was subscribed from.
+CREATE TABLE denylist (
+    kind TEXT NOT NULL,
+    value TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (kind, value, source)
+);
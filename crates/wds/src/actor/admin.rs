@@ -0,0 +1,96 @@
+use iroh_docs::NamespaceId;
+use xdid::core::did::Did;
+
+use crate::{
+    actor::Actor,
+    control::{
+        ForceUnhost,
+        GetUserQuota,
+        HostedInfo,
+        ListHosted,
+        ListPins,
+        ListUsers,
+        PinInfo,
+        PurgeDid,
+        QuotaInfo,
+        SetUserQuota,
+        UserQuota,
+    },
+};
+
+/// Operator calls; the host refuses each unless this actor's DID is one of
+/// its operators.
+impl Actor {
+    /// Every user the host holds a quota for.
+    pub async fn list_users(&self) -> anyhow::Result<Vec<UserQuota>> {
+        let users = self
+            .call("list users", || self.control_client.rpc(ListUsers {}))
+            .await?;
+
+        Ok(users)
+    }
+
+    pub async fn user_quota(&self, did: Did) -> anyhow::Result<QuotaInfo> {
+        let info = self
+            .call("get user quota", || {
+                self.control_client.rpc(GetUserQuota { did: did.clone() })
+            })
+            .await?;
+
+        Ok(info)
+    }
+
+    pub async fn set_user_quota(&self, did: Did, quota_bytes: u64) -> anyhow::Result<()> {
+        self.call("set user quota", || {
+            self.control_client.rpc(SetUserQuota {
+                did: did.clone(),
+                quota_bytes,
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// The blob pins `did` holds at the host.
+    pub async fn user_pins(&self, did: Did) -> anyhow::Result<Vec<PinInfo>> {
+        let pins = self
+            .call("list pins", || {
+                self.control_client.rpc(ListPins { did: did.clone() })
+            })
+            .await?;
+
+        Ok(pins)
+    }
+
+    /// The docs the host replicates on behalf of `did`.
+    pub async fn user_hosted(&self, did: Did) -> anyhow::Result<Vec<HostedInfo>> {
+        let hosted = self
+            .call("list hosted", || {
+                self.control_client.rpc(ListHosted { did: did.clone() })
+            })
+            .await?;
+
+        Ok(hosted)
+    }
+
+    /// Stops the host replicating `ns` for everyone hosting it.
+    pub async fn force_unhost(&self, ns: NamespaceId) -> anyhow::Result<()> {
+        self.call("force unhost", || {
+            self.control_client.rpc(ForceUnhost { ns })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes everything `did` holds at the host.
+    pub async fn purge_did(&self, did: Did) -> anyhow::Result<()> {
+        self.call("purge", || {
+            self.control_client.rpc(PurgeDid { did: did.clone() })
+        })
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{
+    Read,
+    Write,
+};
+
+use anyhow::Context;
+use xdid::core::did::Did;
+
+use crate::{
+    actor::Actor,
+    archive::{
+        self,
+        ImportSummary,
+    },
+    control::{
+        ExportArchive,
+        ImportArchive,
+    },
+};
+
+impl Actor {
+    /// Writes an archive of everything the host holds for `did` to `out`:
+    /// this actor's own if `None`, anyone's for an operator.
+    ///
+    /// Doc write secrets are included only for the host's operators and its
+    /// own user.
+    pub async fn export_archive(
+        &self,
+        did: Option<Did>,
+        out: &mut (impl Write + Send),
+    ) -> anyhow::Result<()> {
+        self.authenticate().await.context("auth")?;
+
+        let mut rx = self
+            .control_client
+            .server_streaming(ExportArchive { did }, 16)
+            .await
+            .context("init export")?;
+
+        while let Some(record) = rx.recv().await? {
+            let record = record.map_err(|e| self.failed("export", e))?;
+            archive::write(out, &record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Restores an archive read from `input` under the DID it was exported
+    /// for, which must be this actor's own unless it is an operator.
+    pub async fn import_archive(
+        &self,
+        input: &mut (impl Read + Send),
+    ) -> anyhow::Result<ImportSummary> {
+        self.authenticate().await.context("auth")?;
+
+        let (tx, rx) = self
+            .control_client
+            .client_streaming(ImportArchive {}, 16)
+            .await
+            .context("init import")?;
+
+        while let Some(record) = archive::read(input)? {
+            // A host refusing the import stops reading; its reason is in the
+            // reply.
+            if tx.send(record).await.is_err() {
+                break;
+            }
+        }
+        drop(tx);
+
+        let summary = rx.await?.map_err(|e| self.failed("import", e))?;
+
+        Ok(summary)
+    }
+}
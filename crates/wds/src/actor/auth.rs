@@ -1,38 +1,88 @@
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+
 use crate::{
-    SessionToken,
     actor::Actor,
     auth::client::authenticate,
+    control::ActAs,
+    error::ApiError,
 };
 
 impl Actor {
-    /// The actor's session at its host, establishing one if needed.
+    /// Authenticates this actor's link to its host, if it is not already.
     ///
-    /// Exposed so a service co-deployed with the store can authorize against
-    /// the same session rather than running its own handshake.
-    pub async fn session(&self) -> anyhow::Result<SessionToken> {
-        self.authenticate().await
-    }
-
-    pub(crate) async fn authenticate(&self) -> anyhow::Result<SessionToken> {
-        let session = self.session.lock().await;
+    /// The host binds the DID to this endpoint, and the control link then
+    /// names it as the one it acts as, so another actor sharing the endpoint
+    /// never acts as this one. This only has to run again once the link has
+    /// dropped. Exposed so a service co-deployed with
+    /// the store can authorize against the same link rather than running its
+    /// own handshake.
+    pub async fn authenticate(&self) -> anyhow::Result<()> {
+        if self.authenticated.load(Ordering::Acquire) {
+            return Ok(());
+        }
 
         // Hold the lock while authenticating so concurrent calls share one
         // handshake.
-        if let Some(s) = session.get().copied() {
-            return Ok(s);
+        let handshake = self.handshake.lock().await;
+        if self.authenticated.load(Ordering::Acquire) {
+            return Ok(());
         }
 
-        let s = authenticate(
+        authenticate(
             self.identity().did().clone(),
             self.identity().signing_key(),
             self.host.id,
             &self.auth_client,
         )
         .await?;
+        self.control_client
+            .rpc(ActAs {
+                did: self.identity().did().clone(),
+            })
+            .await?
+            .map_err(|e| anyhow::anyhow!("act as failed: {e}"))?;
+
+        self.authenticated.store(true, Ordering::Release);
+        drop(handshake);
 
-        session.set(s)?;
-        drop(session);
+        Ok(())
+    }
+
+    /// Authenticates, then makes the call `send` sends. A host that no longer
+    /// knows the link (it dropped and was redialled) refuses with
+    /// `Unauthenticated`, so the call is sent once more after authenticating
+    /// afresh.
+    pub(crate) async fn call<T, E, Fut>(
+        &self,
+        what: &str,
+        send: impl Fn() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = Result<Result<T, ApiError>, E>>,
+        anyhow::Error: From<E>,
+    {
+        self.authenticate().await.context("auth")?;
+        match send().await? {
+            Ok(value) => return Ok(value),
+            Err(ApiError::Unauthenticated) => {
+                self.authenticated.store(false, Ordering::Release);
+            }
+            Err(err) => return Err(anyhow::anyhow!("{what} failed: {err}")),
+        }
 
-        Ok(s)
+        self.authenticate().await.context("auth")?;
+        send().await?.map_err(|e| self.failed(what, e))
+    }
+
+    /// The error for a refused `what`. A host that no longer knows the link
+    /// (it dropped and was redialled) refuses with `Unauthenticated`, so the
+    /// next call authenticates afresh.
+    pub(crate) fn failed(&self, what: &str, err: ApiError) -> anyhow::Error {
+        if matches!(err, ApiError::Unauthenticated) {
+            self.authenticated.store(false, Ordering::Release);
+        }
+        anyhow::anyhow!("{what} failed: {err}")
     }
 }
@@ -0,0 +1,59 @@
+use crate::{
+    actor::Actor,
+    control::{
+        Deny,
+        GetDenylist,
+        ListDenied,
+        Undeny,
+    },
+    denylist::{
+        DeniedEntry,
+        DenyEntry,
+        PublishedDenylist,
+    },
+    signed_bytes::SignedBytes,
+};
+
+impl Actor {
+    /// The denylist the host publishes, signed by its user, if it has one.
+    pub async fn get_denylist(&self) -> anyhow::Result<Option<SignedBytes<PublishedDenylist>>> {
+        let published = self
+            .call("get denylist", || self.control_client.rpc(GetDenylist {}))
+            .await?;
+
+        Ok(published)
+    }
+
+    /// Every entry the host refuses by. Operator only.
+    pub async fn list_denied(&self) -> anyhow::Result<Vec<DeniedEntry>> {
+        let entries = self
+            .call("list denied", || self.control_client.rpc(ListDenied {}))
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Has the host refuse `entry` from now on. Operator only.
+    pub async fn deny(&self, entry: DenyEntry) -> anyhow::Result<()> {
+        self.call("deny", || {
+            self.control_client.rpc(Deny {
+                entry: entry.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lifts one of the host operators' own entries. Operator only.
+    pub async fn undeny(&self, entry: DenyEntry) -> anyhow::Result<()> {
+        self.call("undeny", || {
+            self.control_client.rpc(Undeny {
+                entry: entry.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+}
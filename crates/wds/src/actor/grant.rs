@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use iroh_docs::NamespaceId;
+use time::OffsetDateTime;
+use xdid::core::did::Did;
+
+use crate::{
+    actor::Actor,
+    control::{
+        EntryWrite,
+        WriteEntries,
+    },
+    grant::{
+        Grant,
+        Scope,
+    },
+    signed_bytes::{
+        Signable,
+        SignedBytes,
+    },
+};
+
+impl Actor {
+    /// Signs a grant letting `grantee` act for this actor, limited to `scopes`
+    /// (and to `ns`, if given), for `ttl`.
+    pub fn issue_grant(
+        &self,
+        grantee: Did,
+        ns: Option<NamespaceId>,
+        scopes: Vec<Scope>,
+        ttl: Duration,
+    ) -> anyhow::Result<SignedBytes<Grant>> {
+        Grant {
+            issuer: self.identity().did().clone(),
+            grantee,
+            ns,
+            scopes,
+            expires: (OffsetDateTime::now_utc() + ttl).unix_timestamp(),
+        }
+        .sign(self.identity().signing_key())
+    }
+
+    /// This actor, presenting `grant` with every call so that it acts for the
+    /// grant's issuer. Shares the link, which stays authenticated as the
+    /// grantee.
+    #[must_use]
+    pub fn acting_for(&self, grant: SignedBytes<Grant>) -> Self {
+        Self {
+            grant: Some(grant),
+            ..self.clone()
+        }
+    }
+
+    /// Asks the host to write entries into a doc it hosts, on behalf of the
+    /// doc's owner. The entries are authored by the host, so this only works
+    /// for a namespace the host holds writable: one it created, not one it
+    /// was asked to host from elsewhere.
+    pub async fn write_entries(
+        &self,
+        ns: NamespaceId,
+        writes: Vec<EntryWrite>,
+    ) -> anyhow::Result<()> {
+        self.call("write entries", || {
+            self.control_client.rpc(WriteEntries {
+                grant: self.grant.clone(),
+                ns,
+                writes: writes.clone(),
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,37 +1,48 @@
 use std::{
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::AtomicBool,
+    },
     time::Duration,
 };
 
 use anyhow::Context;
 use blake3::Hash;
 use bytes::Bytes;
-use iroh::EndpointAddr;
+use iroh::{
+    Endpoint,
+    EndpointAddr,
+};
 use iroh_docs::NamespaceId;
 use irpc::Client;
 use time::OffsetDateTime;
-use tokio::sync::{
-    Mutex,
-    OnceCell,
-};
+use tokio::sync::Mutex;
 
 use crate::{
-    SessionToken,
     auth::AuthService,
     control::{
         BlobExists,
         ControlService,
         GetQuota,
         HostDoc,
+        ListMirrored,
         PinBlob,
-        QuotaInfo,
+        QuotaReport,
         UnhostDoc,
+        UnpinBlob,
         UploadBlob,
     },
+    grant::Grant,
     identity::Identity,
+    signed_bytes::SignedBytes,
 };
 
+mod admin;
+mod archive;
 mod auth;
+mod denylist;
+mod grant;
+mod upload;
 
 /// Authenticated actor for WDS control-plane operations.
 ///
@@ -44,7 +55,12 @@ pub struct Actor {
     host:           EndpointAddr,
     control_client: Client<ControlService>,
     auth_client:    Client<AuthService>,
-    session:        Arc<Mutex<OnceCell<SessionToken>>>,
+    /// Whether the host holds this actor's link as authenticated. Shared by
+    /// clones, which use the same link.
+    authenticated:  Arc<AtomicBool>,
+    handshake:      Arc<Mutex<()>>,
+    /// Presented with every call that accepts one; see [`Actor::acting_for`].
+    grant:          Option<SignedBytes<Grant>>,
 }
 
 impl Actor {
@@ -59,10 +75,22 @@ impl Actor {
             host,
             control_client,
             auth_client,
-            session: Arc::new(Mutex::new(OnceCell::default())),
+            authenticated: Arc::default(),
+            handshake: Arc::default(),
+            grant: None,
         }
     }
 
+    /// An actor reaching `host` over `endpoint`, for a caller with no store of
+    /// its own; see [`crate::DataStore::remote_actor`] otherwise.
+    #[must_use]
+    pub fn remote(endpoint: &Endpoint, identity: Arc<Identity>, host: EndpointAddr) -> Self {
+        let control_client =
+            irpc_iroh::client(endpoint.clone(), host.clone(), crate::control::ALPN);
+        let auth_client = irpc_iroh::client(endpoint.clone(), host.clone(), crate::auth::ALPN);
+        Self::new(identity, host, control_client, auth_client)
+    }
+
     #[must_use]
     pub const fn identity(&self) -> &Arc<Identity> {
         &self.identity
@@ -75,83 +103,112 @@ impl Actor {
 
     /// Uploads bytes to the WDS as a blob, returning the blob hash.
     pub async fn upload_blob(&self, bytes: Bytes) -> anyhow::Result<Hash> {
-        let s = self.authenticate().await.context("auth")?;
-
-        let (tx, rx) = self
-            .control_client
-            .client_streaming(UploadBlob { s }, 4)
-            .await
-            .context("init upload blob")?;
-
-        tx.send(bytes).await.context("send bytes")?;
-        drop(tx);
-
-        let hash = rx
-            .await?
-            .map_err(|e| anyhow::anyhow!("upload failed: {e}"))?;
+        let hash = self
+            .call("upload", || async {
+                let (tx, rx) = self
+                    .control_client
+                    .client_streaming(
+                        UploadBlob {
+                            grant: self.grant.clone(),
+                        },
+                        4,
+                    )
+                    .await
+                    .context("init upload blob")?;
+
+                tx.send(bytes.clone()).await.context("send bytes")?;
+                drop(tx);
+
+                anyhow::Ok(rx.await?)
+            })
+            .await?;
 
         Ok(hash)
     }
 
     /// Pins a blob at this actor's host for the given duration.
     pub async fn pin_blob(&self, hash: Hash, ttl: Duration) -> anyhow::Result<()> {
-        let s = self.authenticate().await.context("auth")?;
         let expires = (OffsetDateTime::now_utc() + ttl).unix_timestamp();
 
-        self.control_client
-            .rpc(PinBlob { s, hash, expires })
-            .await?
-            .map_err(|e| anyhow::anyhow!("pin blob failed: {e}"))?;
+        self.call("pin blob", || {
+            self.control_client.rpc(PinBlob {
+                grant: self.grant.clone(),
+                hash,
+                expires,
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drops a pin at this actor's host now, refunding its bytes.
+    pub async fn unpin_blob(&self, hash: Hash) -> anyhow::Result<()> {
+        self.call("unpin blob", || {
+            self.control_client.rpc(UnpinBlob {
+                grant: self.grant.clone(),
+                hash,
+            })
+        })
+        .await?;
 
         Ok(())
     }
 
     /// Checks if a blob exists at this actor's host.
     pub async fn blob_exists(&self, hash: Hash) -> anyhow::Result<bool> {
-        let s = self.authenticate().await.context("auth")?;
-
         let exists = self
-            .control_client
-            .rpc(BlobExists { s, hash })
-            .await?
-            .map_err(|e| anyhow::anyhow!("blob exists check failed: {e}"))?;
+            .call("blob exists check", || {
+                self.control_client.rpc(BlobExists { hash })
+            })
+            .await?;
 
         Ok(exists)
     }
 
-    /// Asks this actor's host to replicate a doc, charged to the actor's quota.
+    /// Asks this actor's host to replicate a doc, charged to the actor's quota
+    /// (or, when [acting for](Actor::acting_for) someone, to theirs).
     pub async fn host_doc(&self, ns: NamespaceId) -> anyhow::Result<()> {
-        let s = self.authenticate().await.context("auth")?;
-
-        self.control_client
-            .rpc(HostDoc { s, ns })
-            .await?
-            .map_err(|e| anyhow::anyhow!("host doc failed: {e}"))?;
+        self.call("host doc", || {
+            self.control_client.rpc(HostDoc {
+                grant: self.grant.clone(),
+                ns,
+            })
+        })
+        .await?;
 
         Ok(())
     }
 
     /// Asks this actor's host to stop replicating a doc.
     pub async fn unhost_doc(&self, ns: NamespaceId) -> anyhow::Result<()> {
-        let s = self.authenticate().await.context("auth")?;
-
-        self.control_client
-            .rpc(UnhostDoc { s, ns })
-            .await?
-            .map_err(|e| anyhow::anyhow!("unhost doc failed: {e}"))?;
+        self.call("unhost doc", || {
+            self.control_client.rpc(UnhostDoc {
+                grant: self.grant.clone(),
+                ns,
+            })
+        })
+        .await?;
 
         Ok(())
     }
 
-    /// Reports the actor's quota usage at this host.
-    pub async fn get_quota(&self) -> anyhow::Result<QuotaInfo> {
-        let s = self.authenticate().await.context("auth")?;
+    /// Lists every namespace the host holds for its users, for a store it
+    /// allows to mirror it.
+    pub async fn list_mirrored(&self) -> anyhow::Result<Vec<NamespaceId>> {
+        let namespaces = self
+            .call("list mirrored", || self.control_client.rpc(ListMirrored {}))
+            .await?;
+
+        Ok(namespaces)
+    }
 
+    /// Reports the actor's quota usage at this host, itemised by hosted doc
+    /// and pinned blob, with its recent history.
+    pub async fn get_quota(&self) -> anyhow::Result<QuotaReport> {
         let info = self
-            .control_client
-            .rpc(GetQuota { s })
-            .await?
-            .map_err(|e| anyhow::anyhow!("get quota failed: {e}"))?;
+            .call("get quota", || self.control_client.rpc(GetQuota {}))
+            .await?;
 
         Ok(info)
     }
@@ -0,0 +1,89 @@
+use blake3::Hash;
+use bytes::Bytes;
+
+use crate::{
+    actor::Actor,
+    control::{
+        BeginUpload,
+        UploadPiece,
+        UploadProgress,
+    },
+    pieces::{
+        self,
+        ChainingValue,
+        PIECE_LEN,
+    },
+};
+
+impl Actor {
+    /// Uploads bytes in verified pieces, returning the blob hash.
+    ///
+    /// Resumable: after a dropped connection, calling this again with the same
+    /// bytes picks up from the last piece the host received.
+    pub async fn upload_blob_resumable(&self, bytes: Bytes) -> anyhow::Result<Hash> {
+        let hash = blake3::hash(&bytes);
+        let size = bytes.len() as u64;
+
+        let mut progress = self
+            .begin_upload(hash, size, pieces::chaining_values(&bytes))
+            .await?;
+
+        while !progress.complete {
+            let index = progress.received / PIECE_LEN;
+            let start = index * PIECE_LEN;
+            let end = start + pieces::len(size, index);
+            let data = bytes.slice(start as usize..end as usize);
+
+            let next = self.upload_piece(hash, index, data).await?;
+            if !next.complete && next.received <= progress.received {
+                anyhow::bail!("upload stalled at {} of {size} bytes", next.received);
+            }
+            progress = next;
+        }
+
+        Ok(hash)
+    }
+
+    /// Opens an upload of `size` bytes hashing to `hash`, or reports how far a
+    /// previous one got. `cvs` is [`pieces::chaining_values`] of the content.
+    pub async fn begin_upload(
+        &self,
+        hash: Hash,
+        size: u64,
+        cvs: Vec<ChainingValue>,
+    ) -> anyhow::Result<UploadProgress> {
+        let progress = self
+            .call("begin upload", || {
+                self.control_client.rpc(BeginUpload {
+                    grant: self.grant.clone(),
+                    hash,
+                    size,
+                    pieces: cvs.clone(),
+                })
+            })
+            .await?;
+
+        Ok(progress)
+    }
+
+    /// Sends piece `index` of an upload opened with [`Actor::begin_upload`].
+    pub async fn upload_piece(
+        &self,
+        hash: Hash,
+        index: u64,
+        data: Bytes,
+    ) -> anyhow::Result<UploadProgress> {
+        let progress = self
+            .call("upload piece", || {
+                self.control_client.rpc(UploadPiece {
+                    grant: self.grant.clone(),
+                    hash,
+                    index,
+                    data: data.clone(),
+                })
+            })
+            .await?;
+
+        Ok(progress)
+    }
+}
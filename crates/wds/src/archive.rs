@@ -0,0 +1,126 @@
+//! Portable archives of everything a store holds for one DID.
+//!
+//! An archive is a stream of [`Record`]s: a [`Record::Header`], then the
+//! owner's quota, the content of every pinned blob and doc entry, and last the
+//! pins and docs that reference it. On disk each record is a little-endian
+//! `u32` length followed by its postcard encoding.
+//!
+//! Importing re-hosts everything under the archive's owner. A doc the archive
+//! carries a write secret for has its entries written back; any other doc is
+//! re-hosted read-only and its entries return by sync, from the exporting
+//! store first.
+
+use std::io::{
+    self,
+    Read,
+    Write,
+};
+
+use anyhow::Context;
+use blake3::Hash;
+use bytes::Bytes;
+use iroh::EndpointId;
+use iroh_docs::{
+    AuthorId,
+    NamespaceId,
+    NamespaceSecret,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use xdid::core::did::Did;
+
+use crate::control::PinInfo;
+
+pub const VERSION: u32 = 1;
+
+/// Blob content is split into [`Record::Data`] chunks of at most this size.
+pub const CHUNK_LEN: usize = 1024 * 1024;
+
+/// Larger frames are refused on read rather than allocated.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Record {
+    /// First in every archive.
+    Header {
+        version:  u32,
+        owner:    Did,
+        /// The exporting store, which a trusted import syncs read-only docs
+        /// from.
+        source:   EndpointId,
+        exported: i64,
+    },
+    /// Restored only by an operator's import.
+    Quota {
+        quota_bytes: i64,
+    },
+    /// Content to follow as [`Record::Data`] chunks totalling `size`.
+    Blob {
+        hash: Hash,
+        size: u64,
+    },
+    Data(Bytes),
+    Pin(PinInfo),
+    Doc(ArchivedDoc),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedDoc {
+    pub ns:      NamespaceId,
+    /// Present only when the exporting store could write the doc and the
+    /// export was made by one of its operators or its own user.
+    pub secret:  Option<NamespaceSecret>,
+    /// Whether the owner hosted the doc, rather than the store merely holding
+    /// it for its own user.
+    pub hosted:  bool,
+    /// The latest entry per key.
+    pub entries: Vec<ArchivedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    pub key:       Bytes,
+    pub author:    AuthorId,
+    pub hash:      Hash,
+    pub size:      u64,
+    pub timestamp: u64,
+}
+
+/// What an import restored.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub blobs:   usize,
+    pub pins:    usize,
+    pub docs:    usize,
+    /// Entries written back into docs imported with their write secret.
+    pub entries: usize,
+    /// Blobs, pins and docs left out because the store denies them.
+    pub denied:  usize,
+}
+
+/// Appends one record to an archive file.
+pub fn write(out: &mut impl Write, record: &Record) -> anyhow::Result<()> {
+    let bytes = postcard::to_stdvec(record)?;
+    let len = u32::try_from(bytes.len()).context("record too large")?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads the next record of an archive file, or `None` at its end.
+pub fn read(input: &mut impl Read) -> anyhow::Result<Option<Record>> {
+    let mut len = [0; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    anyhow::ensure!(len <= MAX_FRAME_LEN, "record of {len} bytes is too large");
+
+    let mut bytes = vec![0; len];
+    input.read_exact(&mut bytes).context("truncated record")?;
+    Ok(Some(postcard::from_bytes(&bytes)?))
+}
@@ -10,7 +10,6 @@ use xdid::{
 };
 
 use crate::{
-    SessionToken,
     auth::{
         AnswerChallenge,
         AuthService,
@@ -20,12 +19,13 @@ use crate::{
     signed_bytes::Signable,
 };
 
+/// Proves `did` to `host`, which binds it to this client's end of the link.
 pub async fn authenticate<S>(
     did: Did,
     signer: &S,
     host: EndpointId,
     auth_client: &Client<AuthService>,
-) -> anyhow::Result<SessionToken>
+) -> anyhow::Result<()>
 where
     S: Signer + Sync,
 {
@@ -43,13 +43,13 @@ where
 
     let payload = challenge.sign(signer).context("sign challenge")?;
 
-    let Some(s) = auth_client
+    let authenticated = auth_client
         .rpc(AnswerChallenge(payload))
         .await
-        .context("answer challenge rpc")?
-    else {
+        .context("answer challenge rpc")?;
+    if !authenticated {
         bail!("challenge answer failed")
-    };
+    }
 
-    Ok(s)
+    Ok(())
 }
@@ -1,29 +1,39 @@
 //! Challenge-response DID authentication, scoped to WDS operations rather
-//! than general connection-level auth.
+//! than general connection-level auth. A proved DID is bound to the peer that
+//! proved it; see [`crate::peer`].
 
 use std::{
     fmt::Debug,
     sync::Arc,
 };
 
-use iroh::EndpointId;
+use iroh::{
+    EndpointId,
+    endpoint::Connection,
+    protocol::{
+        AcceptError,
+        ProtocolHandler,
+    },
+};
 use irpc::{
     Client,
     channel::oneshot,
     rpc_requests,
 };
-use irpc_iroh::IrohProtocol;
 use scc::HashCache;
 use serde::{
     Deserialize,
     Serialize,
 };
-use tracing::error;
+use tracing::{
+    debug,
+    error,
+};
 use xdid::core::did::Did;
 
 use crate::{
-    SessionToken,
     StoreContext,
+    peer::Peer,
     signed_bytes::{
         Signable,
         SignedBytes,
@@ -34,21 +44,86 @@ pub mod client;
 pub mod jwk;
 mod server;
 
-pub const ALPN: &[u8] = b"wds/auth";
+pub const ALPN: &[u8] = b"wds/auth/1";
 
-pub fn protocol(ctx: Arc<StoreContext>) -> (Client<AuthService>, IrohProtocol<AuthService>) {
-    let (tx, mut rx) = irpc::channel::mpsc::channel(16);
+/// Serves `wds/auth`, binding each DID proved to the [`Peer`] that proved it.
+#[derive(Clone)]
+pub struct AuthProtocol {
+    ctx:   Arc<StoreContext>,
+    state: Arc<HandlerState>,
+}
 
-    n0_future::task::spawn(async move {
-        while let Err(e) = handle_requests(&ctx, &mut rx).await {
-            error!("Error handling request: {e:?}");
-        }
-    });
+impl Debug for AuthProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthProtocol").finish_non_exhaustive()
+    }
+}
+
+pub fn protocol(ctx: Arc<StoreContext>) -> AuthProtocol {
+    AuthProtocol {
+        ctx,
+        state: Arc::new(HandlerState {
+            nonces: HashCache::with_capacity(32, 2048),
+        }),
+    }
+}
+
+impl AuthProtocol {
+    /// A client of this store for one local peer, linked while any clone of
+    /// it is alive.
+    pub fn local_client(&self, peer: Peer) -> Client<AuthService> {
+        let (tx, mut rx) = irpc::channel::mpsc::channel(16);
+        let this = self.clone();
+
+        n0_future::task::spawn(async move {
+            this.ctx.link(peer).await;
+            loop {
+                match rx.recv().await {
+                    Ok(Some(msg)) => this.spawn_message(peer, msg),
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Error receiving request: {e:?}");
+                        break;
+                    }
+                }
+            }
+            this.ctx.unlink(peer).await;
+        });
+
+        Client::local(tx)
+    }
+
+    fn spawn_message(&self, peer: Peer, msg: AuthMessage) {
+        let ctx = Arc::clone(&self.ctx);
+        let state = Arc::clone(&self.state);
+
+        n0_future::task::spawn(async move {
+            if let Err(e) = server::handle_message(ctx, state, peer, msg).await {
+                error!("Error handling message: {e:?}");
+            }
+        });
+    }
+}
 
-    let client = Client::local(tx);
-    let local_sender = client.as_local().expect("local client");
+impl ProtocolHandler for AuthProtocol {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let peer = Peer::Remote(connection.remote_id());
+        self.ctx.link(peer).await;
+
+        loop {
+            match irpc_iroh::read_request::<AuthService>(&connection).await {
+                Ok(Some(msg)) => self.spawn_message(peer, msg),
+                Ok(None) => break,
+                Err(e) => {
+                    debug!(%e, "auth connection closed");
+                    break;
+                }
+            }
+        }
 
-    (client, IrohProtocol::with_sender(local_sender))
+        self.ctx.unlink(peer).await;
+        Ok(())
+    }
 }
 
 type Nonce = [u8; 32];
@@ -60,8 +135,9 @@ pub enum AuthService {
     #[wrap(RequestChallenge)]
     RequestChallenge(Did),
     /// Answer a challenge, signing the nonce with an authentication VC or a
-    /// WDS service endpoint key.
-    #[rpc(tx=oneshot::Sender<Option<SessionToken>>)]
+    /// WDS service endpoint key. Replies whether the peer is now
+    /// authenticated as the challenge's DID.
+    #[rpc(tx=oneshot::Sender<bool>)]
     #[wrap(AnswerChallenge)]
     AnswerChallenge(SignedBytes<Challenge>),
 }
@@ -99,25 +175,3 @@ struct Pending {
 struct HandlerState {
     nonces: scc::HashCache<Nonce, Pending>,
 }
-
-async fn handle_requests(
-    ctx: &Arc<StoreContext>,
-    rx: &mut irpc::channel::mpsc::Receiver<AuthMessage>,
-) -> anyhow::Result<()> {
-    let state = Arc::new(HandlerState {
-        nonces: HashCache::with_capacity(32, 2048),
-    });
-
-    while let Some(msg) = rx.recv().await? {
-        let ctx = Arc::clone(ctx);
-        let state = Arc::clone(&state);
-
-        n0_future::task::spawn(async move {
-            if let Err(e) = server::handle_message(ctx, state, msg).await {
-                error!("Error handling message: {e:?}");
-            }
-        });
-    }
-
-    Ok(())
-}
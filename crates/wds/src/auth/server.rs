@@ -22,9 +22,6 @@ use xdid::core::{
 };
 
 use crate::{
-    ConnectionState,
-    SESSION_TTL,
-    SessionToken,
     StoreContext,
     WDS_SERVICE_TYPE,
     auth::{
@@ -38,6 +35,7 @@ use crate::{
         RequestChallenge,
         jwk::verify_jwk_signature,
     },
+    peer::Peer,
     resolve::resolve,
     signed_bytes::SignedBytes,
 };
@@ -47,6 +45,7 @@ const NONCE_TTL: Duration = Duration::from_mins(3);
 pub async fn handle_message(
     ctx: Arc<StoreContext>,
     state: Arc<HandlerState>,
+    peer: Peer,
     msg: AuthMessage,
 ) -> anyhow::Result<()> {
     match msg {
@@ -54,7 +53,7 @@ pub async fn handle_message(
             request_challenge(state, inner, tx).await
         }
         AuthMessage::AnswerChallenge(WithChannels { inner, tx, .. }) => {
-            answer_challenge(ctx, state, inner, tx).await
+            answer_challenge(ctx, state, peer, inner, tx).await
         }
     }
 }
@@ -86,40 +85,32 @@ async fn request_challenge(
 async fn answer_challenge(
     ctx: Arc<StoreContext>,
     state: Arc<HandlerState>,
+    peer: Peer,
     AnswerChallenge(signed): AnswerChallenge,
-    tx: oneshot::Sender<Option<SessionToken>>,
+    tx: oneshot::Sender<bool>,
 ) -> anyhow::Result<()> {
     let Some(did) = redeem_nonce(&state, &signed.payload()?, &ctx).await else {
-        tx.send(None).await?;
+        tx.send(false).await?;
         return Ok(());
     };
 
     let Some(doc) = resolve(&did).await else {
-        tx.send(None).await?;
+        tx.send(false).await?;
         return Ok(());
     };
     if !signature_is_authorized(&doc, &signed) {
         debug!("signature not from valid source");
-        tx.send(None).await?;
+        tx.send(false).await?;
         return Ok(());
     }
 
-    let mut token = SessionToken::default();
-    rand::rng().fill_bytes(&mut token);
-    let expires = (OffsetDateTime::now_utc() + SESSION_TTL).unix_timestamp();
-
-    if ctx
-        .connections
-        .insert_async(token, ConnectionState { did, expires })
-        .await
-        .is_err()
-    {
-        debug!("already authenticated");
-        tx.send(None).await?;
-        return Ok(());
+    // A peer whose links all closed mid-handshake has nothing left to bind.
+    let bound = ctx.bind(peer, did).await;
+    if !bound {
+        debug!("peer disconnected");
     }
 
-    tx.send(Some(token)).await?;
+    tx.send(bound).await?;
     Ok(())
 }
 
@@ -136,7 +127,7 @@ async fn redeem_nonce(
     }
 
     // Taken, not read: a nonce answers exactly one challenge, so a captured
-    // signature cannot be replayed for a second session.
+    // signature cannot be replayed to authenticate a second peer.
     let Some((_, pending)) = state.nonces.remove_async(&challenge.nonce).await else {
         debug!("invalid nonce");
         return None;
@@ -1,6 +1,9 @@
 use std::{
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::AtomicU64,
+    },
     time::Duration,
 };
 
@@ -20,6 +23,7 @@ use iroh_docs::protocol::{
 use iroh_gossip::net::Gossip;
 use n0_future::task::AbortOnDropHandle;
 use parking_lot::RwLock;
+use xdid::core::did::Did;
 
 use crate::{
     DataStore,
@@ -28,9 +32,14 @@ use crate::{
 };
 
 pub struct DataStoreBuilder {
-    endpoint: Endpoint,
-    gc_timer: Option<Duration>,
-    storage:  Storage,
+    endpoint:       Endpoint,
+    denylist_timer: Option<Duration>,
+    denylists:      Vec<Did>,
+    gc_timer:       Option<Duration>,
+    mirror_timer:   Option<Duration>,
+    mirrors:        Vec<Did>,
+    operators:      Vec<Did>,
+    storage:        Storage,
 }
 
 pub enum Storage {
@@ -46,7 +55,12 @@ impl DataStoreBuilder {
     pub const fn new(endpoint: Endpoint) -> Self {
         Self {
             endpoint,
+            denylist_timer: None,
+            denylists: Vec::new(),
             gc_timer: None,
+            mirror_timer: None,
+            mirrors: Vec::new(),
+            operators: Vec::new(),
             storage: Storage::InMemory,
         }
     }
@@ -59,6 +73,52 @@ impl DataStoreBuilder {
         self
     }
 
+    /// Spawns a task to refresh every denylist listed in [`Self::denylists`]
+    /// at a set frequency. Disabled by default.
+    #[must_use]
+    pub const fn denylist_timer(mut self, frequency: Duration) -> Self {
+        self.denylist_timer = Some(frequency);
+        self
+    }
+
+    /// Publishers' DIDs whose signed denylists this store also refuses
+    /// content by, alongside its operators' own. None by default.
+    ///
+    /// Fetching them needs a user identity set on the built store.
+    #[must_use]
+    pub fn denylists(mut self, dids: impl IntoIterator<Item = Did>) -> Self {
+        self.denylists.extend(dids);
+        self
+    }
+
+    /// Spawns a task to mirror every store listed in [`Self::mirrors`] at a set
+    /// frequency. Disabled by default.
+    #[must_use]
+    pub const fn mirror_timer(mut self, frequency: Duration) -> Self {
+        self.mirror_timer = Some(frequency);
+        self
+    }
+
+    /// Peer stores' DIDs, each mirrored in both directions: this store may
+    /// list what they host, and re-hosts what they host under its own user.
+    /// None by default.
+    ///
+    /// Mirroring needs a user identity set on the built store, which is what
+    /// it authenticates to peers as and what its copies are charged to.
+    #[must_use]
+    pub fn mirrors(mut self, dids: impl IntoIterator<Item = Did>) -> Self {
+        self.mirrors.extend(dids);
+        self
+    }
+
+    /// Allows these DIDs the operator calls: reading and setting any user's
+    /// quota, listing what they hold, and evicting it. None by default.
+    #[must_use]
+    pub fn operators(mut self, dids: impl IntoIterator<Item = Did>) -> Self {
+        self.operators.extend(dids);
+        self
+    }
+
     /// Specify a directory path for file storage.
     /// If not provided, defaults to in-memory storage.
     ///
@@ -85,18 +145,23 @@ impl DataStoreBuilder {
 
         let ctx = Arc::new(StoreContext {
             blobs,
-            connections: scc::HashMap::default(),
             db,
+            denylists: self.denylists,
             docs: docs.clone(),
             endpoint: self.endpoint.clone(),
             gossip: gossip.clone(),
             hosted: scc::HashMap::default(),
+            mirrors: self.mirrors,
+            next_local: AtomicU64::default(),
+            operators: self.operators,
+            peers: scc::HashMap::default(),
             user_identity: RwLock::new(None),
         });
 
-        let (control_client, control_protocol) = crate::control::protocol(Arc::clone(&ctx));
-        let (auth_client, auth_protocol) = crate::auth::protocol(Arc::clone(&ctx));
+        let control = crate::control::protocol(Arc::clone(&ctx));
+        let auth = crate::auth::protocol(Arc::clone(&ctx));
 
+        let (control_protocol, auth_protocol) = (control.clone(), auth.clone());
         let router_builder_fn = Box::new(move |builder: RouterBuilder| {
             builder
                 .accept(iroh_blobs::ALPN, blob_protocol)
@@ -119,13 +184,37 @@ impl DataStoreBuilder {
             AbortOnDropHandle::new(handle)
         });
 
+        let mirror_handle = self.mirror_timer.map(|duration| {
+            let ctx = Arc::clone(&ctx);
+            let handle = n0_future::task::spawn(async move {
+                loop {
+                    ctx.run_mirror().await;
+                    n0_future::time::sleep(duration).await;
+                }
+            });
+            AbortOnDropHandle::new(handle)
+        });
+
+        let denylist_handle = self.denylist_timer.map(|duration| {
+            let ctx = Arc::clone(&ctx);
+            let handle = n0_future::task::spawn(async move {
+                loop {
+                    ctx.run_denylist_refresh().await;
+                    n0_future::time::sleep(duration).await;
+                }
+            });
+            AbortOnDropHandle::new(handle)
+        });
+
         Ok((
             DataStore {
-                control_client,
-                auth_client,
+                control,
+                auth,
                 endpoint: self.endpoint,
                 ctx,
                 _gc_handle: gc_handle,
+                _mirror_handle: mirror_handle,
+                _denylist_handle: denylist_handle,
             },
             router_builder_fn,
         ))
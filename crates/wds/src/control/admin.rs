@@ -0,0 +1,252 @@
+//! Operator administration of quotas and hosted content.
+
+use std::{
+    str::FromStr,
+    sync::Arc,
+};
+
+use blake3::Hash;
+use iroh_docs::NamespaceId;
+use irpc::WithChannels;
+use rusqlite::{
+    Connection,
+    params,
+};
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    control::{
+        ControlService,
+        ForceUnhost,
+        GetUserQuota,
+        ListHosted,
+        ListPins,
+        ListUsers,
+        PurgeDid,
+        SetUserQuota,
+        UserQuota,
+        authorize_operator,
+        doc::stop_hosting,
+        quota::{
+            peek_quota,
+            read_hosted,
+            read_pins,
+        },
+    },
+    quota::release_bytes,
+    tag::BlobTag,
+};
+
+pub async fn list_users(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { tx, .. }: WithChannels<ListUsers, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+
+    let rows = ctx
+        .db
+        .call(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT owner, bytes_used, quota_bytes FROM user_quotas ORDER BY owner")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+        .await?;
+
+    let users = rows
+        .into_iter()
+        .filter_map(|(owner, bytes_used, quota_bytes)| {
+            Some(UserQuota {
+                owner: Did::from_str(&owner).ok()?,
+                bytes_used,
+                quota_bytes,
+            })
+        })
+        .collect();
+
+    tx.send(Ok(users)).await?;
+    Ok(())
+}
+
+pub async fn get_user_quota(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<GetUserQuota, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let did_str = inner.did.to_string();
+
+    let info = ctx.db.call(move |conn| peek_quota(conn, &did_str)).await?;
+
+    tx.send(Ok(info)).await?;
+    Ok(())
+}
+
+/// Lowering a quota below what the owner already uses takes nothing away; it
+/// refuses their next charge until they are back under it.
+pub async fn set_user_quota(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<SetUserQuota, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let did_str = inner.did.to_string();
+    let quota_bytes = i64::try_from(inner.quota_bytes).unwrap_or(i64::MAX);
+
+    ctx.db
+        .call(move |conn| {
+            conn.execute(
+                "INSERT INTO user_quotas (owner, quota_bytes) VALUES (?, ?)
+                 ON CONFLICT(owner) DO UPDATE SET quota_bytes = excluded.quota_bytes",
+                params![&did_str, quota_bytes],
+            )?;
+            Ok(())
+        })
+        .await?;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+pub async fn list_pins(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<ListPins, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let did_str = inner.did.to_string();
+
+    let pins = ctx.db.call(move |conn| read_pins(conn, &did_str)).await?;
+
+    tx.send(Ok(pins)).await?;
+    Ok(())
+}
+
+pub async fn list_hosted(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<ListHosted, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let did_str = inner.did.to_string();
+
+    let hosted = ctx.db.call(move |conn| read_hosted(conn, &did_str)).await?;
+
+    tx.send(Ok(hosted)).await?;
+    Ok(())
+}
+
+/// Stops replicating `ns` for every owner hosting it, refunding each.
+pub async fn force_unhost(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<ForceUnhost, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let ns_str = inner.ns.to_string();
+
+    ctx.db
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            let hosts = {
+                let mut stmt =
+                    tx.prepare("SELECT owner, bytes_used FROM hosted_docs WHERE ns = ?")?;
+                let rows = stmt.query_map(params![&ns_str], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            };
+            for (owner, bytes_used) in hosts {
+                release_bytes(&tx, &owner, bytes_used)?;
+            }
+            tx.execute("DELETE FROM hosted_docs WHERE ns = ?", params![&ns_str])?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await?;
+
+    stop_hosting(&ctx, inner.ns).await;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+/// Removes everything a DID holds here: its hosting, its pins and uploads in
+/// progress, its quota row and usage history, and its authentication on every
+/// peer. A namespace someone else still hosts keeps replicating.
+pub async fn purge_did(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<PurgeDid, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+    let did = inner.did;
+    let did_str = did.to_string();
+
+    let (orphaned, pins) = ctx
+        .db
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            let hosted = column(&tx, "SELECT ns FROM hosted_docs WHERE owner = ?", &did_str)?;
+            let pins = column(&tx, "SELECT hash FROM blob_pins WHERE owner = ?", &did_str)?;
+
+            tx.execute("DELETE FROM hosted_docs WHERE owner = ?", params![&did_str])?;
+            tx.execute("DELETE FROM blob_pins WHERE owner = ?", params![&did_str])?;
+            tx.execute(
+                "DELETE FROM upload_pieces WHERE owner = ?",
+                params![&did_str],
+            )?;
+            tx.execute("DELETE FROM uploads WHERE owner = ?", params![&did_str])?;
+            tx.execute("DELETE FROM user_quotas WHERE owner = ?", params![&did_str])?;
+            tx.execute(
+                "DELETE FROM quota_history WHERE owner = ?",
+                params![&did_str],
+            )?;
+
+            let mut orphaned = Vec::new();
+            for ns in hosted {
+                let others: i64 = tx.query_row(
+                    "SELECT COUNT(*) FROM hosted_docs WHERE ns = ?",
+                    params![&ns],
+                    |row| row.get(0),
+                )?;
+                if others == 0 {
+                    orphaned.push(ns);
+                }
+            }
+            tx.commit()?;
+            Ok((orphaned, pins))
+        })
+        .await?;
+
+    for ns in orphaned
+        .iter()
+        .filter_map(|ns| NamespaceId::from_str(ns).ok())
+    {
+        stop_hosting(&ctx, ns).await;
+    }
+    for hash in pins.iter().filter_map(|hash| Hash::from_str(hash).ok()) {
+        let tag = BlobTag::new(did.clone(), hash);
+        if let Err(err) = ctx.blob_store().tags().delete(tag.to_string()).await {
+            tracing::warn!(%did, %hash, "failed to delete purged pin's tag: {err}");
+        }
+    }
+    ctx.unbind(&did).await;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+fn column(conn: &Connection, sql: &str, owner: &str) -> anyhow::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![owner], |row| row.get::<_, String>(0))?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
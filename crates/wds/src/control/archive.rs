@@ -0,0 +1,646 @@
+//! Export and import of [`crate::archive`]s.
+
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    io,
+    sync::Arc,
+};
+
+use blake3::Hash;
+use bytes::Bytes;
+use futures::{
+    SinkExt,
+    StreamExt,
+    channel::mpsc as chunks,
+};
+use iroh::{
+    EndpointAddr,
+    EndpointId,
+};
+use iroh_blobs::api::TempTag;
+use iroh_docs::{
+    Capability,
+    CapabilityKind,
+    NamespaceId,
+    api::protocol::{
+        AddrInfoOptions,
+        ShareMode,
+    },
+    store::Query,
+};
+use irpc::{
+    WithChannels,
+    channel::mpsc,
+};
+use n0_future::task::JoinHandle;
+use rusqlite::params;
+use time::OffsetDateTime;
+use tokio::io::AsyncReadExt;
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    archive::{
+        ArchivedDoc,
+        ArchivedEntry,
+        CHUNK_LEN,
+        ImportSummary,
+        Record,
+        VERSION,
+    },
+    control::{
+        ControlService,
+        ExportArchive,
+        ImportArchive,
+        MAX_PIN_DURATION,
+        PinInfo,
+        blob::insert_pin,
+        doc::host,
+        quota::{
+            read_hosted,
+            read_pins,
+            read_quota,
+        },
+    },
+    denylist::DenyEntry,
+    entries,
+    error::ApiError,
+    quota::ensure_quota_exists,
+    tag::BlobTag,
+};
+
+type RecordSender = mpsc::Sender<Result<Record, ApiError>>;
+
+pub async fn export_archive(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<ExportArchive, ControlService>,
+) -> anyhow::Result<()> {
+    let owner = inner.did.unwrap_or_else(|| caller.clone());
+    if owner != caller && !ctx.operators.contains(&caller) {
+        tx.send(Err(ApiError::AccessDenied)).await?;
+        return Ok(());
+    }
+
+    if let Err(err) = export(&ctx, &owner, ctx.trusted(&caller), &tx).await {
+        tracing::warn!(%owner, "export failed: {err:?}");
+        // The caller may be what went away, in which case there is no one
+        // left to tell.
+        tx.send(Err(ApiError::Internal)).await.ok();
+    }
+    Ok(())
+}
+
+/// Restores an archive under its owner: the caller, or anyone for an operator.
+///
+/// Everything is charged to the owner's quota as it lands. An import refused
+/// part way keeps what it restored so far; importing the archive again after
+/// making room picks up the rest without charging twice.
+pub async fn import_archive(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { tx, rx, .. }: WithChannels<ImportArchive, ControlService>,
+) -> anyhow::Result<()> {
+    let result = import(&ctx, &caller, rx).await?;
+    tx.send(result).await?;
+    Ok(())
+}
+
+impl StoreContext {
+    /// Whether `did` may move write secrets in and out of this store: its
+    /// operators and its own user.
+    fn trusted(&self, did: &Did) -> bool {
+        self.operators.contains(did) || self.is_user(did)
+    }
+
+    fn is_user(&self, did: &Did) -> bool {
+        self.user_identity
+            .read()
+            .as_ref()
+            .is_some_and(|identity| identity.did() == did)
+    }
+}
+
+/// Sends the header and quota, then the content, pins and docs.
+///
+/// Content goes first so an import holds every blob before anything refers to
+/// it. The store's own user also takes every doc the store can write, hosted
+/// or not: that is where their root doc and KV live.
+async fn export(
+    ctx: &StoreContext,
+    owner: &Did,
+    secrets: bool,
+    tx: &RecordSender,
+) -> anyhow::Result<()> {
+    let owner_str = owner.to_string();
+    let (quota, pins, hosted) = ctx
+        .db
+        .call(move |conn| {
+            Ok((
+                read_quota(conn, &owner_str)?,
+                read_pins(conn, &owner_str)?,
+                read_hosted(conn, &owner_str)?,
+            ))
+        })
+        .await?;
+
+    tx.send(Ok(Record::Header {
+        version:  VERSION,
+        owner:    owner.clone(),
+        source:   ctx.endpoint.id(),
+        exported: OffsetDateTime::now_utc().unix_timestamp(),
+    }))
+    .await?;
+    tx.send(Ok(Record::Quota {
+        quota_bytes: quota.quota_bytes,
+    }))
+    .await?;
+
+    let mut docs = hosted.into_iter().map(|h| (h.ns, true)).collect::<Vec<_>>();
+    if ctx.is_user(owner) {
+        let held = ctx.docs.api().list().await?;
+        let mut held = std::pin::pin!(held);
+        while let Some(item) = held.next().await {
+            let (ns, kind) = item?;
+            if kind == CapabilityKind::Write && !docs.iter().any(|(n, _)| *n == ns) {
+                docs.push((ns, false));
+            }
+        }
+    }
+
+    let mut archived = Vec::with_capacity(docs.len());
+    for (ns, hosted) in docs {
+        archived.push(archive_doc(ctx, ns, hosted, secrets).await?);
+    }
+
+    let mut sent = HashSet::new();
+    let hashes = pins.iter().map(|pin| pin.hash).chain(
+        archived
+            .iter()
+            .flat_map(|doc| doc.entries.iter().map(|e| e.hash)),
+    );
+    for hash in hashes {
+        if sent.insert(hash) {
+            send_blob(ctx, hash, tx).await?;
+        }
+    }
+
+    for pin in pins {
+        tx.send(Ok(Record::Pin(pin))).await?;
+    }
+    for doc in archived {
+        tx.send(Ok(Record::Doc(doc))).await?;
+    }
+    Ok(())
+}
+
+async fn archive_doc(
+    ctx: &StoreContext,
+    ns: NamespaceId,
+    hosted: bool,
+    secrets: bool,
+) -> anyhow::Result<ArchivedDoc> {
+    let doc = crate::docs::ensure_open(&ctx.docs, ns).await?;
+
+    // Sharing for write fails on a replica held read-only, which is exactly
+    // when there is no secret to archive.
+    let secret = if secrets {
+        doc.share(ShareMode::Write, AddrInfoOptions::Id)
+            .await
+            .ok()
+            .and_then(|ticket| match ticket.capability {
+                Capability::Write(secret) => Some(secret),
+                Capability::Read(_) => None,
+            })
+    } else {
+        None
+    };
+
+    let mut entries = Vec::new();
+    let stream = doc.get_many(Query::single_latest_per_key()).await?;
+    let mut stream = std::pin::pin!(stream);
+    while let Some(entry) = stream.next().await {
+        let entry = entry?;
+        entries.push(ArchivedEntry {
+            key:       entry.key().to_vec().into(),
+            author:    entry.author(),
+            hash:      entry.content_hash().into(),
+            size:      entry.content_len(),
+            timestamp: entry.timestamp(),
+        });
+    }
+
+    Ok(ArchivedDoc {
+        ns,
+        secret,
+        hosted,
+        entries,
+    })
+}
+
+/// Sends a blob's content in chunks, read as they are sent rather than held
+/// whole. Content not held in full, such as an entry still downloading, is
+/// left out.
+async fn send_blob(ctx: &StoreContext, hash: Hash, tx: &RecordSender) -> anyhow::Result<()> {
+    let blobs = ctx.blob_store().blobs();
+    let id = iroh_blobs::Hash::from(hash);
+    let Ok(held) = blobs.observe(id).await else {
+        return Ok(());
+    };
+    if !held.is_complete() {
+        return Ok(());
+    }
+
+    let size = held.size();
+    tx.send(Ok(Record::Blob { hash, size })).await?;
+    let mut reader = blobs.reader(id);
+    let mut left = size;
+    while left > 0 {
+        let mut chunk = vec![0; left.min(CHUNK_LEN as u64) as usize];
+        reader.read_exact(&mut chunk).await?;
+        left -= chunk.len() as u64;
+        tx.send(Ok(Record::Data(chunk.into()))).await?;
+    }
+    Ok(())
+}
+
+/// A blob whose chunks are still arriving.
+struct Incoming {
+    hash:  Hash,
+    size:  u64,
+    /// Bytes seen so far, kept or not.
+    seen:  u64,
+    /// Where chunks go as they arrive, and the write they feed. `None` for
+    /// denied content, which is read to its end to stay in step with the
+    /// archive, then dropped.
+    store: Option<(
+        chunks::Sender<io::Result<Bytes>>,
+        JoinHandle<Result<TempTag, ApiError>>,
+    )>,
+}
+
+async fn import(
+    ctx: &Arc<StoreContext>,
+    caller: &Did,
+    mut rx: mpsc::Receiver<Record>,
+) -> anyhow::Result<Result<ImportSummary, ApiError>> {
+    let Some(Record::Header {
+        version,
+        owner,
+        source,
+        ..
+    }) = rx.recv().await?
+    else {
+        return Ok(Err(ApiError::InvalidContent));
+    };
+    if version != VERSION {
+        return Ok(Err(ApiError::InvalidContent));
+    }
+    let operator = ctx.operators.contains(caller);
+    if owner != *caller && !operator {
+        return Ok(Err(ApiError::AccessDenied));
+    }
+    if ctx
+        .denies(vec![
+            DenyEntry::Did(caller.clone()),
+            DenyEntry::Did(owner.clone()),
+        ])
+        .await?
+    {
+        return Ok(Err(ApiError::Denied));
+    }
+
+    // Content lands before anything charges for it, so it is held to the
+    // owner's headroom as it arrives, as an upload is.
+    let headroom = if operator {
+        i64::MAX
+    } else {
+        headroom(ctx, owner.to_string()).await?
+    };
+
+    let mut importer = Importer {
+        ctx: Arc::clone(ctx),
+        trusted: ctx.trusted(caller),
+        owner,
+        operator,
+        source,
+        headroom,
+        received: 0,
+        blobs: HashMap::new(),
+        summary: ImportSummary::default(),
+    };
+    let mut incoming: Option<Incoming> = None;
+
+    while let Some(record) = rx.recv().await? {
+        if incoming.is_some() && !matches!(record, Record::Data(_)) {
+            return Ok(Err(ApiError::InvalidContent));
+        }
+
+        let res = match record {
+            Record::Header { .. } => Err(ApiError::InvalidContent),
+            Record::Blob { hash, size } => match importer.begin_blob(hash, size).await? {
+                Ok(blob) => {
+                    incoming = Some(blob);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            Record::Data(data) => match incoming.as_mut() {
+                Some(blob) => {
+                    blob.seen += data.len() as u64;
+                    if blob.seen > blob.size {
+                        Err(ApiError::InvalidContent)
+                    } else if let Some((feed, _)) = blob.store.as_mut() {
+                        // Only fails once the write has given up.
+                        feed.send(Ok(data)).await.map_err(|_| ApiError::Internal)
+                    } else {
+                        Ok(())
+                    }
+                }
+                None => Err(ApiError::InvalidContent),
+            },
+            record => importer.restore(record).await?,
+        };
+        if let Err(err) = res {
+            return Ok(Err(err));
+        }
+
+        // A blob is stored once its last chunk is in, or at once if empty.
+        if let Some(blob) = incoming.take_if(|blob| blob.seen == blob.size)
+            && let Err(err) = importer.finish_blob(blob).await
+        {
+            return Ok(Err(err));
+        }
+    }
+
+    if incoming.is_some() {
+        return Ok(Err(ApiError::InvalidContent));
+    }
+    Ok(Ok(importer.summary))
+}
+
+struct Importer {
+    ctx:      Arc<StoreContext>,
+    owner:    Did,
+    operator: bool,
+    trusted:  bool,
+    source:   EndpointId,
+    headroom: i64,
+    received: i64,
+    /// Held under temp tags until something takes them over.
+    blobs:    HashMap<Hash, (TempTag, i64)>,
+    summary:  ImportSummary,
+}
+
+impl Importer {
+    /// Denied content is still read through, and left out once it ends.
+    async fn begin_blob(
+        &mut self,
+        hash: Hash,
+        size: u64,
+    ) -> anyhow::Result<Result<Incoming, ApiError>> {
+        let denied = self.ctx.denies(vec![DenyEntry::Blob(hash)]).await?;
+        if !denied {
+            self.received = self.received.saturating_add(size.cast_signed());
+            if self.received > self.headroom {
+                return Ok(Err(ApiError::QuotaExceeded));
+            }
+        }
+        let store = (!denied).then(|| {
+            let (tx, rx) = chunks::channel(1);
+            (tx, store_blob(Arc::clone(&self.ctx), rx))
+        });
+        Ok(Ok(Incoming {
+            hash,
+            size,
+            seen: 0,
+            store,
+        }))
+    }
+
+    async fn finish_blob(&mut self, blob: Incoming) -> Result<(), ApiError> {
+        let Some((feed, write)) = blob.store else {
+            self.summary.denied += 1;
+            return Ok(());
+        };
+        // Ending the stream is what lets the write finish.
+        drop(feed);
+        let temp_tag = write.await.map_err(|_| ApiError::Internal)??;
+        if Hash::from(temp_tag.hash()) != blob.hash {
+            return Err(ApiError::InvalidContent);
+        }
+        self.blobs
+            .insert(blob.hash, (temp_tag, blob.size.cast_signed()));
+        self.summary.blobs += 1;
+        Ok(())
+    }
+
+    /// Restores a quota, pin or doc record.
+    async fn restore(&mut self, record: Record) -> anyhow::Result<Result<(), ApiError>> {
+        match record {
+            Record::Quota { quota_bytes } => {
+                if self.operator {
+                    restore_quota(&self.ctx, self.owner.to_string(), quota_bytes).await?;
+                }
+            }
+            Record::Pin(pin) => {
+                if self.ctx.denies(vec![DenyEntry::Blob(pin.hash)]).await? {
+                    self.summary.denied += 1;
+                    return Ok(Ok(()));
+                }
+                // Content the archive left out cannot be charged for, so its
+                // pin is not restored.
+                let Some((temp_tag, size)) = self.blobs.remove(&pin.hash) else {
+                    return Ok(Ok(()));
+                };
+                match restore_pin(&self.ctx, &self.owner, &pin, temp_tag, size).await? {
+                    Ok(true) => self.summary.pins += 1,
+                    Ok(false) => {}
+                    Err(err) => return Ok(Err(err)),
+                }
+            }
+            Record::Doc(doc) => {
+                if self.ctx.denies(vec![DenyEntry::Namespace(doc.ns)]).await? {
+                    self.summary.denied += 1;
+                    return Ok(Ok(()));
+                }
+                let restored =
+                    restore_doc(&self.ctx, &self.owner, doc, self.trusted, self.source).await?;
+                match restored {
+                    Ok(Some(written)) => {
+                        self.summary.docs += 1;
+                        self.summary.entries += written;
+                    }
+                    Ok(None) => {}
+                    Err(err) => return Ok(Err(err)),
+                }
+            }
+            Record::Header { .. } | Record::Blob { .. } | Record::Data(_) => {
+                return Ok(Err(ApiError::InvalidContent));
+            }
+        }
+        Ok(Ok(()))
+    }
+}
+
+async fn headroom(ctx: &StoreContext, owner: String) -> anyhow::Result<i64> {
+    ctx.db
+        .call(move |conn| {
+            ensure_quota_exists(conn, &owner)?;
+            Ok(conn.query_row(
+                "SELECT quota_bytes - bytes_used FROM user_quotas WHERE owner = ?",
+                params![&owner],
+                |row| row.get(0),
+            )?)
+        })
+        .await
+}
+
+async fn restore_quota(ctx: &StoreContext, owner: String, quota_bytes: i64) -> anyhow::Result<()> {
+    ctx.db
+        .call(move |conn| {
+            ensure_quota_exists(conn, &owner)?;
+            conn.execute(
+                "UPDATE user_quotas SET quota_bytes = ? WHERE owner = ?",
+                params![quota_bytes, &owner],
+            )?;
+            Ok(())
+        })
+        .await
+}
+
+/// Writes a blob as its chunks arrive, so no more than one is held at a time.
+///
+/// An import that gives up part way drops the sender, which ends the stream
+/// as if the blob were whole; the short write hashes differently and its temp
+/// tag is dropped unclaimed.
+fn store_blob(
+    ctx: Arc<StoreContext>,
+    data: chunks::Receiver<io::Result<Bytes>>,
+) -> JoinHandle<Result<TempTag, ApiError>> {
+    n0_future::task::spawn(async move {
+        ctx.blob_store()
+            .blobs()
+            .add_stream(data)
+            .await
+            .temp_tag()
+            .await
+            .map_err(|_| ApiError::Internal)
+    })
+}
+
+/// Pins restored content to its owner, capped as any pin is. Returns `false`
+/// for a pin that has since expired, which is left out.
+async fn restore_pin(
+    ctx: &StoreContext,
+    owner: &Did,
+    pin: &PinInfo,
+    temp_tag: TempTag,
+    size: i64,
+) -> anyhow::Result<Result<bool, ApiError>> {
+    let now = OffsetDateTime::now_utc();
+    let expires = pin.expires.min((now + MAX_PIN_DURATION).unix_timestamp());
+    if expires < now.unix_timestamp() {
+        return Ok(Ok(false));
+    }
+
+    let pinned = ctx
+        .db
+        .call_mut({
+            let owner = owner.to_string();
+            let hash = pin.hash.to_string();
+            move |conn| {
+                let tx = conn.transaction()?;
+                ensure_quota_exists(&tx, &owner)?;
+                if !insert_pin(&tx, &owner, &hash, size, expires)? {
+                    return Ok(false);
+                }
+                tx.commit()?;
+                Ok(true)
+            }
+        })
+        .await?;
+    if !pinned {
+        return Ok(Err(ApiError::QuotaExceeded));
+    }
+
+    let tag_name = BlobTag::new(owner.clone(), pin.hash).to_string();
+    ctx.blob_store().tags().set(tag_name, temp_tag).await?;
+    Ok(Ok(true))
+}
+
+/// Restores one doc, returning how many of its entries were written back, or
+/// `None` if it was left out.
+///
+/// Entries can only be written with the doc's secret, and are then authored
+/// by this node at the time of import; an entry the replica already holds
+/// something as new for is left alone. A doc restored without its secret can
+/// only be replicated, which only hosting charges for, so it is restored only
+/// if it was hosted, and hosted before anything is opened. Its entries arrive
+/// as they would on any replica; a trusted import also syncs it from the
+/// store the archive came from.
+async fn restore_doc(
+    ctx: &Arc<StoreContext>,
+    owner: &Did,
+    doc: ArchivedDoc,
+    trusted: bool,
+    source: EndpointId,
+) -> anyhow::Result<Result<Option<usize>, ApiError>> {
+    let ArchivedDoc {
+        ns,
+        secret,
+        hosted,
+        entries,
+    } = doc;
+
+    let secret = secret.filter(|_| trusted);
+    if secret.as_ref().is_some_and(|secret| secret.id() != ns) {
+        return Ok(Err(ApiError::InvalidContent));
+    }
+
+    let Some(secret) = secret else {
+        if !hosted {
+            return Ok(Ok(None));
+        }
+        if !host(ctx, owner, ns).await? {
+            return Ok(Err(ApiError::QuotaExceeded));
+        }
+
+        // The header's source is whatever the caller wrote there.
+        if trusted && source != ctx.endpoint.id() {
+            let replica = crate::docs::ensure_open(&ctx.docs, ns).await?;
+            if let Err(err) = replica.start_sync(vec![EndpointAddr::from(source)]).await {
+                tracing::debug!(%ns, "syncing from the archive's source failed: {err}");
+            }
+        }
+        return Ok(Ok(Some(0)));
+    };
+
+    let replica = ctx
+        .docs
+        .api()
+        .import_namespace(Capability::Write(secret))
+        .await?;
+    let author = entries::author(&ctx.docs).await?;
+    let mut written = 0;
+    for entry in entries {
+        let query = Query::single_latest_per_key().key_exact(&entry.key);
+        let held = replica.get_one(query).await?;
+        if held.is_some_and(|held| held.timestamp() >= entry.timestamp) {
+            continue;
+        }
+        replica
+            .set_hash(author, entry.key, entry.hash.into(), entry.size)
+            .await?;
+        written += 1;
+    }
+
+    if hosted && !host(ctx, owner, ns).await? {
+        return Ok(Err(ApiError::QuotaExceeded));
+    }
+
+    Ok(Ok(Some(written)))
+}
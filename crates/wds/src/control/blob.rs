@@ -15,9 +15,13 @@ use std::{
 use blake3::Hash;
 use futures::StreamExt;
 use irpc::WithChannels;
-use rusqlite::params;
+use rusqlite::{
+    Connection,
+    params,
+};
 use time::OffsetDateTime;
 use tracing::debug;
+use xdid::core::did::Did;
 
 use crate::{
     StoreContext,
@@ -26,11 +30,15 @@ use crate::{
         ControlService,
         MAX_PIN_DURATION,
         PinBlob,
+        UnpinBlob,
         UploadBlob,
-        authenticate,
+        grant::act_for,
+        refuse_denied,
     },
+    denylist::DenyEntry,
     error::ApiError,
     gc::FAST_GC_THRESHOLD,
+    grant::Action,
     quota::{
         ensure_quota_exists,
         reserve_bytes,
@@ -38,17 +46,20 @@ use crate::{
     tag::BlobTag,
 };
 
-const DEFAULT_BLOB_TTL: Duration = Duration::from_hours(1);
+pub(super) const DEFAULT_BLOB_TTL: Duration = Duration::from_hours(1);
 
 /// Ceiling on a single upload, independent of quota: without it one request
 /// can outrun the quota check by however much disk is left.
-const MAX_UPLOAD_BYTES: i64 = 256 * 1024 * 1024;
+pub(super) const MAX_UPLOAD_BYTES: i64 = 256 * 1024 * 1024;
 
 pub async fn upload_blob(
     ctx: Arc<StoreContext>,
+    caller: Did,
     WithChannels { inner, tx, rx, .. }: WithChannels<UploadBlob, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Pin]);
+    refuse_denied!(ctx, tx, [denied_caller, DenyEntry::Did(did.clone())]);
     let did_str = did.to_string();
 
     let headroom = ctx
@@ -123,6 +134,9 @@ pub async fn upload_blob(
     debug!(?blob_len, "wrote blob to store");
 
     let hash: Hash = temp_tag.hash().into();
+    // Only known once the bytes are in; dropping the temp tag leaves them to
+    // garbage collection.
+    refuse_denied!(ctx, tx, [DenyEntry::Blob(hash)]);
     let hash_str = hash.to_string();
     let expires = (OffsetDateTime::now_utc() + DEFAULT_BLOB_TTL).unix_timestamp();
 
@@ -154,41 +168,65 @@ async fn record_pin(
     ctx.db
         .call_mut(move |conn| {
             let tx = conn.transaction()?;
-            // Re-uploading content this owner already pins must not charge
-            // twice: the pin is one row, so it can only be released once.
-            let pinned = tx
-                .query_row(
-                    "SELECT 1 FROM blob_pins WHERE owner = ? AND hash = ?",
-                    params![&did, &hash],
-                    |_| Ok(true),
-                )
-                .unwrap_or(false);
-            if pinned {
-                tx.execute(
-                    "UPDATE blob_pins SET expires = MAX(expires, ?) WHERE owner = ? AND hash = ?",
-                    params![expires, &did, &hash],
-                )?;
-                tx.commit()?;
-                return Ok(true);
-            }
-            if reserve_bytes(&tx, &did, blob_len).is_err() {
+            if !insert_pin(&tx, &did, &hash, blob_len, expires)? {
                 return Ok(false);
             }
-            tx.execute(
-                "INSERT INTO blob_pins (hash, owner, expires, size) VALUES (?, ?, ?, ?)",
-                params![&hash, &did, expires, blob_len],
-            )?;
             tx.commit()?;
             Ok(true)
         })
         .await
 }
 
+/// [`record_pin`] within a caller's transaction, which commits it.
+pub(super) fn insert_pin(
+    conn: &Connection,
+    did: &str,
+    hash: &str,
+    blob_len: i64,
+    expires: i64,
+) -> anyhow::Result<bool> {
+    // Re-uploading content this owner already pins must not charge twice:
+    // the pin is one row, so it can only be released once.
+    let pinned = conn
+        .query_row(
+            "SELECT 1 FROM blob_pins WHERE owner = ? AND hash = ?",
+            params![did, hash],
+            |_| Ok(true),
+        )
+        .unwrap_or(false);
+    if pinned {
+        conn.execute(
+            "UPDATE blob_pins SET expires = MAX(expires, ?) WHERE owner = ? AND hash = ?",
+            params![expires, did, hash],
+        )?;
+        return Ok(true);
+    }
+    if reserve_bytes(conn, did, blob_len).is_err() {
+        return Ok(false);
+    }
+    conn.execute(
+        "INSERT INTO blob_pins (hash, owner, expires, size) VALUES (?, ?, ?, ?)",
+        params![hash, did, expires, blob_len],
+    )?;
+    Ok(true)
+}
+
 pub async fn pin_blob(
     ctx: Arc<StoreContext>,
+    caller: Did,
     WithChannels { inner, tx, .. }: WithChannels<PinBlob, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Pin]);
+    refuse_denied!(
+        ctx,
+        tx,
+        [
+            denied_caller,
+            DenyEntry::Did(did.clone()),
+            DenyEntry::Blob(inner.hash)
+        ]
+    );
     let did_str = did.to_string();
     let hash_str = inner.hash.to_string();
 
@@ -238,6 +276,42 @@ pub async fn pin_blob(
     Ok(())
 }
 
+/// Expires the pin, then collects it as GC would. Should collection fail, the
+/// pin is already past its expiry and goes with the next GC pass.
+pub async fn unpin_blob(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<UnpinBlob, ControlService>,
+) -> anyhow::Result<()> {
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Pin]);
+    let did_str = did.to_string();
+    let hash_str = inner.hash.to_string();
+
+    let expired = ctx
+        .db
+        .call({
+            let did_str = did_str.clone();
+            let hash_str = hash_str.clone();
+            move |conn| {
+                Ok(conn.execute(
+                    "UPDATE blob_pins SET expires = 0 WHERE owner = ? AND hash = ?",
+                    params![&did_str, &hash_str],
+                )?)
+            }
+        })
+        .await?;
+
+    if expired == 0 {
+        tx.send(Err(ApiError::BlobNotFound)).await?;
+        return Ok(());
+    }
+
+    ctx.gc_blob_pin(&did_str, &hash_str).await?;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
 /// Whether the caller's *own* pin of this blob is present.
 ///
 /// Scoped to the caller rather than asking the blob store directly: content
@@ -245,9 +319,9 @@ pub async fn pin_blob(
 /// else's data.
 pub async fn blob_exists(
     ctx: Arc<StoreContext>,
+    did: Did,
     WithChannels { inner, tx, .. }: WithChannels<BlobExists, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
     let did_str = did.to_string();
     let hash_str = inner.hash.to_string();
 
@@ -0,0 +1,76 @@
+//! Operator management of the denylist, and its publication to subscribers.
+
+use std::sync::Arc;
+
+use irpc::WithChannels;
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    control::{
+        ControlService,
+        Deny,
+        GetDenylist,
+        ListDenied,
+        Undeny,
+        authorize_operator,
+    },
+    denylist,
+};
+
+/// Open to any authenticated caller: the list is what the store refuses, not
+/// what it holds.
+pub async fn get_denylist(
+    ctx: Arc<StoreContext>,
+    _caller: Did,
+    WithChannels { tx, .. }: WithChannels<GetDenylist, ControlService>,
+) -> anyhow::Result<()> {
+    let published = ctx.publish_denylist().await?;
+    tx.send(Ok(published)).await?;
+    Ok(())
+}
+
+pub async fn list_denied(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { tx, .. }: WithChannels<ListDenied, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+
+    let entries = ctx.db.call(denylist::list).await?;
+
+    tx.send(Ok(entries)).await?;
+    Ok(())
+}
+
+/// Refuses the entry from now on. What is already held stays; evicting it is
+/// a separate call.
+pub async fn deny(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<Deny, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+
+    ctx.db
+        .call(move |conn| denylist::add(conn, &inner.entry))
+        .await?;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+pub async fn undeny(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<Undeny, ControlService>,
+) -> anyhow::Result<()> {
+    authorize_operator!(ctx, caller, tx);
+
+    ctx.db
+        .call(move |conn| denylist::remove(conn, &inner.entry))
+        .await?;
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    str::FromStr,
+    sync::Arc,
+};
 
 use futures::StreamExt;
 use iroh_docs::{
@@ -13,17 +16,28 @@ use rusqlite::{
     Connection,
     params,
 };
+use xdid::core::did::Did;
 
 use crate::{
     HostedDoc,
     StoreContext,
     control::{
         ControlService,
+        EntryWrite,
         HostDoc,
+        ListMirrored,
         UnhostDoc,
-        authenticate,
+        WriteEntries,
+        grant::act_for,
+        refuse_denied,
+    },
+    denylist::DenyEntry,
+    entries::{
+        self,
+        Write,
     },
     error::ApiError,
+    grant::Action,
     quota::{
         ensure_quota_exists,
         release_bytes,
@@ -33,48 +47,67 @@ use crate::{
 
 pub async fn host_doc(
     ctx: Arc<StoreContext>,
+    caller: Did,
     WithChannels { inner, tx, .. }: WithChannels<HostDoc, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Host(inner.ns)]);
+    refuse_denied!(
+        ctx,
+        tx,
+        [
+            denied_caller,
+            DenyEntry::Did(did.clone()),
+            DenyEntry::Namespace(inner.ns)
+        ]
+    );
+
+    if !host(&ctx, &did, inner.ns).await? {
+        tx.send(Err(ApiError::QuotaExceeded)).await?;
+        return Ok(());
+    }
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+/// Hosts `ns` for `did`, charging its current size. Returns `false` when that
+/// would exceed their quota.
+pub async fn host(ctx: &Arc<StoreContext>, did: &Did, ns: NamespaceId) -> anyhow::Result<bool> {
     let did_str = did.to_string();
-    let ns_str = inner.ns.to_string();
+    let ns_str = ns.to_string();
 
-    let doc = crate::docs::ensure_open(&ctx.docs, inner.ns).await?;
+    let doc = crate::docs::ensure_open(&ctx.docs, ns).await?;
     let size = doc_size(&doc).await?;
 
     let admitted = ctx
         .db
-        .call_mut({
-            let did_str = did_str.clone();
-            let ns_str = ns_str.clone();
-            move |conn| {
-                let tx = conn.transaction()?;
-                ensure_quota_exists(&tx, &did_str)?;
-                // A repeat request inserts nothing, which is what keeps it from
-                // charging twice or spawning a second meter.
-                let rows = tx.execute(
-                    "INSERT OR IGNORE INTO hosted_docs (ns, owner, bytes_used) VALUES (?, ?, 0)",
-                    params![&ns_str, &did_str],
-                )?;
-                if rows == 0 {
-                    return Ok(true);
-                }
-                if reserve_bytes(&tx, &did_str, size).is_err() {
-                    return Ok(false);
-                }
-                tx.execute(
-                    "UPDATE hosted_docs SET bytes_used = ? WHERE owner = ? AND ns = ?",
-                    params![size, &did_str, &ns_str],
-                )?;
-                tx.commit()?;
-                Ok(true)
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_quota_exists(&tx, &did_str)?;
+            // A repeat request inserts nothing, which is what keeps it from
+            // charging twice or spawning a second meter.
+            let rows = tx.execute(
+                "INSERT OR IGNORE INTO hosted_docs (ns, owner, bytes_used) VALUES (?, ?, 0)",
+                params![&ns_str, &did_str],
+            )?;
+            if rows == 0 {
+                return Ok(true);
+            }
+            if reserve_bytes(&tx, &did_str, size).is_err() {
+                return Ok(false);
             }
+            tx.execute(
+                "UPDATE hosted_docs SET bytes_used = ? WHERE owner = ? AND ns = ?",
+                params![size, &did_str, &ns_str],
+            )?;
+            tx.commit()?;
+            Ok(true)
         })
         .await?;
 
     if !admitted {
-        tx.send(Err(ApiError::QuotaExceeded)).await?;
-        return Ok(());
+        return Ok(false);
     }
 
     // Hosting means replicating: a namespace out of the sync set rejects
@@ -82,8 +115,8 @@ pub async fn host_doc(
     doc.start_sync(Vec::new()).await?;
 
     let meter = AbortOnDropHandle::new(n0_future::task::spawn(meter_doc(
-        Arc::clone(&ctx),
-        inner.ns,
+        Arc::clone(ctx),
+        ns,
         doc.clone(),
     )));
     // One meter per namespace, not per owner: a lost race means the namespace
@@ -91,24 +124,39 @@ pub async fn host_doc(
     // closed.
     if let Err((_, loser)) = ctx
         .hosted
-        .insert_async(inner.ns, HostedDoc { doc, _meter: meter })
+        .insert_async(ns, HostedDoc { doc, _meter: meter })
         .await
         && let Err(err) = loser.doc.close().await
     {
-        tracing::debug!(ns = %inner.ns, "closing duplicate doc handle failed: {err}");
+        tracing::debug!(%ns, "closing duplicate doc handle failed: {err}");
     }
 
-    tx.send(Ok(())).await?;
-    Ok(())
+    Ok(true)
 }
 
 pub async fn unhost_doc(
     ctx: Arc<StoreContext>,
+    caller: Did,
     WithChannels { inner, tx, .. }: WithChannels<UnhostDoc, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Host(inner.ns)]);
+
+    // Denying rather than silently succeeding keeps this from doubling as a
+    // probe for which namespaces the node holds.
+    if !unhost(&ctx, &did, inner.ns).await? {
+        tx.send(Err(ApiError::AccessDenied)).await?;
+        return Ok(());
+    }
+
+    tx.send(Ok(())).await?;
+    Ok(())
+}
+
+/// Stops hosting `ns` for `did`, refunding it, and ends replication if no one
+/// else hosts it. Returns `false` if `did` was not hosting it.
+pub async fn unhost(ctx: &StoreContext, did: &Did, ns: NamespaceId) -> anyhow::Result<bool> {
     let did_str = did.to_string();
-    let ns_str = inner.ns.to_string();
+    let ns_str = ns.to_string();
 
     // `None` when the caller was not hosting this namespace at all; `Some(n)`
     // carries how many *other* owners still host it.
@@ -143,15 +191,169 @@ pub async fn unhost_doc(
         })
         .await?;
 
-    // Denying rather than silently succeeding keeps this from doubling as a
-    // probe for which namespaces the node holds.
     let Some(remaining) = remaining else {
+        return Ok(false);
+    };
+    if remaining == 0 {
+        stop_hosting(ctx, ns).await;
+    }
+    Ok(true)
+}
+
+/// Every namespace hosted here for a user, for the stores allowed to mirror
+/// this one.
+///
+/// Namespaces held only because this store mirrors another are left out, so
+/// two stores mirroring each other do not keep each other's copies alive.
+pub async fn list_mirrored(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { tx, .. }: WithChannels<ListMirrored, ControlService>,
+) -> anyhow::Result<()> {
+    if !ctx.mirrors.contains(&caller) {
+        tx.send(Err(ApiError::AccessDenied)).await?;
+        return Ok(());
+    }
+
+    let own = ctx
+        .user_identity
+        .read()
+        .as_ref()
+        .map(|identity| identity.did().to_string())
+        .unwrap_or_default();
+    let namespaces = ctx
+        .db
+        .call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT ns FROM hosted_docs
+                 WHERE owner != ? OR ns NOT IN (SELECT ns FROM mirrored_docs)
+                 ORDER BY ns",
+            )?;
+            let rows = stmt.query_map(params![&own], |row| row.get::<_, String>(0))?;
+            rows.map(|ns| Ok(NamespaceId::from_str(&ns?)?))
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .await?;
+
+    tx.send(Ok(namespaces)).await?;
+    Ok(())
+}
+
+/// Writes entries into a doc the acting owner hosts, authored by this node.
+///
+/// Every set must name a blob the owner has pinned; its size is taken from the
+/// pin, not the caller. A host holding the namespace read-only, as it does any
+/// namespace it did not create, refuses every write with
+/// [`ApiError::ReadOnly`], since it has no capability to make one.
+pub async fn write_entries(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<WriteEntries, ControlService>,
+) -> anyhow::Result<()> {
+    let actions = inner
+        .writes
+        .iter()
+        .map(|w| Action::Write(inner.ns, w.key()))
+        .collect::<Vec<_>>();
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &actions);
+    let denied = [
+        denied_caller,
+        DenyEntry::Did(did.clone()),
+        DenyEntry::Namespace(inner.ns),
+    ]
+    .into_iter()
+    .chain(inner.writes.iter().filter_map(|w| match w {
+        EntryWrite::Set { hash, .. } => Some(DenyEntry::Blob(*hash)),
+        EntryWrite::Remove { .. } => None,
+    }))
+    .collect();
+    if ctx.denies(denied).await? {
+        tx.send(Err(ApiError::Denied)).await?;
+        return Ok(());
+    }
+    let did_str = did.to_string();
+    let ns_str = inner.ns.to_string();
+    let hashes = inner
+        .writes
+        .iter()
+        .filter_map(|w| match w {
+            EntryWrite::Set { hash, .. } => Some(hash.to_string()),
+            EntryWrite::Remove { .. } => None,
+        })
+        .collect::<Vec<_>>();
+
+    let sizes = ctx
+        .db
+        .call(move |conn| {
+            let hosted = conn
+                .query_row(
+                    "SELECT 1 FROM hosted_docs WHERE owner = ? AND ns = ?",
+                    params![&did_str, &ns_str],
+                    |_| Ok(true),
+                )
+                .unwrap_or(false);
+            if !hosted {
+                return Ok(Err(ApiError::AccessDenied));
+            }
+            let mut sizes = Vec::with_capacity(hashes.len());
+            for hash in &hashes {
+                let size: Option<i64> = conn
+                    .query_row(
+                        "SELECT size FROM blob_pins WHERE owner = ? AND hash = ?",
+                        params![&did_str, hash],
+                        |row| row.get(0),
+                    )
+                    .ok();
+                let Some(size) = size else {
+                    return Ok(Err(ApiError::BlobNotFound));
+                };
+                sizes.push(size);
+            }
+            Ok(Ok(sizes))
+        })
+        .await?;
+    let sizes = match sizes {
+        Ok(sizes) => sizes,
+        Err(err) => {
+            tx.send(Err(err)).await?;
+            return Ok(());
+        }
+    };
+
+    let Some(doc) = ctx
+        .hosted
+        .get_async(&inner.ns)
+        .await
+        .map(|hosted| hosted.doc.clone())
+    else {
         tx.send(Err(ApiError::AccessDenied)).await?;
         return Ok(());
     };
+    if !crate::docs::writable(&ctx.docs, inner.ns).await? {
+        tx.send(Err(ApiError::ReadOnly)).await?;
+        return Ok(());
+    }
 
-    if remaining == 0 {
-        stop_hosting(&ctx, inner.ns).await;
+    let mut sizes = sizes.into_iter();
+    let writes = inner
+        .writes
+        .into_iter()
+        .map(|w| match w {
+            EntryWrite::Set { key, hash } => Write::Hash {
+                key,
+                hash: hash.into(),
+                size: sizes.next().unwrap_or_default().cast_unsigned(),
+            },
+            EntryWrite::Remove { key } => Write::Remove { key },
+        })
+        .collect::<Vec<_>>();
+
+    let author = entries::author(&ctx.docs).await?;
+    if let Err(err) = entries::apply(&doc, ctx.blob_store().blobs(), author, writes).await {
+        tracing::debug!(ns = %inner.ns, "delegated write refused: {err}");
+        tx.send(Err(ApiError::AccessDenied)).await?;
+        return Ok(());
     }
 
     tx.send(Ok(())).await?;
@@ -161,7 +363,7 @@ pub async fn unhost_doc(
 /// Ends replication of `ns` and releases this node's own handle on it.
 ///
 /// The replica is dropped only when nothing else still holds it open.
-async fn stop_hosting(ctx: &StoreContext, ns: NamespaceId) {
+pub(super) async fn stop_hosting(ctx: &StoreContext, ns: NamespaceId) {
     let Some((_, hosted)) = ctx.hosted.remove_async(&ns).await else {
         return;
     };
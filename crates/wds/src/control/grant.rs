@@ -0,0 +1,57 @@
+use time::OffsetDateTime;
+use xdid::core::did::Did;
+
+use crate::{
+    error::ApiError,
+    grant::{
+        Action,
+        Grant,
+    },
+    signed_bytes::{
+        SignedBytes,
+        verify_did_signature,
+    },
+};
+
+/// Who a call acts for: the caller themself, or with a grant, its issuer.
+///
+/// The grant must name the caller as grantee, be unexpired, permit every one
+/// of `actions`, and carry the issuer's signature. The signature is checked
+/// last, since it is the only check that resolves a DID.
+pub(super) async fn acting_owner(
+    caller: Did,
+    grant: Option<&SignedBytes<Grant>>,
+    actions: &[Action<'_>],
+) -> Result<Did, ApiError> {
+    let Some(signed) = grant else {
+        return Ok(caller);
+    };
+    let grant = signed.payload().map_err(|_| ApiError::InvalidSignature)?;
+
+    if grant.grantee != caller
+        || grant.expires <= OffsetDateTime::now_utc().unix_timestamp()
+        || !actions.iter().all(|a| grant.permits(*a))
+    {
+        return Err(ApiError::AccessDenied);
+    }
+    if !verify_did_signature(signed, &grant.issuer).await {
+        return Err(ApiError::InvalidSignature);
+    }
+
+    Ok(grant.issuer)
+}
+
+/// [`acting_owner`], replying with the refusal and returning early on error.
+macro_rules! act_for {
+    ($tx:tt, $caller:expr, $grant:expr, $actions:expr) => {
+        match $crate::control::grant::acting_owner($caller, $grant, $actions).await {
+            Ok(owner) => owner,
+            Err(err) => {
+                $tx.send(Err(err)).await?;
+                return Ok(());
+            }
+        }
+    };
+}
+
+pub(crate) use act_for;
@@ -1,5 +1,7 @@
-//! [`irpc`] control-plane API for hosting, pinning, uploads and quota; data
-//! moves over iroh-docs reconciliation and iroh-blobs.
+//! [`irpc`] control-plane API for hosting, pinning, uploads, quota and
+//! archives, their delegation by [`Grant`], and the operator's administration
+//! of them and of the denylist; data moves over iroh-docs reconciliation and
+//! iroh-blobs.
 
 use std::{
     sync::Arc,
@@ -8,6 +10,13 @@ use std::{
 
 use blake3::Hash;
 use bytes::Bytes;
+use iroh::{
+    endpoint::Connection,
+    protocol::{
+        AcceptError,
+        ProtocolHandler,
+    },
+};
 use iroh_docs::NamespaceId;
 use irpc::{
     Client,
@@ -17,29 +26,55 @@ use irpc::{
     },
     rpc_requests,
 };
-use irpc_iroh::IrohProtocol;
+use parking_lot::Mutex;
 use serde::{
     Deserialize,
     Serialize,
 };
 use tracing::{
+    debug,
     error,
     warn,
 };
+use xdid::core::did::Did;
 
 use crate::{
-    SessionToken,
     StoreContext,
+    archive::{
+        ImportSummary,
+        Record,
+    },
+    denylist::{
+        DeniedEntry,
+        DenyEntry,
+        PublishedDenylist,
+    },
     error::ApiError,
+    grant::Grant,
+    peer::Peer,
+    signed_bytes::SignedBytes,
 };
 
+mod admin;
+mod archive;
 mod blob;
+mod denylist;
 mod doc;
+mod grant;
 mod quota;
+mod upload;
+
+pub(crate) use doc::{
+    host,
+    unhost,
+};
 
 const MAX_PIN_DURATION: Duration = Duration::from_hours(24 * 90);
 
-pub const ALPN: &[u8] = b"wds/control/0";
+pub const ALPN: &[u8] = b"wds/control/1";
+
+/// How many days of usage a [`QuotaReport`] looks back over.
+pub const QUOTA_HISTORY_DAYS: i64 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuotaInfo {
@@ -47,94 +82,378 @@ pub struct QuotaInfo {
     pub quota_bytes: i64,
 }
 
-pub(crate) fn protocol(
-    ctx: Arc<StoreContext>,
-) -> (Client<ControlService>, IrohProtocol<ControlService>) {
-    let (tx, mut rx) = irpc::channel::mpsc::channel(32);
+/// A user's own quota, itemised by what is using it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub bytes_used:  i64,
+    pub quota_bytes: i64,
+    /// Largest first.
+    pub hosted:      Vec<HostedInfo>,
+    /// Largest first.
+    pub pins:        Vec<PinInfo>,
+    /// Held by resumable uploads not yet complete.
+    pub uploading:   i64,
+    /// Oldest first, over the last [`QUOTA_HISTORY_DAYS`]. Days on which usage
+    /// never changed have no sample.
+    pub history:     Vec<UsageSample>,
+}
 
-    n0_future::task::spawn(async move {
-        while let Err(err) = handle_requests(&ctx, &mut rx).await {
-            error!("Error handling request: {err:?}");
+/// Bytes in use as of the last change on `day`, the unix timestamp of its
+/// midnight UTC.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct UsageSample {
+    pub day:        i64,
+    pub bytes_used: i64,
+}
+
+/// A user's quota row, as an operator lists it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserQuota {
+    pub owner:       Did,
+    pub bytes_used:  i64,
+    pub quota_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinInfo {
+    pub hash:    Hash,
+    pub expires: i64,
+    pub size:    i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedInfo {
+    pub ns:         NamespaceId,
+    pub bytes_used: i64,
+}
+
+/// How far a resumable upload has got. `received` is where the next piece
+/// starts; `complete` means the blob is assembled and pinned.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct UploadProgress {
+    pub received: u64,
+    pub complete: bool,
+}
+
+/// One entry a host writes into a doc it holds, on a caller's behalf.
+///
+/// A set names content rather than carrying it: the blob must already be
+/// pinned by the doc's owner, so every byte written is one they were charged
+/// for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EntryWrite {
+    Set { key: String, hash: Hash },
+    Remove { key: String },
+}
+
+impl EntryWrite {
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Set { key, .. } | Self::Remove { key } => key,
         }
-    });
+    }
+}
+
+/// Serves `wds/control`, tagging every request with the [`Peer`] it came
+/// from and the DID its link acts as.
+#[derive(Debug, Clone)]
+pub(crate) struct ControlProtocol(Arc<StoreContext>);
 
-    let client = Client::local(tx);
-    let local_sender = client.as_local().expect("local client");
+pub(crate) const fn protocol(ctx: Arc<StoreContext>) -> ControlProtocol {
+    ControlProtocol(ctx)
+}
+
+impl ControlProtocol {
+    /// A client of this store for one local peer, linked while any clone of
+    /// it is alive.
+    pub(crate) fn local_client(&self, peer: Peer) -> Client<ControlService> {
+        let (tx, mut rx) = irpc::channel::mpsc::channel(32);
+        let ctx = Arc::clone(&self.0);
+
+        n0_future::task::spawn(async move {
+            ctx.link(peer).await;
+            let acting = Acting::default();
+            loop {
+                match rx.recv().await {
+                    Ok(Some(msg)) => spawn_message(&ctx, peer, &acting, msg),
+                    Ok(None) => break,
+                    Err(err) => {
+                        error!("Error receiving request: {err:?}");
+                        break;
+                    }
+                }
+            }
+            ctx.unlink(peer).await;
+        });
+
+        Client::local(tx)
+    }
+}
+
+impl ProtocolHandler for ControlProtocol {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let peer = Peer::Remote(connection.remote_id());
+        self.0.link(peer).await;
+        let acting = Acting::default();
+
+        loop {
+            match irpc_iroh::read_request::<ControlService>(&connection).await {
+                Ok(Some(msg)) => spawn_message(&self.0, peer, &acting, msg),
+                Ok(None) => break,
+                Err(err) => {
+                    debug!(%err, "control connection closed");
+                    break;
+                }
+            }
+        }
 
-    (client, IrohProtocol::with_sender(local_sender))
+        self.0.unlink(peer).await;
+        Ok(())
+    }
 }
 
 #[rpc_requests(message = ControlMessage)]
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ControlService {
+    // Names which of its peer's proven DIDs this link acts as, for every call
+    // after it. Refused with `Unauthenticated` for a DID the peer has not
+    // proven.
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(ActAs)]
+    ActAs { did: Did },
+    // `grant`, where present, acts for its issuer instead of the caller; see
+    // `crate::grant`.
     #[rpc(rx=mpsc::Receiver<Bytes>,tx=oneshot::Sender<Result<Hash, ApiError>>)]
     #[wrap(UploadBlob)]
-    UploadBlob { s: SessionToken },
+    UploadBlob { grant: Option<SignedBytes<Grant>> },
+    // Opens, or resumes, an upload sent in pieces; see `crate::pieces`.
+    #[rpc(tx=oneshot::Sender<Result<UploadProgress, ApiError>>)]
+    #[wrap(BeginUpload)]
+    BeginUpload {
+        grant:  Option<SignedBytes<Grant>>,
+        hash:   Hash,
+        size:   u64,
+        pieces: Vec<[u8; 32]>,
+    },
+    #[rpc(tx=oneshot::Sender<Result<UploadProgress, ApiError>>)]
+    #[wrap(UploadPiece)]
+    UploadPiece {
+        grant: Option<SignedBytes<Grant>>,
+        hash:  Hash,
+        index: u64,
+        data:  Bytes,
+    },
     #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
     #[wrap(PinBlob)]
     PinBlob {
-        s:       SessionToken,
+        grant:   Option<SignedBytes<Grant>>,
         hash:    Hash,
         expires: i64,
     },
+    // Drops the pin at once, refunding it, rather than waiting out its expiry.
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(UnpinBlob)]
+    UnpinBlob {
+        grant: Option<SignedBytes<Grant>>,
+        hash:  Hash,
+    },
     #[rpc(tx=oneshot::Sender<Result<bool, ApiError>>)]
     #[wrap(BlobExists)]
-    BlobExists { s: SessionToken, hash: Hash },
+    BlobExists { hash: Hash },
     #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
     #[wrap(HostDoc)]
-    HostDoc { s: SessionToken, ns: NamespaceId },
+    HostDoc {
+        grant: Option<SignedBytes<Grant>>,
+        ns:    NamespaceId,
+    },
     #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
     #[wrap(UnhostDoc)]
-    UnhostDoc { s: SessionToken, ns: NamespaceId },
-    #[rpc(tx=oneshot::Sender<Result<QuotaInfo, ApiError>>)]
+    UnhostDoc {
+        grant: Option<SignedBytes<Grant>>,
+        ns:    NamespaceId,
+    },
+    // Every namespace hosted here for a user. Only for the stores this one
+    // mirrors; see `crate::mirror`.
+    #[rpc(tx=oneshot::Sender<Result<Vec<NamespaceId>, ApiError>>)]
+    #[wrap(ListMirrored)]
+    ListMirrored {},
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(WriteEntries)]
+    WriteEntries {
+        grant:  Option<SignedBytes<Grant>>,
+        ns:     NamespaceId,
+        writes: Vec<EntryWrite>,
+    },
+    #[rpc(tx=oneshot::Sender<Result<QuotaReport, ApiError>>)]
     #[wrap(GetQuota)]
-    GetQuota { s: SessionToken },
+    GetQuota {},
+    // Everything held for `did`, the caller if `None`; anyone else's is for
+    // operators only. See `crate::archive`.
+    #[rpc(tx=mpsc::Sender<Result<Record, ApiError>>)]
+    #[wrap(ExportArchive)]
+    ExportArchive { did: Option<Did> },
+    // Restores an archive under the DID it was exported for: the caller's
+    // own, or anyone's for operators.
+    #[rpc(rx=mpsc::Receiver<Record>,tx=oneshot::Sender<Result<ImportSummary, ApiError>>)]
+    #[wrap(ImportArchive)]
+    ImportArchive {},
+    // The operators' own denylist, signed by the store's user, for other
+    // stores to subscribe to; `None` if the store has no user.
+    #[rpc(tx=oneshot::Sender<Result<Option<SignedBytes<PublishedDenylist>>, ApiError>>)]
+    #[wrap(GetDenylist)]
+    GetDenylist {},
+
+    // Operator only: refused with `AccessDenied` for any other DID.
+    #[rpc(tx=oneshot::Sender<Result<Vec<UserQuota>, ApiError>>)]
+    #[wrap(ListUsers)]
+    ListUsers {},
+    #[rpc(tx=oneshot::Sender<Result<QuotaInfo, ApiError>>)]
+    #[wrap(GetUserQuota)]
+    GetUserQuota { did: Did },
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(SetUserQuota)]
+    SetUserQuota { did: Did, quota_bytes: u64 },
+    #[rpc(tx=oneshot::Sender<Result<Vec<PinInfo>, ApiError>>)]
+    #[wrap(ListPins)]
+    ListPins { did: Did },
+    #[rpc(tx=oneshot::Sender<Result<Vec<HostedInfo>, ApiError>>)]
+    #[wrap(ListHosted)]
+    ListHosted { did: Did },
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(ForceUnhost)]
+    ForceUnhost { ns: NamespaceId },
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(PurgeDid)]
+    PurgeDid { did: Did },
+    #[rpc(tx=oneshot::Sender<Result<Vec<DeniedEntry>, ApiError>>)]
+    #[wrap(ListDenied)]
+    ListDenied {},
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(Deny)]
+    Deny { entry: DenyEntry },
+    // Only the operators' own entries; subscribed ones follow their publisher.
+    #[rpc(tx=oneshot::Sender<Result<(), ApiError>>)]
+    #[wrap(Undeny)]
+    Undeny { entry: DenyEntry },
 }
 
-async fn handle_requests(
-    ctx: &Arc<StoreContext>,
-    rx: &mut irpc::channel::mpsc::Receiver<ControlMessage>,
-) -> anyhow::Result<()> {
-    while let Some(msg) = rx.recv().await? {
-        let ctx = Arc::clone(ctx);
+/// The DID one link acts as, once it has named one.
+///
+/// Held per link rather than per peer: two actors on one endpoint dial a link
+/// each, and neither may act as the other.
+type Acting = Arc<Mutex<Option<Did>>>;
 
-        n0_future::task::spawn(async move {
-            if let Err(err) = handle_message(ctx, msg).await {
-                warn!("Error handling message: {err:?}");
-            }
-        });
-    }
+fn spawn_message(ctx: &Arc<StoreContext>, peer: Peer, acting: &Acting, msg: ControlMessage) {
+    let ctx = Arc::clone(ctx);
+    let acting = Arc::clone(acting);
 
+    n0_future::task::spawn(async move {
+        if let Err(err) = handle_message(ctx, peer, acting, msg).await {
+            warn!("Error handling message: {err:?}");
+        }
+    });
+}
+
+async fn act_as(
+    ctx: &StoreContext,
+    peer: Peer,
+    acting: &Acting,
+    irpc::WithChannels { inner, tx, .. }: irpc::WithChannels<ActAs, ControlService>,
+) -> anyhow::Result<()> {
+    let res = if ctx.proves(peer, &inner.did).await {
+        *acting.lock() = Some(inner.did);
+        Ok(())
+    } else {
+        Err(ApiError::Unauthenticated)
+    };
+    tx.send(res).await?;
     Ok(())
 }
 
-macro_rules! authenticate {
-    ($ctx:tt, $inner:tt, $tx:tt) => {
-        match $ctx.connections.get_async(&$inner.s).await {
-            Some(c) if c.expires > ::time::OffsetDateTime::now_utc().unix_timestamp() => {
-                c.did.clone()
-            }
-            _ => {
-                $tx.send(Err($crate::error::ApiError::Unauthenticated))
-                    .await?;
-                return Ok(());
-            }
+/// Refuses anyone but the store's operators.
+macro_rules! authorize_operator {
+    ($ctx:tt, $did:tt, $tx:tt) => {
+        if !$ctx.operators.contains(&$did) {
+            $tx.send(Err($crate::error::ApiError::AccessDenied)).await?;
+            return Ok(());
         }
     };
 }
 
-pub(crate) use authenticate;
+pub(crate) use authorize_operator;
+
+/// Refuses the call if any of the entries is denied.
+macro_rules! refuse_denied {
+    ($ctx:tt, $tx:tt, [$($entry:expr),* $(,)?]) => {
+        if $ctx.denies(vec![$($entry),*]).await? {
+            $tx.send(Err($crate::error::ApiError::Denied)).await?;
+            return Ok(());
+        }
+    };
+}
+
+pub(crate) use refuse_denied;
 
 // n0_future futures are intentionally !Send on wasm (single-threaded, no
 // Send needed there); Send-bounded elsewhere.
 #[cfg_attr(target_family = "wasm", expect(clippy::future_not_send))]
-async fn handle_message(ctx: Arc<StoreContext>, msg: ControlMessage) -> anyhow::Result<()> {
+async fn handle_message(
+    ctx: Arc<StoreContext>,
+    peer: Peer,
+    acting: Acting,
+    msg: ControlMessage,
+) -> anyhow::Result<()> {
+    // Every call needs a DID; reply `Unauthenticated` until the link has named
+    // one its peer still proves.
+    let named = acting.lock().clone();
+    let did = match named {
+        Some(did) if ctx.proves(peer, &did).await => Some(did),
+        _ => None,
+    };
+    macro_rules! authenticated {
+        ($channels:ident, $handler:path) => {
+            match did {
+                Some(did) => $handler(ctx, did, $channels).await,
+                None => {
+                    $channels.tx.send(Err(ApiError::Unauthenticated)).await?;
+                    Ok(())
+                }
+            }
+        };
+    }
+
     match msg {
-        ControlMessage::UploadBlob(channels) => blob::upload_blob(ctx, channels).await,
-        ControlMessage::PinBlob(channels) => blob::pin_blob(ctx, channels).await,
-        ControlMessage::BlobExists(channels) => blob::blob_exists(ctx, channels).await,
-        ControlMessage::HostDoc(channels) => doc::host_doc(ctx, channels).await,
-        ControlMessage::UnhostDoc(channels) => doc::unhost_doc(ctx, channels).await,
-        ControlMessage::GetQuota(channels) => quota::get_quota(ctx, channels).await,
+        ControlMessage::ActAs(channels) => act_as(&ctx, peer, &acting, channels).await,
+        ControlMessage::UploadBlob(channels) => authenticated!(channels, blob::upload_blob),
+        ControlMessage::BeginUpload(channels) => authenticated!(channels, upload::begin_upload),
+        ControlMessage::UploadPiece(channels) => authenticated!(channels, upload::upload_piece),
+        ControlMessage::PinBlob(channels) => authenticated!(channels, blob::pin_blob),
+        ControlMessage::UnpinBlob(channels) => authenticated!(channels, blob::unpin_blob),
+        ControlMessage::BlobExists(channels) => authenticated!(channels, blob::blob_exists),
+        ControlMessage::HostDoc(channels) => authenticated!(channels, doc::host_doc),
+        ControlMessage::UnhostDoc(channels) => authenticated!(channels, doc::unhost_doc),
+        ControlMessage::ListMirrored(channels) => authenticated!(channels, doc::list_mirrored),
+        ControlMessage::WriteEntries(channels) => authenticated!(channels, doc::write_entries),
+        ControlMessage::GetQuota(channels) => authenticated!(channels, quota::get_quota),
+        ControlMessage::ExportArchive(channels) => {
+            authenticated!(channels, archive::export_archive)
+        }
+        ControlMessage::ImportArchive(channels) => {
+            authenticated!(channels, archive::import_archive)
+        }
+        ControlMessage::GetDenylist(channels) => {
+            authenticated!(channels, denylist::get_denylist)
+        }
+        ControlMessage::ListUsers(channels) => authenticated!(channels, admin::list_users),
+        ControlMessage::GetUserQuota(channels) => authenticated!(channels, admin::get_user_quota),
+        ControlMessage::SetUserQuota(channels) => authenticated!(channels, admin::set_user_quota),
+        ControlMessage::ListPins(channels) => authenticated!(channels, admin::list_pins),
+        ControlMessage::ListHosted(channels) => authenticated!(channels, admin::list_hosted),
+        ControlMessage::ForceUnhost(channels) => authenticated!(channels, admin::force_unhost),
+        ControlMessage::PurgeDid(channels) => authenticated!(channels, admin::purge_did),
+        ControlMessage::ListDenied(channels) => authenticated!(channels, denylist::list_denied),
+        ControlMessage::Deny(channels) => authenticated!(channels, denylist::deny),
+        ControlMessage::Undeny(channels) => authenticated!(channels, denylist::undeny),
     }
 }
@@ -1,42 +1,161 @@
-use std::sync::Arc;
+use std::{
+    str::FromStr,
+    sync::Arc,
+};
 
+use blake3::Hash;
+use iroh_docs::NamespaceId;
 use irpc::WithChannels;
-use rusqlite::params;
+use rusqlite::{
+    Connection,
+    OptionalExtension,
+    params,
+};
+use xdid::core::did::Did;
 
 use crate::{
     StoreContext,
     control::{
         ControlService,
         GetQuota,
+        HostedInfo,
+        PinInfo,
         QuotaInfo,
-        authenticate,
+        QuotaReport,
+        UsageSample,
+    },
+    quota::{
+        DEFAULT_QUOTA_BYTES,
+        ensure_quota_exists,
+        history_start,
     },
-    quota::ensure_quota_exists,
 };
 
 pub async fn get_quota(
     ctx: Arc<StoreContext>,
-    WithChannels { inner, tx, .. }: WithChannels<GetQuota, ControlService>,
+    did: Did,
+    WithChannels { tx, .. }: WithChannels<GetQuota, ControlService>,
 ) -> anyhow::Result<()> {
-    let did = authenticate!(ctx, inner, tx);
     let did_str = did.to_string();
 
-    let info = ctx
-        .db
-        .call(move |conn| {
-            ensure_quota_exists(conn, &did_str)?;
-            let (bytes_used, quota_bytes): (i64, i64) = conn.query_row(
-                "SELECT bytes_used, quota_bytes FROM user_quotas WHERE owner = ?",
-                params![&did_str],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )?;
-            Ok(QuotaInfo {
+    let report = ctx.db.call(move |conn| read_report(conn, &did_str)).await?;
+
+    tx.send(Ok(report)).await?;
+    Ok(())
+}
+
+fn read_report(conn: &Connection, owner: &str) -> anyhow::Result<QuotaReport> {
+    let QuotaInfo {
+        bytes_used,
+        quota_bytes,
+    } = read_quota(conn, owner)?;
+    let uploading = conn.query_row(
+        "SELECT COALESCE(SUM(received), 0) FROM uploads WHERE owner = ?",
+        params![owner],
+        |row| row.get(0),
+    )?;
+
+    Ok(QuotaReport {
+        bytes_used,
+        quota_bytes,
+        hosted: read_hosted(conn, owner)?,
+        pins: read_pins(conn, owner)?,
+        uploading,
+        history: read_history(conn, owner)?,
+    })
+}
+
+/// The owner's quota, creating the default one if they have none yet.
+pub(super) fn read_quota(conn: &Connection, owner: &str) -> anyhow::Result<QuotaInfo> {
+    ensure_quota_exists(conn, owner)?;
+    let (bytes_used, quota_bytes): (i64, i64) = conn.query_row(
+        "SELECT bytes_used, quota_bytes FROM user_quotas WHERE owner = ?",
+        params![owner],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(QuotaInfo {
+        bytes_used,
+        quota_bytes,
+    })
+}
+
+/// The owner's quota as it stands, without creating one: looking a DID up is
+/// not a reason to give it a row.
+pub(super) fn peek_quota(conn: &Connection, owner: &str) -> anyhow::Result<QuotaInfo> {
+    let info = conn
+        .query_row(
+            "SELECT bytes_used, quota_bytes FROM user_quotas WHERE owner = ?",
+            params![owner],
+            |row| {
+                Ok(QuotaInfo {
+                    bytes_used:  row.get(0)?,
+                    quota_bytes: row.get(1)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(info.unwrap_or(QuotaInfo {
+        bytes_used:  0,
+        quota_bytes: DEFAULT_QUOTA_BYTES,
+    }))
+}
+
+/// The owner's blob pins, largest first.
+pub(super) fn read_pins(conn: &Connection, owner: &str) -> anyhow::Result<Vec<PinInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT hash, expires, size FROM blob_pins WHERE owner = ? ORDER BY size DESC, hash",
+    )?;
+    let rows = stmt.query_map(params![owner], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })?;
+
+    Ok(rows
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|(hash, expires, size)| {
+            Some(PinInfo {
+                hash: Hash::from_str(&hash).ok()?,
+                expires,
+                size,
+            })
+        })
+        .collect())
+}
+
+/// The docs hosted for the owner, largest first.
+pub(super) fn read_hosted(conn: &Connection, owner: &str) -> anyhow::Result<Vec<HostedInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT ns, bytes_used FROM hosted_docs WHERE owner = ? ORDER BY bytes_used DESC, ns",
+    )?;
+    let rows = stmt.query_map(params![owner], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+
+    Ok(rows
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|(ns, bytes_used)| {
+            Some(HostedInfo {
+                ns: NamespaceId::from_str(&ns).ok()?,
                 bytes_used,
-                quota_bytes,
             })
         })
-        .await?;
+        .collect())
+}
 
-    tx.send(Ok(info)).await?;
-    Ok(())
+fn read_history(conn: &Connection, owner: &str) -> anyhow::Result<Vec<UsageSample>> {
+    let mut stmt = conn.prepare(
+        "SELECT day, bytes_used FROM quota_history WHERE owner = ? AND day >= ? ORDER BY day",
+    )?;
+    let rows = stmt.query_map(params![owner, history_start()], |row| {
+        Ok(UsageSample {
+            day:        row.get(0)?,
+            bytes_used: row.get(1)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
 }
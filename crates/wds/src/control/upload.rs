@@ -0,0 +1,382 @@
+//! Resumable uploads: a blob sent as [`crate::pieces`], each verified and
+//! charged as it lands, so a dropped connection loses at most one piece.
+
+use std::{
+    io,
+    sync::Arc,
+    time::Duration,
+};
+
+use blake3::Hash;
+use bytes::Bytes;
+use futures::StreamExt;
+use irpc::WithChannels;
+use rusqlite::{
+    OptionalExtension,
+    params,
+};
+use time::OffsetDateTime;
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    control::{
+        BeginUpload,
+        ControlService,
+        UploadPiece,
+        UploadProgress,
+        blob::{
+            DEFAULT_BLOB_TTL,
+            MAX_UPLOAD_BYTES,
+            insert_pin,
+        },
+        grant::act_for,
+        refuse_denied,
+    },
+    denylist::DenyEntry,
+    error::ApiError,
+    grant::Action,
+    pieces::{
+        self,
+        ChainingValue,
+        PIECE_LEN,
+    },
+    quota::{
+        ensure_quota_exists,
+        release_bytes,
+        reserve_bytes,
+    },
+    tag::BlobTag,
+};
+
+/// How long an upload may sit idle before its pieces are dropped and refunded.
+/// Each received piece restarts the clock.
+const UPLOAD_TTL: Duration = Duration::from_hours(24);
+
+/// Answers with how much the host already holds; an upload of content the
+/// owner already pins completes at once.
+pub async fn begin_upload(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<BeginUpload, ControlService>,
+) -> anyhow::Result<()> {
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Pin]);
+    refuse_denied!(
+        ctx,
+        tx,
+        [
+            denied_caller,
+            DenyEntry::Did(did.clone()),
+            DenyEntry::Blob(inner.hash)
+        ]
+    );
+
+    if inner.size > MAX_UPLOAD_BYTES.cast_unsigned() {
+        tx.send(Err(ApiError::QuotaExceeded)).await?;
+        return Ok(());
+    }
+    if !pieces::verify_layout(&inner.hash, inner.size, &inner.pieces) {
+        tx.send(Err(ApiError::InvalidContent)).await?;
+        return Ok(());
+    }
+
+    let did_str = did.to_string();
+    let hash_str = inner.hash.to_string();
+    let size = inner.size.cast_signed();
+    let cvs = inner.pieces.concat();
+    let now = OffsetDateTime::now_utc();
+    let expires = (now + UPLOAD_TTL).unix_timestamp();
+    let pin_expires = (now + DEFAULT_BLOB_TTL).unix_timestamp();
+
+    let progress = ctx
+        .db
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_quota_exists(&tx, &did_str)?;
+            let refreshed = tx.execute(
+                "UPDATE blob_pins SET expires = MAX(expires, ?) WHERE owner = ? AND hash = ?",
+                params![pin_expires, &did_str, &hash_str],
+            )?;
+            if refreshed > 0 {
+                tx.commit()?;
+                return Ok(UploadProgress {
+                    received: size.cast_unsigned(),
+                    complete: true,
+                });
+            }
+            tx.execute(
+                "INSERT INTO uploads (owner, hash, size, pieces, expires) VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT(owner, hash) DO UPDATE SET expires = excluded.expires",
+                params![&did_str, &hash_str, size, cvs, expires],
+            )?;
+            let received: i64 = tx.query_row(
+                "SELECT received FROM uploads WHERE owner = ? AND hash = ?",
+                params![&did_str, &hash_str],
+                |row| row.get(0),
+            )?;
+            tx.commit()?;
+            Ok(UploadProgress {
+                received: received.cast_unsigned(),
+                complete: false,
+            })
+        })
+        .await?;
+
+    tx.send(Ok(progress)).await?;
+    Ok(())
+}
+
+/// Accepts the next piece of an open upload, assembling and pinning the blob
+/// once the last one lands.
+///
+/// A piece other than the next one is ignored rather than refused: the reply
+/// carries where the upload actually stands, which is all a resuming client
+/// needs to realign.
+pub async fn upload_piece(
+    ctx: Arc<StoreContext>,
+    caller: Did,
+    WithChannels { inner, tx, .. }: WithChannels<UploadPiece, ControlService>,
+) -> anyhow::Result<()> {
+    // Checked again on every piece: an entry added mid-upload stops it.
+    let denied_caller = DenyEntry::Did(caller.clone());
+    let did = act_for!(tx, caller, inner.grant.as_ref(), &[Action::Pin]);
+    refuse_denied!(
+        ctx,
+        tx,
+        [
+            denied_caller,
+            DenyEntry::Did(did.clone()),
+            DenyEntry::Blob(inner.hash)
+        ]
+    );
+    let did_str = did.to_string();
+    let hash_str = inner.hash.to_string();
+
+    let upload = ctx
+        .db
+        .call({
+            let did_str = did_str.clone();
+            let hash_str = hash_str.clone();
+            move |conn| {
+                Ok(conn
+                    .query_row(
+                        "SELECT size, pieces, received FROM uploads WHERE owner = ? AND hash = ?",
+                        params![&did_str, &hash_str],
+                        |row| {
+                            Ok((
+                                row.get::<_, i64>(0)?,
+                                row.get::<_, Vec<u8>>(1)?,
+                                row.get::<_, i64>(2)?,
+                            ))
+                        },
+                    )
+                    .optional()?)
+            }
+        })
+        .await?;
+
+    let Some((size, cvs, received)) = upload else {
+        tx.send(Err(ApiError::BlobNotFound)).await?;
+        return Ok(());
+    };
+    let (size, received) = (size.cast_unsigned(), received.cast_unsigned());
+    // Every piece is already here, but assembling them failed last time (or,
+    // for an empty blob, there were never any to send).
+    if received == size {
+        return finish(&ctx, &did, inner.hash, size, tx).await;
+    }
+    let progress = UploadProgress {
+        received,
+        complete: false,
+    };
+    if inner.index != received / PIECE_LEN {
+        tx.send(Ok(progress)).await?;
+        return Ok(());
+    }
+
+    let cvs = cvs
+        .chunks_exact(32)
+        .filter_map(|cv| ChainingValue::try_from(cv).ok())
+        .collect::<Vec<_>>();
+    if !pieces::verify_piece(&inner.hash, size, &cvs, inner.index, &inner.data) {
+        tx.send(Err(ApiError::InvalidContent)).await?;
+        return Ok(());
+    }
+
+    let len = i64::try_from(inner.data.len()).unwrap_or(i64::MAX);
+    let next = received + len.cast_unsigned();
+    let expires = (OffsetDateTime::now_utc() + UPLOAD_TTL).unix_timestamp();
+    let data = inner.data.to_vec();
+
+    // `received` doubles as a guard: a concurrent copy of this piece that won
+    // the race has already moved it, so this one stores nothing.
+    let stored = ctx
+        .db
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            let moved = tx.execute(
+                "UPDATE uploads SET received = ?, expires = ?
+                 WHERE owner = ? AND hash = ? AND received = ?",
+                params![
+                    next.cast_signed(),
+                    expires,
+                    &did_str,
+                    &hash_str,
+                    received.cast_signed()
+                ],
+            )?;
+            if moved == 0 {
+                return Ok(Some(false));
+            }
+            if reserve_bytes(&tx, &did_str, len).is_err() {
+                return Ok(None);
+            }
+            tx.execute(
+                "INSERT INTO upload_pieces (owner, hash, idx, data) VALUES (?, ?, ?, ?)",
+                params![&did_str, &hash_str, inner.index.cast_signed(), data],
+            )?;
+            tx.commit()?;
+            Ok(Some(true))
+        })
+        .await?;
+
+    match stored {
+        None => {
+            tx.send(Err(ApiError::QuotaExceeded)).await?;
+            return Ok(());
+        }
+        Some(false) => {
+            tx.send(Ok(progress)).await?;
+            return Ok(());
+        }
+        Some(true) => {}
+    }
+
+    if next < size {
+        tx.send(Ok(UploadProgress {
+            received: next,
+            complete: false,
+        }))
+        .await?;
+        return Ok(());
+    }
+
+    finish(&ctx, &did, inner.hash, size, tx).await
+}
+
+async fn finish(
+    ctx: &StoreContext,
+    did: &Did,
+    hash: Hash,
+    size: u64,
+    tx: irpc::channel::oneshot::Sender<Result<UploadProgress, ApiError>>,
+) -> anyhow::Result<()> {
+    let result = assemble(ctx, did, hash, size).await?;
+    tx.send(result.map(|()| UploadProgress {
+        received: size,
+        complete: true,
+    }))
+    .await?;
+    Ok(())
+}
+
+/// Streams the stored pieces into the blob store, then swaps the upload's
+/// per-piece charge for an ordinary pin in one transaction.
+async fn assemble(
+    ctx: &StoreContext,
+    did: &Did,
+    hash: Hash,
+    size: u64,
+) -> anyhow::Result<Result<(), ApiError>> {
+    let did_str = did.to_string();
+    let hash_str = hash.to_string();
+
+    let stream = {
+        let db = ctx.db.clone();
+        let did_str = did_str.clone();
+        let hash_str = hash_str.clone();
+        futures::stream::iter(0..size.div_ceil(PIECE_LEN)).then(move |index| {
+            let db = db.clone();
+            let did_str = did_str.clone();
+            let hash_str = hash_str.clone();
+            async move {
+                db.call(move |conn| {
+                    Ok(conn.query_row(
+                        "SELECT data FROM upload_pieces WHERE owner = ? AND hash = ? AND idx = ?",
+                        params![&did_str, &hash_str, index.cast_signed()],
+                        |row| row.get::<_, Vec<u8>>(0),
+                    )?)
+                })
+                .await
+                .map(Bytes::from)
+                .map_err(io::Error::other)
+            }
+        })
+    };
+
+    let Ok(temp_tag) = ctx
+        .blob_store()
+        .blobs()
+        .add_stream(stream)
+        .await
+        .temp_tag()
+        .await
+    else {
+        return Ok(Err(ApiError::Internal));
+    };
+    // Every piece was held to a chaining value that merges to `hash`, so a
+    // mismatch here is a bug, not a bad upload.
+    if Hash::from(temp_tag.hash()) != hash {
+        tracing::error!(%hash, "assembled upload hashed differently");
+        return Ok(Err(ApiError::Internal));
+    }
+
+    let size = size.cast_signed();
+    let expires = (OffsetDateTime::now_utc() + DEFAULT_BLOB_TTL).unix_timestamp();
+    let assembled = ctx
+        .db
+        .call_mut(move |conn| {
+            let tx = conn.transaction()?;
+            // Claiming the upload row first makes the exchange below happen
+            // once: a concurrent finish of the same upload finds it gone, and
+            // leaves the pin to whoever claimed it.
+            let claimed = tx.execute(
+                "DELETE FROM uploads WHERE owner = ? AND hash = ?",
+                params![&did_str, &hash_str],
+            )?;
+            if claimed != 1 {
+                return Ok(Assembled::Already);
+            }
+            // The pieces were charged as they landed; the pin charges again,
+            // so release first for the exchange to net out.
+            release_bytes(&tx, &did_str, size)?;
+            if !insert_pin(&tx, &did_str, &hash_str, size, expires)? {
+                return Ok(Assembled::OverQuota);
+            }
+            tx.execute(
+                "DELETE FROM upload_pieces WHERE owner = ? AND hash = ?",
+                params![&did_str, &hash_str],
+            )?;
+            tx.commit()?;
+            Ok(Assembled::Pinned)
+        })
+        .await?;
+
+    match assembled {
+        Assembled::Pinned => {}
+        Assembled::Already => return Ok(Ok(())),
+        Assembled::OverQuota => return Ok(Err(ApiError::QuotaExceeded)),
+    }
+
+    let tag_name = BlobTag::new(did.clone(), hash).to_string();
+    ctx.blob_store().tags().set(tag_name, temp_tag).await?;
+    Ok(Ok(()))
+}
+
+enum Assembled {
+    Pinned,
+    /// Another finish of the same upload got there first.
+    Already,
+    OverQuota,
+}
@@ -11,7 +11,13 @@ use rusqlite::Connection;
 use time::OffsetDateTime;
 use tokio::sync::Mutex;
 
-const MIGRATIONS: &[&str] = &[include_str!("../migrations/001_initial.sql")];
+const MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/001_initial.sql"),
+    include_str!("../migrations/002_uploads.sql"),
+    include_str!("../migrations/003_quota_history.sql"),
+    include_str!("../migrations/004_mirrored_docs.sql"),
+    include_str!("../migrations/005_denylist.sql"),
+];
 
 #[derive(Clone)]
 pub struct Database {
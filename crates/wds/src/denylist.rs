@@ -0,0 +1,253 @@
+//! Content a store refuses: blob hashes, namespaces and DIDs its operators
+//! have denied, or that a denylist it subscribes to lists.
+//!
+//! A store publishes its operators' own entries, signed by its user, to
+//! anyone who asks; see [`PublishedDenylist`]. Subscribed entries are replaced
+//! wholesale each time the publisher's list is fetched, and never published
+//! onward.
+
+use std::str::FromStr;
+
+use anyhow::Context;
+use blake3::Hash;
+use iroh::EndpointAddr;
+use iroh_docs::NamespaceId;
+use rusqlite::{
+    Connection,
+    params,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use time::OffsetDateTime;
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    actor::Actor,
+    resolve::{
+        resolve_allowing_loopback,
+        wds_endpoint,
+    },
+    signed_bytes::{
+        Signable,
+        SignedBytes,
+        verify_did_signature,
+    },
+};
+
+/// Marks the operator's own entries in the `source` column.
+const OWN_SOURCE: &str = "";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DenyEntry {
+    Blob(Hash),
+    Namespace(NamespaceId),
+    /// Refused as a caller, an owner acted for, and a registry submitter.
+    Did(Did),
+}
+
+impl DenyEntry {
+    fn columns(&self) -> (&'static str, String) {
+        match self {
+            Self::Blob(hash) => ("blob", hash.to_string()),
+            Self::Namespace(ns) => ("ns", ns.to_string()),
+            Self::Did(did) => ("did", did.to_string()),
+        }
+    }
+
+    fn from_columns(kind: &str, value: &str) -> anyhow::Result<Self> {
+        Ok(match kind {
+            "blob" => Self::Blob(Hash::from_str(value)?),
+            "ns" => Self::Namespace(NamespaceId::from_str(value)?),
+            "did" => Self::Did(Did::from_str(value)?),
+            _ => anyhow::bail!("unknown denylist kind {kind}"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeniedEntry {
+    pub entry:  DenyEntry,
+    /// The publisher it was subscribed from; `None` for the operators' own.
+    pub source: Option<Did>,
+}
+
+/// A store's own denylist as it publishes it, signed by `issuer`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedDenylist {
+    pub issuer:  Did,
+    pub entries: Vec<DenyEntry>,
+    /// Unix timestamp of signing.
+    pub issued:  i64,
+}
+
+impl Signable for PublishedDenylist {
+    const SIGNING_CONTEXT: &'static str = "wds/denylist";
+}
+
+/// Whether any of `entries` is denied, from any source.
+pub fn denies(conn: &Connection, entries: &[DenyEntry]) -> anyhow::Result<bool> {
+    let mut stmt = conn.prepare_cached("SELECT 1 FROM denylist WHERE kind = ? AND value = ?")?;
+    for entry in entries {
+        let (kind, value) = entry.columns();
+        if stmt.exists(params![kind, &value])? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Adds one of the operators' own entries. Returns `false` if it was already
+/// denied.
+pub fn add(conn: &Connection, entry: &DenyEntry) -> anyhow::Result<bool> {
+    let (kind, value) = entry.columns();
+    let rows = conn.execute(
+        "INSERT OR IGNORE INTO denylist (kind, value, source) VALUES (?, ?, ?)",
+        params![kind, &value, OWN_SOURCE],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Removes one of the operators' own entries; a subscribed one stays until its
+/// publisher drops it. Returns `false` if there was none.
+pub fn remove(conn: &Connection, entry: &DenyEntry) -> anyhow::Result<bool> {
+    let (kind, value) = entry.columns();
+    let rows = conn.execute(
+        "DELETE FROM denylist WHERE kind = ? AND value = ? AND source = ?",
+        params![kind, &value, OWN_SOURCE],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Every entry, the operators' own first.
+pub fn list(conn: &Connection) -> anyhow::Result<Vec<DeniedEntry>> {
+    let mut stmt =
+        conn.prepare("SELECT kind, value, source FROM denylist ORDER BY source, kind, value")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let (kind, value, source) = row?;
+        out.push(DeniedEntry {
+            entry:  DenyEntry::from_columns(&kind, &value)?,
+            source: if source == OWN_SOURCE {
+                None
+            } else {
+                Some(Did::from_str(&source)?)
+            },
+        });
+    }
+    Ok(out)
+}
+
+impl StoreContext {
+    /// Whether any of `entries` is denied.
+    pub async fn denies(&self, entries: Vec<DenyEntry>) -> anyhow::Result<bool> {
+        self.db.call(move |conn| denies(conn, &entries)).await
+    }
+
+    /// The operators' own entries, signed by the store's user; `None` without
+    /// one to sign as.
+    pub async fn publish_denylist(&self) -> anyhow::Result<Option<SignedBytes<PublishedDenylist>>> {
+        let Some(identity) = self.user_identity.read().clone() else {
+            return Ok(None);
+        };
+
+        let entries = self
+            .db
+            .call(|conn| {
+                Ok(list(conn)?
+                    .into_iter()
+                    .filter(|denied| denied.source.is_none())
+                    .map(|denied| denied.entry)
+                    .collect::<Vec<_>>())
+            })
+            .await?;
+
+        let list = PublishedDenylist {
+            issuer: identity.did().clone(),
+            entries,
+            issued: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+        Ok(Some(list.sign(identity.signing_key())?))
+    }
+
+    /// Refreshes every subscribed denylist once.
+    ///
+    /// Publishers are DIDs the operator configured, so loopback targets
+    /// resolve. One that cannot be reached keeps its last entries.
+    pub async fn run_denylist_refresh(&self) {
+        for publisher in &self.denylists {
+            let round = async {
+                let doc = resolve_allowing_loopback(publisher)
+                    .await
+                    .context("could not resolve")?;
+                let id = wds_endpoint(&doc).context("no WDS endpoint in DID document")?;
+                self.refresh_denylist(publisher, EndpointAddr::from(id))
+                    .await
+            };
+            if let Err(err) = round.await {
+                tracing::warn!(%publisher, "denylist refresh failed: {err:#}");
+            }
+        }
+    }
+
+    /// Replaces what is held from `publisher` with the list its store at
+    /// `host` publishes, once its signature checks out.
+    pub async fn refresh_denylist(
+        &self,
+        publisher: &Did,
+        host: EndpointAddr,
+    ) -> anyhow::Result<()> {
+        let identity = self
+            .user_identity
+            .read()
+            .clone()
+            .context("no user identity to fetch denylists as")?;
+        let actor = Actor::remote(&self.endpoint, identity, host);
+        let signed = actor
+            .get_denylist()
+            .await?
+            .context("publisher has no denylist")?;
+
+        let published = signed.payload()?;
+        anyhow::ensure!(
+            published.issuer == *publisher,
+            "denylist issued by {}, not {publisher}",
+            published.issuer
+        );
+        anyhow::ensure!(
+            verify_did_signature(&signed, publisher).await,
+            "denylist signature does not verify"
+        );
+
+        let source = publisher.to_string();
+        let count = published.entries.len();
+        self.db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute("DELETE FROM denylist WHERE source = ?", params![&source])?;
+                for entry in &published.entries {
+                    let (kind, value) = entry.columns();
+                    tx.execute(
+                        "INSERT OR IGNORE INTO denylist (kind, value, source) VALUES (?, ?, ?)",
+                        params![kind, &value, &source],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await?;
+
+        tracing::debug!(%publisher, count, "refreshed denylist");
+        Ok(())
+    }
+}
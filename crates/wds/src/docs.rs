@@ -1,5 +1,7 @@
+use futures::StreamExt;
 use iroh_docs::{
     Capability,
+    CapabilityKind,
     NamespaceId,
     api::Doc,
     protocol::Docs,
@@ -17,6 +19,21 @@ pub async fn ensure_open(docs: &Docs, ns: NamespaceId) -> anyhow::Result<Doc> {
     Ok(doc)
 }
 
+/// Whether this node holds `ns` with its write capability, and so can author
+/// entries in it. A namespace imported through [`ensure_open`] is held
+/// read-only.
+pub async fn writable(docs: &Docs, ns: NamespaceId) -> anyhow::Result<bool> {
+    let held = docs.api().list().await?;
+    let mut held = std::pin::pin!(held);
+    while let Some(item) = held.next().await {
+        let (id, kind) = item?;
+        if id == ns {
+            return Ok(kind == CapabilityKind::Write);
+        }
+    }
+    Ok(false)
+}
+
 /// Enrols a namespace in the sync set, so incoming requests for it are
 /// answered.
 ///
@@ -20,6 +20,15 @@ pub enum ApiError {
     BlobNotFound,
     #[error("invalid signature")]
     InvalidSignature,
+    #[error("content does not match its hash")]
+    InvalidContent,
+    /// The content, namespace or DID is on the host's denylist.
+    #[error("refused by the host's denylist")]
+    Denied,
+    /// The host holds the doc without its write capability, as it does any
+    /// namespace it did not create, so cannot author entries in it.
+    #[error("the host holds this doc read-only")]
+    ReadOnly,
     #[error("internal error")]
     Internal,
 }
@@ -10,7 +10,10 @@ use xdid::core::did::Did;
 
 use crate::{
     StoreContext,
-    quota::release_bytes,
+    quota::{
+        history_start,
+        release_bytes,
+    },
     tag::BlobTag,
 };
 
@@ -21,15 +24,58 @@ impl StoreContext {
     /// Content referenced by hosted docs is protected by iroh-docs' own store
     /// tags; this pass only reclaims explicit blob pins that have expired.
     pub async fn run_gc(&self) -> anyhow::Result<()> {
-        self.gc_sessions().await;
+        self.gc_uploads().await?;
+        self.gc_quota_history().await?;
         self.gc_blob_pins().await
     }
 
-    /// Drops sessions past their expiry, so the table tracks live sessions
-    /// rather than every session the process has ever issued.
-    async fn gc_sessions(&self) {
+    /// Drops usage history older than any quota report shows.
+    async fn gc_quota_history(&self) -> anyhow::Result<()> {
+        let start = history_start();
+
+        self.db
+            .call(move |conn| {
+                conn.execute("DELETE FROM quota_history WHERE day < ?", params![start])?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Drops resumable uploads left idle past their expiry, refunding the
+    /// pieces they were charged for.
+    async fn gc_uploads(&self) -> anyhow::Result<()> {
         let now = OffsetDateTime::now_utc().unix_timestamp();
-        self.connections.retain_async(|_, c| c.expires > now).await;
+
+        self.db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let expired = {
+                    let mut stmt =
+                        tx.prepare("SELECT owner, hash, received FROM uploads WHERE expires < ?")?;
+                    let rows = stmt.query_map(params![now], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, i64>(2)?,
+                        ))
+                    })?;
+                    rows.collect::<Result<Vec<_>, _>>()?
+                };
+                for (owner, hash, received) in expired {
+                    release_bytes(&tx, &owner, received)?;
+                    tx.execute(
+                        "DELETE FROM upload_pieces WHERE owner = ? AND hash = ?",
+                        params![&owner, &hash],
+                    )?;
+                    tx.execute(
+                        "DELETE FROM uploads WHERE owner = ? AND hash = ?",
+                        params![&owner, &hash],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await
     }
 
     async fn gc_blob_pins(&self) -> anyhow::Result<()> {
@@ -0,0 +1,110 @@
+//! Delegated access: an owner's signed, expiring permission for another DID to
+//! act on their behalf at a host.
+//!
+//! A grant moves no ownership. Whatever the grantee does under it is charged
+//! to, recorded under and revocable by the issuer, exactly as if the issuer
+//! had done it.
+
+use iroh_docs::NamespaceId;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use xdid::core::did::Did;
+
+use crate::signed_bytes::Signable;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scope {
+    /// Write entries whose key starts with `prefix` into docs the issuer hosts.
+    /// An empty prefix covers every key. Only docs the host holds writable,
+    /// which it created itself, can be written this way.
+    Write { prefix: String },
+    /// Upload and pin blobs against the issuer's quota.
+    Pin,
+    /// Host and unhost docs, charged to the issuer.
+    Host,
+}
+
+/// One thing a grantee asks to do; see [`Grant::permits`].
+#[derive(Debug, Clone, Copy)]
+pub enum Action<'a> {
+    Pin,
+    Host(NamespaceId),
+    Write(NamespaceId, &'a str),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Grant {
+    /// The owner whose quota and hosting the grant lends out. Must sign it.
+    pub issuer:  Did,
+    /// The only DID that may present the grant.
+    pub grantee: Did,
+    /// Confines hosting and writes to one namespace; `None` covers any. Pins
+    /// name no namespace, so this never limits them.
+    pub ns:      Option<NamespaceId>,
+    pub scopes:  Vec<Scope>,
+    /// Unix timestamp after which the grant is refused. Grants cannot be
+    /// withdrawn early, so keep this short and reissue.
+    pub expires: i64,
+}
+
+impl Signable for Grant {
+    const SIGNING_CONTEXT: &'static str = "wds/grant";
+}
+
+impl Grant {
+    /// Whether the scopes cover `action`. Says nothing of signature, grantee
+    /// or expiry.
+    #[must_use]
+    pub fn permits(&self, action: Action<'_>) -> bool {
+        match action {
+            Action::Pin => self.scopes.contains(&Scope::Pin),
+            Action::Host(ns) => self.covers(ns) && self.scopes.contains(&Scope::Host),
+            Action::Write(ns, key) => self.covers(ns)
+                && self.scopes.iter().any(
+                    |s| matches!(s, Scope::Write { prefix } if key.starts_with(prefix.as_str())),
+                ),
+        }
+    }
+
+    fn covers(&self, ns: NamespaceId) -> bool {
+        self.ns.is_none_or(|granted| granted == ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xdid::methods::key::keys::{
+        DidKeyPair,
+        PublicKey,
+        p256::P256KeyPair,
+    };
+
+    use super::*;
+
+    fn did() -> Did {
+        P256KeyPair::generate().public().to_did()
+    }
+
+    #[test]
+    fn write_is_confined_to_prefix_and_namespace() {
+        let ns = NamespaceId::from(&[1; 32]);
+        let other = NamespaceId::from(&[2; 32]);
+        let grant = Grant {
+            issuer:  did(),
+            grantee: did(),
+            ns:      Some(ns),
+            scopes:  vec![Scope::Write {
+                prefix: "team/".into(),
+            }],
+            expires: i64::MAX,
+        };
+
+        assert!(grant.permits(Action::Write(ns, "team/a")));
+        assert!(!grant.permits(Action::Write(ns, "other")));
+        assert!(!grant.permits(Action::Write(other, "team/a")));
+        assert!(!grant.permits(Action::Host(ns)));
+        assert!(!grant.permits(Action::Pin));
+    }
+}
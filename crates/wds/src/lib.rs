@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
 
 use derive_more::Debug;
 use identity::Identity;
@@ -10,58 +16,64 @@ use iroh::{
 use iroh_blobs::api::Store as BlobStore;
 use iroh_docs::protocol::Docs;
 use iroh_gossip::Gossip;
-use irpc::Client;
 use n0_future::task::AbortOnDropHandle;
 use parking_lot::RwLock;
 use xdid::core::did::Did;
 
-use crate::builder::{
-    BoxedBlobs,
-    DataStoreBuilder,
+use crate::{
+    builder::{
+        BoxedBlobs,
+        DataStoreBuilder,
+    },
+    peer::{
+        Peer,
+        PeerState,
+    },
 };
 
 /// DID document service `type` value identifying a WDS endpoint.
 pub const WDS_SERVICE_TYPE: &str = "WDSEndpoint";
 
 pub mod actor;
+pub mod archive;
 mod auth;
 pub mod builder;
 pub mod control;
 pub mod db;
+pub mod denylist;
 pub mod docs;
 pub mod entries;
 pub mod error;
 mod gc;
+pub mod grant;
 pub mod identity;
 pub mod kv;
+mod mirror;
+mod peer;
+pub mod pieces;
 mod quota;
 pub mod resolve;
 pub mod signed_bytes;
 pub mod tag;
 
 pub struct DataStore {
-    control_client: Client<control::ControlService>,
-    auth_client:    Client<auth::AuthService>,
-    endpoint:       Endpoint,
-    ctx:            Arc<StoreContext>,
-    _gc_handle:     Option<AbortOnDropHandle<()>>,
+    control:          control::ControlProtocol,
+    auth:             auth::AuthProtocol,
+    endpoint:         Endpoint,
+    ctx:              Arc<StoreContext>,
+    _gc_handle:       Option<AbortOnDropHandle<()>>,
+    _mirror_handle:   Option<AbortOnDropHandle<()>>,
+    _denylist_handle: Option<AbortOnDropHandle<()>>,
 }
 
-// TODO: Replace session token auth with iroh hooks
-pub type SessionToken = [u8; 32];
-
-/// How long an authenticated session stays valid before the holder must
-/// answer a fresh challenge.
-pub const SESSION_TTL: std::time::Duration = std::time::Duration::from_hours(12);
-
 #[derive(Debug)]
 struct StoreContext {
     #[debug("BlobStore")]
     blobs:         BoxedBlobs,
-    #[debug("HashMap({})", connections.len())]
-    connections:   scc::HashMap<SessionToken, ConnectionState>,
     #[debug("Database")]
     db:            db::Database,
+    /// Publishers whose denylists this store subscribes to.
+    denylists:     Vec<Did>,
     #[debug("Docs")]
     docs:          Docs,
     #[debug("Endpoint")]
@@ -72,6 +84,15 @@ struct StoreContext {
     /// doc handle and metering task that hosting it entails.
     #[debug("HashMap({})", hosted.len())]
     hosted:        scc::HashMap<iroh_docs::NamespaceId, HostedDoc>,
+    /// Peer stores mirrored by this one, and allowed to mirror it.
+    mirrors:       Vec<Did>,
+    /// Source of [`Peer::Local`] ids, one per local actor.
+    next_local:    AtomicU64,
+    /// DIDs allowed the control plane's administration calls.
+    operators:     Vec<Did>,
+    /// Every peer with a link open to the store, and the DID it proved.
+    #[debug("HashMap({})", peers.len())]
+    peers:         scc::HashMap<Peer, PeerState>,
     #[debug("Option<Identity>")]
     user_identity: RwLock<Option<Arc<Identity>>>,
 }
@@ -87,15 +108,6 @@ impl StoreContext {
     }
 }
 
-struct ConnectionState {
-    /// The authenticated DID of the connection.
-    /// Set by the `wds/auth` protocol.
-    did:     Did,
-    /// Unix timestamp past which the token no longer authenticates; prevents
-    /// the session table growing without bound.
-    expires: i64,
-}
-
 impl DataStore {
     /// Create a new [`DataStoreBuilder`].
     #[must_use]
@@ -109,22 +121,24 @@ impl DataStore {
     }
 
     /// Create an actor targeting the local WDS.
+    ///
+    /// Each actor is its own peer, over channels of its own, so actors for
+    /// different identities never share an authentication.
     #[must_use]
     pub fn local_actor(&self, identity: Arc<Identity>) -> actor::Actor {
+        let peer = Peer::Local(self.ctx.next_local.fetch_add(1, Ordering::Relaxed));
         actor::Actor::new(
             identity,
             self.endpoint.addr(),
-            self.control_client.clone(),
-            self.auth_client.clone(),
+            self.control.local_client(peer),
+            self.auth.local_client(peer),
         )
     }
 
     /// Create an actor targeting a remote WDS.
     #[must_use]
     pub fn remote_actor(&self, identity: Arc<Identity>, host: EndpointAddr) -> actor::Actor {
-        let control_client = irpc_iroh::client(self.endpoint.clone(), host.clone(), control::ALPN);
-        let auth_client = irpc_iroh::client(self.endpoint.clone(), host.clone(), auth::ALPN);
-        actor::Actor::new(identity, host, control_client, auth_client)
+        actor::Actor::remote(&self.endpoint, identity, host)
     }
 
     /// Returns the docs protocol handle. Primarily for local doc access.
@@ -144,17 +158,15 @@ impl DataStore {
         &self.ctx.gossip
     }
 
-    /// Resolves an authenticated session token to the DID that holds it.
+    /// Whether a remote endpoint has authenticated as `did`, while it holds a
+    /// connection open to this store.
     ///
-    /// Lets another service co-deployed on this node reuse the sessions this
-    /// store has already established, rather than running a second handshake.
-    pub async fn session_did(&self, token: &SessionToken) -> Option<Did> {
-        let now = time::OffsetDateTime::now_utc().unix_timestamp();
-        self.ctx
-            .connections
-            .read_async(token, |_, c| (c.did.clone(), c.expires))
-            .await
-            .and_then(|(did, expires)| (expires > now).then_some(did))
+    /// Lets another service co-deployed on this node reuse the authentication
+    /// this store has already established, rather than running a second
+    /// handshake. An endpoint may have proven several DIDs, so the service
+    /// asks after the one its caller names.
+    pub async fn endpoint_proves(&self, id: EndpointId, did: &Did) -> bool {
+        self.ctx.proves(Peer::Remote(id), did).await
     }
 
     /// Returns the blob store. Primarily for testing.
@@ -179,6 +191,48 @@ impl DataStore {
         self.ctx.run_gc().await
     }
 
+    /// Mirrors every peer store listed at build time once, now.
+    ///
+    /// The mirror timer does this on its own; peers that cannot be reached
+    /// are logged and retried next round.
+    pub async fn run_mirror(&self) {
+        self.ctx.run_mirror().await;
+    }
+
+    /// Mirrors the store at `host` once, as the peer `did`, without resolving
+    /// it. Primarily for testing.
+    pub async fn mirror_peer(&self, did: &Did, host: EndpointAddr) -> anyhow::Result<()> {
+        self.ctx.mirror_peer(did, host).await
+    }
+
+    /// Whether any of `entries` is denied here, by an operator or a subscribed
+    /// denylist.
+    ///
+    /// For services co-deployed on this node to refuse the same content the
+    /// store does.
+    pub async fn denies(&self, entries: Vec<denylist::DenyEntry>) -> anyhow::Result<bool> {
+        self.ctx.denies(entries).await
+    }
+
+    /// Whether `did` may make the store's admin calls.
+    ///
+    /// For services co-deployed on this node to defer to the same operators.
+    #[must_use]
+    pub fn is_operator(&self, did: &Did) -> bool {
+        self.ctx.operators.contains(did)
+    }
+
+    /// Fetches the denylist `publisher` serves from its store at `host` and
+    /// replaces what is held from it, without resolving it. Primarily for
+    /// testing; the denylist timer refreshes every subscription on its own.
+    pub async fn refresh_denylist(
+        &self,
+        publisher: &Did,
+        host: EndpointAddr,
+    ) -> anyhow::Result<()> {
+        self.ctx.refresh_denylist(publisher, host).await
+    }
+
     /// Returns the database. Primarily for testing.
     #[must_use]
     pub fn db(&self) -> &db::Database {
@@ -0,0 +1,198 @@
+//! Re-hosting what peer stores host, so several stores serve the same docs.
+//!
+//! Each round a store asks every peer it mirrors for the namespaces that peer
+//! hosts for its users, hosts any new ones under its own user — read-only, and
+//! charged to that user's quota — and syncs them from the peer. A namespace a
+//! peer stops listing is unhosted here once no mirrored peer lists it.
+//!
+//! Peers list only what their users host, never what they mirror in turn, so
+//! two stores mirroring each other settle instead of keeping each other's
+//! copies alive.
+
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::Arc,
+};
+
+use anyhow::Context;
+use iroh::EndpointAddr;
+use iroh_docs::NamespaceId;
+use rusqlite::params;
+use xdid::core::did::Did;
+
+use crate::{
+    StoreContext,
+    actor::Actor,
+    control::{
+        host,
+        unhost,
+    },
+    denylist::DenyEntry,
+    resolve::{
+        resolve_allowing_loopback,
+        wds_endpoint,
+    },
+};
+
+impl StoreContext {
+    /// One round over every mirrored peer.
+    ///
+    /// Peers are DIDs the operator configured, so loopback targets resolve. A
+    /// peer that cannot be reached keeps what was mirrored from it until it
+    /// answers again.
+    pub async fn run_mirror(self: &Arc<Self>) {
+        for peer in &self.mirrors {
+            let round = async {
+                let doc = resolve_allowing_loopback(peer)
+                    .await
+                    .context("could not resolve")?;
+                let id = wds_endpoint(&doc).context("no WDS endpoint in DID document")?;
+                self.mirror_peer(peer, EndpointAddr::from(id)).await
+            };
+            if let Err(err) = round.await {
+                tracing::warn!(%peer, "mirroring failed: {err:#}");
+            }
+        }
+    }
+
+    /// Brings what this store mirrors from `peer`, found at `host_addr`, in
+    /// line with what that peer lists.
+    pub async fn mirror_peer(
+        self: &Arc<Self>,
+        peer: &Did,
+        host_addr: EndpointAddr,
+    ) -> anyhow::Result<()> {
+        let identity = self
+            .user_identity
+            .read()
+            .clone()
+            .context("no user identity to mirror as")?;
+        let did = identity.did().clone();
+        let actor = Actor::remote(&self.endpoint, identity, host_addr.clone());
+        let listed = actor.list_mirrored().await?;
+
+        let (added, dropped) = self.diff_mirrored(peer, &did, &listed).await?;
+
+        for ns in added {
+            if self.denies(vec![DenyEntry::Namespace(ns)]).await? {
+                self.forget_mirrored(peer, ns).await?;
+                continue;
+            }
+            if !host(self, &did, ns).await? {
+                tracing::warn!(%peer, %ns, "mirroring would exceed quota, skipped");
+                self.forget_mirrored(peer, ns).await?;
+            }
+        }
+        for ns in dropped {
+            unhost(self, &did, ns).await?;
+        }
+
+        // Nothing here can write a mirrored doc; it stays current by syncing
+        // from the peer, which also fills in one just hosted.
+        for ns in listed {
+            let Some(doc) = self.hosted.get_async(&ns).await.map(|h| h.doc.clone()) else {
+                continue;
+            };
+            if let Err(err) = doc.start_sync(vec![host_addr.clone()]).await {
+                tracing::debug!(%peer, %ns, "mirror sync failed: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records which of `listed` this store now mirrors from `peer`, and
+    /// forgets those the peer no longer lists.
+    ///
+    /// Returns the namespaces to host, and those no mirrored peer lists any
+    /// longer. A namespace one of this store's users already hosts is left to
+    /// them rather than mirrored.
+    async fn diff_mirrored(
+        &self,
+        peer: &Did,
+        did: &Did,
+        listed: &[NamespaceId],
+    ) -> anyhow::Result<(Vec<NamespaceId>, Vec<NamespaceId>)> {
+        let peer = peer.to_string();
+        let did = did.to_string();
+        let listed = listed
+            .iter()
+            .map(ToString::to_string)
+            .collect::<HashSet<_>>();
+
+        let (added, dropped) = self
+            .db
+            .call_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let held = {
+                    let mut stmt = tx.prepare("SELECT ns FROM mirrored_docs WHERE peer = ?")?;
+                    let rows = stmt.query_map(params![&peer], |row| row.get::<_, String>(0))?;
+                    rows.collect::<Result<HashSet<_>, _>>()?
+                };
+
+                let mut added = Vec::new();
+                for ns in listed.difference(&held) {
+                    let users_host = tx
+                        .query_row(
+                            "SELECT 1 FROM hosted_docs WHERE ns = ? AND owner != ?",
+                            params![ns, &did],
+                            |_| Ok(true),
+                        )
+                        .unwrap_or(false);
+                    if users_host {
+                        continue;
+                    }
+                    tx.execute(
+                        "INSERT INTO mirrored_docs (ns, peer) VALUES (?, ?)",
+                        params![ns, &peer],
+                    )?;
+                    added.push(ns.clone());
+                }
+
+                let mut dropped = Vec::new();
+                for ns in held.difference(&listed) {
+                    tx.execute(
+                        "DELETE FROM mirrored_docs WHERE ns = ? AND peer = ?",
+                        params![ns, &peer],
+                    )?;
+                    let others: i64 = tx.query_row(
+                        "SELECT COUNT(*) FROM mirrored_docs WHERE ns = ?",
+                        params![ns],
+                        |row| row.get(0),
+                    )?;
+                    if others == 0 {
+                        dropped.push(ns.clone());
+                    }
+                }
+
+                tx.commit()?;
+                Ok((added, dropped))
+            })
+            .await?;
+
+        let parse = |namespaces: Vec<String>| {
+            namespaces
+                .iter()
+                .map(|ns| NamespaceId::from_str(ns))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok((parse(added)?, parse(dropped)?))
+    }
+
+    /// Drops the record of mirroring `ns` from `peer`, so a later round tries
+    /// it again.
+    async fn forget_mirrored(&self, peer: &Did, ns: NamespaceId) -> anyhow::Result<()> {
+        let peer = peer.to_string();
+        let ns = ns.to_string();
+        self.db
+            .call(move |conn| {
+                conn.execute(
+                    "DELETE FROM mirrored_docs WHERE ns = ? AND peer = ?",
+                    params![&ns, &peer],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+}
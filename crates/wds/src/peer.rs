@@ -0,0 +1,83 @@
+//! Who is on the other end of a control-plane request.
+//!
+//! A peer proves a DID over `wds/auth`, and the store binds it to the peer
+//! itself rather than handing back a bearer token. One endpoint may carry
+//! several identities, so a peer holds every DID it has proven, and each of
+//! its control links names the one it acts as; see [`crate::control`]. There
+//! is nothing to replay from another endpoint, and the bindings are dropped
+//! with the peer's last link.
+
+use std::collections::HashSet;
+
+use iroh::EndpointId;
+use scc::hash_map::Entry;
+use xdid::core::did::Did;
+
+use crate::StoreContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Peer {
+    /// A remote endpoint, over however many connections it has open to us.
+    Remote(EndpointId),
+    /// An in-process actor, over the local channels made for it alone.
+    Local(u64),
+}
+
+pub struct PeerState {
+    /// Every DID the peer has proven, alongside any it proved before.
+    dids:  HashSet<Did>,
+    /// Open connections or local channels. The state goes with the last.
+    links: usize,
+}
+
+impl StoreContext {
+    pub(crate) async fn link(&self, peer: Peer) {
+        self.peers
+            .entry_async(peer)
+            .await
+            .and_modify(|state| state.links += 1)
+            .or_insert(PeerState {
+                dids:  HashSet::new(),
+                links: 1,
+            });
+    }
+
+    pub(crate) async fn unlink(&self, peer: Peer) {
+        if let Entry::Occupied(mut entry) = self.peers.entry_async(peer).await {
+            entry.get_mut().links -= 1;
+            if entry.get().links == 0 {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Binds `did` to a linked peer. False if the peer has no open link left
+    /// to bind it to.
+    pub(crate) async fn bind(&self, peer: Peer, did: Did) -> bool {
+        match self.peers.entry_async(peer).await {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().dids.insert(did);
+                true
+            }
+            Entry::Vacant(_) => false,
+        }
+    }
+
+    /// Whether `peer` has proven `did`, and still holds a link.
+    pub(crate) async fn proves(&self, peer: Peer, did: &Did) -> bool {
+        self.peers
+            .read_async(&peer, |_, state| state.dids.contains(did))
+            .await
+            .unwrap_or(false)
+    }
+
+    /// Unbinds `did` from every peer, which must authenticate afresh.
+    pub(crate) async fn unbind(&self, did: &Did) {
+        self.peers
+            .retain_async(|_, state| {
+                state.dids.remove(did);
+                true
+            })
+            .await;
+    }
+}
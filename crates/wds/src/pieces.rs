@@ -0,0 +1,158 @@
+//! A blob's blake3 tree, cut at fixed-size pieces so a host can verify each
+//! piece of an upload the moment it arrives.
+//!
+//! [`PIECE_LEN`] is a power-of-two number of blake3 chunks, which makes every
+//! piece a whole subtree of the blob's tree. The uploader sends the chaining
+//! value of each piece up front; the host checks they merge to the blob's
+//! hash, then holds each piece to its own value. This is bao's verified
+//! streaming at piece granularity, without the per-chunk outboard.
+
+pub use blake3::hazmat::ChainingValue;
+use blake3::{
+    Hash,
+    Hasher,
+    hazmat::{
+        HasherExt,
+        Mode,
+        merge_subtrees_non_root,
+        merge_subtrees_root,
+    },
+};
+
+/// Small enough that a flaky link loses little on a drop, large enough that
+/// the chaining values of the largest upload stay a few KiB.
+pub const PIECE_LEN: u64 = 256 * 1024;
+
+/// Pieces in a blob of `size` bytes. An empty blob is one empty piece.
+#[must_use]
+pub const fn count(size: u64) -> u64 {
+    if size == 0 {
+        1
+    } else {
+        size.div_ceil(PIECE_LEN)
+    }
+}
+
+/// The byte length piece `index` must have.
+#[must_use]
+pub const fn len(size: u64, index: u64) -> u64 {
+    if index + 1 < count(size) {
+        PIECE_LEN
+    } else {
+        size - index * PIECE_LEN
+    }
+}
+
+/// The chaining value of every piece of `bytes`, or none for a blob that fits
+/// in one piece: a lone piece is the root, checked against the hash itself.
+#[must_use]
+pub fn chaining_values(bytes: &[u8]) -> Vec<ChainingValue> {
+    if count(bytes.len() as u64) == 1 {
+        return Vec::new();
+    }
+    bytes
+        .chunks(PIECE_LEN as usize)
+        .zip(0..)
+        .map(|(piece, index)| piece_cv(index, piece))
+        .collect()
+}
+
+/// Whether `cvs` are the pieces of a `size`-byte blob hashing to `hash`.
+#[must_use]
+pub fn verify_layout(hash: &Hash, size: u64, cvs: &[ChainingValue]) -> bool {
+    if count(size) == 1 {
+        return cvs.is_empty();
+    }
+    cvs.len() as u64 == count(size) && root(cvs) == *hash
+}
+
+/// Whether `data` is piece `index` of the blob whose layout `cvs` describes.
+/// The layout must already have passed [`verify_layout`].
+#[must_use]
+pub fn verify_piece(
+    hash: &Hash,
+    size: u64,
+    cvs: &[ChainingValue],
+    index: u64,
+    data: &[u8],
+) -> bool {
+    if index >= count(size) || data.len() as u64 != len(size, index) {
+        return false;
+    }
+    if cvs.is_empty() {
+        return blake3::hash(data) == *hash;
+    }
+    cvs.get(index as usize) == Some(&piece_cv(index, data))
+}
+
+fn piece_cv(index: u64, piece: &[u8]) -> ChainingValue {
+    Hasher::new()
+        .set_input_offset(index * PIECE_LEN)
+        .update(piece)
+        .finalize_non_root()
+}
+
+/// Pieces share blake3's split rule: the left subtree takes the largest power
+/// of two strictly below the total.
+const fn left_len(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+fn root(cvs: &[ChainingValue]) -> Hash {
+    let (left, right) = cvs.split_at(left_len(cvs.len()));
+    merge_subtrees_root(&subtree(left), &subtree(right), Mode::Hash)
+}
+
+fn subtree(cvs: &[ChainingValue]) -> ChainingValue {
+    if let [cv] = cvs {
+        return *cv;
+    }
+    let (left, right) = cvs.split_at(left_len(cvs.len()));
+    merge_subtrees_non_root(&subtree(left), &subtree(right), Mode::Hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(size: u64) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn layout_merges_to_the_blake3_hash() {
+        for size in [
+            0,
+            1,
+            PIECE_LEN,
+            PIECE_LEN + 1,
+            3 * PIECE_LEN,
+            5 * PIECE_LEN + 17,
+        ] {
+            let bytes = blob(size);
+            let hash = blake3::hash(&bytes);
+            let cvs = chaining_values(&bytes);
+
+            assert!(verify_layout(&hash, size, &cvs), "size {size}");
+            for (piece, index) in bytes.chunks(PIECE_LEN as usize).zip(0..) {
+                assert!(verify_piece(&hash, size, &cvs, index, piece), "size {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn a_tampered_piece_is_refused() {
+        let size = 3 * PIECE_LEN + 5;
+        let mut bytes = blob(size);
+        let hash = blake3::hash(&bytes);
+        let cvs = chaining_values(&bytes);
+
+        bytes[PIECE_LEN as usize] ^= 1;
+        let piece = &bytes[PIECE_LEN as usize..2 * PIECE_LEN as usize];
+        assert!(!verify_piece(&hash, size, &cvs, 1, piece));
+
+        let mut forged = cvs;
+        forged[1] = piece_cv(1, piece);
+        assert!(!verify_layout(&hash, size, &forged));
+    }
+}
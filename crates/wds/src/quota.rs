@@ -3,8 +3,13 @@ use rusqlite::{
     Connection,
     params,
 };
+use time::OffsetDateTime;
 
-const DEFAULT_QUOTA_BYTES: i64 = 256 * 1024 * 1024;
+use crate::control::QUOTA_HISTORY_DAYS;
+
+pub const DEFAULT_QUOTA_BYTES: i64 = 256 * 1024 * 1024;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
 
 /// Ensures a quota record exists for the user, creating one with defaults if
 /// not.
@@ -32,6 +37,7 @@ pub fn reserve_bytes(conn: &Connection, owner: &str, n_bytes: i64) -> Result<(),
     if rows_affected == 0 {
         return Err(QuotaExceeded);
     }
+    record_usage(conn, owner);
 
     Ok(())
 }
@@ -43,5 +49,31 @@ pub fn release_bytes(conn: &Connection, owner: &str, n_bytes: i64) -> anyhow::Re
          WHERE owner = ?",
         params![n_bytes, owner],
     )?;
+    record_usage(conn, owner);
     Ok(())
 }
+
+/// Midnight (UTC) of the first day a quota report's history covers.
+pub fn history_start() -> i64 {
+    today() - (QUOTA_HISTORY_DAYS - 1) * DAY_SECS
+}
+
+fn today() -> i64 {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    now - now.rem_euclid(DAY_SECS)
+}
+
+/// Makes the owner's usage as it now stands today's entry in their history.
+///
+/// Best effort: the history only reports usage, so failing to record it must
+/// not fail, or leak, the charge it follows.
+fn record_usage(conn: &Connection, owner: &str) {
+    if let Err(err) = conn.execute(
+        "INSERT INTO quota_history (owner, day, bytes_used)
+         SELECT owner, ?, bytes_used FROM user_quotas WHERE owner = ?
+         ON CONFLICT(owner, day) DO UPDATE SET bytes_used = excluded.bytes_used",
+        params![today(), owner],
+    ) {
+        tracing::warn!(%owner, ?err, "recording quota usage failed");
+    }
+}
@@ -1,10 +1,12 @@
 //! Shared, time-bounded DID resolution.
 
 use std::{
+    str::FromStr,
     sync::LazyLock,
     time::Duration,
 };
 
+use iroh::EndpointId;
 use xdid::{
     core::{
         Method,
@@ -21,6 +23,8 @@ use xdid::{
     resolver::DidResolver,
 };
 
+use crate::WDS_SERVICE_TYPE;
+
 /// Bounds the whole resolution. The `did:web` client caps its own connect and
 /// request phases, but the target check ahead of them resolves DNS without a
 /// deadline of its own.
@@ -66,6 +70,20 @@ pub async fn resolve_allowing_loopback(did: &Did) -> Option<Document> {
     run(LOOPBACK.as_ref()?, did).await
 }
 
+/// The first endpoint a DID document lists under a WDS service.
+///
+/// Entries that are not endpoint ids, such as a server's `registry:` pointer,
+/// are skipped.
+#[must_use]
+pub fn wds_endpoint(doc: &Document) -> Option<EndpointId> {
+    doc.service
+        .iter()
+        .flatten()
+        .filter(|service| service.typ.iter().any(|t| t == WDS_SERVICE_TYPE))
+        .flat_map(|service| &service.service_endpoint)
+        .find_map(|id| EndpointId::from_str(id).ok())
+}
+
 async fn run(resolver: &DidResolver, did: &Did) -> Option<Document> {
     match n0_future::time::timeout(RESOLVE_TIMEOUT, resolver.resolve(did)).await {
         Ok(Ok(doc)) => Some(doc),
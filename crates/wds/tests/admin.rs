@@ -0,0 +1,237 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use iroh::{
+    Endpoint,
+    endpoint::presets::N0DisableRelay,
+    protocol::Router,
+};
+use rand::RngCore;
+use rstest::{
+    fixture,
+    rstest,
+};
+use rusqlite::params;
+use tracing_test::traced_test;
+use wds::{
+    DataStore,
+    actor::Actor,
+    entries,
+    identity::Identity,
+};
+use xdid::methods::key::keys::{
+    DidKeyPair,
+    PublicKey,
+    p256::P256KeyPair,
+};
+
+use crate::common::{
+    DataStoreCtx,
+    ctx,
+};
+
+mod common;
+
+struct AdminCtx {
+    store:    DataStore,
+    operator: Actor,
+    alice:    Actor,
+    _router:  Router,
+}
+
+fn identity() -> Arc<Identity> {
+    let key = P256KeyPair::generate();
+    Arc::new(Identity::new(key.public().to_did(), key))
+}
+
+#[fixture]
+async fn admin_ctx() -> AdminCtx {
+    let endpoint = Endpoint::builder(N0DisableRelay)
+        .bind()
+        .await
+        .expect("bind endpoint");
+
+    let operator = identity();
+    let (store, f) = DataStore::builder(endpoint.clone())
+        .operators([operator.did().clone()])
+        .build()
+        .await
+        .expect("construct data store");
+    let router = f(Router::builder(endpoint)).spawn();
+
+    let alice = identity();
+    let did = alice.did().to_string();
+    store
+        .db()
+        .call(move |conn| {
+            conn.execute(
+                "INSERT INTO user_quotas (owner, bytes_used, quota_bytes) VALUES (?, 0, 10000000)",
+                params![&did],
+            )?;
+            Ok(())
+        })
+        .await
+        .expect("create quota");
+
+    AdminCtx {
+        operator: store.local_actor(operator),
+        alice: store.local_actor(alice),
+        store,
+        _router: router,
+    }
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn a_user_is_not_an_operator(#[future] ctx: DataStoreCtx) {
+    assert!(ctx.alice.list_users().await.is_err());
+    assert!(
+        ctx.alice
+            .set_user_quota(ctx.bob.identity().did().clone(), 0)
+            .await
+            .is_err()
+    );
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn an_operator_sets_a_quota_the_user_is_held_to(#[future] admin_ctx: AdminCtx) {
+    let alice = admin_ctx.alice.identity().did().clone();
+
+    admin_ctx
+        .operator
+        .set_user_quota(alice.clone(), 1024)
+        .await
+        .expect("set quota");
+
+    let info = admin_ctx
+        .operator
+        .user_quota(alice.clone())
+        .await
+        .expect("get quota");
+    assert_eq!(info.quota_bytes, 1024);
+
+    let users = admin_ctx.operator.list_users().await.expect("list users");
+    assert!(
+        users
+            .iter()
+            .any(|u| u.owner == alice && u.quota_bytes == 1024)
+    );
+
+    let mut bytes = vec![0u8; 4096];
+    rand::rng().fill_bytes(&mut bytes);
+    assert!(admin_ctx.alice.upload_blob(bytes.into()).await.is_err());
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn purging_a_did_removes_what_it_holds(#[future] admin_ctx: AdminCtx) {
+    let alice = admin_ctx.alice.identity().did().clone();
+
+    let mut bytes = vec![0u8; 2048];
+    rand::rng().fill_bytes(&mut bytes);
+    let hash = admin_ctx
+        .alice
+        .upload_blob(bytes.into())
+        .await
+        .expect("upload blob");
+    let ns = entries::create(admin_ctx.store.docs())
+        .await
+        .expect("create doc");
+    admin_ctx.alice.host_doc(ns).await.expect("host doc");
+
+    let pins = admin_ctx
+        .operator
+        .user_pins(alice.clone())
+        .await
+        .expect("list pins");
+    assert_eq!(pins.iter().map(|p| p.hash).collect::<Vec<_>>(), [hash]);
+    let hosted = admin_ctx
+        .operator
+        .user_hosted(alice.clone())
+        .await
+        .expect("list hosted");
+    assert_eq!(hosted.iter().map(|h| h.ns).collect::<Vec<_>>(), [ns]);
+
+    admin_ctx
+        .operator
+        .purge_did(alice.clone())
+        .await
+        .expect("purge");
+
+    assert!(
+        admin_ctx
+            .operator
+            .user_pins(alice.clone())
+            .await
+            .expect("list pins")
+            .is_empty()
+    );
+    assert!(
+        admin_ctx
+            .operator
+            .user_hosted(alice.clone())
+            .await
+            .expect("list hosted")
+            .is_empty()
+    );
+    let users = admin_ctx.operator.list_users().await.expect("list users");
+    assert!(users.iter().all(|u| u.owner != alice));
+
+    let info = admin_ctx
+        .operator
+        .user_quota(alice.clone())
+        .await
+        .expect("get quota");
+    assert_eq!(info.bytes_used, 0);
+    let users = admin_ctx.operator.list_users().await.expect("list users");
+    assert!(
+        users.iter().all(|u| u.owner != alice),
+        "looking a purged DID up does not enrol it again"
+    );
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn force_unhost_refunds_every_host(#[future] admin_ctx: AdminCtx) {
+    let alice = admin_ctx.alice.identity().did().clone();
+    let ns = entries::create(admin_ctx.store.docs())
+        .await
+        .expect("create doc");
+    admin_ctx.alice.host_doc(ns).await.expect("host doc");
+
+    admin_ctx
+        .operator
+        .force_unhost(ns)
+        .await
+        .expect("force unhost");
+
+    assert!(
+        admin_ctx
+            .operator
+            .user_hosted(alice.clone())
+            .await
+            .expect("list hosted")
+            .is_empty()
+    );
+    let info = admin_ctx
+        .operator
+        .user_quota(alice)
+        .await
+        .expect("get quota");
+    assert_eq!(info.bytes_used, 0);
+}
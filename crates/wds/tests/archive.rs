@@ -0,0 +1,130 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use rstest::rstest;
+use tracing_test::traced_test;
+use wds::{
+    archive::{
+        ArchivedDoc,
+        Record,
+        VERSION,
+    },
+    entries,
+};
+
+use crate::common::{
+    DataStoreCtx,
+    ctx,
+};
+
+mod common;
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn an_archive_restores_pins_and_hosting_on_another_store(
+    #[future] ctx: DataStoreCtx,
+    #[future]
+    #[from(ctx)]
+    other: DataStoreCtx,
+) {
+    let hash = ctx
+        .alice
+        .upload_blob(Bytes::from(vec![7; 2048]))
+        .await
+        .expect("upload");
+    let ns = entries::create(ctx.store.docs()).await.expect("create doc");
+    ctx.alice.host_doc(ns).await.expect("host");
+
+    let mut archive = Vec::new();
+    ctx.alice
+        .export_archive(None, &mut archive)
+        .await
+        .expect("export");
+
+    assert!(
+        other
+            .bob
+            .import_archive(&mut archive.as_slice())
+            .await
+            .is_err(),
+        "bob cannot restore alice's archive as alice"
+    );
+
+    let alice = other.store.local_actor(Arc::clone(ctx.alice.identity()));
+    let summary = alice
+        .import_archive(&mut archive.as_slice())
+        .await
+        .expect("import");
+    assert_eq!((summary.blobs, summary.pins, summary.docs), (1, 1, 1));
+
+    let report = alice.get_quota().await.expect("get quota");
+    assert_eq!(report.bytes_used, 2048);
+    assert_eq!(
+        report.pins.iter().map(|pin| pin.hash).collect::<Vec<_>>(),
+        [hash]
+    );
+    assert_eq!(
+        report.hosted.iter().map(|doc| doc.ns).collect::<Vec<_>>(),
+        [ns]
+    );
+
+    alice
+        .import_archive(&mut archive.as_slice())
+        .await
+        .expect("import again");
+    let report = alice.get_quota().await.expect("get quota");
+    assert_eq!(report.bytes_used, 2048, "a second import charges nothing");
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn an_unhosted_doc_without_its_secret_opens_nothing(
+    #[future] ctx: DataStoreCtx,
+    #[future]
+    #[from(ctx)]
+    other: DataStoreCtx,
+) {
+    // A doc bob controls elsewhere, which he would have this store replicate
+    // without hosting, and so without paying for it.
+    let ns = entries::create(other.store.docs())
+        .await
+        .expect("create doc");
+
+    let mut archive = Vec::new();
+    for record in [
+        Record::Header {
+            version:  VERSION,
+            owner:    ctx.bob.identity().did().clone(),
+            source:   other.store.endpoint_id(),
+            exported: 0,
+        },
+        Record::Doc(ArchivedDoc {
+            ns,
+            secret: None,
+            hosted: false,
+            entries: Vec::new(),
+        }),
+    ] {
+        wds::archive::write(&mut archive, &record).expect("write record");
+    }
+
+    let summary = ctx
+        .bob
+        .import_archive(&mut archive.as_slice())
+        .await
+        .expect("import");
+    assert_eq!(summary.docs, 0);
+    assert!(
+        !matches!(ctx.store.docs().api().open(ns).await, Ok(Some(_))),
+        "the namespace is not opened"
+    );
+}
@@ -0,0 +1,85 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use iroh::{
+    Endpoint,
+    endpoint::presets::N0DisableRelay,
+};
+use rstest::rstest;
+use tracing_test::traced_test;
+use wds::actor::Actor;
+
+use crate::common::{
+    DataStoreCtx,
+    ctx,
+};
+
+mod common;
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn authentication_ends_with_the_connection(#[future] ctx: DataStoreCtx) {
+    let endpoint = Endpoint::builder(N0DisableRelay)
+        .bind()
+        .await
+        .expect("bind endpoint");
+    let id = endpoint.id();
+    let actor = Actor::remote(
+        &endpoint,
+        Arc::clone(ctx.alice.identity()),
+        ctx.store.endpoint().addr(),
+    );
+
+    let did = ctx.alice.identity().did();
+    actor.get_quota().await.expect("get quota over the link");
+    assert!(ctx.store.endpoint_proves(id, did).await);
+
+    drop(actor);
+    endpoint.close().await;
+
+    while ctx.store.endpoint_proves(id, did).await {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn actors_sharing_an_endpoint_each_act_as_themselves(#[future] ctx: DataStoreCtx) {
+    let endpoint = Endpoint::builder(N0DisableRelay)
+        .bind()
+        .await
+        .expect("bind endpoint");
+    let alice = Actor::remote(
+        &endpoint,
+        Arc::clone(ctx.alice.identity()),
+        ctx.store.endpoint().addr(),
+    );
+    let bob = Actor::remote(
+        &endpoint,
+        Arc::clone(ctx.bob.identity()),
+        ctx.store.endpoint().addr(),
+    );
+
+    alice.authenticate().await.expect("authenticate alice");
+    bob.authenticate().await.expect("authenticate bob");
+
+    alice
+        .upload_blob(vec![7u8; 4096].into())
+        .await
+        .expect("upload as alice");
+
+    assert_eq!(
+        alice.get_quota().await.expect("alice's quota").bytes_used,
+        4096,
+        "bob authenticating on the same endpoint must not take over alice's link"
+    );
+    assert_eq!(bob.get_quota().await.expect("bob's quota").bytes_used, 0);
+}
@@ -0,0 +1,245 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use iroh::{
+    Endpoint,
+    endpoint::presets::N0DisableRelay,
+    protocol::Router,
+};
+use rstest::{
+    fixture,
+    rstest,
+};
+use tracing_test::traced_test;
+use wds::{
+    DataStore,
+    actor::Actor,
+    denylist::DenyEntry,
+    entries,
+    identity::Identity,
+};
+use xdid::methods::key::keys::{
+    DidKeyPair,
+    PublicKey,
+    p256::P256KeyPair,
+};
+
+struct ModeratedCtx {
+    store:    DataStore,
+    operator: Actor,
+    alice:    Actor,
+    _router:  Router,
+}
+
+fn identity() -> Arc<Identity> {
+    let key = P256KeyPair::generate();
+    Arc::new(Identity::new(key.public().to_did(), key))
+}
+
+/// A store whose user is also its operator, as a server's is, so the list its
+/// operator keeps is the one it publishes.
+async fn moderated(subscribe_to: Option<&Identity>) -> ModeratedCtx {
+    let endpoint = Endpoint::builder(N0DisableRelay)
+        .bind()
+        .await
+        .expect("bind endpoint");
+
+    let operator = identity();
+    let (store, f) = DataStore::builder(endpoint.clone())
+        .operators([operator.did().clone()])
+        .denylists(subscribe_to.map(|publisher| publisher.did().clone()))
+        .build()
+        .await
+        .expect("construct data store");
+    let router = f(Router::builder(endpoint)).spawn();
+    store.set_user_identity(Arc::clone(&operator));
+
+    ModeratedCtx {
+        operator: store.local_actor(operator),
+        alice: store.local_actor(identity()),
+        store,
+        _router: router,
+    }
+}
+
+#[fixture]
+async fn moderated_ctx() -> ModeratedCtx {
+    moderated(None).await
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn denied_blobs_namespaces_and_dids_are_refused(#[future] moderated_ctx: ModeratedCtx) {
+    let ModeratedCtx {
+        store,
+        operator,
+        alice,
+        ..
+    } = moderated_ctx;
+
+    assert!(
+        alice
+            .deny(DenyEntry::Did(alice.identity().did().clone()))
+            .await
+            .is_err(),
+        "only an operator manages the denylist"
+    );
+
+    let bad = Bytes::from_static(b"known bad");
+    operator
+        .deny(DenyEntry::Blob(blake3::hash(&bad)))
+        .await
+        .expect("deny blob");
+    assert!(alice.upload_blob(bad).await.is_err());
+    alice
+        .upload_blob(Bytes::from_static(b"fine"))
+        .await
+        .expect("upload other content");
+
+    let ns = entries::create(store.docs()).await.expect("create doc");
+    operator
+        .deny(DenyEntry::Namespace(ns))
+        .await
+        .expect("deny namespace");
+    assert!(alice.host_doc(ns).await.is_err());
+
+    let alice_entry = DenyEntry::Did(alice.identity().did().clone());
+    operator.deny(alice_entry.clone()).await.expect("deny did");
+    assert!(
+        alice
+            .upload_blob(Bytes::from_static(b"more"))
+            .await
+            .is_err()
+    );
+
+    operator.undeny(alice_entry).await.expect("undeny did");
+    alice
+        .upload_blob(Bytes::from_static(b"more"))
+        .await
+        .expect("upload once allowed again");
+
+    let listed = operator.list_denied().await.expect("list denied");
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().all(|denied| denied.source.is_none()));
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn entries_from_a_subscribed_denylist_are_refused(#[future] moderated_ctx: ModeratedCtx) {
+    let publisher = moderated_ctx;
+    let publisher_id = Arc::clone(publisher.operator.identity());
+    let subscriber = moderated(Some(&publisher_id)).await;
+
+    let ns = entries::create(subscriber.store.docs())
+        .await
+        .expect("create doc");
+    publisher
+        .operator
+        .deny(DenyEntry::Namespace(ns))
+        .await
+        .expect("deny namespace");
+
+    subscriber
+        .store
+        .refresh_denylist(publisher_id.did(), publisher.store.endpoint().addr())
+        .await
+        .expect("refresh denylist");
+    assert!(subscriber.alice.host_doc(ns).await.is_err());
+
+    let listed = subscriber
+        .operator
+        .list_denied()
+        .await
+        .expect("list denied");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].source.as_ref(), Some(publisher_id.did()));
+    assert!(
+        subscriber
+            .operator
+            .undeny(listed[0].entry.clone())
+            .await
+            .is_ok()
+            && subscriber.alice.host_doc(ns).await.is_err(),
+        "a subscribed entry is lifted only by its publisher"
+    );
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn an_import_leaves_out_what_the_store_denies(#[future] moderated_ctx: ModeratedCtx) {
+    let source = moderated_ctx;
+    let bad = Bytes::from_static(b"known bad");
+    let fine = Bytes::from_static(b"fine");
+    source
+        .alice
+        .upload_blob(bad.clone())
+        .await
+        .expect("upload bad");
+    source
+        .alice
+        .upload_blob(fine.clone())
+        .await
+        .expect("upload fine");
+    let ns = entries::create(source.store.docs())
+        .await
+        .expect("create doc");
+    source.alice.host_doc(ns).await.expect("host");
+
+    let mut archive = Vec::new();
+    source
+        .alice
+        .export_archive(None, &mut archive)
+        .await
+        .expect("export");
+
+    let target = moderated(None).await;
+    let alice = target
+        .store
+        .local_actor(Arc::clone(source.alice.identity()));
+    target
+        .operator
+        .deny(DenyEntry::Blob(blake3::hash(&bad)))
+        .await
+        .expect("deny blob");
+    target
+        .operator
+        .deny(DenyEntry::Namespace(ns))
+        .await
+        .expect("deny namespace");
+
+    let summary = alice
+        .import_archive(&mut archive.as_slice())
+        .await
+        .expect("import");
+    assert_eq!((summary.blobs, summary.pins, summary.docs), (1, 1, 0));
+    assert_eq!(summary.denied, 3, "the bad blob, its pin and the doc");
+
+    let report = alice.get_quota().await.expect("get quota");
+    assert_eq!(
+        report.pins.iter().map(|pin| pin.hash).collect::<Vec<_>>(),
+        [blake3::hash(&fine)]
+    );
+    assert!(report.hosted.is_empty());
+
+    target
+        .operator
+        .deny(DenyEntry::Did(alice.identity().did().clone()))
+        .await
+        .expect("deny did");
+    assert!(
+        alice.import_archive(&mut archive.as_slice()).await.is_err(),
+        "a denied owner restores nothing"
+    );
+}
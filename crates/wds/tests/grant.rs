@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use rand::RngCore;
+use rstest::rstest;
+use tracing_test::traced_test;
+use wds::{
+    control::EntryWrite,
+    entries,
+    error::ApiError,
+    grant::Scope,
+};
+
+use crate::common::{
+    DataStoreCtx,
+    ctx,
+};
+
+mod common;
+
+const TTL: Duration = Duration::from_hours(1);
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn a_grantee_uploads_against_the_owners_quota(#[future] ctx: DataStoreCtx) {
+    let grant = ctx
+        .alice
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            None,
+            vec![Scope::Pin],
+            TTL,
+        )
+        .expect("issue grant");
+
+    ctx.bob
+        .acting_for(grant)
+        .upload_blob(random_bytes(2048).into())
+        .await
+        .expect("upload as alice");
+
+    let alice = ctx.alice.get_quota().await.expect("alice quota");
+    let bob = ctx.bob.get_quota().await.expect("bob quota");
+    assert_eq!(alice.bytes_used, 2048);
+    assert_eq!(bob.bytes_used, 0);
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn a_grantee_hosts_and_unhosts_for_the_owner(#[future] ctx: DataStoreCtx) {
+    let ns = entries::create(ctx.store.docs()).await.expect("create doc");
+    let grant = ctx
+        .alice
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            Some(ns),
+            vec![Scope::Host],
+            TTL,
+        )
+        .expect("issue grant");
+    let bob = ctx.bob.acting_for(grant);
+
+    bob.host_doc(ns).await.expect("host as alice");
+    // Hosted under alice, so bob acting as himself cannot take it down.
+    assert!(ctx.bob.unhost_doc(ns).await.is_err());
+    bob.unhost_doc(ns).await.expect("unhost as alice");
+
+    let other = entries::create(ctx.store.docs()).await.expect("create doc");
+    assert!(bob.host_doc(other).await.is_err());
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn a_grant_is_refused_to_anyone_but_its_grantee(#[future] ctx: DataStoreCtx) {
+    let grant = ctx
+        .bob
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            None,
+            vec![Scope::Pin],
+            TTL,
+        )
+        .expect("issue grant");
+
+    let result = ctx
+        .alice
+        .acting_for(grant)
+        .upload_blob(random_bytes(64).into())
+        .await;
+    assert!(result.is_err());
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn an_expired_grant_is_refused(#[future] ctx: DataStoreCtx) {
+    let grant = ctx
+        .alice
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            None,
+            vec![Scope::Pin],
+            Duration::ZERO,
+        )
+        .expect("issue grant");
+
+    let result = ctx
+        .bob
+        .acting_for(grant)
+        .upload_blob(random_bytes(64).into())
+        .await;
+    assert!(result.is_err());
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn writes_are_confined_to_the_granted_prefix(#[future] ctx: DataStoreCtx) {
+    let ns = entries::create(ctx.store.docs()).await.expect("create doc");
+    ctx.alice.host_doc(ns).await.expect("alice hosts");
+
+    let grant = ctx
+        .alice
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            Some(ns),
+            vec![
+                Scope::Pin,
+                Scope::Write {
+                    prefix: "team/".into(),
+                },
+            ],
+            TTL,
+        )
+        .expect("issue grant");
+    let bob = ctx.bob.acting_for(grant);
+
+    let hash = bob
+        .upload_blob(random_bytes(256).into())
+        .await
+        .expect("upload as alice");
+
+    bob.write_entries(
+        ns,
+        vec![EntryWrite::Set {
+            key: "team/wall".into(),
+            hash,
+        }],
+    )
+    .await
+    .expect("write under prefix");
+    let outside = bob
+        .write_entries(
+            ns,
+            vec![EntryWrite::Set {
+                key: "spawn".into(),
+                hash,
+            }],
+        )
+        .await;
+    assert!(outside.is_err());
+
+    let doc = ctx
+        .store
+        .docs()
+        .api()
+        .open(ns)
+        .await
+        .expect("open doc")
+        .expect("doc held");
+    let written = entries::list(&doc, &[""]).await.expect("list entries");
+    assert_eq!(
+        written.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+        ["team/wall"]
+    );
+    assert_eq!(written[0].size, 256);
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn writes_to_a_doc_the_host_did_not_create_are_refused_as_read_only(
+    #[future] ctx: DataStoreCtx,
+    #[future]
+    #[from(ctx)]
+    client: DataStoreCtx,
+) {
+    // Created elsewhere, so the host only ever holds it read-only.
+    let ns = entries::create(client.store.docs())
+        .await
+        .expect("create doc");
+    ctx.alice.host_doc(ns).await.expect("alice hosts");
+
+    let grant = ctx
+        .alice
+        .issue_grant(
+            ctx.bob.identity().did().clone(),
+            Some(ns),
+            vec![
+                Scope::Pin,
+                Scope::Write {
+                    prefix: String::new(),
+                },
+            ],
+            TTL,
+        )
+        .expect("issue grant");
+    let bob = ctx.bob.acting_for(grant);
+
+    let hash = bob
+        .upload_blob(random_bytes(256).into())
+        .await
+        .expect("upload as alice");
+    let err = bob
+        .write_entries(
+            ns,
+            vec![EntryWrite::Set {
+                key: "spawn".into(),
+                hash,
+            }],
+        )
+        .await
+        .expect_err("the host cannot write");
+    assert!(
+        err.to_string().contains(&ApiError::ReadOnly.to_string()),
+        "refused as read-only, not as a grant problem: {err}"
+    );
+}
@@ -0,0 +1,153 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use iroh::{
+    Endpoint,
+    endpoint::presets::N0DisableRelay,
+    protocol::Router,
+};
+use iroh_docs::NamespaceId;
+use rstest::{
+    fixture,
+    rstest,
+};
+use rusqlite::params;
+use tracing_test::traced_test;
+use wds::{
+    DataStore,
+    actor::Actor,
+    entries,
+    identity::Identity,
+};
+use xdid::{
+    core::did::Did,
+    methods::key::keys::{
+        DidKeyPair,
+        PublicKey,
+        p256::P256KeyPair,
+    },
+};
+
+struct Server {
+    store:   DataStore,
+    did:     Did,
+    _router: Router,
+}
+
+struct MirrorCtx {
+    primary: Server,
+    mirror:  Server,
+    /// A user of the primary.
+    alice:   Actor,
+}
+
+fn identity() -> Arc<Identity> {
+    let key = P256KeyPair::generate();
+    Arc::new(Identity::new(key.public().to_did(), key))
+}
+
+async fn server(identity: Arc<Identity>, mirrors: Did) -> Server {
+    let endpoint = Endpoint::builder(N0DisableRelay)
+        .bind()
+        .await
+        .expect("bind endpoint");
+
+    let (store, f) = DataStore::builder(endpoint.clone())
+        .mirrors([mirrors])
+        .build()
+        .await
+        .expect("construct data store");
+    let router = f(Router::builder(endpoint)).spawn();
+
+    let did = identity.did().clone();
+    store.set_user_identity(identity);
+
+    Server {
+        store,
+        did,
+        _router: router,
+    }
+}
+
+#[fixture]
+async fn mirror_ctx() -> MirrorCtx {
+    let (primary, mirror) = (identity(), identity());
+    let primary = server(Arc::clone(&primary), mirror.did().clone()).await;
+    let mirror = server(mirror, primary.did.clone()).await;
+    let alice = primary.store.local_actor(identity());
+
+    MirrorCtx {
+        primary,
+        mirror,
+        alice,
+    }
+}
+
+async fn hosted_by(server: &Server) -> Vec<NamespaceId> {
+    let did = server.did.to_string();
+    server
+        .store
+        .db()
+        .call(move |conn| {
+            let mut stmt = conn.prepare("SELECT ns FROM hosted_docs WHERE owner = ?")?;
+            let rows = stmt.query_map(params![&did], |row| row.get::<_, String>(0))?;
+            rows.map(|ns| Ok(ns?.parse::<NamespaceId>()?))
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .await
+        .expect("read hosted docs")
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn a_mirror_follows_what_its_peer_hosts(#[future] mirror_ctx: MirrorCtx) {
+    let MirrorCtx {
+        primary,
+        mirror,
+        alice,
+    } = mirror_ctx;
+
+    let ns = entries::create(primary.store.docs())
+        .await
+        .expect("create doc");
+    alice.host_doc(ns).await.expect("host");
+
+    mirror
+        .store
+        .mirror_peer(&primary.did, primary.store.endpoint().addr())
+        .await
+        .expect("mirror primary");
+    assert_eq!(hosted_by(&mirror).await, [ns]);
+
+    primary
+        .store
+        .mirror_peer(&mirror.did, mirror.store.endpoint().addr())
+        .await
+        .expect("mirror the mirror");
+    assert!(
+        hosted_by(&primary).await.is_empty(),
+        "a mirrored copy is not listed back to its source"
+    );
+
+    alice.unhost_doc(ns).await.expect("unhost");
+    mirror
+        .store
+        .mirror_peer(&primary.did, primary.store.endpoint().addr())
+        .await
+        .expect("mirror primary again");
+    assert!(hosted_by(&mirror).await.is_empty());
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn only_a_listed_mirror_may_list_hosted_docs(#[future] mirror_ctx: MirrorCtx) {
+    assert!(mirror_ctx.alice.list_mirrored().await.is_err());
+}
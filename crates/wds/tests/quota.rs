@@ -173,3 +173,43 @@ async fn test_upload_past_quota_is_refused_midstream(#[future] ctx: DataStoreCtx
     assert!(ctx.alice.upload_blob(bytes.into()).await.is_err());
     assert_eq!(bytes_used(&ctx.store, &did).await, 0);
 }
+
+#[rstest]
+#[timeout(Duration::from_secs(5))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn test_report_itemises_pins_and_unpin_refunds(#[future] ctx: DataStoreCtx) {
+    let mut small = vec![0u8; 1024];
+    let mut large = vec![0u8; 4096];
+    rand::rng().fill_bytes(&mut small);
+    rand::rng().fill_bytes(&mut large);
+    ctx.alice
+        .upload_blob(small.into())
+        .await
+        .expect("upload small");
+    let large = ctx
+        .alice
+        .upload_blob(large.into())
+        .await
+        .expect("upload large");
+
+    let report = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(report.bytes_used, 5120);
+    assert_eq!(
+        report.pins.iter().map(|p| p.size).collect::<Vec<_>>(),
+        [4096, 1024]
+    );
+    assert_eq!(
+        report.history.last().map(|s| s.bytes_used),
+        Some(report.bytes_used)
+    );
+
+    ctx.alice.unpin_blob(large).await.expect("unpin");
+    assert!(ctx.alice.unpin_blob(large).await.is_err());
+
+    let report = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(report.bytes_used, 1024);
+    assert_eq!(report.pins.len(), 1);
+    assert_eq!(report.history.last().map(|s| s.bytes_used), Some(1024));
+}
@@ -1,8 +1,13 @@
 use std::time::Duration;
 
+use bytes::Bytes;
 use rand::RngCore;
 use rstest::rstest;
 use tracing_test::traced_test;
+use wds::pieces::{
+    self,
+    PIECE_LEN,
+};
 
 use crate::common::{
     DataStoreCtx,
@@ -62,3 +67,127 @@ async fn test_pin_extends_existing_blob(#[future] ctx: DataStoreCtx) {
         .await
         .expect("pin existing blob");
 }
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn test_resumable_upload_charges_its_size_once(#[future] ctx: DataStoreCtx) {
+    let mut bytes = vec![0u8; 2 * PIECE_LEN as usize + 100];
+    rand::rng().fill_bytes(&mut bytes);
+
+    let hash = ctx
+        .alice
+        .upload_blob_resumable(bytes.clone().into())
+        .await
+        .expect("upload blob");
+
+    assert_eq!(hash, blake3::hash(&bytes));
+    assert!(ctx.alice.blob_exists(hash).await.expect("blob exists"));
+    let quota = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(quota.bytes_used.cast_unsigned(), bytes.len() as u64);
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn test_interrupted_upload_resumes_from_its_offset(#[future] ctx: DataStoreCtx) {
+    let mut bytes = vec![0u8; 3 * PIECE_LEN as usize];
+    rand::rng().fill_bytes(&mut bytes);
+    let bytes = Bytes::from(bytes);
+    let hash = blake3::hash(&bytes);
+
+    ctx.alice
+        .begin_upload(hash, bytes.len() as u64, pieces::chaining_values(&bytes))
+        .await
+        .expect("begin upload");
+    let progress = ctx
+        .alice
+        .upload_piece(hash, 0, bytes.slice(..PIECE_LEN as usize))
+        .await
+        .expect("first piece");
+    assert_eq!(progress.received, PIECE_LEN);
+
+    // Pieces are charged as they land.
+    let quota = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(quota.bytes_used.cast_unsigned(), PIECE_LEN);
+
+    let resumed = ctx
+        .alice
+        .begin_upload(hash, bytes.len() as u64, pieces::chaining_values(&bytes))
+        .await
+        .expect("resume upload");
+    assert_eq!(resumed.received, PIECE_LEN);
+
+    ctx.alice
+        .upload_blob_resumable(bytes.clone())
+        .await
+        .expect("finish upload");
+    let quota = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(quota.bytes_used.cast_unsigned(), bytes.len() as u64);
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn test_piece_not_matching_its_hash_is_refused(#[future] ctx: DataStoreCtx) {
+    let mut bytes = vec![0u8; 2 * PIECE_LEN as usize];
+    rand::rng().fill_bytes(&mut bytes);
+    let hash = blake3::hash(&bytes);
+
+    ctx.alice
+        .begin_upload(hash, bytes.len() as u64, pieces::chaining_values(&bytes))
+        .await
+        .expect("begin upload");
+
+    let mut piece = bytes[..PIECE_LEN as usize].to_vec();
+    piece[0] ^= 1;
+    let result = ctx.alice.upload_piece(hash, 0, piece.into()).await;
+    assert!(result.is_err());
+
+    let quota = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(quota.bytes_used, 0);
+}
+
+#[rstest]
+#[timeout(Duration::from_secs(10))]
+#[awt]
+#[traced_test]
+#[tokio::test]
+async fn test_concurrent_finishes_charge_the_upload_once(#[future] ctx: DataStoreCtx) {
+    let mut bytes = vec![0u8; 2 * PIECE_LEN as usize];
+    rand::rng().fill_bytes(&mut bytes);
+    let bytes = Bytes::from(bytes);
+    let hash = blake3::hash(&bytes);
+
+    ctx.alice
+        .begin_upload(hash, bytes.len() as u64, pieces::chaining_values(&bytes))
+        .await
+        .expect("begin upload");
+    ctx.alice
+        .upload_piece(hash, 0, bytes.slice(..PIECE_LEN as usize))
+        .await
+        .expect("first piece");
+
+    // Every copy of the last piece that arrives after the first has landed
+    // finds the upload whole, and assembles it again.
+    let last = bytes.slice(PIECE_LEN as usize..);
+    let finishes =
+        futures::future::join_all((0..8).map(|_| ctx.alice.upload_piece(hash, 1, last.clone())))
+            .await;
+    assert!(
+        finishes
+            .into_iter()
+            .any(|progress| progress.is_ok_and(|p| p.complete)),
+        "some copy should complete the upload"
+    );
+
+    assert!(ctx.alice.blob_exists(hash).await.expect("blob exists"));
+    let quota = ctx.alice.get_quota().await.expect("get quota");
+    assert_eq!(quota.bytes_used.cast_unsigned(), bytes.len() as u64);
+}